@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use shared::fbdb::FileBasedDB;
+use shared::model::edit::PostVersion;
 use shared::model::User;
 
 /// Exchanges data with a remote device
@@ -29,30 +31,251 @@ pub fn exchange_users(
     Ok((users_not_known_to_local, users_not_known_to_remote))
 }
 
+/// Compares the key rotations known to a remote device with the local ones
+pub fn exchange_rotations(
+    remote_known_rotation_ids: Vec<String>,
+    fbdb: &mut FileBasedDB,
+) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    // Rotations are few and small, every one of them is compared
+    let known_rotations_local =
+        fbdb.read_key_rotations_filter_map(usize::MAX, |_r| true, |r| r.uuid)?;
+
+    let rotations_not_known_to_local: Vec<String> = remote_known_rotation_ids
+        .iter()
+        .filter(|item| !known_rotations_local.contains(item))
+        .cloned()
+        .collect();
+
+    let rotations_not_known_to_remote: Vec<String> = known_rotations_local
+        .into_iter()
+        .filter(|item| !remote_known_rotation_ids.contains(item))
+        .collect();
+
+    Ok((rotations_not_known_to_local, rotations_not_known_to_remote))
+}
+
+/// Exchanges the tombstones with a remote device, every one that didn't expire
+pub fn exchange_tombstones(
+    remote_known_tombstone_ids: Vec<String>,
+    now: DateTime<Utc>,
+    fbdb: &mut FileBasedDB,
+) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    let known_tombstones_local: Vec<String> = fbdb
+        .read_tombstones(now)?
+        .into_iter()
+        .map(|t| t.uuid)
+        .collect();
+
+    let tombstones_not_known_to_local: Vec<String> = remote_known_tombstone_ids
+        .iter()
+        .filter(|item| !known_tombstones_local.contains(item))
+        .cloned()
+        .collect();
+
+    let tombstones_not_known_to_remote: Vec<String> = known_tombstones_local
+        .into_iter()
+        .filter(|item| !remote_known_tombstone_ids.contains(item))
+        .collect();
+
+    Ok((
+        tombstones_not_known_to_local,
+        tombstones_not_known_to_remote,
+    ))
+}
+
+/// Max number of local replies compared in one request
+const REPLY_LIMIT: usize = 200;
+
+/// Compares the replies in a time range known to a remote device with the local ones
+/// Also returns the posts and replies that local replies answer but that aren't stored
+/// here, the remote device may have them.
+pub fn exchange_replies(
+    start_date: &DateTime<Utc>,
+    end_date: &DateTime<Utc>,
+    remote_known_reply_ids: Vec<String>,
+    fbdb: &mut FileBasedDB,
+) -> anyhow::Result<(Vec<String>, Vec<String>, Vec<String>)> {
+    let local_replies = fbdb.read_replies_filter_map(
+        REPLY_LIMIT,
+        |r| start_date <= &r.timestamp && &r.timestamp <= end_date,
+        |r| (r.uuid, r.parent_id, r.root_id),
+    )?;
+    let known_replies_local: Vec<String> = local_replies
+        .iter()
+        .map(|(uuid, _, _)| uuid.clone())
+        .collect();
+
+    let replies_not_known_to_local: Vec<String> = remote_known_reply_ids
+        .iter()
+        .filter(|item| !known_replies_local.contains(item))
+        .cloned()
+        .collect();
+
+    let replies_not_known_to_remote: Vec<String> = known_replies_local
+        .iter()
+        .filter(|item| !remote_known_reply_ids.contains(item))
+        .cloned()
+        .collect();
+
+    let mut answered: Vec<String> = local_replies
+        .into_iter()
+        .flat_map(|(_, parent_id, root_id)| [parent_id, root_id])
+        .collect();
+    answered.sort();
+    answered.dedup();
+    let stored_posts =
+        fbdb.read_posts_filter_map(usize::MAX, |p| answered.contains(&p.uuid), |p| p.uuid)?;
+    let stored_replies =
+        fbdb.read_replies_filter_map(usize::MAX, |r| answered.contains(&r.uuid), |r| r.uuid)?;
+    let unresolved: Vec<String> = answered
+        .into_iter()
+        .filter(|item| !stored_posts.contains(item) && !stored_replies.contains(item))
+        .collect();
+
+    Ok((
+        replies_not_known_to_local,
+        replies_not_known_to_remote,
+        unresolved,
+    ))
+}
+
+/// Max number of local reposts compared in one request, and of reposted posts asked for
+const REPOST_LIMIT: usize = 200;
+
+/// Compares the reposts in a time range known to a remote device with the local ones
+/// Also returns the posts that local reposts of any time pass on but that aren't stored
+/// here, the remote device may have them.
+pub fn exchange_reposts(
+    start_date: &DateTime<Utc>,
+    end_date: &DateTime<Utc>,
+    remote_known_repost_ids: Vec<String>,
+    fbdb: &mut FileBasedDB,
+) -> anyhow::Result<(Vec<String>, Vec<String>, Vec<String>)> {
+    let known_reposts_local = fbdb.read_reposts_filter_map(
+        REPOST_LIMIT,
+        |r| start_date <= &r.timestamp && &r.timestamp <= end_date,
+        |r| r.uuid,
+    )?;
+
+    let reposts_not_known_to_local: Vec<String> = remote_known_repost_ids
+        .iter()
+        .filter(|item| !known_reposts_local.contains(item))
+        .cloned()
+        .collect();
+
+    let reposts_not_known_to_remote: Vec<String> = known_reposts_local
+        .iter()
+        .filter(|item| !remote_known_repost_ids.contains(item))
+        .cloned()
+        .collect();
+
+    // The posts stay asked for until a phone brings them, however old the repost is,
+    // unless their author deleted them
+    let deleted: Vec<String> = fbdb
+        .read_tombstones(Utc::now())?
+        .into_iter()
+        .map(|t| t.post_id)
+        .collect();
+    let mut reposted =
+        fbdb.read_reposts_filter_map(usize::MAX, |r| !deleted.contains(&r.post_id), |r| r.post_id)?;
+    reposted.sort();
+    reposted.dedup();
+    let stored_posts =
+        fbdb.read_posts_filter_map(usize::MAX, |p| reposted.contains(&p.uuid), |p| p.uuid)?;
+    let unresolved: Vec<String> = reposted
+        .into_iter()
+        .filter(|item| !stored_posts.contains(item))
+        .take(REPOST_LIMIT)
+        .collect();
+
+    Ok((
+        reposts_not_known_to_local,
+        reposts_not_known_to_remote,
+        unresolved,
+    ))
+}
+
+/// Compares the reactions in a time range known to a remote device with the local ones
+/// Only the reactions that count are compared, see `shared::reactions`.
+pub fn exchange_reactions(
+    start_date: &DateTime<Utc>,
+    end_date: &DateTime<Utc>,
+    remote_known_reaction_ids: Vec<String>,
+    fbdb: &mut FileBasedDB,
+) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    let set = fbdb.read_reactions()?;
+    let known_reactions_local: Vec<&String> = set
+        .iter()
+        .filter(|r| start_date <= &r.timestamp && &r.timestamp <= end_date)
+        .map(|r| &r.uuid)
+        .collect();
+
+    let reactions_not_known_to_local: Vec<String> = remote_known_reaction_ids
+        .iter()
+        .filter(|item| !set.holds(item))
+        .cloned()
+        .collect();
+
+    let reactions_not_known_to_remote: Vec<String> = known_reactions_local
+        .into_iter()
+        .filter(|item| !remote_known_reaction_ids.contains(item))
+        .cloned()
+        .collect();
+
+    Ok((reactions_not_known_to_local, reactions_not_known_to_remote))
+}
+
+/// Compares the posts in a time range known to a remote device with the local ones
+/// Edited posts are compared by version, the side with the older version is sent the newer.
+/// Two versions of the same revision differ too, so edits made on two devices at once spread.
 pub fn exchange_posts(
     start_date: &DateTime<Utc>,
     end_date: &DateTime<Utc>,
     remote_known_post_ids: Vec<String>,
+    remote_versions: &HashMap<String, PostVersion>,
     fbdb: &mut FileBasedDB,
-) -> anyhow::Result<((Vec<String>, Vec<String>))> {
-    // Synchronize posts
-    let known_posts_local = fbdb.read_posts_filter_map(
-        100,
-        |p| true,// |p| start_date >= &p.timestamp && &p.timestamp >= end_date,
-        |p| p.uuid,
-    )?; //db.get_post_ids_in_range();
+) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    // Every version is stored, only the newest of each post counts. None stands for a post
+    // as first written, like on the remote side.
+    let mut known_posts_local: HashMap<String, Option<PostVersion>> = HashMap::new();
+    let versions = fbdb.read_posts_filter_map(
+        usize::MAX,
+        |p| start_date <= &p.changed_at() && &p.changed_at() <= end_date,
+        |p| {
+            let version = p.version();
+            (p.uuid, version)
+        },
+    )?;
+    for (uuid, version) in versions {
+        let version = (version.revision > 0).then_some(version);
+        let known = known_posts_local.entry(uuid).or_default();
+        if version > *known {
+            *known = version;
+        }
+    }
+    let remote_version = |uuid: &String| {
+        remote_versions
+            .get(uuid)
+            .filter(|version| version.revision > 0)
+    };
 
-    let posts_not_known_to_remote: Vec<String> = known_posts_local
+    let posts_not_known_to_local: Vec<String> = remote_known_post_ids
         .iter()
-        .filter(|item| !remote_known_post_ids.contains(item))
+        .filter(|item| {
+            known_posts_local
+                .get(*item)
+                .is_none_or(|version| version.as_ref() < remote_version(item))
+        })
         .cloned()
         .collect();
 
-    let posts_not_known_to_local: Vec<String> = remote_known_post_ids
+    let posts_not_known_to_remote: Vec<String> = known_posts_local
         .into_iter()
-        .filter(|item| !known_posts_local.contains(item))
+        .filter(|(item, version)| {
+            !remote_known_post_ids.contains(item) || remote_version(item) < version.as_ref()
+        })
+        .map(|(item, _)| item)
         .collect();
 
-
     Ok((posts_not_known_to_local, posts_not_known_to_remote))
 }
@@ -1,4 +1,5 @@
 mod ble;
+mod request;
 mod wifi;
 mod util;
 
@@ -24,6 +25,7 @@ use std::time::Duration;
 use chrono::Utc;
 use embedded_svc::http::Method::Post;
 use shared::fbdb::FileBasedDB;
+use shared::media::MediaStore;
 // use shared::model::User;
 use wifi::WifiConfig;
 use crate::util::{get_chip_serial, mac_to_id_and_pass};
@@ -119,8 +121,9 @@ fn main() -> anyhow::Result<()> {
     let _mounted_fatfs = MountedFatfs::mount(Fatfs::new_sdcard(0, sd_spi_driver)?, "/sd", 4)?;
 
     let fbdb = Arc::new(Mutex::new(FileBasedDB::init("/sd/fbdb")?));
+    let media = MediaStore::init("/sd/pics")?;
 
-    log::info!("sdcard and fbdb init done.");
+    log::info!("sdcard, fbdb and media store init done.");
 
 
     log::info!("Initializing BLE...");
@@ -150,6 +153,7 @@ fn main() -> anyhow::Result<()> {
     log::info!("Initializing WiFi AP...");
     let (wifi, server) = wifi::init_wifi(
         WifiConfig {
+            totem_id: ssid.clone(),
             ssid,
             password: pass,
         },
@@ -157,6 +161,7 @@ fn main() -> anyhow::Result<()> {
         sys_loop.clone(),
         nvs.clone(),
         Arc::clone(&fbdb),
+        media,
     )?;
 
 
@@ -195,6 +200,12 @@ fn main() -> anyhow::Result<()> {
             timestamp: Default::default(),
             image: None,
             source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         })?;
     }
 
@@ -211,6 +222,12 @@ fn main() -> anyhow::Result<()> {
             timestamp: Default::default(),
             image: None,
             source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         })?;
     }
 
@@ -0,0 +1,111 @@
+//! Request and response helpers for the HTTP server
+//!
+//! The handlers never read from the request directly, but go through the helpers in
+//! here, which enforce the size limits.
+//!
+//! ESP-IDF's httpd limits reads to the `Content-Length` of the request, so bodies without
+//! one are answered with 411, see [`shared::http`].
+//!
+//! Errors are always answered with a JSON [`ApiError`] and the matching status code.
+//! Internal failures are logged here, the client only gets a generic message.
+
+use embedded_svc::http::server::Request;
+use embedded_svc::http::Headers;
+use embedded_svc::io::{Read, Write};
+use esp_idf_svc::http::server::EspHttpConnection;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use shared::api::ApiError;
+use shared::http::{self, BodyError, BodyReader};
+
+/// Adapter exposing an embedded-svc reader as a `std::io::Read`
+pub struct IoRead<'a, R>(&'a mut R);
+
+impl<R: Read> std::io::Read for IoRead<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0
+            .read(buf)
+            .map_err(|e| std::io::Error::other(format!("{:?}", e)))
+    }
+}
+
+/// Open the body of `req`, rejecting it if it is bigger than `limit`
+pub fn body<R: Headers + Read>(
+    req: &mut R,
+    limit: usize,
+) -> Result<BodyReader<IoRead<'_, R>>, BodyError> {
+    let len = http::body_length(req.content_len(), req.header("Transfer-Encoding"), limit)?;
+    Ok(BodyReader::new(IoRead(req), len))
+}
+
+/// Read the whole body of `req` into memory, rejecting it if it is bigger than `limit`
+pub fn read_body<R: Headers + Read>(req: &mut R, limit: usize) -> Result<Vec<u8>, ApiError> {
+    body(req, limit)
+        .and_then(|body| body.read_to_vec())
+        .map_err(log_body_error)
+}
+
+/// Parse a JSON request body
+pub fn parse_json<'a, T: Deserialize<'a>>(buf: &'a [u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(buf).map_err(|e| ApiError::invalid_json(e.to_string()))
+}
+
+/// Log a body error before turning it into the error sent to the client
+pub fn log_body_error(e: BodyError) -> ApiError {
+    if let BodyError::Io(io) = &e {
+        warn!("Failed to read request body: {:?}", io);
+    }
+    ApiError::from(e)
+}
+
+/// Log an internal error, and return the generic error sent to the client instead
+pub fn internal<E: core::fmt::Debug>(message: &str, cause: E) -> ApiError {
+    warn!("{}: {:?}", message, cause);
+    ApiError::internal(message)
+}
+
+/// Answer the request with a JSON document
+pub fn send_json<T: Serialize>(
+    req: Request<&mut EspHttpConnection>,
+    status: u16,
+    value: &T,
+) -> anyhow::Result<()> {
+    send_json_with_headers(req, status, value, &[])
+}
+
+/// Answer the request with a JSON document and some more headers
+pub fn send_json_with_headers<T: Serialize>(
+    req: Request<&mut EspHttpConnection>,
+    status: u16,
+    value: &T,
+    headers: &[(&str, &str)],
+) -> anyhow::Result<()> {
+    let body = serde_json::to_vec(value)?;
+    let mut all_headers = vec![("Content-Type", "application/json")];
+    all_headers.extend_from_slice(headers);
+
+    req.into_response(status, None, &all_headers)?
+        .write_all(&body)?;
+
+    Ok(())
+}
+
+/// Answer the request with an error
+pub fn send_error(req: Request<&mut EspHttpConnection>, err: &ApiError) -> anyhow::Result<()> {
+    info!("{} failed: {}", req.uri(), err);
+    send_json(req, err.status(), err)
+}
+
+/// Answer the request with the outcome of a handler, using `status` on success
+/// This is the exit point of all handlers, so errors never escape to httpd, which
+/// would answer with a plain text 500 containing the error.
+pub fn respond<T: Serialize>(
+    req: Request<&mut EspHttpConnection>,
+    status: u16,
+    result: Result<T, ApiError>,
+) -> anyhow::Result<()> {
+    match result {
+        Ok(value) => send_json(req, status, &value),
+        Err(e) => send_error(req, &e),
+    }
+}
@@ -5,8 +5,8 @@
 
 use core::convert::TryInto;
 use embedded_svc::{
-    http::{Headers, Method},
-    io::{Read, Write},
+    http::{server::Request, Headers, Method},
+    io::Write,
     wifi::{self, AccessPointConfiguration, AuthMethod},
 };
 use esp_idf_hal::modem::WifiModem;
@@ -14,22 +14,42 @@ use esp_idf_hal::sys::EspError;
 use esp_idf_svc::hal::modem::Modem;
 use esp_idf_svc::{
     eventloop::EspSystemEventLoop,
-    http::server::EspHttpServer,
+    http::server::{EspHttpConnection, EspHttpServer},
     nvs::EspDefaultNvsPartition,
     wifi::{BlockingWifi, EspWifi},
 };
 use std::{io, thread};
 use std::time::Duration;
 
-use log::info;
+use log::{info, warn};
 
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use shared::fbdb::FileBasedDB;
-use std::fs::{self, File, OpenOptions};
-use std::io::{BufReader, BufWriter, Read as StdRead, Write as StdWrite};
-use std::str::FromStr;
+use shared::http::{BodyError, ByteRange, ContentRange};
+use shared::media::{ContentHash, MediaError, MediaInfo, MediaStore};
+use std::fs::File;
+use std::io::{Read as StdRead, Seek, SeekFrom};
 use std::sync::{Arc, Mutex};
+use shared::api::{
+    ApiError, BundlesDeliverRequest, BundlesDeliverResponse, BundlesOfferRequest,
+    BundlesOfferResponse, CompareResponse, CreatedResponse, ErrorCode, MailboxCollectRequest,
+    MailboxCollectResponse, MailboxDepositRequest, MailboxDepositResponse, MediaCompareRequest,
+    MediaCompareResponse, PicSaved, PostsCompareRequest, ReactionsBatch, ReactionsCompareRequest,
+    ReactionsCreateResponse, ReactionsFetchRequest, RefusedMessage, RepliesCompareRequest,
+    RepliesCompareResponse, RepostsCompareRequest, RepostsCompareResponse, RotationsCompareRequest,
+    TombstonesCompareRequest, TrendingResponse, UploadStatus, UsersCompareRequest,
+    UsersLastSeenRequest, UsersLastSeenResponse,
+};
+use shared::mailbox::{Refusal, MAX_MAILBOX_BODY};
+use shared::presence::MAX_SIGHTING_SKEW;
+use shared::reactions::MAX_REACTIONS_PER_REQUEST;
+use shared::routing::{Node, MAX_BUNDLE_BODY};
+use shared::trending::{Trending, TRENDING_WINDOW};
 use shared::model;
+use shared::model::validation;
+use shared::model::validation::AttachmentLimits;
+
+use crate::request;
 
 /// Wi-Fi channel, between 1 and 11
 /// Channel 6 is often a good default as it's commonly used and supported
@@ -38,9 +58,31 @@ const CHANNEL: u8 = 6;
 /// Max payload length for HTTP requests
 const MAX_LEN: usize = 1024;
 
+/// How far back the visitors of this totem are shared with clients
+const RECENT_VISITORS_WINDOW: chrono::TimeDelta = chrono::TimeDelta::hours(24);
+
+/// Max number of sightings sent back by /users/last_seen
+const MAX_LAST_SEEN_ENTRIES: usize = 200;
+
+/// Max number of receipts handed out by /mailbox/collect for phones to pass on
+const MAX_PASSED_ON_RECEIPTS: usize = 50;
+
+/// Max size of attachments per kind, the SD card is shared by everyone passing by
+/// Uploads aren't tied to a post yet, so they are only held to the largest limit.
+const ATTACHMENT_LIMITS: AttachmentLimits = AttachmentLimits {
+    image: 4 * 1024 * 1024,
+    clip: 16 * 1024 * 1024,
+    voice: 2 * 1024 * 1024,
+    file: 4 * 1024 * 1024,
+};
+
 /// Stack size for HTTP server (needs to be large for JSON parsing)
 const STACK_SIZE: usize = 20000; // 10240;
 
+/// Max number of handlers the HTTP server takes, registering more fails at startup
+/// 32 are registered by `init_wifi`, the rest is headroom for new endpoints.
+const MAX_URI_HANDLERS: usize = 48;
+
 static INDEX_HTML: &str = include_str!("http_server_page.html");
 
 /// Configuration for the WiFi Access Point
@@ -49,6 +91,8 @@ pub struct WifiConfig {
     pub ssid: String,
     /// Password for the Access Point (WPA2)
     pub password: String,
+    /// ID of this totem, as advertised over BLE
+    pub totem_id: String,
 }
 
 #[derive(Deserialize)]
@@ -58,32 +102,6 @@ struct FormData<'a> {
     birthplace: &'a str,
 }
 
-/// Request structure for /posts/compare endpoint
-#[derive(Deserialize)]
-struct PostsCompareRequest<'a> {
-    time_start: &'a str,     // ISO 8601 timestamp
-    time_end: &'a str,       // ISO 8601 timestamp
-    post_uuids: Vec<String>, // List of UUIDs
-}
-
-#[derive(Debug, Serialize)]
-struct PostsCompareResponse {
-    totem_missing: Vec<String>,
-    client_missing: Vec<String>,
-}
-
-/// Request structure for /users/compare endpoint
-#[derive(Deserialize)]
-struct UsersCompareRequest {
-    user_uuids: Vec<String>, // List of UUIDs
-}
-
-#[derive(Serialize)]
-struct UsersCompareResponse {
-    totem_missing: Vec<String>,
-    client_missing: Vec<String>,
-}
-
 /// Request structure for /users endpoint (create user)
 #[derive(Deserialize)]
 struct UserRequest {
@@ -92,7 +110,16 @@ struct UserRequest {
     pub status: String,
     pub bio: String,
     pub profile_picture: Option<String>,
-    // last_contact will be set to current time on creation
+    /// When the user last changed their profile, older apps don't send it
+    pub last_contact: Option<String>,
+    #[serde(default)]
+    pub profile_picture_preview: Option<model::ImagePreview>,
+    /// Key the ID is derived from, missing for users with a random ID
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// Covers `last_contact` too, so it has to be sent along with the signature
+    #[serde(default)]
+    pub signature: Option<String>,
 }
 
 /// Request structure for /posts endpoint
@@ -108,12 +135,6 @@ struct PostsRequest {
 
 }
 
-/// Request structure for /users/last_seen endpoint
-#[derive(Deserialize)]
-struct UsersLastSeenRequest {
-    user_uuids: Vec<heapless::String<32>>, // List of UUIDs
-}
-
 /// Initialize WiFi Access Point and HTTP server with the given configuration
 pub fn init_wifi(
     config: WifiConfig,
@@ -121,6 +142,7 @@ pub fn init_wifi(
     sys_loop: EspSystemEventLoop,
     nvs: EspDefaultNvsPartition,
     fbdb: Arc<Mutex<FileBasedDB>>,
+    media: MediaStore,
 ) -> anyhow::Result<(BlockingWifi<EspWifi<'static>>, EspHttpServer<'static>)> {
     let mut wifi = BlockingWifi::wrap(EspWifi::new(modem, sys_loop.clone(), Some(nvs))?, sys_loop)?;
 
@@ -128,474 +150,1722 @@ pub fn init_wifi(
 
     let mut server = create_server()?;
 
+    let trending = Arc::new(Mutex::new(load_trending(&fbdb.lock().unwrap())?));
+
     // POST /posts/compare - Compare posts in a time range for a user
     {
-        let mut fbdb = Arc::clone(&fbdb);
+        let fbdb = Arc::clone(&fbdb);
         server.fn_handler::<anyhow::Error, _>("/posts/compare", Method::Post, move |mut req| {
-            let len = req.content_len().unwrap_or(0) as usize;
-
-            if len > MAX_LEN * 10
-            /* bigger MAX_LEN for multiple IDs */
-            {
-                req.into_status_response(413)?
-                    .write_all("Request too big".as_bytes())?;
-                return Ok(());
-            }
-
-            let mut buf = vec![0; len];
-            req.read_exact(&mut buf)?;
-
-            let log_str = String::from_utf8_lossy(&buf);
-            let log_preview = log_str; // if log_str.len() > 100 { &log_str[..100] } else { &log_str };
-            info!("POST /posts/compare - Input: {}", log_preview);
-
-
-            if let Ok(data) = serde_json::from_slice::<PostsCompareRequest>(&buf) {
-                // TODO: Implement posts comparison logic
-                // For now, just acknowledge the request
-
-                /*
-                write!(
-                    resp,
-                    "TODO: Compare posts for user {} from {} to {} (count: {})",
-                    data.user_id,
-                    data.time_start,
-                    data.time_end,
-                    data.post_uuids.len()
-                )?;
-
-                 */
-
-                let res = {
-                    let mut db = fbdb.lock().unwrap();
-
-                    firmware::data_exchange::exchange_posts(
-                        // THIS explodes if there's no tz
-                        &chrono::DateTime::from_str(data.time_start)?,
-                        &chrono::DateTime::from_str(data.time_end)?,
-                        data.post_uuids,
-                        &mut *db,
-                    )?
-                };
-
-                let res = PostsCompareResponse {
-                    totem_missing: res.0,
-                    client_missing: res.1,
-                };
-
-                info!("{:?}", res);
-
-                req.into_ok_response()?.write_all(
-                    serde_json::to_vec(&res)?
-                    .as_slice(),
-                )?;
-            } else {
-                req.into_status_response(400)?.write_all("JSON error".as_bytes())?;
-            }
-
-            Ok(())
+            let result = compare_posts(&mut req, &fbdb);
+            request::respond(req, 200, result)
         })?;
     }
 
     // POST /users/compare - Compare users
     {
-        let mut fbdb = Arc::clone(&fbdb);
+        let fbdb = Arc::clone(&fbdb);
         server.fn_handler::<anyhow::Error, _>("/users/compare", Method::Post, move |mut req| {
-            let len = req.content_len().unwrap_or(0) as usize;
-
-            if len > MAX_LEN * 10
-            /* bigger MAX_LEN for multiple IDs */
-            {
-                req.into_status_response(413)?
-                    .write_all("Request too big".as_bytes())?;
-                return Ok(());
-            }
-
-            let mut buf = vec![0; len];
-            req.read_exact(&mut buf)?;
-
-            let log_str = String::from_utf8_lossy(&buf);
-            let log_preview = if log_str.len() > 100 { &log_str[..100] } else { &log_str };
-            info!("POST /users/compare - Input: {}", log_preview);
-
-            let mut resp = req.into_ok_response()?;
-
-            if let Ok(data) = serde_json::from_slice::<UsersCompareRequest>(&buf) {
-                let res = {
-                    let mut db = fbdb.lock().unwrap();
-
-                    firmware::data_exchange::exchange_users(
-                        data.user_uuids,
-                        &mut *db,
-                    )?
-                };
-
-                resp.write_all(
-                    serde_json::to_vec(&UsersCompareResponse {
-                        totem_missing: res.0,
-                        client_missing: res.1,
-                    })?
-                    .as_slice(),
-                )?;
-            } else {
-                resp.write_all("JSON error".as_bytes())?;
-            }
-
-            Ok(())
+            let result = compare_users(&mut req, &fbdb);
+            request::respond(req, 200, result)
         })?;
     }
 
     // POST /users - Create a new user
     {
-        let mut fbdb = Arc::clone(&fbdb);
+        let fbdb = Arc::clone(&fbdb);
         server.fn_handler::<anyhow::Error, _>("/users/create", Method::Post, move |mut req| {
-            let len = req.content_len().unwrap_or(0) as usize;
+            let result = create_user(&mut req, &fbdb);
+            request::respond(req, 201, result)
+        })?;
+    }
 
-            if len > MAX_LEN * 2 {
-                // Allow larger payload for user with bio and profile picture
-                req.into_status_response(413)?
-                    .write_all("Request too big".as_bytes())?;
-                return Ok(());
-            }
+    // POST /posts - Receive a list of posts
+    {
+        let fbdb = Arc::clone(&fbdb);
+        let media = media.clone();
+        let trending = Arc::clone(&trending);
+        server.fn_handler::<anyhow::Error, _>("/posts/create", Method::Post, move |mut req| {
+            let result = create_post(&mut req, &fbdb, &media, &trending);
+            request::respond(req, 201, result)
+        })?;
+    }
 
-            let mut buf = vec![0; len];
-            req.read_exact(&mut buf)?;
-
-            let log_str = String::from_utf8_lossy(&buf);
-            let log_preview = if log_str.len() > 100 { &log_str[..100] } else { &log_str };
-            info!("POST /users/create - Input: {}", log_preview);
-
-            if let Ok(data) = serde_json::from_slice::<UserRequest>(&buf) {
-                let result = {
-                    let mut db = fbdb.lock().unwrap();
-
-                    // Create a new user using the complete User model
-                    let new_user = shared::model::User {
-                        uuid: data.uuid.clone(),
-                        username: data.username.clone(),
-                        status: data.status.clone(),
-                        bio: data.bio.clone(),
-                        profile_picture: data.profile_picture.clone(),
-                        last_contact: chrono::Utc::now(),
-                    };
-
-                    db.write_user(&new_user)
-                };
-
-                match result {
-                    Ok(_) => {
-                        req.into_ok_response()?.write_all(format!("User {} created successfully", data.uuid).as_bytes())?;
-                    }
-                    Err(e) => {
-                        info!("Error creating user: {:?}", e);
-                        let mut resp = req.into_status_response(500)?;
-                        write!(resp, "Failed to create user: {:?}", e)?;
-                    }
-                }
-            } else {
-                req.into_status_response(400)?.write_all("JSON error".as_bytes())?;
-            }
+    // POST /replies/compare - Compare replies in a time range
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/replies/compare",
+            Method::Post,
+            move |mut req| {
+                let result = compare_replies(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /replies/create - Receive a reply
+    {
+        let fbdb = Arc::clone(&fbdb);
+        let trending = Arc::clone(&trending);
+        server.fn_handler::<anyhow::Error, _>(
+            "/replies/create",
+            Method::Post,
+            move |mut req| {
+                let result = create_reply(&mut req, &fbdb, &trending);
+                request::respond(req, 201, result)
+            },
+        )?;
+    }
+
+    // POST /reposts/compare - Compare reposts in a time range
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/reposts/compare",
+            Method::Post,
+            move |mut req| {
+                let result = compare_reposts(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /reposts/create - Receive a repost
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/reposts/create",
+            Method::Post,
+            move |mut req| {
+                let result = create_repost(&mut req, &fbdb);
+                request::respond(req, 201, result)
+            },
+        )?;
+    }
+
+    // POST /tombstones/compare - Compare tombstones of deleted posts
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/tombstones/compare",
+            Method::Post,
+            move |mut req| {
+                let result = compare_tombstones(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
 
-            Ok(())
+    // POST /tombstones/create - Receive a tombstone and delete its post
+    {
+        let fbdb = Arc::clone(&fbdb);
+        let media = media.clone();
+        server.fn_handler::<anyhow::Error, _>(
+            "/tombstones/create",
+            Method::Post,
+            move |mut req| {
+                let result = create_tombstone(&mut req, &fbdb, &media);
+                request::respond(req, 201, result)
+            },
+        )?;
+    }
+
+    // POST /reactions/compare - Compare reactions in a time range
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/reactions/compare",
+            Method::Post,
+            move |mut req| {
+                let result = compare_reactions(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /reactions/fetch - Hand out reactions by ID
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/reactions/fetch",
+            Method::Post,
+            move |mut req| {
+                let result = fetch_reactions(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /reactions/create - Receive reactions
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/reactions/create",
+            Method::Post,
+            move |mut req| {
+                let result = create_reactions(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /rotations/compare - Compare key rotations
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/rotations/compare",
+            Method::Post,
+            move |mut req| {
+                let result = compare_rotations(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /rotations/create - Receive a key rotation
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/rotations/create",
+            Method::Post,
+            move |mut req| {
+                let result = create_rotation(&mut req, &fbdb);
+                request::respond(req, 201, result)
+            },
+        )?;
+    }
+
+    // POST /mailbox/deposit - Keep messages until their recipients collect them
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/mailbox/deposit",
+            Method::Post,
+            move |mut req| {
+                let result = deposit_mail(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /mailbox/collect - Hand out the messages waiting for a recipient
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>(
+            "/mailbox/collect",
+            Method::Post,
+            move |mut req| {
+                let result = collect_mail(&mut req, &fbdb);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // POST /bundles/offer - Hand bundles to a phone and learn which ones it carries
+    {
+        let fbdb = Arc::clone(&fbdb);
+        let totem_id = config.totem_id.clone();
+        server.fn_handler::<anyhow::Error, _>("/bundles/offer", Method::Post, move |mut req| {
+            let result = offer_bundles(&mut req, &fbdb, &totem_id);
+            request::respond(req, 200, result)
         })?;
     }
 
-    // POST /posts - Receive a list of posts
+    // POST /bundles/deliver - Receive bundles addressed to this totem or to be passed on
     {
-        let mut fbdb = Arc::clone(&fbdb);
-        server.fn_handler::<anyhow::Error, _>("/posts/create", Method::Post, move |mut req| {
-            let len = req.content_len().unwrap_or(0) as usize;
+        let fbdb = Arc::clone(&fbdb);
+        let media = media.clone();
+        let trending = Arc::clone(&trending);
+        let totem_id = config.totem_id.clone();
+        server.fn_handler::<anyhow::Error, _>(
+            "/bundles/deliver",
+            Method::Post,
+            move |mut req| {
+                let result = deliver_bundles(&mut req, &fbdb, &media, &trending, &totem_id);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
 
-            if len > MAX_LEN * 10 {
-                // Allow larger payload for multiple posts
-                req.into_status_response(413)?
-                    .write_all("Request too big".as_bytes())?;
-                return Ok(());
-            }
+    // POST /pic/<sha256> - Save picture to SD card with streaming
+    // Can be sent in pieces using Content-Range, each piece continuing where the last ended
+    {
+        let media = media.clone();
+        server.fn_handler::<anyhow::Error, _>("/pic/*", Method::Post, move |mut req| {
+            let result = save_picture(&mut req, &media);
+            let status = match &result {
+                Ok(saved) if !saved.complete => 202,
+                _ => 201,
+            };
+            request::respond(req, status, result)
+        })?;
+    }
 
-            let mut buf = vec![0; len];
-            req.read_exact(&mut buf)?;
-
-            let log_str = String::from_utf8_lossy(&buf);
-            let log_preview = if log_str.len() > 100 { &log_str[..100] } else { &log_str };
-            info!("POST /posts/create - Input: {}", log_preview);
-
-            if let Ok(data) = serde_json::from_slice::<model::Post>(&buf) {
-                // TODO: Implement posts storage logic
-                // For now, just acknowledge the request
-                let db = fbdb.lock().unwrap();
-                db.write_post(&data)?;
-                write!(req.into_ok_response()?, "Users saved.")?;
-            } else {
-                req.into_status_response(400)?.write_all("JSON error".as_bytes())?;
-            }
+    // GET /uploads/<sha256> - How much of a picture has been received, to resume its upload
+    {
+        let media = media.clone();
+        server.fn_handler::<anyhow::Error, _>("/uploads/*", Method::Get, move |req| {
+            let result = upload_status(&req, &media);
+            request::respond(req, 200, result)
+        })?;
+    }
 
-            Ok(())
+    // POST /media/compare - Compare which pictures the client and the totem have
+    {
+        let media = media.clone();
+        server.fn_handler::<anyhow::Error, _>("/media/compare", Method::Post, move |mut req| {
+            let result = compare_media(&mut req, &media);
+            request::respond(req, 200, result)
         })?;
     }
 
-    // POST /pic/<filename> - Save picture to SD card with streaming
-    server.fn_handler::<anyhow::Error, _>("/pic/*", Method::Post, |mut req| {
-        // Extract filename from URI path (e.g., /pic/image.jpg -> image.jpg)
-        let uri = req.uri();
-        info!("POST /pic/* - URI: {}, Content-Length: {}", uri, req.content_len().unwrap_or(0));
-        let filename = uri.strip_prefix("/pic/").unwrap_or("unknown.webp");
-
-        // Sanitize filename to prevent path traversal
-        let filename = filename.replace("..", "").replace("/", "");
-        let filepath = format!("/sd/pic_{}", filename);
-        log::info!("filename: {filename}, filepath: {filepath}");
-        log::info!("readdir /sd: {:?}, readdir /sd/pics: {:?}", std::fs::read_dir("/sd"), std::fs::read_dir("/sd/pics"));
-
-        // Ensure pics directory exists
-        if let Err(_) = fs::create_dir_all("/sd/pics") {
-            req.into_status_response(500)?
-                .write_all("Failed to create pics directory".as_bytes())?;
-            return Ok(());
+    // GET /pic/<sha256> - Send picture from SD card with streaming, supports Range
+    server.fn_handler::<anyhow::Error, _>("/pic/*", Method::Get, move |req| {
+        info!("GET /pic/* - URI: {}", req.uri());
+
+        let opened = picture_hash(&req).and_then(|hash| media.open(&hash).map_err(media_error));
+        match opened {
+            Ok((file, info)) => send_picture(req, file, info),
+            Err(e) => request::send_error(req, &e),
         }
+    })?;
 
-        let len = req.content_len().unwrap_or(0) as usize;
+    // GET /users/<userId> - Get user by ID
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>("/users/*", Method::Get, move |req| {
+            let result = get_user(&req, &fbdb);
+            request::respond(req, 200, result)
+        })?;
+    }
 
-        fs::remove_file(&filepath).ok();  // ignore result. it's ok if the file didn't exist in the first place
+    // GET /posts/<postId> - Get post by ID
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>("/posts/*", Method::Get, move |req| {
+            let result = get_post(&req, &fbdb);
+            request::respond(req, 200, result)
+        })?;
+    }
 
-        let f = File::open(&filepath);
-        match f {
-            Ok(f) => {
-                ()
-            }
-            Err(_) => {
-                info!("file not found");
-            }
-        }
+    // GET /trending - Most used hashtags and most active threads of the last day
+    {
+        let trending = Arc::clone(&trending);
+        let totem_id = config.totem_id.clone();
+        server.fn_handler::<anyhow::Error, _>("/trending", Method::Get, move |req| {
+            let result = get_trending(&trending, &totem_id);
+            request::respond(req, 200, result)
+        })?;
+    }
 
+    // GET /replies/<replyId> - Get reply by ID
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>("/replies/*", Method::Get, move |req| {
+            let result = get_reply(&req, &fbdb);
+            request::respond(req, 200, result)
+        })?;
+    }
 
-        // Stream the file in chunks to avoid loading entire file into RAM
-        match OpenOptions::new().create(true).write(true).truncate(true).open(&filepath)  {
-            Ok(file) => {
-                let mut writer = BufWriter::new(file);
-                let mut total_written = 0;
-                let chunk_size = 1024; // 1KB chunks
-                let mut buf = vec![0u8; chunk_size];
-
-                loop {
-                    let to_read = std::cmp::min(chunk_size, len - total_written);
-                    if to_read == 0 {
-                        break;
-                    }
-
-                    match req.read(&mut buf[..to_read]) {
-                        Ok(n) if n > 0 => {
-                            writer.write_all(&buf[..n])?;
-                            total_written += n;
-                        }
-                        Ok(_) => break, // EOF
-                        Err(e) => {
-                            info!("Error reading request: {:?}", e);
-                            break;
-                        }
-                    }
-                }
-
-                let mut resp = req.into_ok_response()?;
-                write!(resp, "Saved {} bytes to {}", total_written, filepath)?;
-            }
-            Err(e) => {
-                let mut resp = req.into_status_response(500)?;
-                write!(resp, "Failed to create file: {:?}", e)?;
-            }
-        }
+    // GET /reposts/<repostId> - Get repost by ID
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>("/reposts/*", Method::Get, move |req| {
+            let result = get_repost(&req, &fbdb);
+            request::respond(req, 200, result)
+        })?;
+    }
 
+    // GET /tombstones/<tombstoneId> - Get tombstone by ID
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>("/tombstones/*", Method::Get, move |req| {
+            let result = get_tombstone(&req, &fbdb);
+            request::respond(req, 200, result)
+        })?;
+    }
+
+    // GET /rotations/<rotationId> - Get key rotation by ID
+    {
+        let fbdb = Arc::clone(&fbdb);
+        server.fn_handler::<anyhow::Error, _>("/rotations/*", Method::Get, move |req| {
+            let result = get_rotation(&req, &fbdb);
+            request::respond(req, 200, result)
+        })?;
+    }
+
+    // POST /users/last_seen - Exchange when users were last seen at which totem
+    {
+        let fbdb = Arc::clone(&fbdb);
+        let totem_id = config.totem_id.clone();
+        server.fn_handler::<anyhow::Error, _>(
+            "/users/last_seen",
+            Method::Post,
+            move |mut req| {
+                let result = users_last_seen(&mut req, &fbdb, &totem_id);
+                request::respond(req, 200, result)
+            },
+        )?;
+    }
+
+    // GET /is_totem - Simple endpoint to identify this device as a totem
+    server.fn_handler::<anyhow::Error, _>("/is_totem", Method::Get, |req| {
+        req.into_ok_response()?.write_all(b"OK")?;
         Ok(())
     })?;
 
-    // GET /pic/<filename> - Send picture from SD card with streaming
-    server.fn_handler::<anyhow::Error, _>("/pic/*", Method::Get, |req| {
-        // Extract filename from URI path
-        let uri = req.uri();
-        info!("GET /pic/* - URI: {}", uri);
-        let filename = uri.strip_prefix("/pic/").unwrap_or("");
-
-        if filename.is_empty() {
-            req.into_status_response(400)?
-                .write_all("Filename required".as_bytes())?;
-            return Ok(());
+    // Keep wifi and the server running beyond when main() returns (forever)
+    // Do not call this if you ever want to stop or access them later.
+    // core::mem::forget(wifi);
+    // core::mem::forget(server);
+
+    Ok((wifi, server))
+}
+
+type HttpRequest<'r, 'c> = Request<&'r mut EspHttpConnection<'c>>;
+
+/// Log the start of a request body
+fn log_input(route: &str, buf: &[u8]) {
+    let log_str = String::from_utf8_lossy(buf);
+    let log_preview: String = log_str.chars().take(100).collect();
+    info!("{} - Input: {}", route, log_preview);
+}
+
+/// Parse a timestamp sent by the client
+fn parse_time(field: &str, value: &str) -> Result<chrono::DateTime<chrono::Utc>, ApiError> {
+    validation::parse_timestamp(value).map_err(|reason| {
+        ApiError::new(ErrorCode::ValidationFailed, "Invalid timestamp")
+            .with_detail(Some(field), reason)
+    })
+}
+
+/// Look up the author of a record, who has to be known to this totem
+fn check_author(db: &FileBasedDB, user_id: &str) -> Result<model::User, ApiError> {
+    let known = db
+        .read_users_match(1, |u| u.uuid == user_id)
+        .map_err(|e| request::internal("Failed to read users", e))?;
+
+    known.into_iter().next().ok_or_else(|| {
+        ApiError::new(ErrorCode::ValidationFailed, "Invalid record")
+            .with_detail(Some("user_id"), "unknown author, upload the user first")
+    })
+}
+
+/// The key rotations of a user, to check their records against the right key
+fn user_rotations(db: &FileBasedDB, user_id: &str) -> Result<Vec<model::KeyRotation>, ApiError> {
+    db.read_key_rotations_match(usize::MAX, |r| r.user_id == user_id)
+        .map_err(|e| request::internal("Failed to read key rotations", e))
+}
+
+/// POST /posts/compare
+fn compare_posts(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /posts/compare", &buf);
+
+    let data: PostsCompareRequest = request::parse_json(&buf)?;
+    let time_start = parse_time("time_start", &data.time_start)?;
+    let time_end = parse_time("time_end", &data.time_end)?;
+
+    let res = {
+        let mut db = fbdb.lock().unwrap();
+
+        firmware::data_exchange::exchange_posts(
+            &time_start,
+            &time_end,
+            data.post_uuids,
+            &data.versions,
+            &mut db,
+        )
+        .map_err(|e| request::internal("Failed to compare posts", e))?
+    };
+
+    let res = CompareResponse {
+        totem_missing: res.0,
+        client_missing: res.1,
+    };
+
+    info!("{:?}", res);
+
+    Ok(res)
+}
+
+/// POST /users/compare
+fn compare_users(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /users/compare", &buf);
+
+    let data: UsersCompareRequest = request::parse_json(&buf)?;
+
+    let res = {
+        let mut db = fbdb.lock().unwrap();
+
+        firmware::data_exchange::exchange_users(data.user_uuids, &mut db)
+            .map_err(|e| request::internal("Failed to compare users", e))?
+    };
+
+    Ok(CompareResponse {
+        totem_missing: res.0,
+        client_missing: res.1,
+    })
+}
+
+/// POST /users/create
+fn create_user(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CreatedResponse, ApiError> {
+    // Allow larger payload for user with bio and profile picture
+    let buf = request::read_body(req, MAX_LEN * 2)?;
+    log_input("POST /users/create", &buf);
+
+    let data: UserRequest = request::parse_json(&buf)?;
+    let last_contact = match &data.last_contact {
+        Some(value) => parse_time("last_contact", value)?,
+        None => chrono::Utc::now(),
+    };
+
+    // Create a new user using the complete User model
+    let new_user = shared::model::User {
+        uuid: data.uuid,
+        username: data.username,
+        status: data.status,
+        bio: data.bio,
+        profile_picture: data.profile_picture,
+        last_contact,
+        profile_picture_preview: data.profile_picture_preview,
+        public_key: data.public_key,
+        signature: data.signature,
+    };
+    new_user.validate()?;
+
+    let db = fbdb.lock().unwrap();
+    // Users with a key must have signed their profile with the key they use now
+    new_user.check_signature(&user_rotations(&db, &new_user.uuid)?)?;
+    db.write_user(&new_user)
+        .map_err(|e| request::internal("Failed to create user", e))?;
+
+    Ok(CreatedResponse {
+        uuid: new_user.uuid,
+    })
+}
+
+/// POST /posts/create
+fn create_post(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+    media: &MediaStore,
+    trending: &Mutex<Trending>,
+) -> Result<CreatedResponse, ApiError> {
+    // Allow larger payload for multiple posts
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /posts/create", &buf);
+
+    let post: model::Post = request::parse_json(&buf)?;
+    post.validate()?;
+
+    let db = fbdb.lock().unwrap();
+    store_post(&db, media, trending, &post)?;
+
+    Ok(CreatedResponse { uuid: post.uuid })
+}
+
+/// Store a validated post after checking it against its author and the uploaded media
+/// Edits must keep what the stored versions say, versions stored already are skipped.
+/// Its tags are counted for /trending.
+fn store_post(
+    db: &FileBasedDB,
+    media: &MediaStore,
+    trending: &Mutex<Trending>,
+    post: &model::Post,
+) -> Result<(), ApiError> {
+    let stored = db
+        .read_posts_match(usize::MAX, |p| p.uuid == post.uuid)
+        .map_err(|e| request::internal("Failed to read posts", e))?;
+    if stored.iter().any(|p| p.same_version(post)) {
+        return Ok(());
+    }
+    if let Some(first) = stored.first() {
+        post.check_edit_of(first)?;
+    }
+    let tombstones = db
+        .read_tombstones(chrono::Utc::now())
+        .map_err(|e| request::internal("Failed to read tombstones", e))?;
+    if tombstones.iter().any(|t| t.buries(post)) {
+        return Err(ApiError::new(
+            ErrorCode::Conflict,
+            "Post was deleted by its author",
+        ));
+    }
+
+    post.check_attachment_limits(&ATTACHMENT_LIMITS)?;
+    check_attachment_sizes(post, media)?;
+
+    let author = check_author(db, &post.user_id)?;
+    // Posts of users with a key must be signed by it, anything else was forged on the way.
+    // Edits need the signature in any case, users without a key can't edit.
+    post.check_signature(Some(&author), &user_rotations(db, &author.uuid)?)?;
+    db.write_post(post)
+        .map_err(|e| request::internal("Failed to save post", e))?;
+    trending.lock().unwrap().add_post(post, chrono::Utc::now());
+    Ok(())
+}
+
+/// Count the posts and replies received within the trending window before the last start
+/// Their timestamps stand in for when they were received, which isn't stored.
+fn load_trending(db: &FileBasedDB) -> anyhow::Result<Trending> {
+    let now = chrono::Utc::now();
+    let recent = |timestamp: &chrono::DateTime<chrono::Utc>| now - TRENDING_WINDOW < *timestamp;
+
+    let mut trending = Trending::new();
+    for post in db.read_posts_match(usize::MAX, |p| recent(&p.timestamp))? {
+        trending.add_post(&post, post.timestamp.min(now));
+    }
+    for reply in db.read_replies_match(usize::MAX, |r| recent(&r.timestamp))? {
+        trending.add_reply(&reply, reply.timestamp.min(now));
+    }
+    Ok(trending)
+}
+
+/// POST /replies/compare
+fn compare_replies(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<RepliesCompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /replies/compare", &buf);
+
+    let data: RepliesCompareRequest = request::parse_json(&buf)?;
+    let time_start = parse_time("time_start", &data.time_start)?;
+    let time_end = parse_time("time_end", &data.time_end)?;
+
+    let res = {
+        let mut db = fbdb.lock().unwrap();
+
+        firmware::data_exchange::exchange_replies(&time_start, &time_end, data.reply_uuids, &mut db)
+            .map_err(|e| request::internal("Failed to compare replies", e))?
+    };
+
+    let res = RepliesCompareResponse {
+        totem_missing: res.0,
+        client_missing: res.1,
+        unresolved: res.2,
+    };
+
+    info!("{:?}", res);
+
+    Ok(res)
+}
+
+/// POST /replies/create
+/// The post and the reply it answers may arrive later, see `shared::model::reply`.
+fn create_reply(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+    trending: &Mutex<Trending>,
+) -> Result<CreatedResponse, ApiError> {
+    let buf = request::read_body(req, MAX_LEN * 2)?;
+    log_input("POST /replies/create", &buf);
+
+    let reply: model::Reply = request::parse_json(&buf)?;
+    reply.validate()?;
+
+    let db = fbdb.lock().unwrap();
+    let known = db
+        .read_replies_match(1, |r| r.uuid == reply.uuid)
+        .map_err(|e| request::internal("Failed to read replies", e))?;
+    if !known.is_empty() {
+        return Ok(CreatedResponse { uuid: reply.uuid });
+    }
+
+    let author = check_author(&db, &reply.user_id)?;
+    reply.check_signature(Some(&author), &user_rotations(&db, &author.uuid)?)?;
+    db.write_replies([&reply])
+        .map_err(|e| request::internal("Failed to save reply", e))?;
+    trending
+        .lock()
+        .unwrap()
+        .add_reply(&reply, chrono::Utc::now());
+
+    Ok(CreatedResponse { uuid: reply.uuid })
+}
+
+/// POST /reposts/compare
+fn compare_reposts(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<RepostsCompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /reposts/compare", &buf);
+
+    let data: RepostsCompareRequest = request::parse_json(&buf)?;
+    let time_start = parse_time("time_start", &data.time_start)?;
+    let time_end = parse_time("time_end", &data.time_end)?;
+
+    let res = {
+        let mut db = fbdb.lock().unwrap();
+
+        firmware::data_exchange::exchange_reposts(
+            &time_start,
+            &time_end,
+            data.repost_uuids,
+            &mut db,
+        )
+        .map_err(|e| request::internal("Failed to compare reposts", e))?
+    };
+
+    let res = RepostsCompareResponse {
+        totem_missing: res.0,
+        client_missing: res.1,
+        unresolved: res.2,
+    };
+
+    info!("{:?}", res);
+
+    Ok(res)
+}
+
+/// POST /reposts/create
+/// The reposted post may arrive later, it is asked for in /reposts/compare until then.
+fn create_repost(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CreatedResponse, ApiError> {
+    let buf = request::read_body(req, MAX_LEN)?;
+    log_input("POST /reposts/create", &buf);
+
+    let repost: model::Repost = request::parse_json(&buf)?;
+    repost.validate()?;
+
+    let db = fbdb.lock().unwrap();
+    let known = db
+        .read_reposts_match(1, |r| r.uuid == repost.uuid)
+        .map_err(|e| request::internal("Failed to read reposts", e))?;
+    if !known.is_empty() {
+        return Ok(CreatedResponse { uuid: repost.uuid });
+    }
+
+    let user = check_author(&db, &repost.user_id)?;
+    repost.check_signature(Some(&user), &user_rotations(&db, &user.uuid)?)?;
+    let original = db
+        .read_posts_match(1, |p| p.uuid == repost.post_id)
+        .map_err(|e| request::internal("Failed to read posts", e))?;
+    if let Some(original) = original.first() {
+        repost.check_original(original)?;
+    }
+    db.write_reposts([&repost])
+        .map_err(|e| request::internal("Failed to save repost", e))?;
+
+    Ok(CreatedResponse { uuid: repost.uuid })
+}
+
+/// POST /tombstones/compare
+fn compare_tombstones(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /tombstones/compare", &buf);
+
+    let data: TombstonesCompareRequest = request::parse_json(&buf)?;
+
+    let res = {
+        let mut db = fbdb.lock().unwrap();
+
+        firmware::data_exchange::exchange_tombstones(
+            data.tombstone_uuids,
+            chrono::Utc::now(),
+            &mut db,
+        )
+        .map_err(|e| request::internal("Failed to compare tombstones", e))?
+    };
+
+    Ok(CompareResponse {
+        totem_missing: res.0,
+        client_missing: res.1,
+    })
+}
+
+/// POST /tombstones/create
+/// The post may arrive later, it is refused from then on. Its blobs are removed unless
+/// another post or a profile uses them.
+fn create_tombstone(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+    media: &MediaStore,
+) -> Result<CreatedResponse, ApiError> {
+    let buf = request::read_body(req, MAX_LEN)?;
+    log_input("POST /tombstones/create", &buf);
+
+    let tombstone: model::Tombstone = request::parse_json(&buf)?;
+    tombstone.validate()?;
+
+    let db = fbdb.lock().unwrap();
+    let author = check_author(&db, &tombstone.user_id)?;
+    // Deleting is for authors only, so unlike other records tombstones must be signed
+    tombstone.check_signature(Some(&author), &user_rotations(&db, &author.uuid)?)?;
+    let (_, buried) = db
+        .merge_tombstones([tombstone.clone()], chrono::Utc::now())
+        .map_err(|e| request::internal("Failed to save tombstone", e))?;
+    remove_unused_media(&db, media, &buried)?;
+
+    Ok(CreatedResponse {
+        uuid: tombstone.uuid,
+    })
+}
+
+/// Remove the blobs of deleted posts, unless another post or a profile uses them
+fn remove_unused_media(
+    db: &FileBasedDB,
+    media: &MediaStore,
+    buried: &[model::Post],
+) -> Result<(), ApiError> {
+    let mut hashes: Vec<String> = Vec::new();
+    for hash in buried.iter().flat_map(|post| post.media_hashes()) {
+        if !hashes.iter().any(|h| h == hash) {
+            hashes.push(hash.to_string());
+        }
+    }
+    if hashes.is_empty() {
+        return Ok(());
+    }
+
+    let uses = |hash: &str| hashes.iter().any(|h| h == hash);
+    let used: Vec<String> = db
+        .read_posts_filter_map(
+            usize::MAX,
+            |p| p.media_hashes().into_iter().any(uses),
+            |p| {
+                p.media_hashes()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            },
+        )
+        .map_err(|e| request::internal("Failed to read posts", e))?
+        .concat();
+    let pictures: Vec<String> = db
+        .read_users_filter_map(
+            usize::MAX,
+            |u| u.profile_picture.as_deref().is_some_and(uses),
+            |u| u.profile_picture.unwrap_or_default(),
+        )
+        .map_err(|e| request::internal("Failed to read users", e))?;
+
+    for hash in hashes
+        .iter()
+        .filter(|h| !used.contains(h) && !pictures.contains(h))
+    {
+        let Ok(hash) = hash.parse::<ContentHash>() else {
+            continue;
+        };
+        match media.remove(&hash) {
+            Ok(()) | Err(MediaError::NotFound) => {}
+            Err(e) => return Err(media_error(e)),
         }
+    }
+
+    Ok(())
+}
+
+/// POST /reactions/compare
+fn compare_reactions(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /reactions/compare", &buf);
+
+    let data: ReactionsCompareRequest = request::parse_json(&buf)?;
+    let time_start = parse_time("time_start", &data.time_start)?;
+    let time_end = parse_time("time_end", &data.time_end)?;
+
+    let res = {
+        let mut db = fbdb.lock().unwrap();
+
+        firmware::data_exchange::exchange_reactions(
+            &time_start,
+            &time_end,
+            data.reaction_uuids,
+            &mut db,
+        )
+        .map_err(|e| request::internal("Failed to compare reactions", e))?
+    };
+
+    Ok(CompareResponse {
+        totem_missing: res.0,
+        client_missing: res.1,
+    })
+}
+
+/// POST /reactions/fetch
+fn fetch_reactions(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<ReactionsBatch, ApiError> {
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /reactions/fetch", &buf);
+
+    let data: ReactionsFetchRequest = request::parse_json(&buf)?;
+
+    let set = {
+        let db = fbdb.lock().unwrap();
+        db.read_reactions()
+            .map_err(|e| request::internal("Failed to read reactions", e))?
+    };
+
+    let reactions = set
+        .iter()
+        .filter(|r| data.reaction_uuids.contains(&r.uuid))
+        .take(MAX_REACTIONS_PER_REQUEST)
+        .cloned()
+        .collect();
 
-        // Sanitize filename to prevent path traversal
-        let filename = filename.replace("..", "").replace("/", "");
-        let filepath = format!("/sd/pics/{}", filename);
-
-        // Stream the file in chunks to avoid loading entire file into RAM
-        match File::open(&filepath) {
-            Ok(file) => {
-                let metadata = file.metadata()?;
-                let file_size = metadata.len();
-
-                let mut reader = BufReader::new(file);
-                let mut resp = req.into_ok_response()?;
-
-                let chunk_size = 1024; // 1KB chunks
-                let mut buf = vec![0u8; chunk_size];
-                let mut total_sent = 0;
-
-                loop {
-                    match reader.read(&mut buf) {
-                        Ok(n) if n > 0 => {
-                            resp.write_all(&buf[..n])?;
-                            total_sent += n;
-                        }
-                        Ok(_) => break, // EOF
-                        Err(e) => {
-                            info!("Error reading file: {:?}", e);
-                            break;
-                        }
-                    }
-                }
-
-                info!("Sent {} bytes of {}", total_sent, filepath);
+    Ok(ReactionsBatch { reactions })
+}
+
+/// POST /reactions/create
+fn create_reactions(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<ReactionsCreateResponse, ApiError> {
+    let buf = request::read_body(req, MAX_LEN * 64)?;
+    log_input("POST /reactions/create", &buf);
+
+    let data: ReactionsBatch = request::parse_json(&buf)?;
+    if data.reactions.len() > MAX_REACTIONS_PER_REQUEST {
+        return Err(ApiError::new(
+            ErrorCode::PayloadTooLarge,
+            "Too many reactions",
+        ));
+    }
+    let now = chrono::Utc::now();
+    let mut response = ReactionsCreateResponse::default();
+    let mut valid = Vec::new();
+
+    let db = fbdb.lock().unwrap();
+    for reaction in data.reactions {
+        // Only reactions of known users count, like their posts
+        let checked = reaction
+            .validate_at(now)
+            .map_err(ApiError::from)
+            .and_then(|_| check_author(&db, &reaction.user_id))
+            .and_then(|author| {
+                let rotations = user_rotations(&db, &author.uuid)?;
+                reaction
+                    .check_signature(Some(&author), &rotations)
+                    .map_err(ApiError::from)
+            });
+
+        match checked {
+            Ok(()) => {
+                response.accepted.push(reaction.uuid.clone());
+                valid.push(reaction);
             }
-            Err(_) => {
-                req.into_status_response(404)?
-                    .write_all("File not found".as_bytes())?;
+            Err(e) => {
+                info!("Rejected reaction {}: {}", reaction.uuid, e);
+                response.rejected.push(reaction.uuid);
             }
         }
+    }
 
-        Ok(())
-    })?;
+    db.merge_reactions(valid)
+        .map_err(|e| request::internal("Failed to save reactions", e))?;
 
-    // GET /users/<userId> - Get user by ID
-    {
-        let mut fbdb = Arc::clone(&fbdb);
-        server.fn_handler::<anyhow::Error, _>("/users/*", Method::Get, move |req| {
-            // Extract userId from URI path
-            let uri = req.uri();
-            info!("GET /users/* - URI: {}", uri);
-            let user_id = uri.strip_prefix("/users/").unwrap_or("");
-
-            if user_id.is_empty() {
-                req.into_status_response(400)?
-                    .write_all("User ID required".as_bytes())?;
-                return Ok(());
-            }
+    Ok(response)
+}
 
-            let result = {
-                let mut db = fbdb.lock().unwrap();
+/// POST /rotations/compare
+fn compare_rotations(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /rotations/compare", &buf);
 
-                // Read user by UUID using the filter_map method
-                db.read_users_match(1, |u| u.uuid == user_id)
-            };
+    let data: RotationsCompareRequest = request::parse_json(&buf)?;
+
+    let res = {
+        let mut db = fbdb.lock().unwrap();
 
-            match result {
-                Ok(users) => {
-                    if let Some(user) = users.into_iter().next() {
-                        // Return the complete User model as JSON
-                        let mut resp = req.into_ok_response()?;
-                        resp.write_all(serde_json::to_vec(&user)?.as_slice())?;
-                    } else {
-                        req.into_status_response(404)?
-                            .write_all("User not found".as_bytes())?;
-                    }
-                }
-                Err(e) => {
-                    info!("Error reading user: {:?}", e);
-                    let mut resp = req.into_status_response(500)?;
-                    write!(resp, "Failed to read user: {:?}", e)?;
-                }
+        firmware::data_exchange::exchange_rotations(data.rotation_uuids, &mut db)
+            .map_err(|e| request::internal("Failed to compare key rotations", e))?
+    };
+
+    Ok(CompareResponse {
+        totem_missing: res.0,
+        client_missing: res.1,
+    })
+}
+
+/// POST /rotations/create
+fn create_rotation(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<CreatedResponse, ApiError> {
+    let buf = request::read_body(req, MAX_LEN * 2)?;
+    log_input("POST /rotations/create", &buf);
+
+    let rotation: model::KeyRotation = request::parse_json(&buf)?;
+    rotation.validate()?;
+
+    let db = fbdb.lock().unwrap();
+    let rotations = user_rotations(&db, &rotation.user_id)?;
+    if rotations.iter().any(|r| r.uuid == rotation.uuid) {
+        return Ok(CreatedResponse {
+            uuid: rotation.uuid,
+        });
+    }
+
+    // Only rotations signed by the key they replace, or the recovery key, are passed on
+    // The user may not be known yet, their profile could be signed by the new key already.
+    rotation.check_chain(&rotations)?;
+    db.write_key_rotation(&rotation)
+        .map_err(|e| request::internal("Failed to save key rotation", e))?;
+
+    Ok(CreatedResponse {
+        uuid: rotation.uuid,
+    })
+}
+
+/// Attachments uploaded before their post must have the size the post claims
+/// Otherwise a large blob could hide behind the limit of a smaller kind.
+fn check_attachment_sizes(post: &model::Post, media: &MediaStore) -> Result<(), ApiError> {
+    for attachment in &post.attachments {
+        let Ok(hash) = attachment.hash.parse::<ContentHash>() else {
+            continue;
+        };
+        match media.info(&hash) {
+            Ok(info) if info.size != attachment.size => {
+                return Err(
+                    ApiError::new(ErrorCode::ValidationFailed, "Attachment size mismatch")
+                        .with_detail(
+                            Some("attachments"),
+                            format!("{} has {} bytes", hash, info.size),
+                        ),
+                );
             }
+            Ok(_) | Err(MediaError::NotFound) => {}
+            Err(e) => return Err(media_error(e)),
+        }
+    }
 
-            Ok(())
-        })?;
+    Ok(())
+}
+
+/// Hash of the picture addressed by a /pic/<sha256> request
+fn picture_hash(req: &HttpRequest) -> Result<ContentHash, ApiError> {
+    let name = req.uri().strip_prefix("/pic/").unwrap_or("");
+
+    if name.is_empty() {
+        return Err(ApiError::bad_request("Picture hash required"));
     }
 
-    // GET /posts/<postId> - Get post by ID
+    name.parse::<ContentHash>().map_err(ApiError::from)
+}
+
+/// POST /pic/<sha256>
+fn save_picture(req: &mut HttpRequest, media: &MediaStore) -> Result<PicSaved, ApiError> {
+    info!(
+        "POST /pic/* - URI: {}, Content-Length: {:?}, Transfer-Encoding: {:?}, Content-Range: {:?}",
+        req.uri(),
+        req.content_len(),
+        req.header("Transfer-Encoding"),
+        req.header("Content-Range")
+    );
+    let hash = picture_hash(req)?;
+
+    // Without a Content-Range, the body is the whole picture
+    let range = match req.header("Content-Range") {
+        Some(value) => Some(
+            ContentRange::parse(value)
+                .ok_or_else(|| ApiError::bad_request("Invalid Content-Range"))?,
+        ),
+        None => None,
+    };
+    let max_len = ATTACHMENT_LIMITS.largest();
+    if range.is_some_and(|range| range.total > max_len) {
+        return Err(BodyError::TooLarge.into());
+    }
+    let offset = range.map_or(0, |range| range.range.start);
+    let limit = range.map_or(max_len, |range| range.range.len()) as usize;
+
+    // Check the length before creating the upload, so bad requests are rejected early
+    let mut body = request::body(req, limit).map_err(request::log_body_error)?;
+
+    let mut upload = media.resume_upload(hash, offset).map_err(media_error)?;
+    let mut buf = vec![0u8; 4096];
+
+    if let Err(e) = body.copy_to(&mut upload, &mut buf) {
+        // Keep what arrived, so the client can resume from there
+        let offset = upload.suspend().map_err(media_error)?;
+        info!("Upload of {} interrupted at {}", hash, offset);
+        return Err(request::log_body_error(e));
+    }
+
+    if let Some(range) = range {
+        if upload.size() != range.range.end + 1 {
+            let offset = upload.suspend().map_err(media_error)?;
+            return Err(ApiError::bad_request("Body doesn't match Content-Range")
+                .with_detail(Some("offset"), offset.to_string()));
+        }
+
+        if !range.is_last() {
+            let offset = upload.suspend().map_err(media_error)?;
+            return Ok(PicSaved {
+                name: hash.to_string(),
+                size: offset,
+                complete: false,
+            });
+        }
+    }
+
+    let info = upload.finish().map_err(media_error)?;
+    info!(
+        "Saved {} ({}, {} bytes)",
+        info.hash,
+        info.media_type.mime(),
+        info.size
+    );
+
+    Ok(PicSaved {
+        name: info.hash.to_string(),
+        size: info.size,
+        complete: true,
+    })
+}
+
+/// POST /media/compare
+fn compare_media(
+    req: &mut HttpRequest,
+    media: &MediaStore,
+) -> Result<MediaCompareResponse, ApiError> {
+    // bigger MAX_LEN for multiple hashes
+    let buf = request::read_body(req, MAX_LEN * 20)?;
+    log_input("POST /media/compare", &buf);
+
+    let data: MediaCompareRequest = request::parse_json(&buf)?;
+
+    // Malformed hashes are neither uploaded nor downloaded
+    let stored = |name: &String| {
+        name.parse::<ContentHash>()
+            .map(|hash| media.contains(&hash))
+    };
+
+    Ok(MediaCompareResponse {
+        totem_missing: data
+            .hashes
+            .into_iter()
+            .filter(|name| matches!(stored(name), Ok(false)))
+            .collect(),
+        client_missing: data
+            .wanted
+            .into_iter()
+            .filter(|name| matches!(stored(name), Ok(true)))
+            .collect(),
+    })
+}
+
+/// GET /uploads/<sha256>
+fn upload_status(req: &HttpRequest, media: &MediaStore) -> Result<UploadStatus, ApiError> {
+    let name = req.uri().strip_prefix("/uploads/").unwrap_or("");
+    let hash = name.parse::<ContentHash>().map_err(ApiError::from)?;
+
+    let state = media.upload_state(&hash).map_err(media_error)?;
+
+    Ok(UploadStatus {
+        name: hash.to_string(),
+        offset: state.offset,
+        complete: state.complete,
+    })
+}
+
+/// Answer GET /pic/<sha256> with the picture, or the part of it asked for with Range
+fn send_picture(req: HttpRequest, mut file: File, info: MediaInfo) -> anyhow::Result<()> {
+    let etag = info.hash.etag();
+    // The content of a hash never changes
+    let cache_control = "public, max-age=31536000, immutable";
+
+    if req
+        .header("If-None-Match")
+        .is_some_and(|tags| info.hash.matches_etag(tags))
     {
-        let mut fbdb = Arc::clone(&fbdb);
-        server.fn_handler::<anyhow::Error, _>("/posts/*", Method::Get, move |req| {
-            // Extract postId from URI path
-            let uri = req.uri();
-            info!("GET /posts/* - URI: {}", uri);
-            let post_id = uri.strip_prefix("/posts/").unwrap_or("");
-
-            if post_id.is_empty() {
-                req.into_status_response(400)?
-                    .write_all("Post ID required".as_bytes())?;
-                return Ok(());
-            }
+        req.into_response(
+            304,
+            None,
+            &[("ETag", &etag), ("Cache-Control", cache_control)],
+        )?;
+        return Ok(());
+    }
 
-            let result = {
-                let mut db = fbdb.lock().unwrap();
+    // A Range only applies to the content the client has part of, named by If-Range
+    let range = match req.header("Range") {
+        Some(value)
+            if req
+                .header("If-Range")
+                .is_none_or(|tag| info.hash.matches_etag(tag)) =>
+        {
+            ByteRange::from_range_header(value, info.size)
+        }
+        _ => Ok(None),
+    };
+    let range = match range {
+        Ok(range) => range,
+        Err(e) => {
+            let content_range = e.content_range(info.size);
+            let err = ApiError::from(e);
+            info!("{} failed: {}", req.uri(), err);
+            return request::send_json_with_headers(
+                req,
+                err.status(),
+                &err,
+                &[("Content-Range", &content_range)],
+            );
+        }
+    };
 
-                // Read post by UUID using the filter_map method
-                db.read_posts_match(1, |p| p.uuid == post_id)
-            };
+    let (status, start, len) = match range {
+        Some(range) => (206, range.start, range.len()),
+        None => (200, 0, info.size),
+    };
+    let content_length = len.to_string();
+    let content_range = range.map(|range| range.content_range(info.size));
+
+    let mut headers = vec![
+        ("Content-Type", info.media_type.mime()),
+        ("Content-Length", content_length.as_str()),
+        ("Accept-Ranges", "bytes"),
+        ("ETag", etag.as_str()),
+        ("Cache-Control", cache_control),
+    ];
+    if let Some(content_range) = &content_range {
+        headers.push(("Content-Range", content_range.as_str()));
+    }
+
+    file.seek(SeekFrom::Start(start))?;
+    let mut resp = req.into_response(status, None, &headers)?;
 
-            match result {
-                Ok(posts) => {
-                    if let Some(post) = posts.into_iter().next() {
-                        // Return the complete Post model as JSON
-                        let mut resp = req.into_ok_response()?;
-                        resp.write_all(serde_json::to_vec(&post)?.as_slice())?;
-                    } else {
-                        req.into_status_response(404)?
-                            .write_all("Post not found".as_bytes())?;
-                    }
-                }
-                Err(e) => {
-                    info!("Error reading post: {:?}", e);
-                    let mut resp = req.into_status_response(500)?;
-                    write!(resp, "Failed to read post: {:?}", e)?;
-                }
+    // Stream the file in chunks to avoid loading entire file into RAM
+    let mut reader = file.take(len);
+    let mut buf = vec![0u8; 4096];
+    let mut total_sent = 0;
+
+    loop {
+        match reader.read(&mut buf) {
+            Ok(n) if n > 0 => {
+                resp.write_all(&buf[..n])?;
+                total_sent += n;
+            }
+            Ok(_) => break, // EOF
+            Err(e) => {
+                // The status line is already out, all we can do is cut the response short
+                info!("Error reading file: {:?}", e);
+                break;
             }
+        }
+    }
 
-            Ok(())
-        })?;
+    info!(
+        "Sent {} bytes of {} starting at {}",
+        total_sent, info.hash, start
+    );
+
+    Ok(())
+}
+
+/// Log storage errors of the media store before turning them into the error sent to the client
+fn media_error(e: MediaError) -> ApiError {
+    if let MediaError::Io(io) = &e {
+        warn!("Media store failed: {:?}", io);
+    }
+    ApiError::from(e)
+}
+
+/// GET /users/<userId>
+fn get_user(req: &HttpRequest, fbdb: &Mutex<FileBasedDB>) -> Result<model::User, ApiError> {
+    // Extract userId from URI path
+    let uri = req.uri();
+    info!("GET /users/* - URI: {}", uri);
+    let user_id = uri.strip_prefix("/users/").unwrap_or("");
+
+    if user_id.is_empty() {
+        return Err(ApiError::bad_request("User ID required"));
+    }
+
+    let users = {
+        let db = fbdb.lock().unwrap();
+
+        // Read user by UUID using the filter_map method
+        db.read_users_match(1, |u| u.uuid == user_id)
+            .map_err(|e| request::internal("Failed to read user", e))?
+    };
+
+    users
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::not_found("User not found"))
+}
+
+/// GET /posts/<postId>
+fn get_post(req: &HttpRequest, fbdb: &Mutex<FileBasedDB>) -> Result<model::Post, ApiError> {
+    // Extract postId from URI path
+    let uri = req.uri();
+    info!("GET /posts/* - URI: {}", uri);
+    let post_id = uri.strip_prefix("/posts/").unwrap_or("");
+
+    if post_id.is_empty() {
+        return Err(ApiError::bad_request("Post ID required"));
+    }
+
+    let post = {
+        let db = fbdb.lock().unwrap();
+
+        // Every version of an edited post is stored, the newest one is handed out
+        db.read_post(post_id)
+            .map_err(|e| request::internal("Failed to read post", e))?
+    };
+
+    post.ok_or_else(|| ApiError::not_found("Post not found"))
+}
+
+/// GET /trending
+fn get_trending(trending: &Mutex<Trending>, totem_id: &str) -> Result<TrendingResponse, ApiError> {
+    let res = TrendingResponse {
+        totem_id: totem_id.to_string(),
+        ..trending.lock().unwrap().top(chrono::Utc::now())
+    };
+    info!("{:?}", res);
+    Ok(res)
+}
+
+/// GET /replies/<replyId>
+fn get_reply(req: &HttpRequest, fbdb: &Mutex<FileBasedDB>) -> Result<model::Reply, ApiError> {
+    let uri = req.uri();
+    info!("GET /replies/* - URI: {}", uri);
+    let reply_id = uri.strip_prefix("/replies/").unwrap_or("");
+
+    if reply_id.is_empty() {
+        return Err(ApiError::bad_request("Reply ID required"));
+    }
+
+    let replies = {
+        let db = fbdb.lock().unwrap();
+        db.read_replies_match(1, |r| r.uuid == reply_id)
+            .map_err(|e| request::internal("Failed to read reply", e))?
+    };
+
+    replies
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::not_found("Reply not found"))
+}
+
+/// GET /reposts/<repostId>
+fn get_repost(req: &HttpRequest, fbdb: &Mutex<FileBasedDB>) -> Result<model::Repost, ApiError> {
+    let uri = req.uri();
+    info!("GET /reposts/* - URI: {}", uri);
+    let repost_id = uri.strip_prefix("/reposts/").unwrap_or("");
+
+    if repost_id.is_empty() {
+        return Err(ApiError::bad_request("Repost ID required"));
+    }
+
+    let reposts = {
+        let db = fbdb.lock().unwrap();
+        db.read_reposts_match(1, |r| r.uuid == repost_id)
+            .map_err(|e| request::internal("Failed to read repost", e))?
+    };
+
+    reposts
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::not_found("Repost not found"))
+}
+
+/// GET /tombstones/<tombstoneId>
+fn get_tombstone(
+    req: &HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<model::Tombstone, ApiError> {
+    let uri = req.uri();
+    info!("GET /tombstones/* - URI: {}", uri);
+    let tombstone_id = uri.strip_prefix("/tombstones/").unwrap_or("");
+
+    if tombstone_id.is_empty() {
+        return Err(ApiError::bad_request("Tombstone ID required"));
     }
 
-    // POST /users/last_seen - Update last seen timestamps for users
-    server.fn_handler::<anyhow::Error, _>("/users/last_seen", Method::Post, |mut req| {
-        let len = req.content_len().unwrap_or(0) as usize;
+    let tombstones = {
+        let db = fbdb.lock().unwrap();
+        db.read_tombstones(chrono::Utc::now())
+            .map_err(|e| request::internal("Failed to read tombstones", e))?
+    };
+
+    tombstones
+        .into_iter()
+        .find(|t| t.uuid == tombstone_id)
+        .ok_or_else(|| ApiError::not_found("Tombstone not found"))
+}
+
+/// GET /rotations/<rotationId>
+fn get_rotation(
+    req: &HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<model::KeyRotation, ApiError> {
+    let uri = req.uri();
+    info!("GET /rotations/* - URI: {}", uri);
+    let rotation_id = uri.strip_prefix("/rotations/").unwrap_or("");
+
+    if rotation_id.is_empty() {
+        return Err(ApiError::bad_request("Rotation ID required"));
+    }
+
+    let rotations = {
+        let db = fbdb.lock().unwrap();
+
+        db.read_key_rotations_match(1, |r| r.uuid == rotation_id)
+            .map_err(|e| request::internal("Failed to read key rotation", e))?
+    };
+
+    rotations
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::not_found("Key rotation not found"))
+}
+
+/// POST /users/last_seen
+/// Syncing with the totem is what being seen here means, the client's signed sighting is
+/// recorded if it names this totem and the current time. Relayed sightings that can't be
+/// checked against their user's key are left out.
+fn users_last_seen(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+    totem_id: &str,
+) -> Result<UsersLastSeenResponse, ApiError> {
+    // bigger MAX_LEN for multiple entries
+    let buf = request::read_body(req, MAX_LEN * 10)?;
+    log_input("POST /users/last_seen", &buf);
+
+    let mut data: UsersLastSeenRequest = request::parse_json(&buf)?;
+    let now = chrono::Utc::now();
+
+    for entry in data.entries.iter().chain(&data.sighting) {
+        entry.validate_at(now)?;
+    }
+
+    let table = {
+        let db = fbdb.lock().unwrap();
+
+        if let Some(sighting) = data.sighting.take() {
+            if sighting.totem_id != totem_id {
+                return Err(
+                    ApiError::new(ErrorCode::ValidationFailed, "Invalid request")
+                        .with_detail(Some("totem_id"), "is not this totem"),
+                );
+            }
+            if (sighting.seen_at - now).abs() > MAX_SIGHTING_SKEW {
+                return Err(
+                    ApiError::new(ErrorCode::ValidationFailed, "Invalid request")
+                        .with_detail(Some("seen_at"), "doesn't match the totem's clock"),
+                );
+            }
+            let user = check_author(&db, &sighting.user_id)?;
+            sighting.check_signature(Some(&user), &user_rotations(&db, &user.uuid)?)?;
+
+            data.user_uuids.push(sighting.user_id.clone());
+            data.entries.push(sighting);
+        }
 
-        if len > MAX_LEN {
-            req.into_status_response(413)?
-                .write_all("Request too big".as_bytes())?;
-            return Ok(());
+        let entries = verified_sightings(&db, data.entries)?;
+        db.merge_last_seen(entries)
+            .and_then(|_| db.read_presence())
+            .map_err(|e| request::internal("Failed to update last seen", e))?
+    };
+
+    let mut entries = table.for_users(&data.user_uuids);
+    for visitor in table.recent_visitors(totem_id, now - RECENT_VISITORS_WINDOW) {
+        if !entries.contains(&visitor) {
+            entries.push(visitor);
         }
+    }
+    entries.truncate(MAX_LAST_SEEN_ENTRIES);
 
-        let mut buf = vec![0; len];
-        req.read_exact(&mut buf)?;
+    Ok(UsersLastSeenResponse {
+        totem_id: totem_id.to_string(),
+        entries,
+    })
+}
 
-        let log_str = String::from_utf8_lossy(&buf);
-        let log_preview = if log_str.len() > 100 { &log_str[..100] } else { &log_str };
-        info!("POST /users/last_seen - Input: {}", log_preview);
+/// The sightings whose signatures match the keys of their users
+fn verified_sightings(
+    db: &FileBasedDB,
+    entries: Vec<model::LastSeen>,
+) -> Result<Vec<model::LastSeen>, ApiError> {
+    let user_ids: Vec<&str> = entries.iter().map(|entry| entry.user_id.as_str()).collect();
+    let users = db
+        .read_users_match(usize::MAX, |u| user_ids.contains(&u.uuid.as_str()))
+        .map_err(|e| request::internal("Failed to read users", e))?;
+    let rotations = db
+        .read_key_rotations_match(usize::MAX, |r| user_ids.contains(&r.user_id.as_str()))
+        .map_err(|e| request::internal("Failed to read key rotations", e))?;
+
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            let user = users.iter().find(|u| u.uuid == entry.user_id);
+            let rotations: Vec<_> = rotations
+                .iter()
+                .filter(|r| r.user_id == entry.user_id)
+                .cloned()
+                .collect();
+            entry.check_signature(user, &rotations).is_ok()
+        })
+        .collect())
+}
 
-        let mut resp = req.into_ok_response()?;
+/// POST /mailbox/deposit
+fn deposit_mail(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<MailboxDepositResponse, ApiError> {
+    let buf = request::read_body(req, MAX_MAILBOX_BODY)?;
+    log_input("POST /mailbox/deposit", &buf);
+
+    let data: MailboxDepositRequest = request::parse_json(&buf)?;
+    let now = chrono::Utc::now();
+
+    // Receipts signed by anyone but the recipient could have messages deleted
+    let receipts: Vec<_> = data
+        .receipts
+        .into_iter()
+        .filter(|receipt| receipt.validate_at(now).is_ok() && receipt.check_signature().is_ok())
+        .collect();
+    let (valid, invalid): (Vec<_>, Vec<_>) = data
+        .messages
+        .into_iter()
+        .partition(|message| message.validate_at(now).is_ok());
+
+    let (merged, results) = {
+        let db = fbdb.lock().unwrap();
+
+        // Receipts first, a message delivered in the meantime isn't stored again
+        let merged = db
+            .merge_receipts(receipts, now)
+            .map_err(|e| request::internal("Failed to save receipts", e))?;
+        let results = db
+            .deposit_messages(&valid, now)
+            .map_err(|e| request::internal("Failed to save messages", e))?;
+        (merged, results)
+    };
 
-        if let Ok(data) = serde_json::from_slice::<UsersLastSeenRequest>(&buf) {
-            // STUB: Acknowledge the request
-            write!(
-                resp,
-                "STUB: Received last_seen for {} users",
-                data.user_uuids.len()
-            )?;
-        } else {
-            resp.write_all("JSON error".as_bytes())?;
+    let mut response = MailboxDepositResponse {
+        receipts_accepted: merged.len() as u32,
+        ..Default::default()
+    };
+    for (message, result) in valid.into_iter().zip(results) {
+        match result {
+            Ok(()) => response.stored.push(message.uuid),
+            Err(reason) => response.refused.push(RefusedMessage {
+                uuid: message.uuid,
+                reason,
+            }),
         }
+    }
+    response
+        .refused
+        .extend(invalid.into_iter().map(|message| RefusedMessage {
+            uuid: message.uuid,
+            reason: Refusal::Invalid,
+        }));
 
-        Ok(())
-    })?;
+    info!("{:?}", response);
 
-    // GET /is_totem - Simple endpoint to identify this device as a totem
-    server.fn_handler::<anyhow::Error, _>("/is_totem", Method::Get, |req| {
-        req.into_ok_response()?.write_all(b"OK")?;
-        Ok(())
+    Ok(response)
+}
+
+/// POST /mailbox/collect
+fn collect_mail(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+) -> Result<MailboxCollectResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 20)?;
+    log_input("POST /mailbox/collect", &buf);
+
+    let data: MailboxCollectRequest = request::parse_json(&buf)?;
+    // The messages are encrypted, whoever knows the mailbox may as well have them
+    validation::check_hex(&data.mailbox, 32, 32).map_err(|reason| {
+        ApiError::new(ErrorCode::ValidationFailed, "Invalid request")
+            .with_detail(Some("mailbox"), reason)
     })?;
 
-    // Keep wifi and the server running beyond when main() returns (forever)
-    // Do not call this if you ever want to stop or access them later.
-    // core::mem::forget(wifi);
-    // core::mem::forget(server);
+    let db = fbdb.lock().unwrap();
+    let index = db
+        .read_mailbox(chrono::Utc::now())
+        .map_err(|e| request::internal("Failed to read mailbox", e))?;
+    let (messages, more) = db
+        .read_mailbox_messages(&index, &data.mailbox, &data.known, MAX_MAILBOX_BODY / 2)
+        .map_err(|e| request::internal("Failed to read mailbox", e))?;
+
+    let mut receipts: Vec<_> = data
+        .pending
+        .iter()
+        .filter_map(|uuid| index.receipt_for(uuid).cloned())
+        .collect();
+    receipts.extend(
+        index
+            .receipts()
+            .into_iter()
+            .filter(|receipt| !data.pending.contains(&receipt.message_uuid))
+            .take(MAX_PASSED_ON_RECEIPTS)
+            .cloned(),
+    );
 
-    Ok((wifi, server))
+    Ok(MailboxCollectResponse {
+        messages,
+        receipts,
+        more,
+    })
+}
+
+/// POST /bundles/offer
+fn offer_bundles(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+    totem_id: &str,
+) -> Result<BundlesOfferResponse, ApiError> {
+    // bigger MAX_LEN for multiple IDs
+    let buf = request::read_body(req, MAX_LEN * 20)?;
+    log_input("POST /bundles/offer", &buf);
+
+    let data: BundlesOfferRequest = request::parse_json(&buf)?;
+    if let Some(user_id) = &data.user_id {
+        validation::check_uuid(user_id).map_err(|reason| {
+            ApiError::new(ErrorCode::ValidationFailed, "Invalid request")
+                .with_detail(Some("user_id"), reason)
+        })?;
+    }
+    let now = chrono::Utc::now();
+
+    let db = fbdb.lock().unwrap();
+    let held = db
+        .read_bundle_uuids(now)
+        .map_err(|e| request::internal("Failed to read bundles", e))?;
+    let wanted = data
+        .carried
+        .iter()
+        .filter(|uuid| !held.contains(uuid))
+        .cloned()
+        .collect();
+
+    let phone = Node::Phone {
+        user: data.user_id.as_deref(),
+        courier: data.courier,
+    };
+    let bundles = db
+        .hand_out_bundles(phone, &data.carried, MAX_BUNDLE_BODY / 2, now)
+        .map_err(|e| request::internal("Failed to hand out bundles", e))?;
+
+    Ok(BundlesOfferResponse {
+        totem_id: totem_id.to_string(),
+        wanted,
+        bundles,
+    })
+}
+
+/// POST /bundles/deliver
+fn deliver_bundles(
+    req: &mut HttpRequest,
+    fbdb: &Mutex<FileBasedDB>,
+    media: &MediaStore,
+    trending: &Mutex<Trending>,
+    totem_id: &str,
+) -> Result<BundlesDeliverResponse, ApiError> {
+    let buf = request::read_body(req, MAX_BUNDLE_BODY)?;
+    log_input("POST /bundles/deliver", &buf);
+
+    let data: BundlesDeliverRequest = request::parse_json(&buf)?;
+    let now = chrono::Utc::now();
+    let mut response = BundlesDeliverResponse::default();
+    let mut custody = Vec::new();
+
+    let db = fbdb.lock().unwrap();
+    for bundle in data.bundles {
+        if let Err(e) = bundle.validate_at(now) {
+            info!("Refused bundle {}: {}", bundle.uuid, e);
+            response.refused.push(bundle.uuid);
+            continue;
+        }
+
+        if !bundle.header.is_for(Node::Totem(totem_id)) {
+            // Messages wait in the mailbox here as well, their recipient may come by
+            if let model::BundlePayload::Message(message) = &bundle.payload {
+                db.deposit_messages(std::slice::from_ref(message), now)
+                    .map_err(|e| request::internal("Failed to save messages", e))?;
+            }
+            custody.push(bundle);
+            continue;
+        }
+
+        match deliver_payload(&db, media, trending, &bundle.payload, now) {
+            Ok(()) => response.accepted.push(bundle.uuid),
+            Err(e) => {
+                info!("Refused bundle {}: {}", bundle.uuid, e);
+                response.refused.push(bundle.uuid);
+            }
+        }
+    }
+
+    let uuids: Vec<String> = custody.iter().map(|bundle| bundle.uuid.clone()).collect();
+    let taken = db
+        .take_custody(custody, now)
+        .map_err(|e| request::internal("Failed to save bundles", e))?;
+    for (uuid, taken) in uuids.into_iter().zip(taken) {
+        if taken {
+            response.accepted.push(uuid);
+        } else {
+            response.refused.push(uuid);
+        }
+    }
+
+    info!("{:?}", response);
+
+    Ok(response)
+}
+
+/// Take the content of a bundle addressed to this totem as if it was uploaded here
+fn deliver_payload(
+    db: &FileBasedDB,
+    media: &MediaStore,
+    trending: &Mutex<Trending>,
+    payload: &model::BundlePayload,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<(), ApiError> {
+    match payload {
+        model::BundlePayload::Post(post) => store_post(db, media, trending, post),
+        model::BundlePayload::Message(message) => {
+            let deposited = db
+                .deposit_messages(std::slice::from_ref(message), now)
+                .map_err(|e| request::internal("Failed to save messages", e))?;
+            match deposited.into_iter().next() {
+                Some(Err(reason)) => Err(ApiError::new(ErrorCode::Conflict, reason.to_string())
+                    .with_detail(Some("reason"), reason.as_str())),
+                _ => Ok(()),
+            }
+        }
+    }
 }
 
 fn connect_wifi(
@@ -647,6 +1917,7 @@ fn connect_wifi(
 fn create_server() -> anyhow::Result<EspHttpServer<'static>> {
     let server_configuration = esp_idf_svc::http::server::Configuration {
         stack_size: STACK_SIZE,
+        max_uri_handlers: MAX_URI_HANDLERS,
         uri_match_wildcard: true,
         ..Default::default()
     };
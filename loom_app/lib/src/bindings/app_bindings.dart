@@ -3,9 +3,11 @@ import 'package:get/get.dart';
 import '../controllers/main_controller.dart';
 import '../controllers/images_controller.dart';
 import '../controllers/ble_provisioning_controller.dart';
+import '../controllers/identity_controller.dart';
 import '../controllers/posts_controller.dart';
 import '../controllers/profiles_controller.dart';
 import '../controllers/settings_controller.dart';
+import '../controllers/sync_controller.dart';
 import '../controllers/totems_controller.dart';
 import '../controllers/totem_proximity_controller.dart';
 
@@ -14,6 +16,9 @@ class AppBindings extends Bindings {
   void dependencies() {
     Get.put(MainController(), permanent: true);
 
+    // Before the controllers that read or write records, they sign with it
+    Get.put(IdentityController(), permanent: true);
+    Get.put(SyncController(), permanent: true);
     Get.put(ProfilesController(), permanent: true);
     Get.put(PostsController(), permanent: true);
     Get.put(TotemsController(), permanent: true);
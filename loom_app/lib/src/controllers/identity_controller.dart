@@ -0,0 +1,123 @@
+import 'dart:io';
+
+import 'package:flutter/foundation.dart';
+import 'package:get/get.dart';
+import 'package:loom_app/src/rust/api/identity.dart' as rust_identity;
+import 'package:loom_app/src/rust/api/simple.dart' as rust;
+import 'package:path_provider/path_provider.dart';
+
+/// Owns the database handle and the identity this phone's records are signed with.
+///
+/// The identity is unlocked on the handle, so everything that creates or syncs
+/// records has to go through [database] instead of opening its own handle.
+class IdentityController extends GetxController {
+  /// The user ID derived from this phone's key, empty until it is known.
+  final RxString currentUserId = ''.obs;
+
+  /// True once the start-up check found neither an identity nor a legacy user.
+  final RxBool needsRegistration = false.obs;
+
+  /// Random user IDs from before identities, mapped to the ID they moved to.
+  final RxMap<String, String> userLinks = <String, String>{}.obs;
+
+  late final Future<rust.AppDatabase> database = _open();
+
+  /// Completes once the identity is unlocked, or known to be missing.
+  late final Future<void> ready;
+
+  Future<String> get _keyDir async =>
+      (await getApplicationSupportDirectory()).path;
+
+  Future<rust.AppDatabase> _open() async {
+    final directory = await getApplicationDocumentsDirectory();
+    return rust.AppDatabase(path: '${directory.path}/loom_app.db');
+  }
+
+  /// Where versions before identities kept the random user ID.
+  Future<File> _legacyIdentityFile() async {
+    final directory = await getApplicationDocumentsDirectory();
+    return File('${directory.path}/user_identity.txt');
+  }
+
+  @override
+  void onInit() {
+    super.onInit();
+    ready = _unlock();
+  }
+
+  Future<void> _unlock() async {
+    try {
+      final db = await database;
+      final keyDir = await _keyDir;
+
+      rust_identity.IdentityInfo? info = await rust_identity.unlockIdentity(
+        db: db,
+        keyDir: keyDir,
+      );
+
+      final legacyFile = await _legacyIdentityFile();
+      if (info == null && await legacyFile.exists()) {
+        final legacyUserId = (await legacyFile.readAsString()).trim();
+        try {
+          info = await rust_identity.migrateIdentity(
+            db: db,
+            keyDir: keyDir,
+            legacyUserId: legacyUserId,
+          );
+          await legacyFile.delete();
+          debugPrint('Moved $legacyUserId to ${info.userId}');
+        } catch (e) {
+          // Without the legacy profile there is nothing to move, start over
+          debugPrint('Error moving $legacyUserId to an identity: $e');
+        }
+      }
+
+      if (info == null) {
+        needsRegistration.value = true;
+        return;
+      }
+      currentUserId.value = info.userId;
+      await loadUserLinks();
+    } catch (e) {
+      debugPrint('Error unlocking identity: $e');
+    }
+  }
+
+  /// Create this phone's identity and the profile of its user.
+  Future<void> register({
+    required String username,
+    required String status,
+    required String bio,
+  }) async {
+    final db = await database;
+    final info = await rust_identity.registerUser(
+      db: db,
+      keyDir: await _keyDir,
+      // The UUID is replaced by the one derived from the new key
+      user: rust.User(
+        uuid: '',
+        username: username,
+        status: status,
+        bio: bio,
+        lastContact: DateTime.now().toUtc(),
+      ),
+    );
+    needsRegistration.value = false;
+    currentUserId.value = info.userId;
+  }
+
+  Future<void> loadUserLinks() async {
+    try {
+      final db = await database;
+      final links = await db.getUserLinks();
+      userLinks.assignAll(<String, String>{
+        for (final link in links) link.legacyId: link.userId,
+      });
+    } catch (e) {
+      debugPrint('Error loading user links: $e');
+    }
+  }
+
+  /// The ID of the profile behind records of [userId].
+  String resolveUserId(String userId) => userLinks[userId] ?? userId;
+}
@@ -1,11 +1,13 @@
-import 'dart:io';
+import 'dart:async';
+
 import 'package:flutter/material.dart';
 import 'package:get/get.dart';
+import 'package:loom_app/src/controllers/identity_controller.dart';
 import 'package:loom_app/src/controllers/profiles_controller.dart';
+import 'package:loom_app/src/controllers/sync_controller.dart';
 import 'package:loom_app/src/models/post.dart';
-import 'package:loom_app/src/network/networker.dart';
 import 'package:loom_app/src/rust/api/simple.dart' as rust;
-import 'package:path_provider/path_provider.dart';
+import 'package:loom_app/src/util/media_path.dart';
 import 'package:uuid/uuid.dart';
 
 class PostsController extends GetxController {
@@ -19,8 +21,14 @@ class PostsController extends GetxController {
   // (Not persisted across app restarts.)
   final RxSet<String> pinnedPostIds = <String>{}.obs;
 
-  // Stores the current user's UUID. Default is empty until loaded.
-  final RxString currentUserId = "".obs;
+  // Whether each post was signed by its author, by post ID.
+  final RxMap<String, rust.Verification> verifications =
+      <String, rust.Verification>{}.obs;
+
+  final IdentityController _identity = Get.find<IdentityController>();
+
+  // The current user's derived ID, empty until the identity is unlocked.
+  RxString get currentUserId => _identity.currentUserId;
 
   bool isSaved(String postId) => savedPostIds.contains(postId);
 
@@ -66,40 +74,29 @@ class PostsController extends GetxController {
     checkUserIdentity().then((_) => loadPosts());
   }
 
-  /// Checks if a local file containing the user UUID exists.
-  /// If not, it triggers the registration flow.
+  /// Waits for the identity to be unlocked.
+  /// If the phone has none yet, it triggers the registration flow.
   Future<void> checkUserIdentity() async {
     try {
-      final dir = await getApplicationDocumentsDirectory();
-      final file = File("${dir.path}/user_identity.txt");
-
-      if (await file.exists()) {
-        // A. User exists: Read UUID and set state
-        final storedUuid = await file.readAsString();
-        currentUserId.value = storedUuid.trim();
-        debugPrint("Logged in as: $storedUuid");
-      } else {
-        // B. New User: Generate UUID and show Dialog
-        final newUuid = const Uuid().v4();
+      await _identity.ready;
+      if (!_identity.needsRegistration.value) {
+        debugPrint("Logged in as: ${currentUserId.value}");
+        return;
+      }
 
-        // We need a slight delay to ensure the UI is ready for the dialog
-        await Future.delayed(const Duration(milliseconds: 500));
+      // We need a slight delay to ensure the UI is ready for the dialog
+      await Future.delayed(const Duration(milliseconds: 500));
 
-        if (Get.context != null) {
-          _showRegistrationDialog(Get.context!, newUuid, file);
-        }
+      if (Get.context != null) {
+        _showRegistrationDialog(Get.context!);
       }
     } catch (e) {
       debugPrint("Error checking identity: $e");
     }
   }
 
-  /// Shows a dialog asking for details, then saves the user to Rust & File.
-  void _showRegistrationDialog(
-    BuildContext context,
-    String newUuid,
-    File identityFile,
-  ) {
+  /// Shows a dialog asking for details, then creates the identity and profile.
+  void _showRegistrationDialog(BuildContext context) {
     final nameCtrl = TextEditingController();
     final bioCtrl = TextEditingController();
     final statusCtrl = TextEditingController();
@@ -147,22 +144,15 @@ class PostsController extends GetxController {
         actions: [
           FilledButton(
             onPressed: () async {
-              if (nameCtrl.text.isNotEmpty) {
-                // 1. Create User in Rust DB
-                await _registerUserInDb(
-                  uuid: newUuid,
-                  username: nameCtrl.text,
-                  status: statusCtrl.text,
+              if (nameCtrl.text.trim().isNotEmpty) {
+                // 1. Create the key and the profile under its ID
+                await _identity.register(
+                  username: nameCtrl.text.trim(),
+                  status: statusCtrl.text.trim(),
                   bio: bioCtrl.text,
                 );
 
-                // 2. Save UUID to local file
-                await identityFile.writeAsString(newUuid);
-
-                // 3. Update State
-                currentUserId.value = newUuid;
-
-                // 4. Refresh profiles so the current user appears in UI
+                // 2. Refresh profiles so the current user appears in UI
                 try {
                   Get.find<ProfilesController>().refreshProfiles();
                 } catch (_) {}
@@ -185,8 +175,7 @@ class PostsController extends GetxController {
     required String status,
     required String bio,
   }) async {
-    final dbPath = await _getDatabasePath();
-    final db = rust.AppDatabase(path: dbPath);
+    final db = await _identity.database;
 
     await db.createUser(
       user: rust.User(
@@ -210,20 +199,26 @@ class PostsController extends GetxController {
     }
   }
 
-  Future<String> _getDatabasePath() async {
-    final directory = await getApplicationDocumentsDirectory();
-    return "${directory.path}/loom_app.db";
-  }
-
   Future<void> loadPosts() async {
     try {
-      final dbPath = await _getDatabasePath();
-      final database = rust.AppDatabase(path: dbPath);
+      final database = await _identity.database;
       final rustPosts = await database.getAllPosts();
+      final images = await Future.wait(
+        rustPosts.map((p) => resolveMediaPath(database, p.image)),
+      );
 
-      posts.assignAll(
-        rustPosts.map((p) => p.toFlutterPost()).toList().reversed.toList(),
+      posts.assignAll(<Post>[
+        for (var i = rustPosts.length - 1; i >= 0; i--)
+          rustPosts[i].toFlutterPost(imageUrl: images[i]),
+      ]);
+
+      final checked = await Future.wait(
+        rustPosts.map((p) => database.getPostVerification(uuid: p.uuid)),
       );
+      verifications.assignAll(<String, rust.Verification>{
+        for (var i = 0; i < rustPosts.length; i++)
+          rustPosts[i].uuid: checked[i],
+      });
     } catch (e) {
       debugPrint("Error loading posts: $e");
     }
@@ -237,8 +232,7 @@ class PostsController extends GetxController {
     }
 
     try {
-      final dbPath = await _getDatabasePath();
-      final db = rust.AppDatabase(path: dbPath);
+      final db = await _identity.database;
       final sourceTotemId = await _resolveSourceTotemId(db);
 
       final newPost = rust.Post(
@@ -252,16 +246,11 @@ class PostsController extends GetxController {
       );
 
       await db.createPost(post: newPost);
-
-      try {
-        await updateUserDatabase();
-        await updatePostDatabase();
-      } catch (e) {
-        debugPrint("Error syncing after post create: $e");
-      }
-
       await loadPosts();
 
+      // Reloads the posts again once it's done, failures show up on the feed
+      unawaited(Get.find<SyncController>().sync());
+
       Get.snackbar("Success", "Post created successfully!");
     } catch (e) {
       Get.snackbar("Error", "Failed to create post: $e");
@@ -325,13 +314,14 @@ class PostsController extends GetxController {
 
 // ... (Keep your PostMapper extension here)
 extension PostMapper on rust.Post {
-  Post toFlutterPost() {
+  /// [imageUrl] is the file of [image], see [resolveMediaPath].
+  Post toFlutterPost({String? imageUrl}) {
     return Post(
       id: uuid,
       authorId: userId,
       title: title,
       text: body,
-      imageUrl: image,
+      imageUrl: imageUrl,
       timeAgoLabel: _formatTimeAgo(timestamp),
       likes: 0,
       comments: 0,
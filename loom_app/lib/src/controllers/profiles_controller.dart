@@ -1,67 +1,85 @@
-import 'dart:io';
-
 import 'package:get/get.dart';
+import 'package:loom_app/src/controllers/identity_controller.dart';
+import 'package:loom_app/src/controllers/totems_controller.dart';
 import 'package:loom_app/src/models/profile.dart';
+import 'package:loom_app/src/models/totem.dart';
 import 'package:loom_app/src/rust/api/simple.dart' as rust;
-import 'package:path_provider/path_provider.dart';
+import 'package:loom_app/src/util/media_path.dart';
 
 class ProfilesController extends GetxController {
   final RxList<Profile> profiles = <Profile>[].obs;
-  final RxString currentUserId = ''.obs;
+
+  // Whether each profile was signed by its user, by user ID.
+  final RxMap<String, rust.Verification> verifications =
+      <String, rust.Verification>{}.obs;
+
+  final IdentityController _identity = Get.find<IdentityController>();
+
+  // Where and when each user was last seen at a totem, by user ID.
+  final RxMap<String, rust.LastSeen> sightings =
+      <String, rust.LastSeen>{}.obs;
+
+  // Profile pictures as the records reference them, by user ID.
+  // [Profile.profilePicture] holds the file they resolved to instead.
+  final Map<String, String?> _storedPictures = <String, String?>{};
+
+  RxString get currentUserId => _identity.currentUserId;
 
   @override
   void onInit() {
     super.onInit();
-    _loadIdentity().then((_) => loadProfiles());
+    _identity.ready.then((_) => loadProfiles());
   }
 
   Future<void> refreshProfiles() async {
-    await _loadIdentity();
+    await _identity.loadUserLinks();
     await loadProfiles();
   }
 
-  Future<void> _loadIdentity() async {
-    try {
-      final dir = await getApplicationDocumentsDirectory();
-      final file = File('${dir.path}/user_identity.txt');
-      if (await file.exists()) {
-        currentUserId.value = (await file.readAsString()).trim();
-      } else {
-        currentUserId.value = '';
-      }
-    } catch (_) {
-      currentUserId.value = '';
-    }
-  }
-
-  Future<String> _getDatabasePath() async {
-    final directory = await getApplicationDocumentsDirectory();
-    return '${directory.path}/loom_app.db';
-  }
-
   Future<void> loadProfiles() async {
     try {
-      final dbPath = await _getDatabasePath();
-      final database = rust.AppDatabase(path: dbPath);
+      final database = await _identity.database;
       final rustUsers = await database.getAllUsers();
+      final pictures = await Future.wait(
+        rustUsers.map((u) => resolveMediaPath(database, u.profilePicture)),
+      );
 
-      profiles.assignAll(
-        rustUsers
-            .map(
-              (u) => Profile(
-                id: u.uuid,
-                name: u.username,
-                handle: _handleFromUsername(u.username),
-                status: u.status,
-                bio: u.bio,
-                profilePicture: u.profilePicture,
-                lastSeenAt: u.lastContact,
-                lastSeenLabel: _formatTimeAgo(u.lastContact),
-                isCurrentUser: u.uuid == currentUserId.value,
-              ),
-            )
-            .toList(growable: false),
+      _storedPictures
+        ..clear()
+        ..addAll(<String, String?>{
+          for (final u in rustUsers) u.uuid: u.profilePicture,
+        });
+      profiles.assignAll(<Profile>[
+        for (var i = 0; i < rustUsers.length; i++)
+          Profile(
+            id: rustUsers[i].uuid,
+            name: rustUsers[i].username,
+            handle: _handleFromUsername(rustUsers[i].username),
+            status: rustUsers[i].status,
+            bio: rustUsers[i].bio,
+            profilePicture: pictures[i],
+            lastSeenAt: rustUsers[i].lastContact,
+            lastSeenLabel: _formatTimeAgo(rustUsers[i].lastContact),
+            isCurrentUser: rustUsers[i].uuid == currentUserId.value,
+          ),
+      ]);
+
+      final checked = await Future.wait(
+        rustUsers.map((u) => database.getUserVerification(uuid: u.uuid)),
+      );
+      verifications.assignAll(<String, rust.Verification>{
+        for (var i = 0; i < rustUsers.length; i++)
+          rustUsers[i].uuid: checked[i],
+      });
+
+      // Includes what the totems saw of a user's random ID from before identities
+      final seen = await Future.wait(
+        rustUsers.map((u) => database.getLatestLastSeen(userId: u.uuid)),
       );
+      sightings.assignAll(<String, rust.LastSeen>{
+        for (var i = 0; i < rustUsers.length; i++)
+          if (seen[i] != null) rustUsers[i].uuid: seen[i]!,
+      });
     } catch (_) {
       profiles.assignAll(const <Profile>[]);
     }
@@ -78,15 +96,18 @@ class ProfilesController extends GetxController {
 
     final Profile? existing = byId(uuid);
     final DateTime lastContact = DateTime.now().toUtc();
-    final String? picture = profilePicture ?? existing?.profilePicture;
+    // An unchanged picture comes back as the file it resolved to
+    final String? picture =
+        profilePicture == null || profilePicture == existing?.profilePicture
+        ? _storedPictures[uuid]
+        : profilePicture;
 
-    final dbPath = await _getDatabasePath();
-    final database = rust.AppDatabase(path: dbPath);
+    final database = await _identity.database;
     await database.updateUser(
       user: rust.User(
         uuid: uuid,
-        username: name,
-        status: status,
+        username: name.trim(),
+        status: status.trim(),
         bio: bio,
         profilePicture: picture,
         lastContact: lastContact,
@@ -96,13 +117,30 @@ class ProfilesController extends GetxController {
     await refreshProfiles();
   }
 
+  /// The profile behind [id], following a random ID to the one it moved to.
   Profile? byId(String id) {
+    final String resolved = _identity.resolveUserId(id);
     for (final p in profiles) {
-      if (p.id == id) return p;
+      if (p.id == resolved) return p;
     }
     return null;
   }
 
+  /// Whether a record of [userId] was written by the user of [profile].
+  bool isAuthor(Profile? profile, String userId) =>
+      profile != null && _identity.resolveUserId(userId) == profile.id;
+
+  /// Where and when the user of [profile] was last seen, null if no totem saw them.
+  String? sightingLabel(Profile? profile) {
+    final rust.LastSeen? seen = profile == null ? null : sightings[profile.id];
+    if (seen == null) return null;
+
+    final Totem? totem = Get.isRegistered<TotemsController>()
+        ? Get.find<TotemsController>().byId(seen.totemId)
+        : null;
+    return 'Seen at ${totem?.name ?? 'a totem'} · ${_formatTimeAgo(seen.seenAt)}';
+  }
+
   Profile? byName(String name) {
     for (final p in profiles) {
       if (p.name == name) return p;
@@ -0,0 +1,82 @@
+import 'package:flutter/foundation.dart';
+import 'package:get/get.dart';
+import 'package:loom_app/src/controllers/identity_controller.dart';
+import 'package:loom_app/src/controllers/posts_controller.dart';
+import 'package:loom_app/src/controllers/profiles_controller.dart';
+import 'package:loom_app/src/rust/api/media.dart' as rust_media;
+import 'package:loom_app/src/rust/api/sync.dart' as rust_sync;
+import 'package:path_provider/path_provider.dart';
+
+/// Reconciles the records on this phone with the totem it is connected to.
+///
+/// The sync runs on the handle of the [IdentityController], so records are
+/// signed and the phone's sighting at the totem is sent along. Pictures the
+/// records reference are transferred afterwards.
+class SyncController extends GetxController {
+  final IdentityController _identity = Get.find<IdentityController>();
+
+  final RxBool syncing = false.obs;
+  final Rxn<rust_sync.RecordSyncSummary> lastSummary =
+      Rxn<rust_sync.RecordSyncSummary>();
+  final RxnString syncError = RxnString();
+
+  /// The picture being transferred, null when none is.
+  final Rxn<rust_media.MediaSyncProgress> mediaProgress =
+      Rxn<rust_media.MediaSyncProgress>();
+  final Rxn<rust_media.MediaSyncSummary> lastMediaSummary =
+      Rxn<rust_media.MediaSyncSummary>();
+
+  Future<String> get _mediaDir async =>
+      '${(await getApplicationDocumentsDirectory()).path}/media';
+
+  /// Sync with the totem, then reload what the pages show.
+  ///
+  /// A sync that is already running is awaited instead of started twice.
+  Future<void> sync() {
+    return _running ??= _sync().whenComplete(() => _running = null);
+  }
+
+  Future<void>? _running;
+
+  Future<void> _sync() async {
+    syncing.value = true;
+    syncError.value = null;
+    lastMediaSummary.value = null;
+    try {
+      await _identity.ready;
+      final db = await _identity.database;
+      final summary = await rust_sync.syncRecords(db: db);
+      lastSummary.value = summary;
+      if (summary.rejected > 0) {
+        debugPrint('Sync rejected ${summary.rejected} records');
+      }
+
+      // Needs the records first, they say which pictures are missing
+      final media = await rust_media.syncMedia(
+        db: db,
+        mediaDir: await _mediaDir,
+        onProgress: (progress) {
+          mediaProgress.value = progress.done ? null : progress;
+          if (progress.error != null) {
+            debugPrint('Error transferring ${progress.hash}: ${progress.error}');
+          }
+        },
+      );
+      lastMediaSummary.value = media;
+    } catch (e) {
+      syncError.value = 'Sync failed: $e';
+      debugPrint('Error syncing with the totem: $e');
+    } finally {
+      syncing.value = false;
+      mediaProgress.value = null;
+    }
+
+    // Show whatever arrived, even if the sync stopped halfway
+    if (Get.isRegistered<ProfilesController>()) {
+      await Get.find<ProfilesController>().refreshProfiles();
+    }
+    if (Get.isRegistered<PostsController>()) {
+      await Get.find<PostsController>().loadPosts();
+    }
+  }
+}
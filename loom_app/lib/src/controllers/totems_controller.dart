@@ -3,6 +3,7 @@ import 'package:loom_app/src/models/totem.dart';
 import 'package:loom_app/src/network/wifi_connector.dart';
 import 'package:loom_app/src/storage/totem_wifi_store.dart';
 import 'package:loom_app/src/rust/api/simple.dart' as rust;
+import 'package:loom_app/src/rust/api/trending.dart' as rust_trending;
 import 'package:path_provider/path_provider.dart';
 import 'package:permission_handler/permission_handler.dart';
 import 'dart:io';
@@ -15,6 +16,13 @@ class TotemsController extends GetxController {
 
   final RxBool wifiConnectInProgress = false.obs;
 
+  final Rxn<rust_trending.TrendingTopics> trending =
+      Rxn<rust_trending.TrendingTopics>();
+  final RxnString trendingError = RxnString();
+  final RxBool trendingLoading = false.obs;
+
+  final RxList<rust.LastSeen> visitors = <rust.LastSeen>[].obs;
+
   @override
   void onInit() {
     super.onInit();
@@ -131,6 +139,49 @@ class TotemsController extends GetxController {
     }
   }
 
+  /// Fetches what is talked about at [totem].
+  /// Totems are only reachable on their own Wi‑Fi, so the phone has to be
+  /// connected to it.
+  Future<void> loadTrending(Totem totem) async {
+    if (trendingLoading.value) return;
+    trendingLoading.value = true;
+    trending.value = null;
+    trendingError.value = null;
+
+    try {
+      final dbPath = await _getDatabasePath();
+      final db = rust.AppDatabase(path: dbPath);
+      final topics = await rust_trending.getTrending(db: db);
+
+      // Older totems don't say who they are.
+      if (topics.totemId.isNotEmpty && topics.totemId != totem.id) {
+        trendingError.value = 'Connected to another totem';
+        return;
+      }
+      trending.value = topics;
+    } catch (_) {
+      trendingError.value =
+          'Connect to "${totem.name}" to see what is trending';
+    } finally {
+      trendingLoading.value = false;
+    }
+  }
+
+  /// Loads who was seen at [totem] during the last day.
+  /// Sightings arrive with the record sync, so this works without a connection.
+  Future<void> loadVisitors(Totem totem) async {
+    try {
+      final dbPath = await _getDatabasePath();
+      final db = rust.AppDatabase(path: dbPath);
+      final since = DateTime.now().toUtc().subtract(const Duration(days: 1));
+      visitors.assignAll(
+        await db.getRecentVisitors(totemId: totem.id, since: since),
+      );
+    } catch (_) {
+      visitors.clear();
+    }
+  }
+
   Future<String> _getDatabasePath() async {
     final directory = await getApplicationDocumentsDirectory();
     return '${directory.path}/loom_app.db';
@@ -1,9 +1,7 @@
 import 'dart:io';
 
 import 'package:get/get.dart';
-import 'package:loom_app/src/controllers/posts_controller.dart';
-import 'package:loom_app/src/controllers/profiles_controller.dart';
-import 'package:loom_app/src/network/networker.dart';
+import 'package:loom_app/src/controllers/sync_controller.dart';
 import 'package:wifi_iot/wifi_iot.dart';
 
 class WifiConnector {
@@ -67,16 +65,8 @@ class WifiConnector {
       withInternet: true,
     );
 
-    if (ok) {
-      await updateUserDatabase();
-      await updatePostDatabase();
-
-      if (Get.isRegistered<ProfilesController>()) {
-        await Get.find<ProfilesController>().refreshProfiles();
-      }
-      if (Get.isRegistered<PostsController>()) {
-        await Get.find<PostsController>().loadPosts();
-      }
+    if (ok && Get.isRegistered<SyncController>()) {
+      await Get.find<SyncController>().sync();
     }
 
     return ok;
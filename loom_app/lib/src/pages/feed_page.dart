@@ -3,6 +3,7 @@ import 'package:get/get.dart';
 import 'package:loom_app/src/controllers/images_controller.dart';
 import 'package:loom_app/src/controllers/posts_controller.dart';
 import 'package:loom_app/src/controllers/profiles_controller.dart';
+import 'package:loom_app/src/controllers/sync_controller.dart';
 import 'package:loom_app/src/controllers/totems_controller.dart';
 import 'package:loom_app/src/models/post.dart';
 import 'package:loom_app/src/models/profile.dart';
@@ -13,10 +14,11 @@ import 'package:loom_app/src/pages/full_screen_post_page.dart';
 import 'package:loom_app/src/pages/ble_provisioning_page.dart';
 import 'package:loom_app/src/pages/profile_page.dart';
 import 'package:loom_app/src/pages/qr_scanner_page.dart';
-import 'package:loom_app/src/network/networker.dart';
+import 'package:loom_app/src/rust/api/media.dart' show MediaDirection;
 import 'package:loom_app/src/rust/api/simple.dart' as rust;
 import 'package:loom_app/src/widgets/expandable_text.dart';
 import 'package:loom_app/src/widgets/path_image.dart';
+import 'package:loom_app/src/widgets/verification_badge.dart';
 
 class FeedPage extends StatelessWidget {
   const FeedPage({super.key});
@@ -25,6 +27,7 @@ class FeedPage extends StatelessWidget {
   Widget build(BuildContext context) {
     final profilesController = Get.find<ProfilesController>();
     final postsController = Get.find<PostsController>();
+    final syncController = Get.find<SyncController>();
     final theme = Theme.of(context);
 
     return Scaffold(
@@ -98,19 +101,7 @@ class FeedPage extends StatelessWidget {
         final topics = postsController.trendingTags(limit: 6);
 
         return RefreshIndicator(
-          onRefresh: () async {
-            try {
-              await updateUserDatabase();
-            } catch (_) {}
-            try {
-              await updatePostDatabase();
-            } catch (_) {}
-
-            await Future.wait(<Future<void>>[
-              profilesController.refreshProfiles(),
-              postsController.loadPosts(),
-            ]);
-          },
+          onRefresh: syncController.sync,
           child: CustomScrollView(
             physics: const AlwaysScrollableScrollPhysics(
               parent: BouncingScrollPhysics(),
@@ -122,6 +113,7 @@ class FeedPage extends StatelessWidget {
                   subtitle: 'Here is what your circles are sharing today.',
                 ),
               ),
+              const SliverToBoxAdapter(child: _SyncStatus()),
               SliverToBoxAdapter(child: _ProfilesSection(profiles: profiles)),
               SliverToBoxAdapter(
                 child: _TopicsSection(
@@ -338,6 +330,70 @@ class _CreatePostSheetContentState extends State<_CreatePostSheetContent> {
 
 // ... (Keep existing _HomeHeader, _StoriesSection, _TopicsSection, _PostStat, _initial)
 
+/// What the last sync with a totem brought, or why it failed.
+class _SyncStatus extends StatelessWidget {
+  const _SyncStatus();
+
+  @override
+  Widget build(BuildContext context) {
+    final ThemeData theme = Theme.of(context);
+    final syncController = Get.find<SyncController>();
+
+    return Obx(() {
+      final String? error = syncController.syncError.value;
+      final summary = syncController.lastSummary.value;
+      final media = syncController.lastMediaSummary.value;
+      final transfer = syncController.mediaProgress.value;
+
+      final String? message;
+      double? progress;
+      if (transfer != null) {
+        final bool upload = transfer.direction == MediaDirection.upload;
+        message = upload ? 'Uploading a picture…' : 'Downloading a picture…';
+        if (transfer.total > BigInt.zero) {
+          progress = transfer.transferred / transfer.total;
+        }
+      } else if (syncController.syncing.value) {
+        message = 'Syncing with the totem…';
+      } else if (error != null) {
+        message = error;
+      } else if (summary != null && summary.rejected > 0) {
+        message = summary.rejected == 1
+            ? '1 record failed verification and was skipped.'
+            : '${summary.rejected} records failed verification and were skipped.';
+      } else if (media != null && media.failed > 0) {
+        message = media.failed == 1
+            ? '1 picture couldn\'t be transferred, it is retried with the next sync.'
+            : '${media.failed} pictures couldn\'t be transferred, they are retried with the next sync.';
+      } else {
+        message = null;
+      }
+      if (message == null) return const SizedBox.shrink();
+
+      return Padding(
+        padding: const EdgeInsets.fromLTRB(16, 0, 16, 12),
+        child: Column(
+          crossAxisAlignment: CrossAxisAlignment.start,
+          children: <Widget>[
+            Text(
+              message,
+              style: theme.textTheme.bodySmall?.copyWith(
+                color: error != null
+                    ? theme.colorScheme.error
+                    : theme.colorScheme.onSurfaceVariant,
+              ),
+            ),
+            if (syncController.syncing.value) ...<Widget>[
+              const SizedBox(height: 6),
+              LinearProgressIndicator(value: progress),
+            ],
+          ],
+        ),
+      );
+    });
+  }
+}
+
 class _HomeHeader extends StatelessWidget {
   const _HomeHeader({required this.greeting, required this.subtitle});
   final String greeting;
@@ -672,11 +728,24 @@ class _PostCard extends StatelessWidget {
                           ),
                   ),
                 ),
-                title: Text(
-                  author?.name ?? 'Unknown',
-                  style: theme.textTheme.titleMedium?.copyWith(
-                    fontWeight: FontWeight.w700,
-                  ),
+                title: Row(
+                  children: <Widget>[
+                    Flexible(
+                      child: Text(
+                        author?.name ?? 'Unknown',
+                        style: theme.textTheme.titleMedium?.copyWith(
+                          fontWeight: FontWeight.w700,
+                        ),
+                        overflow: TextOverflow.ellipsis,
+                      ),
+                    ),
+                    const SizedBox(width: 4),
+                    Obx(
+                      () => VerificationBadge(
+                        verification: postsController.verifications[post.id],
+                      ),
+                    ),
+                  ],
                 ),
                 subtitle: Text(
                   '${author?.handle ?? ''} • ${post.timeAgoLabel}'.trim(),
@@ -4,6 +4,7 @@ import 'package:loom_app/src/controllers/posts_controller.dart';
 import 'package:loom_app/src/controllers/profiles_controller.dart';
 import 'package:loom_app/src/models/post.dart';
 import 'package:loom_app/src/pages/full_screen_post_page.dart';
+import 'package:loom_app/src/widgets/verification_badge.dart';
 
 class FriendProfilePage extends StatelessWidget {
   const FriendProfilePage({super.key, required this.friendName});
@@ -20,8 +21,9 @@ class FriendProfilePage extends StatelessWidget {
     return Obx(() {
       final profile = profilesController.byName(friendName);
       final bio = profile?.bio ?? '';
+      final String? sighting = profilesController.sightingLabel(profile);
       final recent = postsController.posts
-          .where((Post p) => p.authorId == (profile?.id ?? ''))
+          .where((Post p) => profilesController.isAuthor(profile, p.authorId))
           .take(2)
           .toList(growable: false);
 
@@ -71,17 +73,36 @@ class FriendProfilePage extends StatelessWidget {
                               mainAxisSize: MainAxisSize.min,
                               crossAxisAlignment: CrossAxisAlignment.start,
                               children: <Widget>[
-                                Text(
-                                  friendName,
-                                  maxLines: 1,
-                                  overflow: TextOverflow.ellipsis,
-                                  style: theme.textTheme.headlineSmall?.copyWith(fontWeight: FontWeight.w800),
+                                Row(
+                                  children: <Widget>[
+                                    Flexible(
+                                      child: Text(
+                                        friendName,
+                                        maxLines: 1,
+                                        overflow: TextOverflow.ellipsis,
+                                        style: theme.textTheme.headlineSmall?.copyWith(fontWeight: FontWeight.w800),
+                                      ),
+                                    ),
+                                    const SizedBox(width: 6),
+                                    VerificationBadge(
+                                      verification: profile == null
+                                          ? null
+                                          : profilesController.verifications[profile.id],
+                                    ),
+                                  ],
                                 ),
                                 const SizedBox(height: 4),
                                 Text(
                                   profile?.status ?? '',
                                   style: theme.textTheme.bodyMedium?.copyWith(color: cs.onSurfaceVariant),
                                 ),
+                                if (sighting != null)
+                                  Text(
+                                    sighting,
+                                    maxLines: 1,
+                                    overflow: TextOverflow.ellipsis,
+                                    style: theme.textTheme.bodySmall?.copyWith(color: cs.onSurfaceVariant),
+                                  ),
                               ],
                             ),
                           ),
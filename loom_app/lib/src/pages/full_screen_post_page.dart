@@ -6,6 +6,7 @@ import 'package:loom_app/src/models/post.dart';
 import 'package:loom_app/src/models/profile.dart';
 import 'package:loom_app/src/pages/full_screen_image_page.dart';
 import 'package:loom_app/src/widgets/path_image.dart';
+import 'package:loom_app/src/widgets/verification_badge.dart';
 
 class FullScreenPostPage extends StatelessWidget {
   const FullScreenPostPage({super.key, required this.postId});
@@ -132,9 +133,22 @@ class _PostBody extends StatelessWidget {
                           ),
                   ),
                 ),
-                title: Text(
-                  authorName,
-                  style: theme.textTheme.titleMedium?.copyWith(fontWeight: FontWeight.w700),
+                title: Row(
+                  children: <Widget>[
+                    Flexible(
+                      child: Text(
+                        authorName,
+                        style: theme.textTheme.titleMedium?.copyWith(fontWeight: FontWeight.w700),
+                        overflow: TextOverflow.ellipsis,
+                      ),
+                    ),
+                    const SizedBox(width: 4),
+                    Obx(
+                      () => VerificationBadge(
+                        verification: postsController.verifications[post.id],
+                      ),
+                    ),
+                  ],
                 ),
                 subtitle: Text('${authorHandle.isEmpty ? '' : authorHandle} • ${post.timeAgoLabel}'.trim()),
                 trailing: Obx(() {
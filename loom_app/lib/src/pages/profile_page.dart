@@ -7,6 +7,7 @@ import 'package:loom_app/src/models/post.dart';
 import 'package:loom_app/src/pages/full_screen_image_page.dart';
 import 'package:loom_app/src/pages/full_screen_post_page.dart';
 import 'package:loom_app/src/widgets/path_image.dart';
+import 'package:loom_app/src/widgets/verification_badge.dart';
 
 class ProfilePage extends StatefulWidget {
   const ProfilePage({super.key});
@@ -90,8 +91,9 @@ class _ProfilePageState extends State<ProfilePage> {
       final bio = profile?.bio ?? '';
       final status = profile?.status ?? '';
       final String? currentProfilePicture = profile?.profilePicture;
+      final String? sighting = profilesController.sightingLabel(profile);
       final recent = postsController.posts
-          .where((Post p) => p.authorId == (profile?.id ?? ''))
+          .where((Post p) => profilesController.isAuthor(profile, p.authorId))
           .take(2)
           .toList(growable: false);
 
@@ -197,13 +199,25 @@ class _ProfilePageState extends State<ProfilePage> {
                                     ),
                                   )
                                 else
-                                  Text(
-                                    displayName,
-                                    maxLines: 1,
-                                    overflow: TextOverflow.ellipsis,
-                                    style: theme.textTheme.headlineSmall?.copyWith(
-                                      fontWeight: FontWeight.w800,
-                                    ),
+                                  Row(
+                                    children: <Widget>[
+                                      Flexible(
+                                        child: Text(
+                                          displayName,
+                                          maxLines: 1,
+                                          overflow: TextOverflow.ellipsis,
+                                          style: theme.textTheme.headlineSmall?.copyWith(
+                                            fontWeight: FontWeight.w800,
+                                          ),
+                                        ),
+                                      ),
+                                      const SizedBox(width: 6),
+                                      VerificationBadge(
+                                        verification: profile == null
+                                            ? null
+                                            : profilesController.verifications[profile.id],
+                                      ),
+                                    ],
                                   ),
                                 const SizedBox(height: 4),
                                 if (_isEditing)
@@ -226,6 +240,15 @@ class _ProfilePageState extends State<ProfilePage> {
                                       color: cs.onSurfaceVariant,
                                     ),
                                   ),
+                                if (!_isEditing && sighting != null)
+                                  Text(
+                                    sighting,
+                                    maxLines: 1,
+                                    overflow: TextOverflow.ellipsis,
+                                    style: theme.textTheme.bodySmall?.copyWith(
+                                      color: cs.onSurfaceVariant,
+                                    ),
+                                  ),
                               ],
                             ),
                           ),
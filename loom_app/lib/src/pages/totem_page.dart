@@ -0,0 +1,267 @@
+import 'package:flutter/material.dart';
+import 'package:get/get.dart';
+import 'package:loom_app/src/controllers/profiles_controller.dart';
+import 'package:loom_app/src/controllers/totems_controller.dart';
+import 'package:loom_app/src/models/profile.dart';
+import 'package:loom_app/src/models/totem.dart';
+import 'package:loom_app/src/pages/friend_profile_page.dart';
+import 'package:loom_app/src/pages/full_screen_post_page.dart';
+import 'package:loom_app/src/rust/api/simple.dart' as rust;
+import 'package:loom_app/src/rust/api/trending.dart' as rust_trending;
+
+class TotemPage extends StatefulWidget {
+  const TotemPage({super.key, required this.totem});
+
+  final Totem totem;
+
+  static Future<void> open(BuildContext context, Totem totem) {
+    return Navigator.of(context).push(
+      MaterialPageRoute<void>(builder: (_) => TotemPage(totem: totem)),
+    );
+  }
+
+  @override
+  State<TotemPage> createState() => _TotemPageState();
+}
+
+class _TotemPageState extends State<TotemPage> {
+  final TotemsController controller = Get.find<TotemsController>();
+
+  @override
+  void initState() {
+    super.initState();
+    controller.loadTrending(widget.totem);
+    controller.loadVisitors(widget.totem);
+  }
+
+  Future<void> _connect() async {
+    final bool ok = await controller.connectToTotemWifi(widget.totem);
+    if (ok) {
+      await controller.loadTrending(widget.totem);
+    }
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    final ThemeData theme = Theme.of(context);
+    final Totem totem = widget.totem;
+
+    return Scaffold(
+      backgroundColor: theme.colorScheme.surface,
+      appBar: AppBar(title: Text(totem.name)),
+      body: RefreshIndicator(
+        onRefresh: () => Future.wait(<Future<void>>[
+          controller.loadTrending(totem),
+          controller.loadVisitors(totem),
+        ]),
+        child: Obx(() {
+          final rust_trending.TrendingTopics? topics =
+              controller.trending.value;
+          final String? error = controller.trendingError.value;
+
+          return ListView(
+            physics: const AlwaysScrollableScrollPhysics(
+              parent: BouncingScrollPhysics(),
+            ),
+            padding: const EdgeInsets.fromLTRB(16, 12, 16, 80),
+            children: <Widget>[
+              if (totem.description.isNotEmpty) ...<Widget>[
+                Text(totem.description, style: theme.textTheme.bodyMedium),
+                const SizedBox(height: 16),
+              ],
+              Text(
+                'Seen here today',
+                style: theme.textTheme.titleMedium?.copyWith(
+                  fontWeight: FontWeight.w700,
+                ),
+              ),
+              const SizedBox(height: 8),
+              _Visitors(visitors: controller.visitors.toList()),
+              const SizedBox(height: 16),
+              Text(
+                'Trending',
+                style: theme.textTheme.titleMedium?.copyWith(
+                  fontWeight: FontWeight.w700,
+                ),
+              ),
+              const SizedBox(height: 8),
+              if (controller.trendingLoading.value && topics == null)
+                const Padding(
+                  padding: EdgeInsets.all(24),
+                  child: Center(child: CircularProgressIndicator()),
+                )
+              else if (error != null)
+                _MessageCard(
+                  message: error,
+                  action: TextButton.icon(
+                    onPressed: controller.wifiConnectInProgress.value
+                        ? null
+                        : _connect,
+                    icon: const Icon(Icons.wifi_rounded, size: 18),
+                    label: const Text('Connect'),
+                  ),
+                )
+              else if (topics != null) ...<Widget>[
+                _TrendingTags(tags: topics.tags),
+                const SizedBox(height: 16),
+                _TrendingThreads(threads: topics.threads),
+              ],
+            ],
+          );
+        }),
+      ),
+    );
+  }
+}
+
+class _MessageCard extends StatelessWidget {
+  const _MessageCard({required this.message, this.action});
+
+  final String message;
+  final Widget? action;
+
+  @override
+  Widget build(BuildContext context) {
+    return Card(
+      elevation: 0,
+      shape: RoundedRectangleBorder(borderRadius: BorderRadius.circular(18)),
+      child: Padding(
+        padding: const EdgeInsets.all(16),
+        child: Column(
+          crossAxisAlignment: CrossAxisAlignment.start,
+          children: <Widget>[
+            Text(message),
+            if (action != null) ...<Widget>[
+              const SizedBox(height: 8),
+              Align(alignment: Alignment.centerRight, child: action),
+            ],
+          ],
+        ),
+      ),
+    );
+  }
+}
+
+class _Visitors extends StatelessWidget {
+  const _Visitors({required this.visitors});
+
+  final List<rust.LastSeen> visitors;
+
+  @override
+  Widget build(BuildContext context) {
+    final ProfilesController profilesController =
+        Get.find<ProfilesController>();
+
+    // A user may show up under the random ID from before identities too
+    final Map<String, Profile> seen = <String, Profile>{};
+    for (final rust.LastSeen visitor in visitors) {
+      final Profile? profile = profilesController.byId(visitor.userId);
+      if (profile != null) seen.putIfAbsent(profile.id, () => profile);
+    }
+    if (seen.isEmpty) {
+      return const _MessageCard(message: 'Nobody you know was seen here today.');
+    }
+
+    return Wrap(
+      spacing: 8,
+      runSpacing: 8,
+      children: seen.values
+          .map(
+            (Profile profile) => ActionChip(
+              avatar: CircleAvatar(child: Text(_initial(profile.name))),
+              label: Text(profile.name),
+              shape: RoundedRectangleBorder(
+                borderRadius: BorderRadius.circular(18),
+              ),
+              onPressed: profile.isCurrentUser
+                  ? null
+                  : () => Navigator.of(context).push(
+                      MaterialPageRoute<FriendProfilePage>(
+                        builder: (BuildContext _) =>
+                            FriendProfilePage(friendName: profile.name),
+                      ),
+                    ),
+            ),
+          )
+          .toList(growable: false),
+    );
+  }
+}
+
+String _initial(String value) {
+  final String trimmed = value.trim();
+  if (trimmed.isEmpty) return '?';
+  return String.fromCharCode(trimmed.runes.first).toUpperCase();
+}
+
+class _TrendingTags extends StatelessWidget {
+  const _TrendingTags({required this.tags});
+
+  final List<rust_trending.TrendingTag> tags;
+
+  @override
+  Widget build(BuildContext context) {
+    if (tags.isEmpty) {
+      return const _MessageCard(message: 'No hashtags in the last day.');
+    }
+
+    return Wrap(
+      spacing: 8,
+      runSpacing: 8,
+      children: tags
+          .map(
+            (rust_trending.TrendingTag tag) => Chip(
+              label: Text('#${tag.tag} · ${tag.posts}'),
+              shape: RoundedRectangleBorder(
+                borderRadius: BorderRadius.circular(18),
+              ),
+            ),
+          )
+          .toList(growable: false),
+    );
+  }
+}
+
+class _TrendingThreads extends StatelessWidget {
+  const _TrendingThreads({required this.threads});
+
+  final List<rust_trending.TrendingThread> threads;
+
+  @override
+  Widget build(BuildContext context) {
+    final ThemeData theme = Theme.of(context);
+    if (threads.isEmpty) {
+      return const _MessageCard(message: 'No active threads in the last day.');
+    }
+
+    return Card(
+      clipBehavior: Clip.antiAlias,
+      elevation: 0,
+      shape: RoundedRectangleBorder(borderRadius: BorderRadius.circular(18)),
+      child: Column(
+        children: threads.map((rust_trending.TrendingThread thread) {
+          final String title = thread.post?.title ?? '';
+          final int replies = thread.replies;
+          return ListTile(
+            leading: Icon(
+              Icons.forum_outlined,
+              color: theme.colorScheme.primary,
+            ),
+            // Posts that weren't synced yet arrive with the next sync.
+            title: Text(
+              thread.post == null
+                  ? 'Not synced yet'
+                  : (title.isEmpty ? thread.post!.body : title),
+              maxLines: 2,
+              overflow: TextOverflow.ellipsis,
+            ),
+            subtitle: Text(replies == 1 ? '1 reply' : '$replies replies'),
+            onTap: thread.post == null
+                ? null
+                : () => FullScreenPostPage.open(context, thread.postId),
+          );
+        }).toList(growable: false),
+      ),
+    );
+  }
+}
@@ -2,6 +2,7 @@ import 'package:flutter/material.dart';
 import 'package:get/get.dart';
 import 'package:loom_app/src/controllers/totems_controller.dart';
 import 'package:loom_app/src/models/totem.dart';
+import 'package:loom_app/src/pages/totem_page.dart';
 
 IconData _signalIconFor(int strength) {
   final int s = strength.clamp(0, 4);
@@ -130,68 +131,71 @@ class _TotemCard extends StatelessWidget {
       shape: RoundedRectangleBorder(borderRadius: BorderRadius.circular(18)),
       elevation: 0,
       color: theme.colorScheme.surface,
-      child: Padding(
-        padding: const EdgeInsets.symmetric(horizontal: 16, vertical: 12),
-        child: LayoutBuilder(
-          builder: (BuildContext context, BoxConstraints constraints) {
-            final bool isNarrow = constraints.maxWidth < 420;
-            final Widget details = Column(
-              crossAxisAlignment: CrossAxisAlignment.start,
-              children: <Widget>[
-                Row(
-                  children: <Widget>[
-                    Expanded(
-                      child: Text(
-                        'Name: ${totem.name}',
-                        style: theme.textTheme.titleSmall?.copyWith(
-                          fontWeight: FontWeight.w700,
+      child: InkWell(
+        onTap: () => TotemPage.open(context, totem),
+        child: Padding(
+          padding: const EdgeInsets.symmetric(horizontal: 16, vertical: 12),
+          child: LayoutBuilder(
+            builder: (BuildContext context, BoxConstraints constraints) {
+              final bool isNarrow = constraints.maxWidth < 420;
+              final Widget details = Column(
+                crossAxisAlignment: CrossAxisAlignment.start,
+                children: <Widget>[
+                  Row(
+                    children: <Widget>[
+                      Expanded(
+                        child: Text(
+                          'Name: ${totem.name}',
+                          style: theme.textTheme.titleSmall?.copyWith(
+                            fontWeight: FontWeight.w700,
+                          ),
                         ),
                       ),
-                    ),
-                    const SizedBox(width: 8),
-                  ],
-                ),
-                const SizedBox(height: 4),
-                Text(
-                  'Description: ${totem.description}',
-                  style: theme.textTheme.bodyMedium,
-                  softWrap: true,
+                      const SizedBox(width: 8),
+                    ],
+                  ),
+                  const SizedBox(height: 4),
+                  Text(
+                    'Description: ${totem.description}',
+                    style: theme.textTheme.bodyMedium,
+                    softWrap: true,
+                  ),
+                ],
+              );
+
+              final Widget connect = ElevatedButton.icon(
+                onPressed: () {
+                  Get.find<TotemsController>().connectToTotemWifi(totem);
+                },
+                icon: Icon(
+                  _signalIconFor(totem.signalStrength),
+                  size: 18,
+                  color: theme.colorScheme.primary,
                 ),
-              ],
-            );
+                label: const Text('Connect'),
+              );
 
-            final Widget connect = ElevatedButton.icon(
-              onPressed: () {
-                Get.find<TotemsController>().connectToTotemWifi(totem);
-              },
-              icon: Icon(
-                _signalIconFor(totem.signalStrength),
-                size: 18,
-                color: theme.colorScheme.primary,
-              ),
-              label: const Text('Connect'),
-            );
+              if (isNarrow) {
+                return Column(
+                  crossAxisAlignment: CrossAxisAlignment.start,
+                  children: <Widget>[
+                    details,
+                    const SizedBox(height: 12),
+                    Align(alignment: Alignment.centerRight, child: connect),
+                  ],
+                );
+              }
 
-            if (isNarrow) {
-              return Column(
+              return Row(
                 crossAxisAlignment: CrossAxisAlignment.start,
                 children: <Widget>[
-                  details,
-                  const SizedBox(height: 12),
-                  Align(alignment: Alignment.centerRight, child: connect),
+                  Expanded(child: details),
+                  const SizedBox(width: 16),
+                  connect,
                 ],
               );
-            }
-
-            return Row(
-              crossAxisAlignment: CrossAxisAlignment.start,
-              children: <Widget>[
-                Expanded(child: details),
-                const SizedBox(width: 16),
-                connect,
-              ],
-            );
-          },
+            },
+          ),
         ),
       ),
     );
@@ -0,0 +1,60 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`
+
+
+            /// Exchange bundles with a totem
+///
+/// With `courier` set the phone carries bundles addressed to others, too. Bundles addressed
+/// to this phone's user are only taken while the identity is unlocked.
+/// `totem_url` defaults to the address of a totem on its own access point.
+Future<BundleSyncSummary>  syncBundles({required AppDatabase db , String? totemUrl , required bool courier }) => RustLib.instance.api.crateApiBundlesSyncBundles(db: db, totemUrl: totemUrl, courier: courier);
+
+/// Have phones carry a stored post to a totem that is out of reach
+///
+/// The post is handed over to couriers at the next syncs until one drops it off there.
+/// Returns the UUID of the bundle.
+Future<String>  sendPostToTotem({required AppDatabase db , required String postUuid , required String totemId }) => RustLib.instance.api.crateApiBundlesSendPostToTotem(db: db, postUuid: postUuid, totemId: totemId);
+
+            class BundleSyncSummary  {
+                /// Bundles addressed to this phone's user
+final int delivered;
+/// Bundles taken to be carried for others
+final int taken;
+/// Bundles handed over to the totem
+final int handedOver;
+/// Bundles the totem didn't take, they are kept for the next one
+final int refused;
+/// Bundles that failed validation, or whose content did
+final int rejected;
+/// Carried bundles that expired or were used up
+final int dropped;
+
+                const BundleSyncSummary({required this.delivered ,required this.taken ,required this.handedOver ,required this.refused ,required this.rejected ,required this.dropped ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => delivered.hashCode^taken.hashCode^handedOver.hashCode^refused.hashCode^rejected.hashCode^dropped.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is BundleSyncSummary &&
+                runtimeType == other.runtimeType
+                && delivered == other.delivered&& taken == other.taken&& handedOver == other.handedOver&& refused == other.refused&& rejected == other.rejected&& dropped == other.dropped;
+        
+            }
+            
\ No newline at end of file
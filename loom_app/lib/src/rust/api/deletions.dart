@@ -0,0 +1,21 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These functions are ignored because they are not marked as `pub`: `bury`
+
+
+            /// Delete a post written by this phone's user, here and on every totem and phone the
+/// deletion reaches, see `shared::model::tombstone`
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+Future<void>  deletePost({required AppDatabase db , required String uuid }) => RustLib.instance.api.crateApiDeletionsDeletePost(db: db, uuid: uuid);
+
+            
+            
\ No newline at end of file
@@ -0,0 +1,52 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`, `from`
+
+
+            /// Change the title and body of a post written by this phone's user
+/// The edit is synced like a new post, the versions it replaces stay in the history.
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+Future<Post>  editPost({required AppDatabase db , required String uuid , required String title , required String body }) => RustLib.instance.api.crateApiEditsEditPost(db: db, uuid: uuid, title: title, body: body);
+
+/// Every known version of a post, oldest first
+/// Posts that were never edited have a single one.
+Future<List<PostRevision>>  getPostHistory({required AppDatabase db , required String uuid }) => RustLib.instance.api.crateApiEditsGetPostHistory(db: db, uuid: uuid);
+
+            /// A version of a post, see `shared::model::edit`
+class PostRevision  {
+                /// 0 for the post as first written
+final int revision;
+/// `None` for the post as first written
+final DateTime? editedAt;
+final String title;
+final String body;
+
+                const PostRevision({required this.revision ,this.editedAt ,required this.title ,required this.body ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => revision.hashCode^editedAt.hashCode^title.hashCode^body.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is PostRevision &&
+                runtimeType == other.runtimeType
+                && revision == other.revision&& editedAt == other.editedAt&& title == other.title&& body == other.body;
+        
+            }
+            
\ No newline at end of file
@@ -0,0 +1,90 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These functions are ignored because they are not marked as `pub`: `install`, `load_existing`, `load_or_create`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`, `from`
+
+
+            /// The identity stored in `key_dir`, if this phone has one
+///
+/// `key_dir` has to be private to the app, like the application support directory.
+Future<IdentityInfo?>  loadIdentity({required String keyDir }) => RustLib.instance.api.crateApiIdentityLoadIdentity(keyDir: keyDir);
+
+/// Load the identity stored in `key_dir` to sign this user's records with
+///
+/// Has to be called on every start before posts are created. Returns `None` if the phone
+/// has no identity yet, its user's records stay unsigned then.
+Future<IdentityInfo?>  unlockIdentity({required AppDatabase db , required String keyDir }) => RustLib.instance.api.crateApiIdentityUnlockIdentity(db: db, keyDir: keyDir);
+
+/// Create the profile of this phone's user, under the ID of their identity
+///
+/// The `uuid` of `user` is replaced by the ID derived from the identity's public key.
+Future<IdentityInfo>  registerUser({required AppDatabase db , required String keyDir , required User user }) => RustLib.instance.api.crateApiIdentityRegisterUser(db: db, keyDir: keyDir, user: user);
+
+/// Move a user with a random ID from before identities existed to a derived ID
+///
+/// Their profile is taken over by the new ID and signed with the identity. Their posts
+/// and sightings stay with the random ID, like the copies on totems and other phones: the
+/// UUIDs of the posts are known there already, signed under the new ID they would be
+/// versions of the same post by another author. The random ID is linked to the new one
+/// instead, so the old records lead to the new profile on this phone.
+Future<IdentityInfo>  migrateIdentity({required AppDatabase db , required String keyDir , required String legacyUserId }) => RustLib.instance.api.crateApiIdentityMigrateIdentity(db: db, keyDir: keyDir, legacyUserId: legacyUserId);
+
+/// The secret key as 24 words to write down
+///
+/// Anyone who has the words can post as the user, they must only be shown on request.
+Future<String>  exportMnemonic({required String keyDir }) => RustLib.instance.api.crateApiIdentityExportMnemonic(keyDir: keyDir);
+
+/// Restore the identity from the words of [`export_mnemonic`], on a new phone
+///
+/// The user's profile and posts come back with the next sync, they are stored on totems
+/// under the same ID.
+Future<IdentityInfo>  restoreFromMnemonic({required AppDatabase db , required String keyDir , required String phrase }) => RustLib.instance.api.crateApiIdentityRestoreFromMnemonic(db: db, keyDir: keyDir, phrase: phrase);
+
+/// The secret key encrypted with `passphrase`, to be saved as a file
+///
+/// Takes a moment on purpose, the passphrase goes through a memory-hard key derivation.
+Future<Uint8List>  exportBackup({required String keyDir , required String passphrase }) => RustLib.instance.api.crateApiIdentityExportBackup(keyDir: keyDir, passphrase: passphrase);
+
+/// Restore the identity from a file of [`export_backup`], on a new phone
+Future<IdentityInfo>  restoreFromBackup({required AppDatabase db , required String keyDir , required List<int> file , required String passphrase }) => RustLib.instance.api.crateApiIdentityRestoreFromBackup(db: db, keyDir: keyDir, file: file, passphrase: passphrase);
+
+/// Register a new recovery key for this phone's user, returned as 24 words to write down
+///
+/// The words can revoke the user's key if the phone is stolen, they are never stored on
+/// the phone. The registration reaches the totems with the next sync.
+Future<String>  createRecoveryKey({required AppDatabase db , required String keyDir }) => RustLib.instance.api.crateApiIdentityCreateRecoveryKey(db: db, keyDir: keyDir);
+
+            /// The public part of this phone's identity
+class IdentityInfo  {
+                /// Derived from the public key, used as the user's UUID everywhere
+final String userId;
+final String publicKey;
+
+                const IdentityInfo({required this.userId ,required this.publicKey ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => userId.hashCode^publicKey.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is IdentityInfo &&
+                runtimeType == other.runtimeType
+                && userId == other.userId&& publicKey == other.publicKey;
+        
+            }
+            
\ No newline at end of file
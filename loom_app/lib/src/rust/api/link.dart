@@ -0,0 +1,85 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'identity.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These functions are ignored because they are not marked as `pub`: `accept`, `new`, `path_str`, `seal`
+// These types are ignored because they are neither used by any `pub` functions nor (for structs and enums) marked `#[frb(unignore)]`: `TempFile`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `drop`, `fmt`
+
+
+            /// Take over the identity of the device showing `qr_code`, over the local network
+///
+/// Its posts and profiles are added to this phone's if it sent them along. `cache_dir`
+/// holds them while they are read.
+Future<LinkSummary>  linkOverNetwork({required AppDatabase db , required String keyDir , required String cacheDir , required String qrCode }) => RustLib.instance.api.crateApiLinkLinkOverNetwork(db: db, keyDir: keyDir, cacheDir: cacheDir, qrCode: qrCode);
+
+/// Take over the identity of the device showing `qr_code`, from a file of [`LinkOffer::export`]
+Future<LinkSummary>  linkFromFile({required AppDatabase db , required String keyDir , required String cacheDir , required String qrCode , required List<int> file }) => RustLib.instance.api.crateApiLinkLinkFromFile(db: db, keyDir: keyDir, cacheDir: cacheDir, qrCode: qrCode, file: file);
+
+            
+                // Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<LinkOffer>>
+                abstract class LinkOffer implements RustOpaqueInterface {
+                    /// Stop waiting in [`LinkOffer::send`]
+ void  cancel();
+
+
+/// The identity sealed for the scanning device, to be passed on as a file
+ Future<Uint8List>  export_({required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase });
+
+
+/// `local_ip` is this phone's address on the local network
+/// Without one the QR code has no address and the transfer has to go through a file.
+factory LinkOffer({String? localIp })=>RustLib.instance.api.crateApiLinkLinkOfferNew(localIp: localIp);
+
+
+/// The text of the QR code to show, it must not be shown to anyone else
+ String  qrCode();
+
+
+/// Wait up to `timeout_secs` for the scanning device and hand it the identity
+///
+/// With `include_database` it also gets a copy of this phone's posts and profiles.
+/// `cache_dir` holds the copy while it is being sent.
+ Future<void>  send({required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase , required int timeoutSecs });
+
+
+
+                    
+                }
+                
+
+/// Result of linking this phone to the identity of another device
+class LinkSummary  {
+                final IdentityInfo identity;
+final int usersImported;
+final int postsImported;
+/// Records of the other device's database that failed validation
+final int rejected;
+
+                const LinkSummary({required this.identity ,required this.usersImported ,required this.postsImported ,required this.rejected ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => identity.hashCode^usersImported.hashCode^postsImported.hashCode^rejected.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is LinkSummary &&
+                runtimeType == other.runtimeType
+                && identity == other.identity&& usersImported == other.usersImported&& postsImported == other.postsImported&& rejected == other.rejected;
+        
+            }
+            
\ No newline at end of file
@@ -0,0 +1,186 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These functions are ignored because they are not marked as `pub`: `store_image`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `assert_fields_are_eq`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `eq`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `from`, `from`
+
+
+            /// Transfer the pictures referenced by posts and users that are missing on either side
+///
+/// Run this after posts and users have been reconciled with the totem. Downloaded blobs
+/// are stored in `media_dir` and recorded in the database, see [`AppDatabase::get_media_path`].
+/// `totem_url` defaults to the address of a totem on its own access point.
+Future<MediaSyncSummary>  syncMedia({required AppDatabase db , required String mediaDir , String? totemUrl , required FutureOr<void> Function(MediaSyncProgress) onProgress }) => RustLib.instance.api.crateApiMediaSyncMedia(db: db, mediaDir: mediaDir, totemUrl: totemUrl, onProgress: onProgress);
+
+ImageOptions  defaultImageOptions() => RustLib.instance.api.crateApiMediaDefaultImageOptions();
+
+/// Downscale a picture picked by the user, strip its metadata and store it with a thumbnail
+///
+/// `data` may be a JPEG, PNG or WebP file. Both variants are re-encoded as JPEG, stored
+/// in `media_dir` under their content hash and recorded in the database.
+Future<PreparedImage>  prepareImage({required AppDatabase db , required List<int> data , required String mediaDir , ImageOptions? options }) => RustLib.instance.api.crateApiMediaPrepareImage(db: db, data: data, mediaDir: mediaDir, options: options);
+
+/// Store a clip, voice note or file picked by the user, to be attached to a post
+///
+/// Pictures go through [`prepare_image`] first, so they lose their metadata like everywhere
+/// else. The MIME type is recognised from the content, unknown types are rejected.
+Future<Attachment>  storeAttachment({required AppDatabase db , required List<int> data , required String mediaDir , required AttachmentKind kind , int? durationMs , String? altText }) => RustLib.instance.api.crateApiMediaStoreAttachment(db: db, data: data, mediaDir: mediaDir, kind: kind, durationMs: durationMs, altText: altText);
+
+            /// How pictures are downscaled and compressed before they are stored
+class ImageOptions  {
+                /// Longest side of the stored picture in pixels
+final int maxDimension;
+/// Longest side of the thumbnail shown in feeds
+final int thumbnailDimension;
+/// JPEG quality from 1 to 100
+final int quality;
+
+                const ImageOptions({required this.maxDimension ,required this.thumbnailDimension ,required this.quality ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => maxDimension.hashCode^thumbnailDimension.hashCode^quality.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is ImageOptions &&
+                runtimeType == other.runtimeType
+                && maxDimension == other.maxDimension&& thumbnailDimension == other.thumbnailDimension&& quality == other.quality;
+        
+            }
+
+enum MediaDirection {
+                    upload,
+download,
+                    ;
+                    
+                }
+
+/// Progress of the transfer of a single blob, reported after every piece
+class MediaSyncProgress  {
+                final String hash;
+final MediaDirection direction;
+final BigInt transferred;
+/// Size of the blob, 0 until it is known
+final BigInt total;
+/// The transfer is over, successfully unless `error` is set
+final bool done;
+final String? error;
+
+                const MediaSyncProgress({required this.hash ,required this.direction ,required this.transferred ,required this.total ,required this.done ,this.error ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => hash.hashCode^direction.hashCode^transferred.hashCode^total.hashCode^done.hashCode^error.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is MediaSyncProgress &&
+                runtimeType == other.runtimeType
+                && hash == other.hash&& direction == other.direction&& transferred == other.transferred&& total == other.total&& done == other.done&& error == other.error;
+        
+            }
+
+class MediaSyncSummary  {
+                final int uploaded;
+final int downloaded;
+final int failed;
+
+                const MediaSyncSummary({required this.uploaded ,required this.downloaded ,required this.failed ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => uploaded.hashCode^downloaded.hashCode^failed.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is MediaSyncSummary &&
+                runtimeType == other.runtimeType
+                && uploaded == other.uploaded&& downloaded == other.downloaded&& failed == other.failed;
+        
+            }
+
+/// A picture ready to be referenced by a post or profile
+/// See [`AppDatabase::create_post_with_image`] and [`AppDatabase::set_profile_picture`].
+class PreparedImage  {
+                final StoredImage image;
+final StoredImage thumbnail;
+/// Blurred placeholder shown until the picture has been downloaded
+final String blurhash;
+
+                const PreparedImage({required this.image ,required this.thumbnail ,required this.blurhash ,});
+
+                /// The stored picture as an attachment of a post
+ Attachment  attachment({String? altText })=>RustLib.instance.api.crateApiMediaPreparedImageAttachment(that: this, altText: altText);
+
+
+                
+
+                
+        @override
+        int get hashCode => image.hashCode^thumbnail.hashCode^blurhash.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is PreparedImage &&
+                runtimeType == other.runtimeType
+                && image == other.image&& thumbnail == other.thumbnail&& blurhash == other.blurhash;
+        
+            }
+
+/// A picture in the media store of this phone
+class StoredImage  {
+                final String hash;
+final String path;
+final int width;
+final int height;
+final BigInt size;
+
+                const StoredImage({required this.hash ,required this.path ,required this.width ,required this.height ,required this.size ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => hash.hashCode^path.hashCode^width.hashCode^height.hashCode^size.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is StoredImage &&
+                runtimeType == other.runtimeType
+                && hash == other.hash&& path == other.path&& width == other.width&& height == other.height&& size == other.size;
+        
+            }
+            
\ No newline at end of file
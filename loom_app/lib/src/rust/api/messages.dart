@@ -0,0 +1,120 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These functions are ignored because they are not marked as `pub`: `decrypt`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `clone`, `clone`, `fmt`, `fmt`, `fmt`
+
+
+            /// Encrypt `text` to another user and store it until it is synced
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+Future<DirectMessage>  sendMessage({required AppDatabase db , required String recipientId , required String text }) => RustLib.instance.api.crateApiMessagesSendMessage(db: db, recipientId: recipientId, text: text);
+
+/// The messages exchanged with another user, oldest first
+Future<List<DirectMessage>>  getConversation({required AppDatabase db , required String userId }) => RustLib.instance.api.crateApiMessagesGetConversation(db: db, userId: userId);
+
+/// Every user messages were exchanged with, most recent conversation first
+Future<List<Conversation>>  listConversations({required AppDatabase db }) => RustLib.instance.api.crateApiMessagesListConversations(db: db);
+
+/// Collect the messages waiting at a totem and leave the undelivered ones for their recipients
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+/// `totem_url` defaults to the address of a totem on its own access point.
+Future<MessageSyncSummary>  syncMessages({required AppDatabase db , String? totemUrl }) => RustLib.instance.api.crateApiMessagesSyncMessages(db: db, totemUrl: totemUrl);
+
+            /// The latest message exchanged with another user
+class Conversation  {
+                final String userId;
+final DirectMessage lastMessage;
+final int messageCount;
+
+                const Conversation({required this.userId ,required this.lastMessage ,required this.messageCount ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => userId.hashCode^lastMessage.hashCode^messageCount.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is Conversation &&
+                runtimeType == other.runtimeType
+                && userId == other.userId&& lastMessage == other.lastMessage&& messageCount == other.messageCount;
+        
+            }
+
+/// A direct message, decrypted if this phone can read it
+class DirectMessage  {
+                final String uuid;
+final String senderId;
+final String recipientId;
+final DateTime timestamp;
+/// `None` if it was encrypted to a key this phone doesn't have, or was altered
+final String? text;
+/// Whether the sender wrote it with their own key
+final Verification verification;
+/// Whether the recipient collected it, only known for messages of this phone's user
+final bool delivered;
+
+                const DirectMessage({required this.uuid ,required this.senderId ,required this.recipientId ,required this.timestamp ,this.text ,required this.verification ,required this.delivered ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => uuid.hashCode^senderId.hashCode^recipientId.hashCode^timestamp.hashCode^text.hashCode^verification.hashCode^delivered.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is DirectMessage &&
+                runtimeType == other.runtimeType
+                && uuid == other.uuid&& senderId == other.senderId&& recipientId == other.recipientId&& timestamp == other.timestamp&& text == other.text&& verification == other.verification&& delivered == other.delivered;
+        
+            }
+
+class MessageSyncSummary  {
+                final int received;
+final int sent;
+/// Messages of this phone's user that turned out to be delivered
+final int delivered;
+/// Messages the totem had no room for, they are tried again at the next totem
+final int refused;
+/// Messages and receipts that failed validation or decryption
+final int rejected;
+
+                const MessageSyncSummary({required this.received ,required this.sent ,required this.delivered ,required this.refused ,required this.rejected ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => received.hashCode^sent.hashCode^delivered.hashCode^refused.hashCode^rejected.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is MessageSyncSummary &&
+                runtimeType == other.runtimeType
+                && received == other.received&& sent == other.sent&& delivered == other.delivered&& refused == other.refused&& rejected == other.rejected;
+        
+            }
+            
\ No newline at end of file
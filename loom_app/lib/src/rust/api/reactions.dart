@@ -0,0 +1,56 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These functions are ignored because they are not marked as `pub`: `post_reactions`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `assert_fields_are_eq`, `clone`, `clone`, `eq`, `fmt`, `fmt`, `from`, `from`
+
+
+            /// Like or share a post as this phone's user, or take it back with `active` unset
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+Future<PostReactions>  react({required AppDatabase db , required String postId , required ReactionKind kind , required bool active }) => RustLib.instance.api.crateApiReactionsReact(db: db, postId: postId, kind: kind, active: active);
+
+Future<PostReactions>  getPostReactions({required AppDatabase db , required String postId }) => RustLib.instance.api.crateApiReactionsGetPostReactions(db: db, postId: postId);
+
+            /// Reactions to a post as far as this phone knows them
+class PostReactions  {
+                final int likes;
+final int shares;
+/// Whether this phone's user likes the post, false while the identity is locked
+final bool liked;
+final bool shared;
+
+                const PostReactions({required this.likes ,required this.shares ,required this.liked ,required this.shared ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => likes.hashCode^shares.hashCode^liked.hashCode^shared.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is PostReactions &&
+                runtimeType == other.runtimeType
+                && likes == other.likes&& shares == other.shares&& liked == other.liked&& shared == other.shared;
+        
+            }
+
+enum ReactionKind {
+                    like,
+share,
+                    ;
+                    
+                }
+            
\ No newline at end of file
@@ -0,0 +1,60 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These functions are ignored because they are not marked as `pub`: `thread_reply`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`
+
+
+            /// Answer a post or a reply as this phone's user
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+Future<ThreadReply>  replyTo({required AppDatabase db , required String parentId , required String body }) => RustLib.instance.api.crateApiRepliesReplyTo(db: db, parentId: parentId, body: body);
+
+/// The replies to a post in thread order, each followed by its answers
+Future<List<ThreadReply>>  getThread({required AppDatabase db , required String postId }) => RustLib.instance.api.crateApiRepliesGetThread(db: db, postId: postId);
+
+/// Number of replies in the thread of a post, including ones whose parent didn't arrive yet
+Future<int>  getReplyCount({required AppDatabase db , required String postId }) => RustLib.instance.api.crateApiRepliesGetReplyCount(db: db, postId: postId);
+
+            /// A reply in its place in the thread of a post
+class ThreadReply  {
+                final String uuid;
+final String userId;
+final String parentId;
+final String rootId;
+final String body;
+final DateTime timestamp;
+/// 1 for replies to the post, 2 for replies to those and so on
+final int depth;
+/// The reply it answers didn't arrive yet, it is shown as a reply to the post until then
+final bool parentMissing;
+/// Whether the author wrote it with their own key
+final Verification verification;
+
+                const ThreadReply({required this.uuid ,required this.userId ,required this.parentId ,required this.rootId ,required this.body ,required this.timestamp ,required this.depth ,required this.parentMissing ,required this.verification ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => uuid.hashCode^userId.hashCode^parentId.hashCode^rootId.hashCode^body.hashCode^timestamp.hashCode^depth.hashCode^parentMissing.hashCode^verification.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is ThreadReply &&
+                runtimeType == other.runtimeType
+                && uuid == other.uuid&& userId == other.userId&& parentId == other.parentId&& rootId == other.rootId&& body == other.body&& timestamp == other.timestamp&& depth == other.depth&& parentMissing == other.parentMissing&& verification == other.verification;
+        
+            }
+            
\ No newline at end of file
@@ -0,0 +1,56 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`
+
+
+            /// Pass someone's post on as this phone's user
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+Future<FeedItem>  repost({required AppDatabase db , required String postId }) => RustLib.instance.api.crateApiRepostsRepost(db: db, postId: postId);
+
+/// Posts and reposts, newest first
+/// Reposts that attribute a post to someone who didn't write it, or of posts their
+/// author deleted, are left out.
+Future<List<FeedItem>>  getFeed({required AppDatabase db }) => RustLib.instance.api.crateApiRepostsGetFeed(db: db);
+
+            /// A post in the feed, either as written or as passed on by someone
+class FeedItem  {
+                final String postId;
+/// `None` for a repost whose post didn't arrive yet, it is fetched at the next syncs
+final Post? post;
+/// Who wrote the post
+final String authorId;
+/// Who reposted it, `None` for the post itself
+final String? repostedBy;
+final String? repostUuid;
+/// When it was posted or reposted
+final DateTime timestamp;
+
+                const FeedItem({required this.postId ,this.post ,required this.authorId ,this.repostedBy ,this.repostUuid ,required this.timestamp ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => postId.hashCode^post.hashCode^authorId.hashCode^repostedBy.hashCode^repostUuid.hashCode^timestamp.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is FeedItem &&
+                runtimeType == other.runtimeType
+                && postId == other.postId&& post == other.post&& authorId == other.authorId&& repostedBy == other.repostedBy&& repostUuid == other.repostUuid&& timestamp == other.timestamp;
+        
+            }
+            
\ No newline at end of file
@@ -4,153 +4,385 @@
 // ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
 
 import '../frb_generated.dart';
+import 'media.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 
-// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `clone`, `clone`, `fmt`, `fmt`, `fmt`, `from`, `from`, `from`, `into`, `into`, `into`
-
-String greet({required String name}) =>
-    RustLib.instance.api.crateApiSimpleGreet(name: name);
-
-// Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<AppDatabase>>
-abstract class AppDatabase implements RustOpaqueInterface {
-  Future<void> createPost({required Post post});
-
-  Future<void> createTotem({required Totem totem});
-
-  Future<void> createUser({required User user});
-
-  Future<List<Post>> getAllPosts();
-
-  Future<List<Totem>> getAllTotems();
-
-  Future<List<User>> getAllUsers();
-
-  Future<Post> getPostById({required String uuid});
-
-  Future<List<String>> getPostIdsInRange({
-    required DateTime start,
-    required DateTime end,
-  });
-
-  Future<User> getUserById({required String uuid});
-
-  factory AppDatabase({required String path}) =>
-      RustLib.instance.api.crateApiSimpleAppDatabaseNew(path: path);
-
-  Future<void> updateTotemLastContact({
-    required String uuid,
-    required DateTime lastContact,
-  });
-
-  Future<void> updateUser({required User user});
-}
-
-class Post {
-  final String uuid;
-  final String userId;
-  final String title;
-  final String body;
-  final DateTime timestamp;
-  final String? image;
-  final String sourceTotem;
-
-  const Post({
-    required this.uuid,
-    required this.userId,
-    required this.title,
-    required this.body,
-    required this.timestamp,
-    this.image,
-    required this.sourceTotem,
-  });
-
-  @override
-  int get hashCode =>
-      uuid.hashCode ^
-      userId.hashCode ^
-      title.hashCode ^
-      body.hashCode ^
-      timestamp.hashCode ^
-      image.hashCode ^
-      sourceTotem.hashCode;
-
-  @override
-  bool operator ==(Object other) =>
-      identical(this, other) ||
-      other is Post &&
-          runtimeType == other.runtimeType &&
-          uuid == other.uuid &&
-          userId == other.userId &&
-          title == other.title &&
-          body == other.body &&
-          timestamp == other.timestamp &&
-          image == other.image &&
-          sourceTotem == other.sourceTotem;
-}
-
-class Totem {
-  final String uuid;
-  final String name;
-  final String location;
-  final DateTime lastContact;
-
-  const Totem({
-    required this.uuid,
-    required this.name,
-    required this.location,
-    required this.lastContact,
-  });
-
-  @override
-  int get hashCode =>
-      uuid.hashCode ^ name.hashCode ^ location.hashCode ^ lastContact.hashCode;
-
-  @override
-  bool operator ==(Object other) =>
-      identical(this, other) ||
-      other is Totem &&
-          runtimeType == other.runtimeType &&
-          uuid == other.uuid &&
-          name == other.name &&
-          location == other.location &&
-          lastContact == other.lastContact;
-}
-
-class User {
-  final String uuid;
-  final String username;
-  final String status;
-  final String bio;
-  final String? profilePicture;
-  final DateTime lastContact;
-
-  const User({
-    required this.uuid,
-    required this.username,
-    required this.status,
-    required this.bio,
-    this.profilePicture,
-    required this.lastContact,
-  });
-
-  @override
-  int get hashCode =>
-      uuid.hashCode ^
-      username.hashCode ^
-      status.hashCode ^
-      bio.hashCode ^
-      profilePicture.hashCode ^
-      lastContact.hashCode;
-
-  @override
-  bool operator ==(Object other) =>
-      identical(this, other) ||
-      other is User &&
-          runtimeType == other.runtimeType &&
-          uuid == other.uuid &&
-          username == other.username &&
-          status == other.status &&
-          bio == other.bio &&
-          profilePicture == other.profilePicture &&
-          lastContact == other.lastContact;
-}
+
+            // These functions are ignored because they are not marked as `pub`: `database`, `preview_of`, `set_identity`, `sign_post`, `sign_user`, `validation_issues`, `with_identity`
+// These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `assert_fields_are_eq`, `assert_fields_are_eq`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `clone`, `eq`, `eq`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `fmt`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`, `from`
+
+
+            /// Check a user before saving it, so forms can point at the offending fields
+List<ValidationIssue>  validateUser({required User user }) => RustLib.instance.api.crateApiSimpleValidateUser(user: user);
+
+/// Check a post before saving it, so forms can point at the offending fields
+List<ValidationIssue>  validatePost({required Post post }) => RustLib.instance.api.crateApiSimpleValidatePost(post: post);
+
+String  greet({required String name }) => RustLib.instance.api.crateApiSimpleGreet(name: name);
+
+            
+                // Rust type: RustOpaqueMoi<flutter_rust_bridge::for_generated::RustAutoOpaqueInner<AppDatabase>>
+                abstract class AppDatabase implements RustOpaqueInterface {
+                     Future<void>  createPost({required Post post });
+
+
+/// Create a post with attachments from [`crate::api::media::store_attachment`]
+ Future<void>  createPostWithAttachments({required Post post , required List<Attachment> attachments });
+
+
+/// Create a post showing a picture from [`crate::api::media::prepare_image`]
+ Future<void>  createPostWithImage({required Post post , required PreparedImage image , String? altText });
+
+
+ Future<void>  createTotem({required Totem totem });
+
+
+ Future<void>  createUser({required User user });
+
+
+/// All known sightings, newest first
+ Future<List<LastSeen>>  getAllLastSeen();
+
+
+ Future<List<Post>>  getAllPosts();
+
+
+ Future<List<Totem>>  getAllTotems();
+
+
+/// All users, without the profiles of random IDs that moved to another one
+ Future<List<User>>  getAllUsers();
+
+
+/// Where and when a user was seen last, for "seen at Totem X, 2 hours ago"
+ Future<LastSeen?>  getLatestLastSeen({required String userId });
+
+
+/// Where the blob with the given content hash is stored on this phone, if it is
+ Future<String?>  getMediaPath({required String hash });
+
+
+/// The attachments of a post, in the order they are shown
+ Future<List<Attachment>>  getPostAttachments({required String uuid });
+
+
+ Future<Post>  getPostById({required String uuid });
+
+
+ Future<List<String>>  getPostIdsInRange({required DateTime start , required DateTime end });
+
+
+/// The picture of a post with its thumbnail and placeholder, if it has one
+ Future<PostImage?>  getPostImage({required String uuid });
+
+
+/// Whether a post was made by its author, checked against the stored author
+ Future<Verification>  getPostVerification({required String uuid });
+
+
+/// Placeholder of a user's profile picture, if they have one
+ Future<ImagePreview?>  getProfilePicturePreview({required String uuid });
+
+
+/// Users that visited a totem since the given time, newest first
+ Future<List<LastSeen>>  getRecentVisitors({required String totemId , required DateTime since });
+
+
+/// The profile behind `uuid`, the one a random ID from before identities moved to
+ Future<User>  getUserById({required String uuid });
+
+
+/// Random IDs from before identities, with the ID their user moved to
+/// Records under a random ID belong to the profile of the ID it moved to.
+ Future<List<UserLink>>  getUserLinks();
+
+
+/// Whether a user's profile was made by the owner of their ID
+ Future<Verification>  getUserVerification({required String uuid });
+
+
+factory AppDatabase({required String path })=>RustLib.instance.api.crateApiSimpleAppDatabaseNew(path: path);
+
+
+/// Record where the blob with the given content hash is stored on this phone
+ Future<void>  setMediaPath({required String hash , required String localPath });
+
+
+/// Show a picture from [`crate::api::media::prepare_image`] on a user's profile
+ Future<void>  setProfilePicture({required String uuid , required PreparedImage image });
+
+
+ Future<void>  updateTotemLastContact({required String uuid , required DateTime lastContact });
+
+
+ Future<void>  updateUser({required User user });
+
+
+
+                    
+                }
+                
+
+/// A blob attached to a post, by content hash, see [`AppDatabase::get_media_path`]
+class Attachment  {
+                final AttachmentKind kind;
+final String mime;
+final String hash;
+final BigInt size;
+final int? durationMs;
+final int? width;
+final int? height;
+final String? altText;
+
+                const Attachment({required this.kind ,required this.mime ,required this.hash ,required this.size ,this.durationMs ,this.width ,this.height ,this.altText ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => kind.hashCode^mime.hashCode^hash.hashCode^size.hashCode^durationMs.hashCode^width.hashCode^height.hashCode^altText.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is Attachment &&
+                runtimeType == other.runtimeType
+                && kind == other.kind&& mime == other.mime&& hash == other.hash&& size == other.size&& durationMs == other.durationMs&& width == other.width&& height == other.height&& altText == other.altText;
+        
+            }
+
+enum AttachmentKind {
+                    image,
+clip,
+voice,
+file,
+                    ;
+                    
+                }
+
+/// Blurred placeholder of a picture and its size, to lay out the feed before it is downloaded
+class ImagePreview  {
+                final String blurhash;
+final int width;
+final int height;
+
+                const ImagePreview({required this.blurhash ,required this.width ,required this.height ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => blurhash.hashCode^width.hashCode^height.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is ImagePreview &&
+                runtimeType == other.runtimeType
+                && blurhash == other.blurhash&& width == other.width&& height == other.height;
+        
+            }
+
+/// When a user last synced with a totem
+class LastSeen  {
+                final String userId;
+final String totemId;
+final DateTime seenAt;
+
+                const LastSeen({required this.userId ,required this.totemId ,required this.seenAt ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => userId.hashCode^totemId.hashCode^seenAt.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is LastSeen &&
+                runtimeType == other.runtimeType
+                && userId == other.userId&& totemId == other.totemId&& seenAt == other.seenAt;
+        
+            }
+
+class Post  {
+                final String uuid;
+final String userId;
+final String title;
+final String body;
+final DateTime timestamp;
+final String? image;
+final String sourceTotem;
+
+                const Post({required this.uuid ,required this.userId ,required this.title ,required this.body ,required this.timestamp ,this.image ,required this.sourceTotem ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => uuid.hashCode^userId.hashCode^title.hashCode^body.hashCode^timestamp.hashCode^image.hashCode^sourceTotem.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is Post &&
+                runtimeType == other.runtimeType
+                && uuid == other.uuid&& userId == other.userId&& title == other.title&& body == other.body&& timestamp == other.timestamp&& image == other.image&& sourceTotem == other.sourceTotem;
+        
+            }
+
+/// The picture of a post, by content hash, see [`AppDatabase::get_media_path`]
+class PostImage  {
+                final String image;
+final String? thumbnail;
+final ImagePreview? preview;
+
+                const PostImage({required this.image ,this.thumbnail ,this.preview ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => image.hashCode^thumbnail.hashCode^preview.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is PostImage &&
+                runtimeType == other.runtimeType
+                && image == other.image&& thumbnail == other.thumbnail&& preview == other.preview;
+        
+            }
+
+class Totem  {
+                final String uuid;
+final String name;
+final String location;
+final DateTime lastContact;
+
+                const Totem({required this.uuid ,required this.name ,required this.location ,required this.lastContact ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => uuid.hashCode^name.hashCode^location.hashCode^lastContact.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is Totem &&
+                runtimeType == other.runtimeType
+                && uuid == other.uuid&& name == other.name&& location == other.location&& lastContact == other.lastContact;
+        
+            }
+
+class User  {
+                final String uuid;
+final String username;
+final String status;
+final String bio;
+final String? profilePicture;
+final DateTime lastContact;
+
+                const User({required this.uuid ,required this.username ,required this.status ,required this.bio ,this.profilePicture ,required this.lastContact ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => uuid.hashCode^username.hashCode^status.hashCode^bio.hashCode^profilePicture.hashCode^lastContact.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is User &&
+                runtimeType == other.runtimeType
+                && uuid == other.uuid&& username == other.username&& status == other.status&& bio == other.bio&& profilePicture == other.profilePicture&& lastContact == other.lastContact;
+        
+            }
+
+/// A random user ID from before identities, and the derived ID its user moved to
+class UserLink  {
+                final String legacyId;
+final String userId;
+
+                const UserLink({required this.legacyId ,required this.userId ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => legacyId.hashCode^userId.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is UserLink &&
+                runtimeType == other.runtimeType
+                && legacyId == other.legacyId&& userId == other.userId;
+        
+            }
+
+/// A field that failed validation, see `shared::model::validation` for the rules
+class ValidationIssue  {
+                final String field;
+final String message;
+
+                const ValidationIssue({required this.field ,required this.message ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => field.hashCode^message.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is ValidationIssue &&
+                runtimeType == other.runtimeType
+                && field == other.field&& message == other.message;
+        
+            }
+
+/// Whether a record was made by its author, see `shared::model::signing`
+enum Verification {
+                    verified,
+/// From before identities existed, the UI should say that it can't be verified
+unsigned,
+unknownAuthor,
+/// Forged or altered, only records created before verification existed can be like this
+invalid,
+                    ;
+                    
+                }
+            
\ No newline at end of file
@@ -0,0 +1,62 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`
+
+
+            /// Exchange the key rotations, users, tombstones and recent posts, replies, reposts and reactions
+/// missing on either side with a totem
+///
+/// Sightings of the known users are exchanged too. While the identity is unlocked, the
+/// totem records the phone's user as seen there.
+/// Run [`crate::api::media::sync_media`] afterwards to transfer the pictures they reference.
+/// `totem_url` defaults to the address of a totem on its own access point.
+Future<RecordSyncSummary>  syncRecords({required AppDatabase db , String? totemUrl }) => RustLib.instance.api.crateApiSyncSyncRecords(db: db, totemUrl: totemUrl);
+
+            class RecordSyncSummary  {
+                final int rotationsDownloaded;
+final int rotationsUploaded;
+final int usersDownloaded;
+final int usersUploaded;
+/// Sightings of users at totems that were new to the phone
+final int lastSeenDownloaded;
+final int tombstonesDownloaded;
+final int tombstonesUploaded;
+final int postsDownloaded;
+final int postsUploaded;
+final int repliesDownloaded;
+final int repliesUploaded;
+final int repostsDownloaded;
+final int repostsUploaded;
+final int reactionsDownloaded;
+final int reactionsUploaded;
+/// Records that failed validation on either side
+final int rejected;
+
+                const RecordSyncSummary({required this.rotationsDownloaded ,required this.rotationsUploaded ,required this.usersDownloaded ,required this.usersUploaded ,required this.lastSeenDownloaded ,required this.tombstonesDownloaded ,required this.tombstonesUploaded ,required this.postsDownloaded ,required this.postsUploaded ,required this.repliesDownloaded ,required this.repliesUploaded ,required this.repostsDownloaded ,required this.repostsUploaded ,required this.reactionsDownloaded ,required this.reactionsUploaded ,required this.rejected ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => rotationsDownloaded.hashCode^rotationsUploaded.hashCode^usersDownloaded.hashCode^usersUploaded.hashCode^lastSeenDownloaded.hashCode^tombstonesDownloaded.hashCode^tombstonesUploaded.hashCode^postsDownloaded.hashCode^postsUploaded.hashCode^repliesDownloaded.hashCode^repliesUploaded.hashCode^repostsDownloaded.hashCode^repostsUploaded.hashCode^reactionsDownloaded.hashCode^reactionsUploaded.hashCode^rejected.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is RecordSyncSummary &&
+                runtimeType == other.runtimeType
+                && rotationsDownloaded == other.rotationsDownloaded&& rotationsUploaded == other.rotationsUploaded&& usersDownloaded == other.usersDownloaded&& usersUploaded == other.usersUploaded&& lastSeenDownloaded == other.lastSeenDownloaded&& tombstonesDownloaded == other.tombstonesDownloaded&& tombstonesUploaded == other.tombstonesUploaded&& postsDownloaded == other.postsDownloaded&& postsUploaded == other.postsUploaded&& repliesDownloaded == other.repliesDownloaded&& repliesUploaded == other.repliesUploaded&& repostsDownloaded == other.repostsDownloaded&& repostsUploaded == other.repostsUploaded&& reactionsDownloaded == other.reactionsDownloaded&& reactionsUploaded == other.reactionsUploaded&& rejected == other.rejected;
+        
+            }
+            
\ No newline at end of file
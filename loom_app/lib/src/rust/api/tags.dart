@@ -0,0 +1,56 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `fmt`, `from`
+
+
+            /// Read the hashtags and mentions from a body, as they will be indexed
+PostEntities  parseEntities({required String body }) => RustLib.instance.api.crateApiTagsParseEntities(body: body);
+
+Future<PostEntities>  getPostEntities({required AppDatabase db , required String postId }) => RustLib.instance.api.crateApiTagsGetPostEntities(db: db, postId: postId);
+
+/// Posts tagged with `tag`, newest first
+/// The tag may be given as typed, with `#` or in another case.
+Future<List<Post>>  getPostsWithTag({required AppDatabase db , required String tag }) => RustLib.instance.api.crateApiTagsGetPostsWithTag(db: db, tag: tag);
+
+/// Posts mentioning this phone's user by username or key fingerprint, newest first
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+Future<List<Post>>  getMentions({required AppDatabase db }) => RustLib.instance.api.crateApiTagsGetMentions(db: db);
+
+            /// The hashtags and mentions in the body of a post, see `shared::model::entities`
+class PostEntities  {
+                /// In lowercase, without `#`
+final List<String> tags;
+/// Usernames, in lowercase
+final List<String> handles;
+/// Key fingerprints of the users mentioned that way
+final List<String> fingerprints;
+
+                const PostEntities({required this.tags ,required this.handles ,required this.fingerprints ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => tags.hashCode^handles.hashCode^fingerprints.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is PostEntities &&
+                runtimeType == other.runtimeType
+                && tags == other.tags&& handles == other.handles&& fingerprints == other.fingerprints;
+        
+            }
+            
\ No newline at end of file
@@ -0,0 +1,101 @@
+// This file is automatically generated, so please do not edit it.
+// @generated by `flutter_rust_bridge`@ 2.11.1.
+
+// ignore_for_file: invalid_use_of_internal_member, unused_import, unnecessary_import
+
+import '../frb_generated.dart';
+import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
+import 'simple.dart';
+
+
+            // These function are ignored because they are on traits that is not defined in current crate (put an empty `#[frb]` on it to unignore): `clone`, `clone`, `clone`, `fmt`, `fmt`, `fmt`
+
+
+            /// Ask a totem what is talked about there lately
+/// `totem_url` defaults to the address of a totem on its own access point.
+Future<TrendingTopics>  getTrending({required AppDatabase db , String? totemUrl }) => RustLib.instance.api.crateApiTrendingGetTrending(db: db, totemUrl: totemUrl);
+
+            class TrendingTag  {
+                /// In lowercase, without `#`
+final String tag;
+/// Posts the totem received with it
+final int posts;
+
+                const TrendingTag({required this.tag ,required this.posts ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => tag.hashCode^posts.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is TrendingTag &&
+                runtimeType == other.runtimeType
+                && tag == other.tag&& posts == other.posts;
+        
+            }
+
+class TrendingThread  {
+                final String postId;
+/// Replies the totem received in the thread
+final int replies;
+/// `None` until the post has been synced to this phone
+final Post? post;
+
+                const TrendingThread({required this.postId ,required this.replies ,this.post ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => postId.hashCode^replies.hashCode^post.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is TrendingThread &&
+                runtimeType == other.runtimeType
+                && postId == other.postId&& replies == other.replies&& post == other.post;
+        
+            }
+
+/// What is talked about at a totem, see `shared::trending`
+class TrendingTopics  {
+                /// The totem that answered, empty for totems from before it was sent
+final String totemId;
+/// Start of the counted time range
+final DateTime since;
+/// Most used hashtags first
+final List<TrendingTag> tags;
+/// Threads with the most replies first
+final List<TrendingThread> threads;
+
+                const TrendingTopics({required this.totemId ,required this.since ,required this.tags ,required this.threads ,});
+
+                
+                
+
+                
+        @override
+        int get hashCode => totemId.hashCode^since.hashCode^tags.hashCode^threads.hashCode;
+        
+
+                
+        @override
+        bool operator ==(Object other) =>
+            identical(this, other) ||
+            other is TrendingTopics &&
+                runtimeType == other.runtimeType
+                && totemId == other.totemId&& since == other.since&& tags == other.tags&& threads == other.threads;
+        
+            }
+            
\ No newline at end of file
@@ -3,1322 +3,3811 @@
 
 // ignore_for_file: unused_import, unused_element, unnecessary_import, duplicate_ignore, invalid_use_of_internal_member, annotate_overrides, non_constant_identifier_names, curly_braces_in_flow_control_structures, prefer_const_literals_to_create_immutables, unused_field
 
+import 'api/bundles.dart';
+import 'api/deletions.dart';
+import 'api/edits.dart';
+import 'api/identity.dart';
+import 'api/link.dart';
+import 'api/media.dart';
+import 'api/messages.dart';
+import 'api/reactions.dart';
+import 'api/replies.dart';
+import 'api/reposts.dart';
 import 'api/simple.dart';
+import 'api/sync.dart';
+import 'api/tags.dart';
+import 'api/trending.dart';
 import 'dart:async';
 import 'dart:convert';
 import 'frb_generated.dart';
-import 'frb_generated.io.dart'
-    if (dart.library.js_interop) 'frb_generated.web.dart';
+import 'frb_generated.io.dart' if (dart.library.js_interop) 'frb_generated.web.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated.dart';
 
-/// Main entrypoint of the Rust API
-class RustLib extends BaseEntrypoint<RustLibApi, RustLibApiImpl, RustLibWire> {
-  @internal
-  static final instance = RustLib._();
-
-  RustLib._();
-
-  /// Initialize flutter_rust_bridge
-  static Future<void> init({
-    RustLibApi? api,
-    BaseHandler? handler,
-    ExternalLibrary? externalLibrary,
-    bool forceSameCodegenVersion = true,
-  }) async {
-    await instance.initImpl(
-      api: api,
-      handler: handler,
-      externalLibrary: externalLibrary,
-      forceSameCodegenVersion: forceSameCodegenVersion,
-    );
-  }
-
-  /// Initialize flutter_rust_bridge in mock mode.
-  /// No libraries for FFI are loaded.
-  static void initMock({required RustLibApi api}) {
-    instance.initMockImpl(api: api);
-  }
-
-  /// Dispose flutter_rust_bridge
-  ///
-  /// The call to this function is optional, since flutter_rust_bridge (and everything else)
-  /// is automatically disposed when the app stops.
-  static void dispose() => instance.disposeImpl();
-
-  @override
-  ApiImplConstructor<RustLibApiImpl, RustLibWire> get apiImplConstructor =>
-      RustLibApiImpl.new;
-
-  @override
-  WireConstructor<RustLibWire> get wireConstructor =>
-      RustLibWire.fromExternalLibrary;
-
-  @override
-  Future<void> executeRustInitializers() async {
-    await api.crateApiSimpleInitApp();
-  }
-
-  @override
-  ExternalLibraryLoaderConfig get defaultExternalLibraryLoaderConfig =>
-      kDefaultExternalLibraryLoaderConfig;
-
-  @override
-  String get codegenVersion => '2.11.1';
-
-  @override
-  int get rustContentHash => -1228057230;
-
-  static const kDefaultExternalLibraryLoaderConfig =
-      ExternalLibraryLoaderConfig(
-        stem: 'rust_lib_loom_app',
-        ioDirectory: 'rust/target/release/',
-        webPrefix: 'pkg/',
-      );
-}
-
-abstract class RustLibApi extends BaseApi {
-  Future<void> crateApiSimpleAppDatabaseCreatePost({
-    required AppDatabase that,
-    required Post post,
-  });
-
-  Future<void> crateApiSimpleAppDatabaseCreateTotem({
-    required AppDatabase that,
-    required Totem totem,
-  });
-
-  Future<void> crateApiSimpleAppDatabaseCreateUser({
-    required AppDatabase that,
-    required User user,
-  });
-
-  Future<List<Post>> crateApiSimpleAppDatabaseGetAllPosts({
-    required AppDatabase that,
-  });
-
-  Future<List<Totem>> crateApiSimpleAppDatabaseGetAllTotems({
-    required AppDatabase that,
-  });
-
-  Future<List<User>> crateApiSimpleAppDatabaseGetAllUsers({
-    required AppDatabase that,
-  });
-
-  Future<Post> crateApiSimpleAppDatabaseGetPostById({
-    required AppDatabase that,
-    required String uuid,
-  });
-
-  Future<List<String>> crateApiSimpleAppDatabaseGetPostIdsInRange({
-    required AppDatabase that,
-    required DateTime start,
-    required DateTime end,
-  });
-
-  Future<User> crateApiSimpleAppDatabaseGetUserById({
-    required AppDatabase that,
-    required String uuid,
-  });
-
-  AppDatabase crateApiSimpleAppDatabaseNew({required String path});
-
-  Future<void> crateApiSimpleAppDatabaseUpdateTotemLastContact({
-    required AppDatabase that,
-    required String uuid,
-    required DateTime lastContact,
-  });
-
-  Future<void> crateApiSimpleAppDatabaseUpdateUser({
-    required AppDatabase that,
-    required User user,
-  });
-
-  String crateApiSimpleGreet({required String name});
-
-  Future<void> crateApiSimpleInitApp();
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_AppDatabase;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_AppDatabase;
-
-  CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_AppDatabasePtr;
-}
-
-class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
-  RustLibApiImpl({
-    required super.handler,
-    required super.wire,
-    required super.generalizedFrbRustBinding,
-    required super.portManager,
-  });
-
-  @override
-  Future<void> crateApiSimpleAppDatabaseCreatePost({
-    required AppDatabase that,
-    required Post post,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_box_autoadd_post(post, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 1,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+
+                /// Main entrypoint of the Rust API
+                class RustLib extends BaseEntrypoint<RustLibApi, RustLibApiImpl, RustLibWire> {
+                  @internal
+                  static final instance = RustLib._();
+
+                  RustLib._();
+
+                  /// Initialize flutter_rust_bridge
+                  static Future<void> init({
+                    RustLibApi? api,
+                    BaseHandler? handler,
+                    ExternalLibrary? externalLibrary,
+                    bool forceSameCodegenVersion = true,
+                  }) async {
+                    await instance.initImpl(
+                      api: api,
+                      handler: handler,
+                      externalLibrary: externalLibrary,
+                      forceSameCodegenVersion: forceSameCodegenVersion,
+                    );
+                  }
+
+                  /// Initialize flutter_rust_bridge in mock mode.
+                  /// No libraries for FFI are loaded.
+                  static void initMock({
+                    required RustLibApi api,
+                  }) {
+                    instance.initMockImpl(
+                      api: api,
+                    );
+                  }
+
+                  /// Dispose flutter_rust_bridge
+                  ///
+                  /// The call to this function is optional, since flutter_rust_bridge (and everything else)
+                  /// is automatically disposed when the app stops.
+                  static void dispose() => instance.disposeImpl();
+
+                  @override
+                  ApiImplConstructor<RustLibApiImpl, RustLibWire> get apiImplConstructor => RustLibApiImpl.new;
+
+                  @override
+                  WireConstructor<RustLibWire> get wireConstructor => RustLibWire.fromExternalLibrary;
+
+                  @override
+                  Future<void> executeRustInitializers() async {
+                    await api.crateApiSimpleInitApp();
+
+                  }
+
+                  @override
+                  ExternalLibraryLoaderConfig get defaultExternalLibraryLoaderConfig => kDefaultExternalLibraryLoaderConfig;
+
+                  @override
+                  String get codegenVersion => '2.11.1';
+
+                  @override
+                  int get rustContentHash => 1052000254;
+
+                  static const kDefaultExternalLibraryLoaderConfig = ExternalLibraryLoaderConfig(
+                    stem: 'rust_lib_loom_app',
+                    ioDirectory: 'rust/target/release/',
+                    webPrefix: 'pkg/',
+                  );
+                }
+                
+
+                abstract class RustLibApi extends BaseApi {
+                  Future<void> crateApiSimpleAppDatabaseCreatePost({required AppDatabase that , required Post post });
+
+Future<void> crateApiSimpleAppDatabaseCreatePostWithAttachments({required AppDatabase that , required Post post , required List<Attachment> attachments });
+
+Future<void> crateApiSimpleAppDatabaseCreatePostWithImage({required AppDatabase that , required Post post , required PreparedImage image , String? altText });
+
+Future<void> crateApiSimpleAppDatabaseCreateTotem({required AppDatabase that , required Totem totem });
+
+Future<void> crateApiSimpleAppDatabaseCreateUser({required AppDatabase that , required User user });
+
+Future<List<LastSeen>> crateApiSimpleAppDatabaseGetAllLastSeen({required AppDatabase that });
+
+Future<List<Post>> crateApiSimpleAppDatabaseGetAllPosts({required AppDatabase that });
+
+Future<List<Totem>> crateApiSimpleAppDatabaseGetAllTotems({required AppDatabase that });
+
+Future<List<User>> crateApiSimpleAppDatabaseGetAllUsers({required AppDatabase that });
+
+Future<LastSeen?> crateApiSimpleAppDatabaseGetLatestLastSeen({required AppDatabase that , required String userId });
+
+Future<String?> crateApiSimpleAppDatabaseGetMediaPath({required AppDatabase that , required String hash });
+
+Future<List<Attachment>> crateApiSimpleAppDatabaseGetPostAttachments({required AppDatabase that , required String uuid });
+
+Future<Post> crateApiSimpleAppDatabaseGetPostById({required AppDatabase that , required String uuid });
+
+Future<List<String>> crateApiSimpleAppDatabaseGetPostIdsInRange({required AppDatabase that , required DateTime start , required DateTime end });
+
+Future<PostImage?> crateApiSimpleAppDatabaseGetPostImage({required AppDatabase that , required String uuid });
+
+Future<Verification> crateApiSimpleAppDatabaseGetPostVerification({required AppDatabase that , required String uuid });
+
+Future<ImagePreview?> crateApiSimpleAppDatabaseGetProfilePicturePreview({required AppDatabase that , required String uuid });
+
+Future<List<LastSeen>> crateApiSimpleAppDatabaseGetRecentVisitors({required AppDatabase that , required String totemId , required DateTime since });
+
+Future<User> crateApiSimpleAppDatabaseGetUserById({required AppDatabase that , required String uuid });
+
+Future<List<UserLink>> crateApiSimpleAppDatabaseGetUserLinks({required AppDatabase that });
+
+Future<Verification> crateApiSimpleAppDatabaseGetUserVerification({required AppDatabase that , required String uuid });
+
+AppDatabase crateApiSimpleAppDatabaseNew({required String path });
+
+Future<void> crateApiSimpleAppDatabaseSetMediaPath({required AppDatabase that , required String hash , required String localPath });
+
+Future<void> crateApiSimpleAppDatabaseSetProfilePicture({required AppDatabase that , required String uuid , required PreparedImage image });
+
+Future<void> crateApiSimpleAppDatabaseUpdateTotemLastContact({required AppDatabase that , required String uuid , required DateTime lastContact });
+
+Future<void> crateApiSimpleAppDatabaseUpdateUser({required AppDatabase that , required User user });
+
+void crateApiLinkLinkOfferCancel({required LinkOffer that });
+
+Future<Uint8List> crateApiLinkLinkOfferExport({required LinkOffer that , required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase });
+
+LinkOffer crateApiLinkLinkOfferNew({String? localIp });
+
+String crateApiLinkLinkOfferQrCode({required LinkOffer that });
+
+Future<void> crateApiLinkLinkOfferSend({required LinkOffer that , required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase , required int timeoutSecs });
+
+Future<String> crateApiIdentityCreateRecoveryKey({required AppDatabase db , required String keyDir });
+
+ImageOptions crateApiMediaDefaultImageOptions();
+
+Future<void> crateApiDeletionsDeletePost({required AppDatabase db , required String uuid });
+
+Future<Post> crateApiEditsEditPost({required AppDatabase db , required String uuid , required String title , required String body });
+
+Future<Uint8List> crateApiIdentityExportBackup({required String keyDir , required String passphrase });
+
+Future<String> crateApiIdentityExportMnemonic({required String keyDir });
+
+Future<List<DirectMessage>> crateApiMessagesGetConversation({required AppDatabase db , required String userId });
+
+Future<List<FeedItem>> crateApiRepostsGetFeed({required AppDatabase db });
+
+Future<List<Post>> crateApiTagsGetMentions({required AppDatabase db });
+
+Future<PostEntities> crateApiTagsGetPostEntities({required AppDatabase db , required String postId });
+
+Future<List<PostRevision>> crateApiEditsGetPostHistory({required AppDatabase db , required String uuid });
+
+Future<PostReactions> crateApiReactionsGetPostReactions({required AppDatabase db , required String postId });
+
+Future<List<Post>> crateApiTagsGetPostsWithTag({required AppDatabase db , required String tag });
+
+Future<int> crateApiRepliesGetReplyCount({required AppDatabase db , required String postId });
+
+Future<List<ThreadReply>> crateApiRepliesGetThread({required AppDatabase db , required String postId });
+
+Future<TrendingTopics> crateApiTrendingGetTrending({required AppDatabase db , String? totemUrl });
+
+String crateApiSimpleGreet({required String name });
+
+Future<void> crateApiSimpleInitApp();
+
+Future<LinkSummary> crateApiLinkLinkFromFile({required AppDatabase db , required String keyDir , required String cacheDir , required String qrCode , required List<int> file });
+
+Future<LinkSummary> crateApiLinkLinkOverNetwork({required AppDatabase db , required String keyDir , required String cacheDir , required String qrCode });
+
+Future<List<Conversation>> crateApiMessagesListConversations({required AppDatabase db });
+
+Future<IdentityInfo?> crateApiIdentityLoadIdentity({required String keyDir });
+
+Future<IdentityInfo> crateApiIdentityMigrateIdentity({required AppDatabase db , required String keyDir , required String legacyUserId });
+
+PostEntities crateApiTagsParseEntities({required String body });
+
+Future<PreparedImage> crateApiMediaPrepareImage({required AppDatabase db , required List<int> data , required String mediaDir , ImageOptions? options });
+
+Attachment crateApiMediaPreparedImageAttachment({required PreparedImage that , String? altText });
+
+Future<PostReactions> crateApiReactionsReact({required AppDatabase db , required String postId , required ReactionKind kind , required bool active });
+
+Future<IdentityInfo> crateApiIdentityRegisterUser({required AppDatabase db , required String keyDir , required User user });
+
+Future<ThreadReply> crateApiRepliesReplyTo({required AppDatabase db , required String parentId , required String body });
+
+Future<FeedItem> crateApiRepostsRepost({required AppDatabase db , required String postId });
+
+Future<IdentityInfo> crateApiIdentityRestoreFromBackup({required AppDatabase db , required String keyDir , required List<int> file , required String passphrase });
+
+Future<IdentityInfo> crateApiIdentityRestoreFromMnemonic({required AppDatabase db , required String keyDir , required String phrase });
+
+Future<DirectMessage> crateApiMessagesSendMessage({required AppDatabase db , required String recipientId , required String text });
+
+Future<String> crateApiBundlesSendPostToTotem({required AppDatabase db , required String postUuid , required String totemId });
+
+Future<Attachment> crateApiMediaStoreAttachment({required AppDatabase db , required List<int> data , required String mediaDir , required AttachmentKind kind , int? durationMs , String? altText });
+
+Future<BundleSyncSummary> crateApiBundlesSyncBundles({required AppDatabase db , String? totemUrl , required bool courier });
+
+Future<MediaSyncSummary> crateApiMediaSyncMedia({required AppDatabase db , required String mediaDir , String? totemUrl , required FutureOr<void> Function(MediaSyncProgress) onProgress });
+
+Future<MessageSyncSummary> crateApiMessagesSyncMessages({required AppDatabase db , String? totemUrl });
+
+Future<RecordSyncSummary> crateApiSyncSyncRecords({required AppDatabase db , String? totemUrl });
+
+Future<IdentityInfo?> crateApiIdentityUnlockIdentity({required AppDatabase db , required String keyDir });
+
+List<ValidationIssue> crateApiSimpleValidatePost({required Post post });
+
+List<ValidationIssue> crateApiSimpleValidateUser({required User user });
+
+RustArcIncrementStrongCountFnType get rust_arc_increment_strong_count_AppDatabase;
+
+RustArcDecrementStrongCountFnType get rust_arc_decrement_strong_count_AppDatabase;
+
+CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_AppDatabasePtr;
+
+RustArcIncrementStrongCountFnType get rust_arc_increment_strong_count_LinkOffer;
+
+RustArcDecrementStrongCountFnType get rust_arc_decrement_strong_count_LinkOffer;
+
+CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_LinkOfferPtr;
+
+
+                }
+                
+
+                class RustLibApiImpl extends RustLibApiImplPlatform implements RustLibApi {
+                  RustLibApiImpl({
+                    required super.handler,
+                    required super.wire,
+                    required super.generalizedFrbRustBinding,
+                    required super.portManager,
+                  });
+
+                  @override Future<void> crateApiSimpleAppDatabaseCreatePost({required AppDatabase that , required Post post })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_box_autoadd_post(post, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 1, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseCreatePostConstMeta,
+            argValues: [that, post],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseCreatePostConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_create_post",
+            argNames: ["that", "post"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseCreatePostWithAttachments({required AppDatabase that , required Post post , required List<Attachment> attachments })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_box_autoadd_post(post, serializer);
+sse_encode_list_attachment(attachments, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 2, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseCreatePostWithAttachmentsConstMeta,
+            argValues: [that, post, attachments],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseCreatePostWithAttachmentsConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_create_post_with_attachments",
+            argNames: ["that", "post", "attachments"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseCreatePostWithImage({required AppDatabase that , required Post post , required PreparedImage image , String? altText })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_box_autoadd_post(post, serializer);
+sse_encode_box_autoadd_prepared_image(image, serializer);
+sse_encode_opt_String(altText, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 3, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseCreatePostConstMeta,
-        argValues: [that, post],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseCreatePostConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_create_post",
-        argNames: ["that", "post"],
-      );
-
-  @override
-  Future<void> crateApiSimpleAppDatabaseCreateTotem({
-    required AppDatabase that,
-    required Totem totem,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_box_autoadd_totem(totem, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 2,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseCreatePostWithImageConstMeta,
+            argValues: [that, post, image, altText],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseCreatePostWithImageConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_create_post_with_image",
+            argNames: ["that", "post", "image", "altText"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseCreateTotem({required AppDatabase that , required Totem totem })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_box_autoadd_totem(totem, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 4, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseCreateTotemConstMeta,
-        argValues: [that, totem],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseCreateTotemConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_create_totem",
-        argNames: ["that", "totem"],
-      );
-
-  @override
-  Future<void> crateApiSimpleAppDatabaseCreateUser({
-    required AppDatabase that,
-    required User user,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_box_autoadd_user(user, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 3,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseCreateTotemConstMeta,
+            argValues: [that, totem],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseCreateTotemConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_create_totem",
+            argNames: ["that", "totem"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseCreateUser({required AppDatabase that , required User user })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_box_autoadd_user(user, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 5, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseCreateUserConstMeta,
-        argValues: [that, user],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseCreateUserConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_create_user",
-        argNames: ["that", "user"],
-      );
-
-  @override
-  Future<List<Post>> crateApiSimpleAppDatabaseGetAllPosts({
-    required AppDatabase that,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 4,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseCreateUserConstMeta,
+            argValues: [that, user],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseCreateUserConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_create_user",
+            argNames: ["that", "user"],
+        );
+        
+
+@override Future<List<LastSeen>> crateApiSimpleAppDatabaseGetAllLastSeen({required AppDatabase that })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 6, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_last_seen,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetAllLastSeenConstMeta,
+            argValues: [that],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetAllLastSeenConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_all_last_seen",
+            argNames: ["that"],
+        );
+        
+
+@override Future<List<Post>> crateApiSimpleAppDatabaseGetAllPosts({required AppDatabase that })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 7, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_list_post,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseGetAllPostsConstMeta,
-        argValues: [that],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseGetAllPostsConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_get_all_posts",
-        argNames: ["that"],
-      );
-
-  @override
-  Future<List<Totem>> crateApiSimpleAppDatabaseGetAllTotems({
-    required AppDatabase that,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 5,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetAllPostsConstMeta,
+            argValues: [that],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetAllPostsConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_all_posts",
+            argNames: ["that"],
+        );
+        
+
+@override Future<List<Totem>> crateApiSimpleAppDatabaseGetAllTotems({required AppDatabase that })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 8, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_list_totem,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseGetAllTotemsConstMeta,
-        argValues: [that],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseGetAllTotemsConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_get_all_totems",
-        argNames: ["that"],
-      );
-
-  @override
-  Future<List<User>> crateApiSimpleAppDatabaseGetAllUsers({
-    required AppDatabase that,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 6,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetAllTotemsConstMeta,
+            argValues: [that],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetAllTotemsConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_all_totems",
+            argNames: ["that"],
+        );
+        
+
+@override Future<List<User>> crateApiSimpleAppDatabaseGetAllUsers({required AppDatabase that })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 9, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_list_user,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseGetAllUsersConstMeta,
-        argValues: [that],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseGetAllUsersConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_get_all_users",
-        argNames: ["that"],
-      );
-
-  @override
-  Future<Post> crateApiSimpleAppDatabaseGetPostById({
-    required AppDatabase that,
-    required String uuid,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_String(uuid, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 7,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetAllUsersConstMeta,
+            argValues: [that],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetAllUsersConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_all_users",
+            argNames: ["that"],
+        );
+        
+
+@override Future<LastSeen?> crateApiSimpleAppDatabaseGetLatestLastSeen({required AppDatabase that , required String userId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(userId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 10, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_opt_box_autoadd_last_seen,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetLatestLastSeenConstMeta,
+            argValues: [that, userId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetLatestLastSeenConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_latest_last_seen",
+            argNames: ["that", "userId"],
+        );
+        
+
+@override Future<String?> crateApiSimpleAppDatabaseGetMediaPath({required AppDatabase that , required String hash })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(hash, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 11, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_opt_String,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetMediaPathConstMeta,
+            argValues: [that, hash],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetMediaPathConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_media_path",
+            argNames: ["that", "hash"],
+        );
+        
+
+@override Future<List<Attachment>> crateApiSimpleAppDatabaseGetPostAttachments({required AppDatabase that , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 12, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_attachment,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetPostAttachmentsConstMeta,
+            argValues: [that, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetPostAttachmentsConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_post_attachments",
+            argNames: ["that", "uuid"],
+        );
+        
+
+@override Future<Post> crateApiSimpleAppDatabaseGetPostById({required AppDatabase that , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 13, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_post,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseGetPostByIdConstMeta,
-        argValues: [that, uuid],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseGetPostByIdConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_get_post_by_id",
-        argNames: ["that", "uuid"],
-      );
-
-  @override
-  Future<List<String>> crateApiSimpleAppDatabaseGetPostIdsInRange({
-    required AppDatabase that,
-    required DateTime start,
-    required DateTime end,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_Chrono_Utc(start, serializer);
-          sse_encode_Chrono_Utc(end, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 8,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetPostByIdConstMeta,
+            argValues: [that, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetPostByIdConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_post_by_id",
+            argNames: ["that", "uuid"],
+        );
+        
+
+@override Future<List<String>> crateApiSimpleAppDatabaseGetPostIdsInRange({required AppDatabase that , required DateTime start , required DateTime end })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_Chrono_Utc(start, serializer);
+sse_encode_Chrono_Utc(end, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 14, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_list_String,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseGetPostIdsInRangeConstMeta,
-        argValues: [that, start, end],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseGetPostIdsInRangeConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_get_post_ids_in_range",
-        argNames: ["that", "start", "end"],
-      );
-
-  @override
-  Future<User> crateApiSimpleAppDatabaseGetUserById({
-    required AppDatabase that,
-    required String uuid,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_String(uuid, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 9,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetPostIdsInRangeConstMeta,
+            argValues: [that, start, end],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetPostIdsInRangeConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_post_ids_in_range",
+            argNames: ["that", "start", "end"],
+        );
+        
+
+@override Future<PostImage?> crateApiSimpleAppDatabaseGetPostImage({required AppDatabase that , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 15, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_opt_box_autoadd_post_image,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetPostImageConstMeta,
+            argValues: [that, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetPostImageConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_post_image",
+            argNames: ["that", "uuid"],
+        );
+        
+
+@override Future<Verification> crateApiSimpleAppDatabaseGetPostVerification({required AppDatabase that , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 16, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_verification,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetPostVerificationConstMeta,
+            argValues: [that, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetPostVerificationConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_post_verification",
+            argNames: ["that", "uuid"],
+        );
+        
+
+@override Future<ImagePreview?> crateApiSimpleAppDatabaseGetProfilePicturePreview({required AppDatabase that , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 17, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_opt_box_autoadd_image_preview,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetProfilePicturePreviewConstMeta,
+            argValues: [that, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetProfilePicturePreviewConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_profile_picture_preview",
+            argNames: ["that", "uuid"],
+        );
+        
+
+@override Future<List<LastSeen>> crateApiSimpleAppDatabaseGetRecentVisitors({required AppDatabase that , required String totemId , required DateTime since })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(totemId, serializer);
+sse_encode_Chrono_Utc(since, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 18, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_last_seen,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetRecentVisitorsConstMeta,
+            argValues: [that, totemId, since],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetRecentVisitorsConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_recent_visitors",
+            argNames: ["that", "totemId", "since"],
+        );
+        
+
+@override Future<User> crateApiSimpleAppDatabaseGetUserById({required AppDatabase that , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 19, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_user,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseGetUserByIdConstMeta,
-        argValues: [that, uuid],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseGetUserByIdConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_get_user_by_id",
-        argNames: ["that", "uuid"],
-      );
-
-  @override
-  AppDatabase crateApiSimpleAppDatabaseNew({required String path}) {
-    return handler.executeSync(
-      SyncTask(
-        callFfi: () {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_String(path, serializer);
-          return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 10)!;
-        },
-        codec: SseCodec(
-          decodeSuccessData:
-              sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetUserByIdConstMeta,
+            argValues: [that, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetUserByIdConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_user_by_id",
+            argNames: ["that", "uuid"],
+        );
+        
+
+@override Future<List<UserLink>> crateApiSimpleAppDatabaseGetUserLinks({required AppDatabase that })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 20, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_user_link,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetUserLinksConstMeta,
+            argValues: [that],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetUserLinksConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_user_links",
+            argNames: ["that"],
+        );
+        
+
+@override Future<Verification> crateApiSimpleAppDatabaseGetUserVerification({required AppDatabase that , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 21, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_verification,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseGetUserVerificationConstMeta,
+            argValues: [that, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseGetUserVerificationConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_get_user_verification",
+            argNames: ["that", "uuid"],
+        );
+        
+
+@override AppDatabase crateApiSimpleAppDatabaseNew({required String path })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_String(path, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 22)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase,
           decodeErrorData: null,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseNewConstMeta,
-        argValues: [path],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseNewConstMeta =>
-      const TaskConstMeta(debugName: "AppDatabase_new", argNames: ["path"]);
-
-  @override
-  Future<void> crateApiSimpleAppDatabaseUpdateTotemLastContact({
-    required AppDatabase that,
-    required String uuid,
-    required DateTime lastContact,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_String(uuid, serializer);
-          sse_encode_Chrono_Utc(lastContact, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 11,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseNewConstMeta,
+            argValues: [path],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseNewConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_new",
+            argNames: ["path"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseSetMediaPath({required AppDatabase that , required String hash , required String localPath })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(hash, serializer);
+sse_encode_String(localPath, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 23, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseSetMediaPathConstMeta,
+            argValues: [that, hash, localPath],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseSetMediaPathConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_set_media_path",
+            argNames: ["that", "hash", "localPath"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseSetProfilePicture({required AppDatabase that , required String uuid , required PreparedImage image })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+sse_encode_box_autoadd_prepared_image(image, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 24, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseSetProfilePictureConstMeta,
+            argValues: [that, uuid, image],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseSetProfilePictureConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_set_profile_picture",
+            argNames: ["that", "uuid", "image"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseUpdateTotemLastContact({required AppDatabase that , required String uuid , required DateTime lastContact })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_String(uuid, serializer);
+sse_encode_Chrono_Utc(lastContact, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 25, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseUpdateTotemLastContactConstMeta,
+            argValues: [that, uuid, lastContact],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseUpdateTotemLastContactConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_update_totem_last_contact",
+            argNames: ["that", "uuid", "lastContact"],
+        );
+        
+
+@override Future<void> crateApiSimpleAppDatabaseUpdateUser({required AppDatabase that , required User user })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(that, serializer);
+sse_encode_box_autoadd_user(user, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 26, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseUpdateTotemLastContactConstMeta,
-        argValues: [that, uuid, lastContact],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseUpdateTotemLastContactConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_update_totem_last_contact",
-        argNames: ["that", "uuid", "lastContact"],
-      );
-
-  @override
-  Future<void> crateApiSimpleAppDatabaseUpdateUser({
-    required AppDatabase that,
-    required User user,
-  }) {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-            that,
-            serializer,
-          );
-          sse_encode_box_autoadd_user(user, serializer);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 12,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleAppDatabaseUpdateUserConstMeta,
+            argValues: [that, user],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleAppDatabaseUpdateUserConstMeta => const TaskConstMeta(
+            debugName: "AppDatabase_update_user",
+            argNames: ["that", "user"],
+        );
+        
+
+@override void crateApiLinkLinkOfferCancel({required LinkOffer that })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(that, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 27)!;
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_unit,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiLinkLinkOfferCancelConstMeta,
+            argValues: [that],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiLinkLinkOfferCancelConstMeta => const TaskConstMeta(
+            debugName: "LinkOffer_cancel",
+            argNames: ["that"],
+        );
+        
+
+@override Future<Uint8List> crateApiLinkLinkOfferExport({required LinkOffer that , required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(that, serializer);
+sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_String(cacheDir, serializer);
+sse_encode_bool(includeDatabase, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 28, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_prim_u_8_strict,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiLinkLinkOfferExportConstMeta,
+            argValues: [that, db, keyDir, cacheDir, includeDatabase],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiLinkLinkOfferExportConstMeta => const TaskConstMeta(
+            debugName: "LinkOffer_export",
+            argNames: ["that", "db", "keyDir", "cacheDir", "includeDatabase"],
+        );
+        
+
+@override LinkOffer crateApiLinkLinkOfferNew({String? localIp })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_opt_String(localIp, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 29)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiLinkLinkOfferNewConstMeta,
+            argValues: [localIp],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiLinkLinkOfferNewConstMeta => const TaskConstMeta(
+            debugName: "LinkOffer_new",
+            argNames: ["localIp"],
+        );
+        
+
+@override String crateApiLinkLinkOfferQrCode({required LinkOffer that })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(that, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 30)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_String,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiLinkLinkOfferQrCodeConstMeta,
+            argValues: [that],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiLinkLinkOfferQrCodeConstMeta => const TaskConstMeta(
+            debugName: "LinkOffer_qr_code",
+            argNames: ["that"],
+        );
+        
+
+@override Future<void> crateApiLinkLinkOfferSend({required LinkOffer that , required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase , required int timeoutSecs })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(that, serializer);
+sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_String(cacheDir, serializer);
+sse_encode_bool(includeDatabase, serializer);
+sse_encode_u_32(timeoutSecs, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 31, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiLinkLinkOfferSendConstMeta,
+            argValues: [that, db, keyDir, cacheDir, includeDatabase, timeoutSecs],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiLinkLinkOfferSendConstMeta => const TaskConstMeta(
+            debugName: "LinkOffer_send",
+            argNames: ["that", "db", "keyDir", "cacheDir", "includeDatabase", "timeoutSecs"],
+        );
+        
+
+@override Future<String> crateApiIdentityCreateRecoveryKey({required AppDatabase db , required String keyDir })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 32, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_String,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityCreateRecoveryKeyConstMeta,
+            argValues: [db, keyDir],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityCreateRecoveryKeyConstMeta => const TaskConstMeta(
+            debugName: "create_recovery_key",
+            argNames: ["db", "keyDir"],
+        );
+        
+
+@override ImageOptions crateApiMediaDefaultImageOptions()  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 33)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_image_options,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiMediaDefaultImageOptionsConstMeta,
+            argValues: [],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMediaDefaultImageOptionsConstMeta => const TaskConstMeta(
+            debugName: "default_image_options",
+            argNames: [],
+        );
+        
+
+@override Future<void> crateApiDeletionsDeletePost({required AppDatabase db , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 34, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_unit,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiDeletionsDeletePostConstMeta,
+            argValues: [db, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiDeletionsDeletePostConstMeta => const TaskConstMeta(
+            debugName: "delete_post",
+            argNames: ["db", "uuid"],
+        );
+        
+
+@override Future<Post> crateApiEditsEditPost({required AppDatabase db , required String uuid , required String title , required String body })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(uuid, serializer);
+sse_encode_String(title, serializer);
+sse_encode_String(body, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 35, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_post,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiEditsEditPostConstMeta,
+            argValues: [db, uuid, title, body],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiEditsEditPostConstMeta => const TaskConstMeta(
+            debugName: "edit_post",
+            argNames: ["db", "uuid", "title", "body"],
+        );
+        
+
+@override Future<Uint8List> crateApiIdentityExportBackup({required String keyDir , required String passphrase })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_String(keyDir, serializer);
+sse_encode_String(passphrase, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 36, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_prim_u_8_strict,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityExportBackupConstMeta,
+            argValues: [keyDir, passphrase],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityExportBackupConstMeta => const TaskConstMeta(
+            debugName: "export_backup",
+            argNames: ["keyDir", "passphrase"],
+        );
+        
+
+@override Future<String> crateApiIdentityExportMnemonic({required String keyDir })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_String(keyDir, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 37, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_String,
           decodeErrorData: sse_decode_AnyhowException,
-        ),
-        constMeta: kCrateApiSimpleAppDatabaseUpdateUserConstMeta,
-        argValues: [that, user],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleAppDatabaseUpdateUserConstMeta =>
-      const TaskConstMeta(
-        debugName: "AppDatabase_update_user",
-        argNames: ["that", "user"],
-      );
-
-  @override
-  String crateApiSimpleGreet({required String name}) {
-    return handler.executeSync(
-      SyncTask(
-        callFfi: () {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          sse_encode_String(name, serializer);
-          return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 13)!;
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiIdentityExportMnemonicConstMeta,
+            argValues: [keyDir],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityExportMnemonicConstMeta => const TaskConstMeta(
+            debugName: "export_mnemonic",
+            argNames: ["keyDir"],
+        );
+        
+
+@override Future<List<DirectMessage>> crateApiMessagesGetConversation({required AppDatabase db , required String userId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(userId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 38, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_direct_message,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiMessagesGetConversationConstMeta,
+            argValues: [db, userId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMessagesGetConversationConstMeta => const TaskConstMeta(
+            debugName: "get_conversation",
+            argNames: ["db", "userId"],
+        );
+        
+
+@override Future<List<FeedItem>> crateApiRepostsGetFeed({required AppDatabase db })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 39, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_feed_item,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiRepostsGetFeedConstMeta,
+            argValues: [db],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiRepostsGetFeedConstMeta => const TaskConstMeta(
+            debugName: "get_feed",
+            argNames: ["db"],
+        );
+        
+
+@override Future<List<Post>> crateApiTagsGetMentions({required AppDatabase db })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 40, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_post,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiTagsGetMentionsConstMeta,
+            argValues: [db],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiTagsGetMentionsConstMeta => const TaskConstMeta(
+            debugName: "get_mentions",
+            argNames: ["db"],
+        );
+        
+
+@override Future<PostEntities> crateApiTagsGetPostEntities({required AppDatabase db , required String postId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(postId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 41, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_post_entities,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiTagsGetPostEntitiesConstMeta,
+            argValues: [db, postId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiTagsGetPostEntitiesConstMeta => const TaskConstMeta(
+            debugName: "get_post_entities",
+            argNames: ["db", "postId"],
+        );
+        
+
+@override Future<List<PostRevision>> crateApiEditsGetPostHistory({required AppDatabase db , required String uuid })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(uuid, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 42, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_post_revision,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiEditsGetPostHistoryConstMeta,
+            argValues: [db, uuid],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiEditsGetPostHistoryConstMeta => const TaskConstMeta(
+            debugName: "get_post_history",
+            argNames: ["db", "uuid"],
+        );
+        
+
+@override Future<PostReactions> crateApiReactionsGetPostReactions({required AppDatabase db , required String postId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(postId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 43, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_post_reactions,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiReactionsGetPostReactionsConstMeta,
+            argValues: [db, postId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiReactionsGetPostReactionsConstMeta => const TaskConstMeta(
+            debugName: "get_post_reactions",
+            argNames: ["db", "postId"],
+        );
+        
+
+@override Future<List<Post>> crateApiTagsGetPostsWithTag({required AppDatabase db , required String tag })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(tag, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 44, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_post,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiTagsGetPostsWithTagConstMeta,
+            argValues: [db, tag],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiTagsGetPostsWithTagConstMeta => const TaskConstMeta(
+            debugName: "get_posts_with_tag",
+            argNames: ["db", "tag"],
+        );
+        
+
+@override Future<int> crateApiRepliesGetReplyCount({required AppDatabase db , required String postId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(postId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 45, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_u_32,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiRepliesGetReplyCountConstMeta,
+            argValues: [db, postId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiRepliesGetReplyCountConstMeta => const TaskConstMeta(
+            debugName: "get_reply_count",
+            argNames: ["db", "postId"],
+        );
+        
+
+@override Future<List<ThreadReply>> crateApiRepliesGetThread({required AppDatabase db , required String postId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(postId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 46, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_thread_reply,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiRepliesGetThreadConstMeta,
+            argValues: [db, postId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiRepliesGetThreadConstMeta => const TaskConstMeta(
+            debugName: "get_thread",
+            argNames: ["db", "postId"],
+        );
+        
+
+@override Future<TrendingTopics> crateApiTrendingGetTrending({required AppDatabase db , String? totemUrl })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_opt_String(totemUrl, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 47, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_trending_topics,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiTrendingGetTrendingConstMeta,
+            argValues: [db, totemUrl],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiTrendingGetTrendingConstMeta => const TaskConstMeta(
+            debugName: "get_trending",
+            argNames: ["db", "totemUrl"],
+        );
+        
+
+@override String crateApiSimpleGreet({required String name })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_String(name, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 48)!;
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_String,
           decodeErrorData: null,
-        ),
-        constMeta: kCrateApiSimpleGreetConstMeta,
-        argValues: [name],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleGreetConstMeta =>
-      const TaskConstMeta(debugName: "greet", argNames: ["name"]);
-
-  @override
-  Future<void> crateApiSimpleInitApp() {
-    return handler.executeNormal(
-      NormalTask(
-        callFfi: (port_) {
-          final serializer = SseSerializer(generalizedFrbRustBinding);
-          pdeCallFfi(
-            generalizedFrbRustBinding,
-            serializer,
-            funcId: 14,
-            port: port_,
-          );
-        },
-        codec: SseCodec(
+        )
+        ,
+            constMeta: kCrateApiSimpleGreetConstMeta,
+            argValues: [name],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleGreetConstMeta => const TaskConstMeta(
+            debugName: "greet",
+            argNames: ["name"],
+        );
+        
+
+@override Future<void> crateApiSimpleInitApp()  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 49, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
           decodeSuccessData: sse_decode_unit,
           decodeErrorData: null,
-        ),
-        constMeta: kCrateApiSimpleInitAppConstMeta,
-        argValues: [],
-        apiImpl: this,
-      ),
-    );
-  }
-
-  TaskConstMeta get kCrateApiSimpleInitAppConstMeta =>
-      const TaskConstMeta(debugName: "init_app", argNames: []);
-
-  RustArcIncrementStrongCountFnType
-  get rust_arc_increment_strong_count_AppDatabase => wire
-      .rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase;
-
-  RustArcDecrementStrongCountFnType
-  get rust_arc_decrement_strong_count_AppDatabase => wire
-      .rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase;
-
-  @protected
-  AnyhowException dco_decode_AnyhowException(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return AnyhowException(raw as String);
-  }
-
-  @protected
-  AppDatabase
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return AppDatabaseImpl.frbInternalDcoDecode(raw as List<dynamic>);
-  }
-
-  @protected
-  AppDatabase
-  dco_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return AppDatabaseImpl.frbInternalDcoDecode(raw as List<dynamic>);
-  }
-
-  @protected
-  DateTime dco_decode_Chrono_Utc(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return dcoDecodeTimestamp(ts: dco_decode_i_64(raw).toInt(), isUtc: true);
-  }
-
-  @protected
-  AppDatabase
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    dynamic raw,
-  ) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return AppDatabaseImpl.frbInternalDcoDecode(raw as List<dynamic>);
-  }
-
-  @protected
-  String dco_decode_String(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as String;
-  }
-
-  @protected
-  Post dco_decode_box_autoadd_post(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return dco_decode_post(raw);
-  }
-
-  @protected
-  Totem dco_decode_box_autoadd_totem(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return dco_decode_totem(raw);
-  }
-
-  @protected
-  User dco_decode_box_autoadd_user(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return dco_decode_user(raw);
-  }
-
-  @protected
-  PlatformInt64 dco_decode_i_64(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return dcoDecodeI64(raw);
-  }
-
-  @protected
-  List<String> dco_decode_list_String(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_String).toList();
-  }
-
-  @protected
-  List<Post> dco_decode_list_post(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_post).toList();
-  }
-
-  @protected
-  Uint8List dco_decode_list_prim_u_8_strict(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as Uint8List;
-  }
-
-  @protected
-  List<Totem> dco_decode_list_totem(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_totem).toList();
-  }
-
-  @protected
-  List<User> dco_decode_list_user(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return (raw as List<dynamic>).map(dco_decode_user).toList();
-  }
-
-  @protected
-  String? dco_decode_opt_String(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw == null ? null : dco_decode_String(raw);
-  }
-
-  @protected
-  Post dco_decode_post(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    final arr = raw as List<dynamic>;
-    if (arr.length != 7)
-      throw Exception('unexpected arr length: expect 7 but see ${arr.length}');
-    return Post(
-      uuid: dco_decode_String(arr[0]),
-      userId: dco_decode_String(arr[1]),
-      title: dco_decode_String(arr[2]),
-      body: dco_decode_String(arr[3]),
-      timestamp: dco_decode_Chrono_Utc(arr[4]),
-      image: dco_decode_opt_String(arr[5]),
-      sourceTotem: dco_decode_String(arr[6]),
-    );
-  }
-
-  @protected
-  Totem dco_decode_totem(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    final arr = raw as List<dynamic>;
-    if (arr.length != 4)
-      throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
-    return Totem(
-      uuid: dco_decode_String(arr[0]),
-      name: dco_decode_String(arr[1]),
-      location: dco_decode_String(arr[2]),
-      lastContact: dco_decode_Chrono_Utc(arr[3]),
-    );
-  }
-
-  @protected
-  int dco_decode_u_8(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return raw as int;
-  }
-
-  @protected
-  void dco_decode_unit(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return;
-  }
-
-  @protected
-  User dco_decode_user(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    final arr = raw as List<dynamic>;
-    if (arr.length != 6)
-      throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
-    return User(
-      uuid: dco_decode_String(arr[0]),
-      username: dco_decode_String(arr[1]),
-      status: dco_decode_String(arr[2]),
-      bio: dco_decode_String(arr[3]),
-      profilePicture: dco_decode_opt_String(arr[4]),
-      lastContact: dco_decode_Chrono_Utc(arr[5]),
-    );
-  }
-
-  @protected
-  BigInt dco_decode_usize(dynamic raw) {
-    // Codec=Dco (DartCObject based), see doc to use other codecs
-    return dcoDecodeU64(raw);
-  }
-
-  @protected
-  AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var inner = sse_decode_String(deserializer);
-    return AnyhowException(inner);
-  }
-
-  @protected
-  AppDatabase
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return AppDatabaseImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
-  @protected
-  AppDatabase
-  sse_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return AppDatabaseImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
-  @protected
-  DateTime sse_decode_Chrono_Utc(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var inner = sse_decode_i_64(deserializer);
-    return DateTime.fromMicrosecondsSinceEpoch(inner.toInt(), isUtc: true);
-  }
-
-  @protected
-  AppDatabase
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    SseDeserializer deserializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return AppDatabaseImpl.frbInternalSseDecode(
-      sse_decode_usize(deserializer),
-      sse_decode_i_32(deserializer),
-    );
-  }
-
-  @protected
-  String sse_decode_String(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var inner = sse_decode_list_prim_u_8_strict(deserializer);
-    return utf8.decoder.convert(inner);
-  }
-
-  @protected
-  Post sse_decode_box_autoadd_post(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return (sse_decode_post(deserializer));
-  }
-
-  @protected
-  Totem sse_decode_box_autoadd_totem(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return (sse_decode_totem(deserializer));
-  }
-
-  @protected
-  User sse_decode_box_autoadd_user(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return (sse_decode_user(deserializer));
-  }
-
-  @protected
-  PlatformInt64 sse_decode_i_64(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getPlatformInt64();
-  }
-
-  @protected
-  List<String> sse_decode_list_String(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-
-    var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <String>[];
-    for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_String(deserializer));
-    }
-    return ans_;
-  }
-
-  @protected
-  List<Post> sse_decode_list_post(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-
-    var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <Post>[];
-    for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_post(deserializer));
-    }
-    return ans_;
-  }
-
-  @protected
-  Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var len_ = sse_decode_i_32(deserializer);
-    return deserializer.buffer.getUint8List(len_);
-  }
-
-  @protected
-  List<Totem> sse_decode_list_totem(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-
-    var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <Totem>[];
-    for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_totem(deserializer));
-    }
-    return ans_;
-  }
-
-  @protected
-  List<User> sse_decode_list_user(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-
-    var len_ = sse_decode_i_32(deserializer);
-    var ans_ = <User>[];
-    for (var idx_ = 0; idx_ < len_; ++idx_) {
-      ans_.add(sse_decode_user(deserializer));
-    }
-    return ans_;
-  }
-
-  @protected
-  String? sse_decode_opt_String(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-
-    if (sse_decode_bool(deserializer)) {
-      return (sse_decode_String(deserializer));
-    } else {
-      return null;
-    }
-  }
-
-  @protected
-  Post sse_decode_post(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var var_uuid = sse_decode_String(deserializer);
-    var var_userId = sse_decode_String(deserializer);
-    var var_title = sse_decode_String(deserializer);
-    var var_body = sse_decode_String(deserializer);
-    var var_timestamp = sse_decode_Chrono_Utc(deserializer);
-    var var_image = sse_decode_opt_String(deserializer);
-    var var_sourceTotem = sse_decode_String(deserializer);
-    return Post(
-      uuid: var_uuid,
-      userId: var_userId,
-      title: var_title,
-      body: var_body,
-      timestamp: var_timestamp,
-      image: var_image,
-      sourceTotem: var_sourceTotem,
-    );
-  }
-
-  @protected
-  Totem sse_decode_totem(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var var_uuid = sse_decode_String(deserializer);
-    var var_name = sse_decode_String(deserializer);
-    var var_location = sse_decode_String(deserializer);
-    var var_lastContact = sse_decode_Chrono_Utc(deserializer);
-    return Totem(
-      uuid: var_uuid,
-      name: var_name,
-      location: var_location,
-      lastContact: var_lastContact,
-    );
-  }
-
-  @protected
-  int sse_decode_u_8(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getUint8();
-  }
-
-  @protected
-  void sse_decode_unit(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-  }
-
-  @protected
-  User sse_decode_user(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    var var_uuid = sse_decode_String(deserializer);
-    var var_username = sse_decode_String(deserializer);
-    var var_status = sse_decode_String(deserializer);
-    var var_bio = sse_decode_String(deserializer);
-    var var_profilePicture = sse_decode_opt_String(deserializer);
-    var var_lastContact = sse_decode_Chrono_Utc(deserializer);
-    return User(
-      uuid: var_uuid,
-      username: var_username,
-      status: var_status,
-      bio: var_bio,
-      profilePicture: var_profilePicture,
-      lastContact: var_lastContact,
-    );
-  }
-
-  @protected
-  BigInt sse_decode_usize(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getBigUint64();
-  }
-
-  @protected
-  int sse_decode_i_32(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getInt32();
-  }
-
-  @protected
-  bool sse_decode_bool(SseDeserializer deserializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    return deserializer.buffer.getUint8() != 0;
-  }
-
-  @protected
-  void sse_encode_AnyhowException(
-    AnyhowException self,
-    SseSerializer serializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_String(self.message, serializer);
-  }
-
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    AppDatabase self,
-    SseSerializer serializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as AppDatabaseImpl).frbInternalSseEncode(move: true),
-      serializer,
-    );
-  }
-
-  @protected
-  void
-  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    AppDatabase self,
-    SseSerializer serializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as AppDatabaseImpl).frbInternalSseEncode(move: false),
-      serializer,
-    );
-  }
-
-  @protected
-  void sse_encode_Chrono_Utc(DateTime self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_i_64(
-      PlatformInt64Util.from(self.microsecondsSinceEpoch),
-      serializer,
-    );
-  }
-
-  @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    AppDatabase self,
-    SseSerializer serializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_usize(
-      (self as AppDatabaseImpl).frbInternalSseEncode(move: null),
-      serializer,
-    );
-  }
-
-  @protected
-  void sse_encode_String(String self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_list_prim_u_8_strict(utf8.encoder.convert(self), serializer);
-  }
-
-  @protected
-  void sse_encode_box_autoadd_post(Post self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_post(self, serializer);
-  }
-
-  @protected
-  void sse_encode_box_autoadd_totem(Totem self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_totem(self, serializer);
-  }
-
-  @protected
-  void sse_encode_box_autoadd_user(User self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_user(self, serializer);
-  }
-
-  @protected
-  void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putPlatformInt64(self);
-  }
-
-  @protected
-  void sse_encode_list_String(List<String> self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_i_32(self.length, serializer);
-    for (final item in self) {
-      sse_encode_String(item, serializer);
-    }
-  }
-
-  @protected
-  void sse_encode_list_post(List<Post> self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_i_32(self.length, serializer);
-    for (final item in self) {
-      sse_encode_post(item, serializer);
-    }
-  }
-
-  @protected
-  void sse_encode_list_prim_u_8_strict(
-    Uint8List self,
-    SseSerializer serializer,
-  ) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_i_32(self.length, serializer);
-    serializer.buffer.putUint8List(self);
-  }
-
-  @protected
-  void sse_encode_list_totem(List<Totem> self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_i_32(self.length, serializer);
-    for (final item in self) {
-      sse_encode_totem(item, serializer);
-    }
-  }
-
-  @protected
-  void sse_encode_list_user(List<User> self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_i_32(self.length, serializer);
-    for (final item in self) {
-      sse_encode_user(item, serializer);
-    }
-  }
-
-  @protected
-  void sse_encode_opt_String(String? self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-
-    sse_encode_bool(self != null, serializer);
-    if (self != null) {
-      sse_encode_String(self, serializer);
-    }
-  }
-
-  @protected
-  void sse_encode_post(Post self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_String(self.uuid, serializer);
-    sse_encode_String(self.userId, serializer);
-    sse_encode_String(self.title, serializer);
-    sse_encode_String(self.body, serializer);
-    sse_encode_Chrono_Utc(self.timestamp, serializer);
-    sse_encode_opt_String(self.image, serializer);
-    sse_encode_String(self.sourceTotem, serializer);
-  }
-
-  @protected
-  void sse_encode_totem(Totem self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_String(self.uuid, serializer);
-    sse_encode_String(self.name, serializer);
-    sse_encode_String(self.location, serializer);
-    sse_encode_Chrono_Utc(self.lastContact, serializer);
-  }
-
-  @protected
-  void sse_encode_u_8(int self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putUint8(self);
-  }
-
-  @protected
-  void sse_encode_unit(void self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-  }
-
-  @protected
-  void sse_encode_user(User self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    sse_encode_String(self.uuid, serializer);
-    sse_encode_String(self.username, serializer);
-    sse_encode_String(self.status, serializer);
-    sse_encode_String(self.bio, serializer);
-    sse_encode_opt_String(self.profilePicture, serializer);
-    sse_encode_Chrono_Utc(self.lastContact, serializer);
-  }
-
-  @protected
-  void sse_encode_usize(BigInt self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putBigUint64(self);
-  }
-
-  @protected
-  void sse_encode_i_32(int self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putInt32(self);
-  }
-
-  @protected
-  void sse_encode_bool(bool self, SseSerializer serializer) {
-    // Codec=Sse (Serialization based), see doc to use other codecs
-    serializer.buffer.putUint8(self ? 1 : 0);
-  }
-}
-
-@sealed
-class AppDatabaseImpl extends RustOpaque implements AppDatabase {
-  // Not to be used by end users
-  AppDatabaseImpl.frbInternalDcoDecode(List<dynamic> wire)
-    : super.frbInternalDcoDecode(wire, _kStaticData);
-
-  // Not to be used by end users
-  AppDatabaseImpl.frbInternalSseDecode(BigInt ptr, int externalSizeOnNative)
-    : super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
-
-  static final _kStaticData = RustArcStaticData(
-    rustArcIncrementStrongCount:
-        RustLib.instance.api.rust_arc_increment_strong_count_AppDatabase,
-    rustArcDecrementStrongCount:
-        RustLib.instance.api.rust_arc_decrement_strong_count_AppDatabase,
-    rustArcDecrementStrongCountPtr:
-        RustLib.instance.api.rust_arc_decrement_strong_count_AppDatabasePtr,
-  );
-
-  Future<void> createPost({required Post post}) => RustLib.instance.api
-      .crateApiSimpleAppDatabaseCreatePost(that: this, post: post);
-
-  Future<void> createTotem({required Totem totem}) => RustLib.instance.api
-      .crateApiSimpleAppDatabaseCreateTotem(that: this, totem: totem);
-
-  Future<void> createUser({required User user}) => RustLib.instance.api
-      .crateApiSimpleAppDatabaseCreateUser(that: this, user: user);
-
-  Future<List<Post>> getAllPosts() =>
-      RustLib.instance.api.crateApiSimpleAppDatabaseGetAllPosts(that: this);
-
-  Future<List<Totem>> getAllTotems() =>
-      RustLib.instance.api.crateApiSimpleAppDatabaseGetAllTotems(that: this);
-
-  Future<List<User>> getAllUsers() =>
-      RustLib.instance.api.crateApiSimpleAppDatabaseGetAllUsers(that: this);
-
-  Future<Post> getPostById({required String uuid}) => RustLib.instance.api
-      .crateApiSimpleAppDatabaseGetPostById(that: this, uuid: uuid);
-
-  Future<List<String>> getPostIdsInRange({
-    required DateTime start,
-    required DateTime end,
-  }) => RustLib.instance.api.crateApiSimpleAppDatabaseGetPostIdsInRange(
-    that: this,
-    start: start,
-    end: end,
-  );
-
-  Future<User> getUserById({required String uuid}) => RustLib.instance.api
-      .crateApiSimpleAppDatabaseGetUserById(that: this, uuid: uuid);
-
-  Future<void> updateTotemLastContact({
-    required String uuid,
-    required DateTime lastContact,
-  }) => RustLib.instance.api.crateApiSimpleAppDatabaseUpdateTotemLastContact(
-    that: this,
-    uuid: uuid,
-    lastContact: lastContact,
-  );
-
-  Future<void> updateUser({required User user}) => RustLib.instance.api
-      .crateApiSimpleAppDatabaseUpdateUser(that: this, user: user);
-}
+        )
+        ,
+            constMeta: kCrateApiSimpleInitAppConstMeta,
+            argValues: [],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleInitAppConstMeta => const TaskConstMeta(
+            debugName: "init_app",
+            argNames: [],
+        );
+        
+
+@override Future<LinkSummary> crateApiLinkLinkFromFile({required AppDatabase db , required String keyDir , required String cacheDir , required String qrCode , required List<int> file })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_String(cacheDir, serializer);
+sse_encode_String(qrCode, serializer);
+sse_encode_list_prim_u_8_loose(file, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 50, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_link_summary,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiLinkLinkFromFileConstMeta,
+            argValues: [db, keyDir, cacheDir, qrCode, file],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiLinkLinkFromFileConstMeta => const TaskConstMeta(
+            debugName: "link_from_file",
+            argNames: ["db", "keyDir", "cacheDir", "qrCode", "file"],
+        );
+        
+
+@override Future<LinkSummary> crateApiLinkLinkOverNetwork({required AppDatabase db , required String keyDir , required String cacheDir , required String qrCode })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_String(cacheDir, serializer);
+sse_encode_String(qrCode, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 51, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_link_summary,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiLinkLinkOverNetworkConstMeta,
+            argValues: [db, keyDir, cacheDir, qrCode],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiLinkLinkOverNetworkConstMeta => const TaskConstMeta(
+            debugName: "link_over_network",
+            argNames: ["db", "keyDir", "cacheDir", "qrCode"],
+        );
+        
+
+@override Future<List<Conversation>> crateApiMessagesListConversations({required AppDatabase db })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 52, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_conversation,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiMessagesListConversationsConstMeta,
+            argValues: [db],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMessagesListConversationsConstMeta => const TaskConstMeta(
+            debugName: "list_conversations",
+            argNames: ["db"],
+        );
+        
+
+@override Future<IdentityInfo?> crateApiIdentityLoadIdentity({required String keyDir })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_String(keyDir, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 53, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_opt_box_autoadd_identity_info,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityLoadIdentityConstMeta,
+            argValues: [keyDir],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityLoadIdentityConstMeta => const TaskConstMeta(
+            debugName: "load_identity",
+            argNames: ["keyDir"],
+        );
+        
+
+@override Future<IdentityInfo> crateApiIdentityMigrateIdentity({required AppDatabase db , required String keyDir , required String legacyUserId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_String(legacyUserId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 54, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_identity_info,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityMigrateIdentityConstMeta,
+            argValues: [db, keyDir, legacyUserId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityMigrateIdentityConstMeta => const TaskConstMeta(
+            debugName: "migrate_identity",
+            argNames: ["db", "keyDir", "legacyUserId"],
+        );
+        
+
+@override PostEntities crateApiTagsParseEntities({required String body })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_String(body, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 55)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_post_entities,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiTagsParseEntitiesConstMeta,
+            argValues: [body],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiTagsParseEntitiesConstMeta => const TaskConstMeta(
+            debugName: "parse_entities",
+            argNames: ["body"],
+        );
+        
+
+@override Future<PreparedImage> crateApiMediaPrepareImage({required AppDatabase db , required List<int> data , required String mediaDir , ImageOptions? options })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_list_prim_u_8_loose(data, serializer);
+sse_encode_String(mediaDir, serializer);
+sse_encode_opt_box_autoadd_image_options(options, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 56, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_prepared_image,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiMediaPrepareImageConstMeta,
+            argValues: [db, data, mediaDir, options],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMediaPrepareImageConstMeta => const TaskConstMeta(
+            debugName: "prepare_image",
+            argNames: ["db", "data", "mediaDir", "options"],
+        );
+        
+
+@override Attachment crateApiMediaPreparedImageAttachment({required PreparedImage that , String? altText })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_box_autoadd_prepared_image(that, serializer);
+sse_encode_opt_String(altText, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 57)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_attachment,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiMediaPreparedImageAttachmentConstMeta,
+            argValues: [that, altText],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMediaPreparedImageAttachmentConstMeta => const TaskConstMeta(
+            debugName: "prepared_image_attachment",
+            argNames: ["that", "altText"],
+        );
+        
+
+@override Future<PostReactions> crateApiReactionsReact({required AppDatabase db , required String postId , required ReactionKind kind , required bool active })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(postId, serializer);
+sse_encode_reaction_kind(kind, serializer);
+sse_encode_bool(active, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 58, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_post_reactions,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiReactionsReactConstMeta,
+            argValues: [db, postId, kind, active],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiReactionsReactConstMeta => const TaskConstMeta(
+            debugName: "react",
+            argNames: ["db", "postId", "kind", "active"],
+        );
+        
+
+@override Future<IdentityInfo> crateApiIdentityRegisterUser({required AppDatabase db , required String keyDir , required User user })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_box_autoadd_user(user, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 59, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_identity_info,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityRegisterUserConstMeta,
+            argValues: [db, keyDir, user],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityRegisterUserConstMeta => const TaskConstMeta(
+            debugName: "register_user",
+            argNames: ["db", "keyDir", "user"],
+        );
+        
+
+@override Future<ThreadReply> crateApiRepliesReplyTo({required AppDatabase db , required String parentId , required String body })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(parentId, serializer);
+sse_encode_String(body, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 60, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_thread_reply,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiRepliesReplyToConstMeta,
+            argValues: [db, parentId, body],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiRepliesReplyToConstMeta => const TaskConstMeta(
+            debugName: "reply_to",
+            argNames: ["db", "parentId", "body"],
+        );
+        
+
+@override Future<FeedItem> crateApiRepostsRepost({required AppDatabase db , required String postId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(postId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 61, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_feed_item,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiRepostsRepostConstMeta,
+            argValues: [db, postId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiRepostsRepostConstMeta => const TaskConstMeta(
+            debugName: "repost",
+            argNames: ["db", "postId"],
+        );
+        
+
+@override Future<IdentityInfo> crateApiIdentityRestoreFromBackup({required AppDatabase db , required String keyDir , required List<int> file , required String passphrase })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_list_prim_u_8_loose(file, serializer);
+sse_encode_String(passphrase, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 62, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_identity_info,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityRestoreFromBackupConstMeta,
+            argValues: [db, keyDir, file, passphrase],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityRestoreFromBackupConstMeta => const TaskConstMeta(
+            debugName: "restore_from_backup",
+            argNames: ["db", "keyDir", "file", "passphrase"],
+        );
+        
+
+@override Future<IdentityInfo> crateApiIdentityRestoreFromMnemonic({required AppDatabase db , required String keyDir , required String phrase })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+sse_encode_String(phrase, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 63, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_identity_info,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityRestoreFromMnemonicConstMeta,
+            argValues: [db, keyDir, phrase],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityRestoreFromMnemonicConstMeta => const TaskConstMeta(
+            debugName: "restore_from_mnemonic",
+            argNames: ["db", "keyDir", "phrase"],
+        );
+        
+
+@override Future<DirectMessage> crateApiMessagesSendMessage({required AppDatabase db , required String recipientId , required String text })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(recipientId, serializer);
+sse_encode_String(text, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 64, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_direct_message,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiMessagesSendMessageConstMeta,
+            argValues: [db, recipientId, text],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMessagesSendMessageConstMeta => const TaskConstMeta(
+            debugName: "send_message",
+            argNames: ["db", "recipientId", "text"],
+        );
+        
+
+@override Future<String> crateApiBundlesSendPostToTotem({required AppDatabase db , required String postUuid , required String totemId })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(postUuid, serializer);
+sse_encode_String(totemId, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 65, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_String,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiBundlesSendPostToTotemConstMeta,
+            argValues: [db, postUuid, totemId],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiBundlesSendPostToTotemConstMeta => const TaskConstMeta(
+            debugName: "send_post_to_totem",
+            argNames: ["db", "postUuid", "totemId"],
+        );
+        
+
+@override Future<Attachment> crateApiMediaStoreAttachment({required AppDatabase db , required List<int> data , required String mediaDir , required AttachmentKind kind , int? durationMs , String? altText })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_list_prim_u_8_loose(data, serializer);
+sse_encode_String(mediaDir, serializer);
+sse_encode_attachment_kind(kind, serializer);
+sse_encode_opt_box_autoadd_u_32(durationMs, serializer);
+sse_encode_opt_String(altText, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 66, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_attachment,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiMediaStoreAttachmentConstMeta,
+            argValues: [db, data, mediaDir, kind, durationMs, altText],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMediaStoreAttachmentConstMeta => const TaskConstMeta(
+            debugName: "store_attachment",
+            argNames: ["db", "data", "mediaDir", "kind", "durationMs", "altText"],
+        );
+        
+
+@override Future<BundleSyncSummary> crateApiBundlesSyncBundles({required AppDatabase db , String? totemUrl , required bool courier })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_opt_String(totemUrl, serializer);
+sse_encode_bool(courier, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 67, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_bundle_sync_summary,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiBundlesSyncBundlesConstMeta,
+            argValues: [db, totemUrl, courier],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiBundlesSyncBundlesConstMeta => const TaskConstMeta(
+            debugName: "sync_bundles",
+            argNames: ["db", "totemUrl", "courier"],
+        );
+        
+
+@override Future<MediaSyncSummary> crateApiMediaSyncMedia({required AppDatabase db , required String mediaDir , String? totemUrl , required FutureOr<void> Function(MediaSyncProgress) onProgress })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(mediaDir, serializer);
+sse_encode_opt_String(totemUrl, serializer);
+sse_encode_DartFn_Inputs_media_sync_progress_Output_unit_AnyhowException(onProgress, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 68, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_media_sync_summary,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiMediaSyncMediaConstMeta,
+            argValues: [db, mediaDir, totemUrl, onProgress],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMediaSyncMediaConstMeta => const TaskConstMeta(
+            debugName: "sync_media",
+            argNames: ["db", "mediaDir", "totemUrl", "onProgress"],
+        );
+        
+
+@override Future<MessageSyncSummary> crateApiMessagesSyncMessages({required AppDatabase db , String? totemUrl })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_opt_String(totemUrl, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 69, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_message_sync_summary,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiMessagesSyncMessagesConstMeta,
+            argValues: [db, totemUrl],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiMessagesSyncMessagesConstMeta => const TaskConstMeta(
+            debugName: "sync_messages",
+            argNames: ["db", "totemUrl"],
+        );
+        
+
+@override Future<RecordSyncSummary> crateApiSyncSyncRecords({required AppDatabase db , String? totemUrl })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_opt_String(totemUrl, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 70, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_record_sync_summary,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiSyncSyncRecordsConstMeta,
+            argValues: [db, totemUrl],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSyncSyncRecordsConstMeta => const TaskConstMeta(
+            debugName: "sync_records",
+            argNames: ["db", "totemUrl"],
+        );
+        
+
+@override Future<IdentityInfo?> crateApiIdentityUnlockIdentity({required AppDatabase db , required String keyDir })  { return handler.executeNormal(NormalTask(
+            callFfi: (port_) {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(db, serializer);
+sse_encode_String(keyDir, serializer);
+            pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 71, port: port_);
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_opt_box_autoadd_identity_info,
+          decodeErrorData: sse_decode_AnyhowException,
+        )
+        ,
+            constMeta: kCrateApiIdentityUnlockIdentityConstMeta,
+            argValues: [db, keyDir],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiIdentityUnlockIdentityConstMeta => const TaskConstMeta(
+            debugName: "unlock_identity",
+            argNames: ["db", "keyDir"],
+        );
+        
+
+@override List<ValidationIssue> crateApiSimpleValidatePost({required Post post })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_box_autoadd_post(post, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 72)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_validation_issue,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiSimpleValidatePostConstMeta,
+            argValues: [post],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleValidatePostConstMeta => const TaskConstMeta(
+            debugName: "validate_post",
+            argNames: ["post"],
+        );
+        
+
+@override List<ValidationIssue> crateApiSimpleValidateUser({required User user })  { return handler.executeSync(SyncTask(
+            callFfi: () {
+              
+            final serializer = SseSerializer(generalizedFrbRustBinding);sse_encode_box_autoadd_user(user, serializer);
+            return pdeCallFfi(generalizedFrbRustBinding, serializer, funcId: 73)!;
+            
+            },
+            codec: 
+        SseCodec(
+          decodeSuccessData: sse_decode_list_validation_issue,
+          decodeErrorData: null,
+        )
+        ,
+            constMeta: kCrateApiSimpleValidateUserConstMeta,
+            argValues: [user],
+            apiImpl: this,
+        )); }
+
+
+        TaskConstMeta get kCrateApiSimpleValidateUserConstMeta => const TaskConstMeta(
+            debugName: "validate_user",
+            argNames: ["user"],
+        );
+        
+
+
+            Future<void> Function(int, dynamic)
+                encode_DartFn_Inputs_media_sync_progress_Output_unit_AnyhowException(FutureOr<void> Function(MediaSyncProgress) raw) {
+              return (callId, rawArg0) async {
+                final arg0 = dco_decode_media_sync_progress(rawArg0);
+
+
+                Box<void>? rawOutput;
+                Box<AnyhowException>? rawError;
+                try {
+                    rawOutput = Box(await raw(arg0));
+                } catch (e, s) {
+                    rawError = Box(AnyhowException("$e\n\n$s"));
+                }
+
+                final serializer = SseSerializer(generalizedFrbRustBinding);
+                assert((rawOutput != null) ^ (rawError != null));
+                if (rawOutput != null) {
+                    serializer.buffer.putUint8(0);
+                    sse_encode_unit(rawOutput.value, serializer);
+                } else {
+                    serializer.buffer.putUint8(1);
+                    sse_encode_AnyhowException(rawError!.value, serializer);
+                }
+                final output = serializer.intoRaw();
+
+                generalizedFrbRustBinding.dartFnDeliverOutput(
+                  callId: callId, ptr: output.ptr, rustVecLen: output.rustVecLen, dataLen: output.dataLen);
+              };
+            }
+            RustArcIncrementStrongCountFnType get rust_arc_increment_strong_count_AppDatabase => wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase;
+
+RustArcDecrementStrongCountFnType get rust_arc_decrement_strong_count_AppDatabase => wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase;
+
+RustArcIncrementStrongCountFnType get rust_arc_increment_strong_count_LinkOffer => wire.rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer;
+
+RustArcDecrementStrongCountFnType get rust_arc_decrement_strong_count_LinkOffer => wire.rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer;
+
+
+
+                  @protected AnyhowException dco_decode_AnyhowException(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return AnyhowException(raw as String); }
+
+@protected AppDatabase dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return AppDatabaseImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected LinkOffer dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return LinkOfferImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected AppDatabase dco_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return AppDatabaseImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected LinkOffer dco_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return LinkOfferImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected DateTime dco_decode_Chrono_Utc(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeTimestamp(ts: dco_decode_i_64(raw).toInt(), isUtc: true); }
+
+@protected FutureOr<void> Function(MediaSyncProgress) dco_decode_DartFn_Inputs_media_sync_progress_Output_unit_AnyhowException(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+throw UnimplementedError(''); }
+
+@protected Object dco_decode_DartOpaque(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return decodeDartOpaque(raw, generalizedFrbRustBinding); }
+
+@protected AppDatabase dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return AppDatabaseImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected LinkOffer dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return LinkOfferImpl.frbInternalDcoDecode(raw as List<dynamic>); }
+
+@protected String dco_decode_String(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as String; }
+
+@protected Attachment dco_decode_attachment(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 8) throw Exception('unexpected arr length: expect 8 but see ${arr.length}');
+                return Attachment(kind: dco_decode_attachment_kind(arr[0]),
+mime: dco_decode_String(arr[1]),
+hash: dco_decode_String(arr[2]),
+size: dco_decode_u_64(arr[3]),
+durationMs: dco_decode_opt_box_autoadd_u_32(arr[4]),
+width: dco_decode_opt_box_autoadd_u_32(arr[5]),
+height: dco_decode_opt_box_autoadd_u_32(arr[6]),
+altText: dco_decode_opt_String(arr[7]),); }
+
+@protected AttachmentKind dco_decode_attachment_kind(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return AttachmentKind.values[raw as int]; }
+
+@protected bool dco_decode_bool(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as bool; }
+
+@protected DateTime dco_decode_box_autoadd_Chrono_Utc(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_Chrono_Utc(raw); }
+
+@protected IdentityInfo dco_decode_box_autoadd_identity_info(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_identity_info(raw); }
+
+@protected ImageOptions dco_decode_box_autoadd_image_options(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_image_options(raw); }
+
+@protected ImagePreview dco_decode_box_autoadd_image_preview(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_image_preview(raw); }
+
+@protected LastSeen dco_decode_box_autoadd_last_seen(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_last_seen(raw); }
+
+@protected Post dco_decode_box_autoadd_post(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_post(raw); }
+
+@protected PostImage dco_decode_box_autoadd_post_image(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_post_image(raw); }
+
+@protected PreparedImage dco_decode_box_autoadd_prepared_image(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_prepared_image(raw); }
+
+@protected Totem dco_decode_box_autoadd_totem(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_totem(raw); }
+
+@protected int dco_decode_box_autoadd_u_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected User dco_decode_box_autoadd_user(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dco_decode_user(raw); }
+
+@protected BundleSyncSummary dco_decode_bundle_sync_summary(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 6) throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+                return BundleSyncSummary(delivered: dco_decode_u_32(arr[0]),
+taken: dco_decode_u_32(arr[1]),
+handedOver: dco_decode_u_32(arr[2]),
+refused: dco_decode_u_32(arr[3]),
+rejected: dco_decode_u_32(arr[4]),
+dropped: dco_decode_u_32(arr[5]),); }
+
+@protected Conversation dco_decode_conversation(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return Conversation(userId: dco_decode_String(arr[0]),
+lastMessage: dco_decode_direct_message(arr[1]),
+messageCount: dco_decode_u_32(arr[2]),); }
+
+@protected DirectMessage dco_decode_direct_message(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 7) throw Exception('unexpected arr length: expect 7 but see ${arr.length}');
+                return DirectMessage(uuid: dco_decode_String(arr[0]),
+senderId: dco_decode_String(arr[1]),
+recipientId: dco_decode_String(arr[2]),
+timestamp: dco_decode_Chrono_Utc(arr[3]),
+text: dco_decode_opt_String(arr[4]),
+verification: dco_decode_verification(arr[5]),
+delivered: dco_decode_bool(arr[6]),); }
+
+@protected FeedItem dco_decode_feed_item(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 6) throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+                return FeedItem(postId: dco_decode_String(arr[0]),
+post: dco_decode_opt_box_autoadd_post(arr[1]),
+authorId: dco_decode_String(arr[2]),
+repostedBy: dco_decode_opt_String(arr[3]),
+repostUuid: dco_decode_opt_String(arr[4]),
+timestamp: dco_decode_Chrono_Utc(arr[5]),); }
+
+@protected int dco_decode_i_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected PlatformInt64 dco_decode_i_64(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeI64(raw); }
+
+@protected IdentityInfo dco_decode_identity_info(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+                return IdentityInfo(userId: dco_decode_String(arr[0]),
+publicKey: dco_decode_String(arr[1]),); }
+
+@protected ImageOptions dco_decode_image_options(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return ImageOptions(maxDimension: dco_decode_u_32(arr[0]),
+thumbnailDimension: dco_decode_u_32(arr[1]),
+quality: dco_decode_u_8(arr[2]),); }
+
+@protected ImagePreview dco_decode_image_preview(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return ImagePreview(blurhash: dco_decode_String(arr[0]),
+width: dco_decode_u_32(arr[1]),
+height: dco_decode_u_32(arr[2]),); }
+
+@protected PlatformInt64 dco_decode_isize(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeI64(raw); }
+
+@protected LastSeen dco_decode_last_seen(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return LastSeen(userId: dco_decode_String(arr[0]),
+totemId: dco_decode_String(arr[1]),
+seenAt: dco_decode_Chrono_Utc(arr[2]),); }
+
+@protected LinkSummary dco_decode_link_summary(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 4) throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+                return LinkSummary(identity: dco_decode_identity_info(arr[0]),
+usersImported: dco_decode_u_32(arr[1]),
+postsImported: dco_decode_u_32(arr[2]),
+rejected: dco_decode_u_32(arr[3]),); }
+
+@protected List<String> dco_decode_list_String(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_String).toList(); }
+
+@protected List<Attachment> dco_decode_list_attachment(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_attachment).toList(); }
+
+@protected List<Conversation> dco_decode_list_conversation(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_conversation).toList(); }
+
+@protected List<DirectMessage> dco_decode_list_direct_message(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_direct_message).toList(); }
+
+@protected List<FeedItem> dco_decode_list_feed_item(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_feed_item).toList(); }
+
+@protected List<LastSeen> dco_decode_list_last_seen(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_last_seen).toList(); }
+
+@protected List<Post> dco_decode_list_post(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_post).toList(); }
+
+@protected List<PostRevision> dco_decode_list_post_revision(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_post_revision).toList(); }
+
+@protected List<int> dco_decode_list_prim_u_8_loose(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as List<int>; }
+
+@protected Uint8List dco_decode_list_prim_u_8_strict(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as Uint8List; }
+
+@protected List<ThreadReply> dco_decode_list_thread_reply(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_thread_reply).toList(); }
+
+@protected List<Totem> dco_decode_list_totem(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_totem).toList(); }
+
+@protected List<TrendingTag> dco_decode_list_trending_tag(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_trending_tag).toList(); }
+
+@protected List<TrendingThread> dco_decode_list_trending_thread(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_trending_thread).toList(); }
+
+@protected List<User> dco_decode_list_user(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_user).toList(); }
+
+@protected List<UserLink> dco_decode_list_user_link(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_user_link).toList(); }
+
+@protected List<ValidationIssue> dco_decode_list_validation_issue(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return (raw as List<dynamic>).map(dco_decode_validation_issue).toList(); }
+
+@protected MediaDirection dco_decode_media_direction(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return MediaDirection.values[raw as int]; }
+
+@protected MediaSyncProgress dco_decode_media_sync_progress(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 6) throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+                return MediaSyncProgress(hash: dco_decode_String(arr[0]),
+direction: dco_decode_media_direction(arr[1]),
+transferred: dco_decode_u_64(arr[2]),
+total: dco_decode_u_64(arr[3]),
+done: dco_decode_bool(arr[4]),
+error: dco_decode_opt_String(arr[5]),); }
+
+@protected MediaSyncSummary dco_decode_media_sync_summary(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return MediaSyncSummary(uploaded: dco_decode_u_32(arr[0]),
+downloaded: dco_decode_u_32(arr[1]),
+failed: dco_decode_u_32(arr[2]),); }
+
+@protected MessageSyncSummary dco_decode_message_sync_summary(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 5) throw Exception('unexpected arr length: expect 5 but see ${arr.length}');
+                return MessageSyncSummary(received: dco_decode_u_32(arr[0]),
+sent: dco_decode_u_32(arr[1]),
+delivered: dco_decode_u_32(arr[2]),
+refused: dco_decode_u_32(arr[3]),
+rejected: dco_decode_u_32(arr[4]),); }
+
+@protected String? dco_decode_opt_String(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_String(raw); }
+
+@protected DateTime? dco_decode_opt_box_autoadd_Chrono_Utc(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_Chrono_Utc(raw); }
+
+@protected IdentityInfo? dco_decode_opt_box_autoadd_identity_info(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_identity_info(raw); }
+
+@protected ImageOptions? dco_decode_opt_box_autoadd_image_options(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_image_options(raw); }
+
+@protected ImagePreview? dco_decode_opt_box_autoadd_image_preview(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_image_preview(raw); }
+
+@protected LastSeen? dco_decode_opt_box_autoadd_last_seen(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_last_seen(raw); }
+
+@protected Post? dco_decode_opt_box_autoadd_post(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_post(raw); }
+
+@protected PostImage? dco_decode_opt_box_autoadd_post_image(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_post_image(raw); }
+
+@protected int? dco_decode_opt_box_autoadd_u_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw == null ? null : dco_decode_box_autoadd_u_32(raw); }
+
+@protected Post dco_decode_post(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 7) throw Exception('unexpected arr length: expect 7 but see ${arr.length}');
+                return Post(uuid: dco_decode_String(arr[0]),
+userId: dco_decode_String(arr[1]),
+title: dco_decode_String(arr[2]),
+body: dco_decode_String(arr[3]),
+timestamp: dco_decode_Chrono_Utc(arr[4]),
+image: dco_decode_opt_String(arr[5]),
+sourceTotem: dco_decode_String(arr[6]),); }
+
+@protected PostEntities dco_decode_post_entities(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return PostEntities(tags: dco_decode_list_String(arr[0]),
+handles: dco_decode_list_String(arr[1]),
+fingerprints: dco_decode_list_String(arr[2]),); }
+
+@protected PostImage dco_decode_post_image(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return PostImage(image: dco_decode_String(arr[0]),
+thumbnail: dco_decode_opt_String(arr[1]),
+preview: dco_decode_opt_box_autoadd_image_preview(arr[2]),); }
+
+@protected PostReactions dco_decode_post_reactions(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 4) throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+                return PostReactions(likes: dco_decode_u_32(arr[0]),
+shares: dco_decode_u_32(arr[1]),
+liked: dco_decode_bool(arr[2]),
+shared: dco_decode_bool(arr[3]),); }
+
+@protected PostRevision dco_decode_post_revision(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 4) throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+                return PostRevision(revision: dco_decode_u_32(arr[0]),
+editedAt: dco_decode_opt_box_autoadd_Chrono_Utc(arr[1]),
+title: dco_decode_String(arr[2]),
+body: dco_decode_String(arr[3]),); }
+
+@protected PreparedImage dco_decode_prepared_image(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return PreparedImage(image: dco_decode_stored_image(arr[0]),
+thumbnail: dco_decode_stored_image(arr[1]),
+blurhash: dco_decode_String(arr[2]),); }
+
+@protected ReactionKind dco_decode_reaction_kind(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return ReactionKind.values[raw as int]; }
+
+@protected RecordSyncSummary dco_decode_record_sync_summary(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 16) throw Exception('unexpected arr length: expect 16 but see ${arr.length}');
+                return RecordSyncSummary(rotationsDownloaded: dco_decode_u_32(arr[0]),
+rotationsUploaded: dco_decode_u_32(arr[1]),
+usersDownloaded: dco_decode_u_32(arr[2]),
+usersUploaded: dco_decode_u_32(arr[3]),
+lastSeenDownloaded: dco_decode_u_32(arr[4]),
+tombstonesDownloaded: dco_decode_u_32(arr[5]),
+tombstonesUploaded: dco_decode_u_32(arr[6]),
+postsDownloaded: dco_decode_u_32(arr[7]),
+postsUploaded: dco_decode_u_32(arr[8]),
+repliesDownloaded: dco_decode_u_32(arr[9]),
+repliesUploaded: dco_decode_u_32(arr[10]),
+repostsDownloaded: dco_decode_u_32(arr[11]),
+repostsUploaded: dco_decode_u_32(arr[12]),
+reactionsDownloaded: dco_decode_u_32(arr[13]),
+reactionsUploaded: dco_decode_u_32(arr[14]),
+rejected: dco_decode_u_32(arr[15]),); }
+
+@protected StoredImage dco_decode_stored_image(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 5) throw Exception('unexpected arr length: expect 5 but see ${arr.length}');
+                return StoredImage(hash: dco_decode_String(arr[0]),
+path: dco_decode_String(arr[1]),
+width: dco_decode_u_32(arr[2]),
+height: dco_decode_u_32(arr[3]),
+size: dco_decode_u_64(arr[4]),); }
+
+@protected ThreadReply dco_decode_thread_reply(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 9) throw Exception('unexpected arr length: expect 9 but see ${arr.length}');
+                return ThreadReply(uuid: dco_decode_String(arr[0]),
+userId: dco_decode_String(arr[1]),
+parentId: dco_decode_String(arr[2]),
+rootId: dco_decode_String(arr[3]),
+body: dco_decode_String(arr[4]),
+timestamp: dco_decode_Chrono_Utc(arr[5]),
+depth: dco_decode_u_32(arr[6]),
+parentMissing: dco_decode_bool(arr[7]),
+verification: dco_decode_verification(arr[8]),); }
+
+@protected Totem dco_decode_totem(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 4) throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+                return Totem(uuid: dco_decode_String(arr[0]),
+name: dco_decode_String(arr[1]),
+location: dco_decode_String(arr[2]),
+lastContact: dco_decode_Chrono_Utc(arr[3]),); }
+
+@protected TrendingTag dco_decode_trending_tag(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+                return TrendingTag(tag: dco_decode_String(arr[0]),
+posts: dco_decode_u_32(arr[1]),); }
+
+@protected TrendingThread dco_decode_trending_thread(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+                return TrendingThread(postId: dco_decode_String(arr[0]),
+replies: dco_decode_u_32(arr[1]),
+post: dco_decode_opt_box_autoadd_post(arr[2]),); }
+
+@protected TrendingTopics dco_decode_trending_topics(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 4) throw Exception('unexpected arr length: expect 4 but see ${arr.length}');
+                return TrendingTopics(totemId: dco_decode_String(arr[0]),
+since: dco_decode_Chrono_Utc(arr[1]),
+tags: dco_decode_list_trending_tag(arr[2]),
+threads: dco_decode_list_trending_thread(arr[3]),); }
+
+@protected int dco_decode_u_32(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected BigInt dco_decode_u_64(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeU64(raw); }
+
+@protected int dco_decode_u_8(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return raw as int; }
+
+@protected void dco_decode_unit(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return; }
+
+@protected User dco_decode_user(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 6) throw Exception('unexpected arr length: expect 6 but see ${arr.length}');
+                return User(uuid: dco_decode_String(arr[0]),
+username: dco_decode_String(arr[1]),
+status: dco_decode_String(arr[2]),
+bio: dco_decode_String(arr[3]),
+profilePicture: dco_decode_opt_String(arr[4]),
+lastContact: dco_decode_Chrono_Utc(arr[5]),); }
+
+@protected UserLink dco_decode_user_link(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+                return UserLink(legacyId: dco_decode_String(arr[0]),
+userId: dco_decode_String(arr[1]),); }
+
+@protected BigInt dco_decode_usize(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return dcoDecodeU64(raw); }
+
+@protected ValidationIssue dco_decode_validation_issue(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+final arr = raw as List<dynamic>;
+                if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+                return ValidationIssue(field: dco_decode_String(arr[0]),
+message: dco_decode_String(arr[1]),); }
+
+@protected Verification dco_decode_verification(dynamic raw){ // Codec=Dco (DartCObject based), see doc to use other codecs
+return Verification.values[raw as int]; }
+
+@protected AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_String(deserializer);
+        return AnyhowException(inner); }
+
+@protected AppDatabase sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return AppDatabaseImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected LinkOffer sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return LinkOfferImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected AppDatabase sse_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return AppDatabaseImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected LinkOffer sse_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return LinkOfferImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected DateTime sse_decode_Chrono_Utc(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_i_64(deserializer);
+        return DateTime.fromMicrosecondsSinceEpoch(inner.toInt(), isUtc: true); }
+
+@protected Object sse_decode_DartOpaque(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_isize(deserializer);
+        return decodeDartOpaque(inner, generalizedFrbRustBinding); }
+
+@protected AppDatabase sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return AppDatabaseImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected LinkOffer sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return LinkOfferImpl.frbInternalSseDecode(sse_decode_usize(deserializer), sse_decode_i_32(deserializer)); }
+
+@protected String sse_decode_String(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_list_prim_u_8_strict(deserializer);
+        return utf8.decoder.convert(inner); }
+
+@protected Attachment sse_decode_attachment(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_kind = sse_decode_attachment_kind(deserializer);
+var var_mime = sse_decode_String(deserializer);
+var var_hash = sse_decode_String(deserializer);
+var var_size = sse_decode_u_64(deserializer);
+var var_durationMs = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_width = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_height = sse_decode_opt_box_autoadd_u_32(deserializer);
+var var_altText = sse_decode_opt_String(deserializer);
+return Attachment(kind: var_kind, mime: var_mime, hash: var_hash, size: var_size, durationMs: var_durationMs, width: var_width, height: var_height, altText: var_altText); }
+
+@protected AttachmentKind sse_decode_attachment_kind(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_i_32(deserializer);
+        return AttachmentKind.values[inner]; }
+
+@protected bool sse_decode_bool(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getUint8() != 0; }
+
+@protected DateTime sse_decode_box_autoadd_Chrono_Utc(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_Chrono_Utc(deserializer)); }
+
+@protected IdentityInfo sse_decode_box_autoadd_identity_info(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_identity_info(deserializer)); }
+
+@protected ImageOptions sse_decode_box_autoadd_image_options(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_image_options(deserializer)); }
+
+@protected ImagePreview sse_decode_box_autoadd_image_preview(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_image_preview(deserializer)); }
+
+@protected LastSeen sse_decode_box_autoadd_last_seen(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_last_seen(deserializer)); }
+
+@protected Post sse_decode_box_autoadd_post(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_post(deserializer)); }
+
+@protected PostImage sse_decode_box_autoadd_post_image(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_post_image(deserializer)); }
+
+@protected PreparedImage sse_decode_box_autoadd_prepared_image(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_prepared_image(deserializer)); }
+
+@protected Totem sse_decode_box_autoadd_totem(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_totem(deserializer)); }
+
+@protected int sse_decode_box_autoadd_u_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_u_32(deserializer)); }
+
+@protected User sse_decode_box_autoadd_user(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return (sse_decode_user(deserializer)); }
+
+@protected BundleSyncSummary sse_decode_bundle_sync_summary(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_delivered = sse_decode_u_32(deserializer);
+var var_taken = sse_decode_u_32(deserializer);
+var var_handedOver = sse_decode_u_32(deserializer);
+var var_refused = sse_decode_u_32(deserializer);
+var var_rejected = sse_decode_u_32(deserializer);
+var var_dropped = sse_decode_u_32(deserializer);
+return BundleSyncSummary(delivered: var_delivered, taken: var_taken, handedOver: var_handedOver, refused: var_refused, rejected: var_rejected, dropped: var_dropped); }
+
+@protected Conversation sse_decode_conversation(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_userId = sse_decode_String(deserializer);
+var var_lastMessage = sse_decode_direct_message(deserializer);
+var var_messageCount = sse_decode_u_32(deserializer);
+return Conversation(userId: var_userId, lastMessage: var_lastMessage, messageCount: var_messageCount); }
+
+@protected DirectMessage sse_decode_direct_message(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_uuid = sse_decode_String(deserializer);
+var var_senderId = sse_decode_String(deserializer);
+var var_recipientId = sse_decode_String(deserializer);
+var var_timestamp = sse_decode_Chrono_Utc(deserializer);
+var var_text = sse_decode_opt_String(deserializer);
+var var_verification = sse_decode_verification(deserializer);
+var var_delivered = sse_decode_bool(deserializer);
+return DirectMessage(uuid: var_uuid, senderId: var_senderId, recipientId: var_recipientId, timestamp: var_timestamp, text: var_text, verification: var_verification, delivered: var_delivered); }
+
+@protected FeedItem sse_decode_feed_item(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_postId = sse_decode_String(deserializer);
+var var_post = sse_decode_opt_box_autoadd_post(deserializer);
+var var_authorId = sse_decode_String(deserializer);
+var var_repostedBy = sse_decode_opt_String(deserializer);
+var var_repostUuid = sse_decode_opt_String(deserializer);
+var var_timestamp = sse_decode_Chrono_Utc(deserializer);
+return FeedItem(postId: var_postId, post: var_post, authorId: var_authorId, repostedBy: var_repostedBy, repostUuid: var_repostUuid, timestamp: var_timestamp); }
+
+@protected int sse_decode_i_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getInt32(); }
+
+@protected PlatformInt64 sse_decode_i_64(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getPlatformInt64(); }
+
+@protected IdentityInfo sse_decode_identity_info(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_userId = sse_decode_String(deserializer);
+var var_publicKey = sse_decode_String(deserializer);
+return IdentityInfo(userId: var_userId, publicKey: var_publicKey); }
+
+@protected ImageOptions sse_decode_image_options(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_maxDimension = sse_decode_u_32(deserializer);
+var var_thumbnailDimension = sse_decode_u_32(deserializer);
+var var_quality = sse_decode_u_8(deserializer);
+return ImageOptions(maxDimension: var_maxDimension, thumbnailDimension: var_thumbnailDimension, quality: var_quality); }
+
+@protected ImagePreview sse_decode_image_preview(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_blurhash = sse_decode_String(deserializer);
+var var_width = sse_decode_u_32(deserializer);
+var var_height = sse_decode_u_32(deserializer);
+return ImagePreview(blurhash: var_blurhash, width: var_width, height: var_height); }
+
+@protected PlatformInt64 sse_decode_isize(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getPlatformInt64(); }
+
+@protected LastSeen sse_decode_last_seen(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_userId = sse_decode_String(deserializer);
+var var_totemId = sse_decode_String(deserializer);
+var var_seenAt = sse_decode_Chrono_Utc(deserializer);
+return LastSeen(userId: var_userId, totemId: var_totemId, seenAt: var_seenAt); }
+
+@protected LinkSummary sse_decode_link_summary(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_identity = sse_decode_identity_info(deserializer);
+var var_usersImported = sse_decode_u_32(deserializer);
+var var_postsImported = sse_decode_u_32(deserializer);
+var var_rejected = sse_decode_u_32(deserializer);
+return LinkSummary(identity: var_identity, usersImported: var_usersImported, postsImported: var_postsImported, rejected: var_rejected); }
+
+@protected List<String> sse_decode_list_String(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <String>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_String(deserializer)); }
+        return ans_;
+         }
+
+@protected List<Attachment> sse_decode_list_attachment(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <Attachment>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_attachment(deserializer)); }
+        return ans_;
+         }
+
+@protected List<Conversation> sse_decode_list_conversation(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <Conversation>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_conversation(deserializer)); }
+        return ans_;
+         }
+
+@protected List<DirectMessage> sse_decode_list_direct_message(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <DirectMessage>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_direct_message(deserializer)); }
+        return ans_;
+         }
+
+@protected List<FeedItem> sse_decode_list_feed_item(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <FeedItem>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_feed_item(deserializer)); }
+        return ans_;
+         }
+
+@protected List<LastSeen> sse_decode_list_last_seen(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <LastSeen>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_last_seen(deserializer)); }
+        return ans_;
+         }
+
+@protected List<Post> sse_decode_list_post(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <Post>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_post(deserializer)); }
+        return ans_;
+         }
+
+@protected List<PostRevision> sse_decode_list_post_revision(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <PostRevision>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_post_revision(deserializer)); }
+        return ans_;
+         }
+
+@protected List<int> sse_decode_list_prim_u_8_loose(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var len_ = sse_decode_i_32(deserializer);
+                return deserializer.buffer.getUint8List(len_); }
+
+@protected Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var len_ = sse_decode_i_32(deserializer);
+                return deserializer.buffer.getUint8List(len_); }
+
+@protected List<ThreadReply> sse_decode_list_thread_reply(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <ThreadReply>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_thread_reply(deserializer)); }
+        return ans_;
+         }
+
+@protected List<Totem> sse_decode_list_totem(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <Totem>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_totem(deserializer)); }
+        return ans_;
+         }
+
+@protected List<TrendingTag> sse_decode_list_trending_tag(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <TrendingTag>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_trending_tag(deserializer)); }
+        return ans_;
+         }
+
+@protected List<TrendingThread> sse_decode_list_trending_thread(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <TrendingThread>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_trending_thread(deserializer)); }
+        return ans_;
+         }
+
+@protected List<User> sse_decode_list_user(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <User>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_user(deserializer)); }
+        return ans_;
+         }
+
+@protected List<UserLink> sse_decode_list_user_link(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <UserLink>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_user_link(deserializer)); }
+        return ans_;
+         }
+
+@protected List<ValidationIssue> sse_decode_list_validation_issue(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+        var len_ = sse_decode_i_32(deserializer);
+        var ans_ = <ValidationIssue>[];
+        for (var idx_ = 0; idx_ < len_; ++idx_) { ans_.add(sse_decode_validation_issue(deserializer)); }
+        return ans_;
+         }
+
+@protected MediaDirection sse_decode_media_direction(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_i_32(deserializer);
+        return MediaDirection.values[inner]; }
+
+@protected MediaSyncProgress sse_decode_media_sync_progress(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_hash = sse_decode_String(deserializer);
+var var_direction = sse_decode_media_direction(deserializer);
+var var_transferred = sse_decode_u_64(deserializer);
+var var_total = sse_decode_u_64(deserializer);
+var var_done = sse_decode_bool(deserializer);
+var var_error = sse_decode_opt_String(deserializer);
+return MediaSyncProgress(hash: var_hash, direction: var_direction, transferred: var_transferred, total: var_total, done: var_done, error: var_error); }
+
+@protected MediaSyncSummary sse_decode_media_sync_summary(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_uploaded = sse_decode_u_32(deserializer);
+var var_downloaded = sse_decode_u_32(deserializer);
+var var_failed = sse_decode_u_32(deserializer);
+return MediaSyncSummary(uploaded: var_uploaded, downloaded: var_downloaded, failed: var_failed); }
+
+@protected MessageSyncSummary sse_decode_message_sync_summary(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_received = sse_decode_u_32(deserializer);
+var var_sent = sse_decode_u_32(deserializer);
+var var_delivered = sse_decode_u_32(deserializer);
+var var_refused = sse_decode_u_32(deserializer);
+var var_rejected = sse_decode_u_32(deserializer);
+return MessageSyncSummary(received: var_received, sent: var_sent, delivered: var_delivered, refused: var_refused, rejected: var_rejected); }
+
+@protected String? sse_decode_opt_String(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_String(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected DateTime? sse_decode_opt_box_autoadd_Chrono_Utc(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_Chrono_Utc(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected IdentityInfo? sse_decode_opt_box_autoadd_identity_info(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_identity_info(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected ImageOptions? sse_decode_opt_box_autoadd_image_options(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_image_options(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected ImagePreview? sse_decode_opt_box_autoadd_image_preview(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_image_preview(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected LastSeen? sse_decode_opt_box_autoadd_last_seen(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_last_seen(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected Post? sse_decode_opt_box_autoadd_post(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_post(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected PostImage? sse_decode_opt_box_autoadd_post_image(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_post_image(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected int? sse_decode_opt_box_autoadd_u_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+            if (sse_decode_bool(deserializer)) {
+                return (sse_decode_box_autoadd_u_32(deserializer));
+            } else {
+                return null;
+            }
+             }
+
+@protected Post sse_decode_post(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_uuid = sse_decode_String(deserializer);
+var var_userId = sse_decode_String(deserializer);
+var var_title = sse_decode_String(deserializer);
+var var_body = sse_decode_String(deserializer);
+var var_timestamp = sse_decode_Chrono_Utc(deserializer);
+var var_image = sse_decode_opt_String(deserializer);
+var var_sourceTotem = sse_decode_String(deserializer);
+return Post(uuid: var_uuid, userId: var_userId, title: var_title, body: var_body, timestamp: var_timestamp, image: var_image, sourceTotem: var_sourceTotem); }
+
+@protected PostEntities sse_decode_post_entities(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_tags = sse_decode_list_String(deserializer);
+var var_handles = sse_decode_list_String(deserializer);
+var var_fingerprints = sse_decode_list_String(deserializer);
+return PostEntities(tags: var_tags, handles: var_handles, fingerprints: var_fingerprints); }
+
+@protected PostImage sse_decode_post_image(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_image = sse_decode_String(deserializer);
+var var_thumbnail = sse_decode_opt_String(deserializer);
+var var_preview = sse_decode_opt_box_autoadd_image_preview(deserializer);
+return PostImage(image: var_image, thumbnail: var_thumbnail, preview: var_preview); }
+
+@protected PostReactions sse_decode_post_reactions(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_likes = sse_decode_u_32(deserializer);
+var var_shares = sse_decode_u_32(deserializer);
+var var_liked = sse_decode_bool(deserializer);
+var var_shared = sse_decode_bool(deserializer);
+return PostReactions(likes: var_likes, shares: var_shares, liked: var_liked, shared: var_shared); }
+
+@protected PostRevision sse_decode_post_revision(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_revision = sse_decode_u_32(deserializer);
+var var_editedAt = sse_decode_opt_box_autoadd_Chrono_Utc(deserializer);
+var var_title = sse_decode_String(deserializer);
+var var_body = sse_decode_String(deserializer);
+return PostRevision(revision: var_revision, editedAt: var_editedAt, title: var_title, body: var_body); }
+
+@protected PreparedImage sse_decode_prepared_image(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_image = sse_decode_stored_image(deserializer);
+var var_thumbnail = sse_decode_stored_image(deserializer);
+var var_blurhash = sse_decode_String(deserializer);
+return PreparedImage(image: var_image, thumbnail: var_thumbnail, blurhash: var_blurhash); }
+
+@protected ReactionKind sse_decode_reaction_kind(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_i_32(deserializer);
+        return ReactionKind.values[inner]; }
+
+@protected RecordSyncSummary sse_decode_record_sync_summary(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_rotationsDownloaded = sse_decode_u_32(deserializer);
+var var_rotationsUploaded = sse_decode_u_32(deserializer);
+var var_usersDownloaded = sse_decode_u_32(deserializer);
+var var_usersUploaded = sse_decode_u_32(deserializer);
+var var_lastSeenDownloaded = sse_decode_u_32(deserializer);
+var var_tombstonesDownloaded = sse_decode_u_32(deserializer);
+var var_tombstonesUploaded = sse_decode_u_32(deserializer);
+var var_postsDownloaded = sse_decode_u_32(deserializer);
+var var_postsUploaded = sse_decode_u_32(deserializer);
+var var_repliesDownloaded = sse_decode_u_32(deserializer);
+var var_repliesUploaded = sse_decode_u_32(deserializer);
+var var_repostsDownloaded = sse_decode_u_32(deserializer);
+var var_repostsUploaded = sse_decode_u_32(deserializer);
+var var_reactionsDownloaded = sse_decode_u_32(deserializer);
+var var_reactionsUploaded = sse_decode_u_32(deserializer);
+var var_rejected = sse_decode_u_32(deserializer);
+return RecordSyncSummary(rotationsDownloaded: var_rotationsDownloaded, rotationsUploaded: var_rotationsUploaded, usersDownloaded: var_usersDownloaded, usersUploaded: var_usersUploaded, lastSeenDownloaded: var_lastSeenDownloaded, tombstonesDownloaded: var_tombstonesDownloaded, tombstonesUploaded: var_tombstonesUploaded, postsDownloaded: var_postsDownloaded, postsUploaded: var_postsUploaded, repliesDownloaded: var_repliesDownloaded, repliesUploaded: var_repliesUploaded, repostsDownloaded: var_repostsDownloaded, repostsUploaded: var_repostsUploaded, reactionsDownloaded: var_reactionsDownloaded, reactionsUploaded: var_reactionsUploaded, rejected: var_rejected); }
+
+@protected StoredImage sse_decode_stored_image(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_hash = sse_decode_String(deserializer);
+var var_path = sse_decode_String(deserializer);
+var var_width = sse_decode_u_32(deserializer);
+var var_height = sse_decode_u_32(deserializer);
+var var_size = sse_decode_u_64(deserializer);
+return StoredImage(hash: var_hash, path: var_path, width: var_width, height: var_height, size: var_size); }
+
+@protected ThreadReply sse_decode_thread_reply(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_uuid = sse_decode_String(deserializer);
+var var_userId = sse_decode_String(deserializer);
+var var_parentId = sse_decode_String(deserializer);
+var var_rootId = sse_decode_String(deserializer);
+var var_body = sse_decode_String(deserializer);
+var var_timestamp = sse_decode_Chrono_Utc(deserializer);
+var var_depth = sse_decode_u_32(deserializer);
+var var_parentMissing = sse_decode_bool(deserializer);
+var var_verification = sse_decode_verification(deserializer);
+return ThreadReply(uuid: var_uuid, userId: var_userId, parentId: var_parentId, rootId: var_rootId, body: var_body, timestamp: var_timestamp, depth: var_depth, parentMissing: var_parentMissing, verification: var_verification); }
+
+@protected Totem sse_decode_totem(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_uuid = sse_decode_String(deserializer);
+var var_name = sse_decode_String(deserializer);
+var var_location = sse_decode_String(deserializer);
+var var_lastContact = sse_decode_Chrono_Utc(deserializer);
+return Totem(uuid: var_uuid, name: var_name, location: var_location, lastContact: var_lastContact); }
+
+@protected TrendingTag sse_decode_trending_tag(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_tag = sse_decode_String(deserializer);
+var var_posts = sse_decode_u_32(deserializer);
+return TrendingTag(tag: var_tag, posts: var_posts); }
+
+@protected TrendingThread sse_decode_trending_thread(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_postId = sse_decode_String(deserializer);
+var var_replies = sse_decode_u_32(deserializer);
+var var_post = sse_decode_opt_box_autoadd_post(deserializer);
+return TrendingThread(postId: var_postId, replies: var_replies, post: var_post); }
+
+@protected TrendingTopics sse_decode_trending_topics(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_totemId = sse_decode_String(deserializer);
+var var_since = sse_decode_Chrono_Utc(deserializer);
+var var_tags = sse_decode_list_trending_tag(deserializer);
+var var_threads = sse_decode_list_trending_thread(deserializer);
+return TrendingTopics(totemId: var_totemId, since: var_since, tags: var_tags, threads: var_threads); }
+
+@protected int sse_decode_u_32(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getUint32(); }
+
+@protected BigInt sse_decode_u_64(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getBigUint64(); }
+
+@protected int sse_decode_u_8(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getUint8(); }
+
+@protected void sse_decode_unit(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+ }
+
+@protected User sse_decode_user(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_uuid = sse_decode_String(deserializer);
+var var_username = sse_decode_String(deserializer);
+var var_status = sse_decode_String(deserializer);
+var var_bio = sse_decode_String(deserializer);
+var var_profilePicture = sse_decode_opt_String(deserializer);
+var var_lastContact = sse_decode_Chrono_Utc(deserializer);
+return User(uuid: var_uuid, username: var_username, status: var_status, bio: var_bio, profilePicture: var_profilePicture, lastContact: var_lastContact); }
+
+@protected UserLink sse_decode_user_link(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_legacyId = sse_decode_String(deserializer);
+var var_userId = sse_decode_String(deserializer);
+return UserLink(legacyId: var_legacyId, userId: var_userId); }
+
+@protected BigInt sse_decode_usize(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+return deserializer.buffer.getBigUint64(); }
+
+@protected ValidationIssue sse_decode_validation_issue(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var var_field = sse_decode_String(deserializer);
+var var_message = sse_decode_String(deserializer);
+return ValidationIssue(field: var_field, message: var_message); }
+
+@protected Verification sse_decode_verification(SseDeserializer deserializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+var inner = sse_decode_i_32(deserializer);
+        return Verification.values[inner]; }
+
+@protected void sse_encode_AnyhowException(AnyhowException self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.message, serializer); }
+
+@protected void sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(AppDatabase self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as AppDatabaseImpl).frbInternalSseEncode(move: true), serializer); }
+
+@protected void sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(LinkOffer self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as LinkOfferImpl).frbInternalSseEncode(move: true), serializer); }
+
+@protected void sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(AppDatabase self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as AppDatabaseImpl).frbInternalSseEncode(move: false), serializer); }
+
+@protected void sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(LinkOffer self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as LinkOfferImpl).frbInternalSseEncode(move: false), serializer); }
+
+@protected void sse_encode_Chrono_Utc(DateTime self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_64(PlatformInt64Util.from(self.microsecondsSinceEpoch), serializer); }
+
+@protected void sse_encode_DartFn_Inputs_media_sync_progress_Output_unit_AnyhowException(FutureOr<void> Function(MediaSyncProgress) self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_DartOpaque(encode_DartFn_Inputs_media_sync_progress_Output_unit_AnyhowException(self), serializer); }
+
+@protected void sse_encode_DartOpaque(Object self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_isize(PlatformPointerUtil.ptrToPlatformInt64(encodeDartOpaque(self, portManager.dartHandlerPort, generalizedFrbRustBinding)), serializer); }
+
+@protected void sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(AppDatabase self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as AppDatabaseImpl).frbInternalSseEncode(move: null), serializer); }
+
+@protected void sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(LinkOffer self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_usize((self as LinkOfferImpl).frbInternalSseEncode(move: null), serializer); }
+
+@protected void sse_encode_String(String self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_list_prim_u_8_strict(utf8.encoder.convert(self), serializer); }
+
+@protected void sse_encode_attachment(Attachment self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_attachment_kind(self.kind, serializer);
+sse_encode_String(self.mime, serializer);
+sse_encode_String(self.hash, serializer);
+sse_encode_u_64(self.size, serializer);
+sse_encode_opt_box_autoadd_u_32(self.durationMs, serializer);
+sse_encode_opt_box_autoadd_u_32(self.width, serializer);
+sse_encode_opt_box_autoadd_u_32(self.height, serializer);
+sse_encode_opt_String(self.altText, serializer);
+ }
+
+@protected void sse_encode_attachment_kind(AttachmentKind self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.index, serializer); }
+
+@protected void sse_encode_bool(bool self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putUint8(self ? 1 : 0); }
+
+@protected void sse_encode_box_autoadd_Chrono_Utc(DateTime self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_Chrono_Utc(self, serializer); }
+
+@protected void sse_encode_box_autoadd_identity_info(IdentityInfo self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_identity_info(self, serializer); }
+
+@protected void sse_encode_box_autoadd_image_options(ImageOptions self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_image_options(self, serializer); }
+
+@protected void sse_encode_box_autoadd_image_preview(ImagePreview self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_image_preview(self, serializer); }
+
+@protected void sse_encode_box_autoadd_last_seen(LastSeen self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_last_seen(self, serializer); }
+
+@protected void sse_encode_box_autoadd_post(Post self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_post(self, serializer); }
+
+@protected void sse_encode_box_autoadd_post_image(PostImage self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_post_image(self, serializer); }
+
+@protected void sse_encode_box_autoadd_prepared_image(PreparedImage self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_prepared_image(self, serializer); }
+
+@protected void sse_encode_box_autoadd_totem(Totem self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_totem(self, serializer); }
+
+@protected void sse_encode_box_autoadd_u_32(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self, serializer); }
+
+@protected void sse_encode_box_autoadd_user(User self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_user(self, serializer); }
+
+@protected void sse_encode_bundle_sync_summary(BundleSyncSummary self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self.delivered, serializer);
+sse_encode_u_32(self.taken, serializer);
+sse_encode_u_32(self.handedOver, serializer);
+sse_encode_u_32(self.refused, serializer);
+sse_encode_u_32(self.rejected, serializer);
+sse_encode_u_32(self.dropped, serializer);
+ }
+
+@protected void sse_encode_conversation(Conversation self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.userId, serializer);
+sse_encode_direct_message(self.lastMessage, serializer);
+sse_encode_u_32(self.messageCount, serializer);
+ }
+
+@protected void sse_encode_direct_message(DirectMessage self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.uuid, serializer);
+sse_encode_String(self.senderId, serializer);
+sse_encode_String(self.recipientId, serializer);
+sse_encode_Chrono_Utc(self.timestamp, serializer);
+sse_encode_opt_String(self.text, serializer);
+sse_encode_verification(self.verification, serializer);
+sse_encode_bool(self.delivered, serializer);
+ }
+
+@protected void sse_encode_feed_item(FeedItem self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.postId, serializer);
+sse_encode_opt_box_autoadd_post(self.post, serializer);
+sse_encode_String(self.authorId, serializer);
+sse_encode_opt_String(self.repostedBy, serializer);
+sse_encode_opt_String(self.repostUuid, serializer);
+sse_encode_Chrono_Utc(self.timestamp, serializer);
+ }
+
+@protected void sse_encode_i_32(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putInt32(self); }
+
+@protected void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putPlatformInt64(self); }
+
+@protected void sse_encode_identity_info(IdentityInfo self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.userId, serializer);
+sse_encode_String(self.publicKey, serializer);
+ }
+
+@protected void sse_encode_image_options(ImageOptions self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self.maxDimension, serializer);
+sse_encode_u_32(self.thumbnailDimension, serializer);
+sse_encode_u_8(self.quality, serializer);
+ }
+
+@protected void sse_encode_image_preview(ImagePreview self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.blurhash, serializer);
+sse_encode_u_32(self.width, serializer);
+sse_encode_u_32(self.height, serializer);
+ }
+
+@protected void sse_encode_isize(PlatformInt64 self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putPlatformInt64(self); }
+
+@protected void sse_encode_last_seen(LastSeen self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.userId, serializer);
+sse_encode_String(self.totemId, serializer);
+sse_encode_Chrono_Utc(self.seenAt, serializer);
+ }
+
+@protected void sse_encode_link_summary(LinkSummary self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_identity_info(self.identity, serializer);
+sse_encode_u_32(self.usersImported, serializer);
+sse_encode_u_32(self.postsImported, serializer);
+sse_encode_u_32(self.rejected, serializer);
+ }
+
+@protected void sse_encode_list_String(List<String> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_String(item, serializer); } }
+
+@protected void sse_encode_list_attachment(List<Attachment> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_attachment(item, serializer); } }
+
+@protected void sse_encode_list_conversation(List<Conversation> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_conversation(item, serializer); } }
+
+@protected void sse_encode_list_direct_message(List<DirectMessage> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_direct_message(item, serializer); } }
+
+@protected void sse_encode_list_feed_item(List<FeedItem> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_feed_item(item, serializer); } }
+
+@protected void sse_encode_list_last_seen(List<LastSeen> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_last_seen(item, serializer); } }
+
+@protected void sse_encode_list_post(List<Post> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_post(item, serializer); } }
+
+@protected void sse_encode_list_post_revision(List<PostRevision> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_post_revision(item, serializer); } }
+
+@protected void sse_encode_list_prim_u_8_loose(List<int> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+                    serializer.buffer.putUint8List(self is Uint8List ? self : Uint8List.fromList(self)); }
+
+@protected void sse_encode_list_prim_u_8_strict(Uint8List self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+                    serializer.buffer.putUint8List(self); }
+
+@protected void sse_encode_list_thread_reply(List<ThreadReply> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_thread_reply(item, serializer); } }
+
+@protected void sse_encode_list_totem(List<Totem> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_totem(item, serializer); } }
+
+@protected void sse_encode_list_trending_tag(List<TrendingTag> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_trending_tag(item, serializer); } }
+
+@protected void sse_encode_list_trending_thread(List<TrendingThread> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_trending_thread(item, serializer); } }
+
+@protected void sse_encode_list_user(List<User> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_user(item, serializer); } }
+
+@protected void sse_encode_list_user_link(List<UserLink> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_user_link(item, serializer); } }
+
+@protected void sse_encode_list_validation_issue(List<ValidationIssue> self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.length, serializer);
+        for (final item in self) { sse_encode_validation_issue(item, serializer); } }
+
+@protected void sse_encode_media_direction(MediaDirection self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.index, serializer); }
+
+@protected void sse_encode_media_sync_progress(MediaSyncProgress self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.hash, serializer);
+sse_encode_media_direction(self.direction, serializer);
+sse_encode_u_64(self.transferred, serializer);
+sse_encode_u_64(self.total, serializer);
+sse_encode_bool(self.done, serializer);
+sse_encode_opt_String(self.error, serializer);
+ }
+
+@protected void sse_encode_media_sync_summary(MediaSyncSummary self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self.uploaded, serializer);
+sse_encode_u_32(self.downloaded, serializer);
+sse_encode_u_32(self.failed, serializer);
+ }
+
+@protected void sse_encode_message_sync_summary(MessageSyncSummary self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self.received, serializer);
+sse_encode_u_32(self.sent, serializer);
+sse_encode_u_32(self.delivered, serializer);
+sse_encode_u_32(self.refused, serializer);
+sse_encode_u_32(self.rejected, serializer);
+ }
+
+@protected void sse_encode_opt_String(String? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_String(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_Chrono_Utc(DateTime? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_Chrono_Utc(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_identity_info(IdentityInfo? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_identity_info(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_image_options(ImageOptions? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_image_options(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_image_preview(ImagePreview? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_image_preview(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_last_seen(LastSeen? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_last_seen(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_post(Post? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_post(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_post_image(PostImage? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_post_image(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_opt_box_autoadd_u_32(int? self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+
+                sse_encode_bool(self != null, serializer);
+                if (self != null) {
+                    sse_encode_box_autoadd_u_32(self, serializer);
+                }
+                 }
+
+@protected void sse_encode_post(Post self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.uuid, serializer);
+sse_encode_String(self.userId, serializer);
+sse_encode_String(self.title, serializer);
+sse_encode_String(self.body, serializer);
+sse_encode_Chrono_Utc(self.timestamp, serializer);
+sse_encode_opt_String(self.image, serializer);
+sse_encode_String(self.sourceTotem, serializer);
+ }
+
+@protected void sse_encode_post_entities(PostEntities self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_list_String(self.tags, serializer);
+sse_encode_list_String(self.handles, serializer);
+sse_encode_list_String(self.fingerprints, serializer);
+ }
+
+@protected void sse_encode_post_image(PostImage self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.image, serializer);
+sse_encode_opt_String(self.thumbnail, serializer);
+sse_encode_opt_box_autoadd_image_preview(self.preview, serializer);
+ }
+
+@protected void sse_encode_post_reactions(PostReactions self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self.likes, serializer);
+sse_encode_u_32(self.shares, serializer);
+sse_encode_bool(self.liked, serializer);
+sse_encode_bool(self.shared, serializer);
+ }
+
+@protected void sse_encode_post_revision(PostRevision self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self.revision, serializer);
+sse_encode_opt_box_autoadd_Chrono_Utc(self.editedAt, serializer);
+sse_encode_String(self.title, serializer);
+sse_encode_String(self.body, serializer);
+ }
+
+@protected void sse_encode_prepared_image(PreparedImage self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_stored_image(self.image, serializer);
+sse_encode_stored_image(self.thumbnail, serializer);
+sse_encode_String(self.blurhash, serializer);
+ }
+
+@protected void sse_encode_reaction_kind(ReactionKind self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.index, serializer); }
+
+@protected void sse_encode_record_sync_summary(RecordSyncSummary self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_u_32(self.rotationsDownloaded, serializer);
+sse_encode_u_32(self.rotationsUploaded, serializer);
+sse_encode_u_32(self.usersDownloaded, serializer);
+sse_encode_u_32(self.usersUploaded, serializer);
+sse_encode_u_32(self.lastSeenDownloaded, serializer);
+sse_encode_u_32(self.tombstonesDownloaded, serializer);
+sse_encode_u_32(self.tombstonesUploaded, serializer);
+sse_encode_u_32(self.postsDownloaded, serializer);
+sse_encode_u_32(self.postsUploaded, serializer);
+sse_encode_u_32(self.repliesDownloaded, serializer);
+sse_encode_u_32(self.repliesUploaded, serializer);
+sse_encode_u_32(self.repostsDownloaded, serializer);
+sse_encode_u_32(self.repostsUploaded, serializer);
+sse_encode_u_32(self.reactionsDownloaded, serializer);
+sse_encode_u_32(self.reactionsUploaded, serializer);
+sse_encode_u_32(self.rejected, serializer);
+ }
+
+@protected void sse_encode_stored_image(StoredImage self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.hash, serializer);
+sse_encode_String(self.path, serializer);
+sse_encode_u_32(self.width, serializer);
+sse_encode_u_32(self.height, serializer);
+sse_encode_u_64(self.size, serializer);
+ }
+
+@protected void sse_encode_thread_reply(ThreadReply self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.uuid, serializer);
+sse_encode_String(self.userId, serializer);
+sse_encode_String(self.parentId, serializer);
+sse_encode_String(self.rootId, serializer);
+sse_encode_String(self.body, serializer);
+sse_encode_Chrono_Utc(self.timestamp, serializer);
+sse_encode_u_32(self.depth, serializer);
+sse_encode_bool(self.parentMissing, serializer);
+sse_encode_verification(self.verification, serializer);
+ }
+
+@protected void sse_encode_totem(Totem self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.uuid, serializer);
+sse_encode_String(self.name, serializer);
+sse_encode_String(self.location, serializer);
+sse_encode_Chrono_Utc(self.lastContact, serializer);
+ }
+
+@protected void sse_encode_trending_tag(TrendingTag self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.tag, serializer);
+sse_encode_u_32(self.posts, serializer);
+ }
+
+@protected void sse_encode_trending_thread(TrendingThread self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.postId, serializer);
+sse_encode_u_32(self.replies, serializer);
+sse_encode_opt_box_autoadd_post(self.post, serializer);
+ }
+
+@protected void sse_encode_trending_topics(TrendingTopics self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.totemId, serializer);
+sse_encode_Chrono_Utc(self.since, serializer);
+sse_encode_list_trending_tag(self.tags, serializer);
+sse_encode_list_trending_thread(self.threads, serializer);
+ }
+
+@protected void sse_encode_u_32(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putUint32(self); }
+
+@protected void sse_encode_u_64(BigInt self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putBigUint64(self); }
+
+@protected void sse_encode_u_8(int self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putUint8(self); }
+
+@protected void sse_encode_unit(void self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+ }
+
+@protected void sse_encode_user(User self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.uuid, serializer);
+sse_encode_String(self.username, serializer);
+sse_encode_String(self.status, serializer);
+sse_encode_String(self.bio, serializer);
+sse_encode_opt_String(self.profilePicture, serializer);
+sse_encode_Chrono_Utc(self.lastContact, serializer);
+ }
+
+@protected void sse_encode_user_link(UserLink self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.legacyId, serializer);
+sse_encode_String(self.userId, serializer);
+ }
+
+@protected void sse_encode_usize(BigInt self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+serializer.buffer.putBigUint64(self); }
+
+@protected void sse_encode_validation_issue(ValidationIssue self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_String(self.field, serializer);
+sse_encode_String(self.message, serializer);
+ }
+
+@protected void sse_encode_verification(Verification self, SseSerializer serializer){ // Codec=Sse (Serialization based), see doc to use other codecs
+sse_encode_i_32(self.index, serializer); }
+                }
+                
+
+            @sealed class AppDatabaseImpl extends RustOpaque implements AppDatabase {
+                // Not to be used by end users
+                AppDatabaseImpl.frbInternalDcoDecode(List<dynamic> wire):
+                    super.frbInternalDcoDecode(wire, _kStaticData);
+
+                // Not to be used by end users
+                AppDatabaseImpl.frbInternalSseDecode(BigInt ptr, int externalSizeOnNative):
+                    super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
+
+                static final _kStaticData = RustArcStaticData(
+                    rustArcIncrementStrongCount: RustLib.instance.api.rust_arc_increment_strong_count_AppDatabase,
+                    rustArcDecrementStrongCount: RustLib.instance.api.rust_arc_decrement_strong_count_AppDatabase,
+                    rustArcDecrementStrongCountPtr: RustLib.instance.api.rust_arc_decrement_strong_count_AppDatabasePtr,
+                );
+
+                 Future<void>  createPost({required Post post })=>RustLib.instance.api.crateApiSimpleAppDatabaseCreatePost(that: this, post: post);
+
+
+/// Create a post with attachments from [`crate::api::media::store_attachment`]
+ Future<void>  createPostWithAttachments({required Post post , required List<Attachment> attachments })=>RustLib.instance.api.crateApiSimpleAppDatabaseCreatePostWithAttachments(that: this, post: post, attachments: attachments);
+
+
+/// Create a post showing a picture from [`crate::api::media::prepare_image`]
+ Future<void>  createPostWithImage({required Post post , required PreparedImage image , String? altText })=>RustLib.instance.api.crateApiSimpleAppDatabaseCreatePostWithImage(that: this, post: post, image: image, altText: altText);
+
+
+ Future<void>  createTotem({required Totem totem })=>RustLib.instance.api.crateApiSimpleAppDatabaseCreateTotem(that: this, totem: totem);
+
+
+ Future<void>  createUser({required User user })=>RustLib.instance.api.crateApiSimpleAppDatabaseCreateUser(that: this, user: user);
+
+
+/// All known sightings, newest first
+ Future<List<LastSeen>>  getAllLastSeen()=>RustLib.instance.api.crateApiSimpleAppDatabaseGetAllLastSeen(that: this, );
+
+
+ Future<List<Post>>  getAllPosts()=>RustLib.instance.api.crateApiSimpleAppDatabaseGetAllPosts(that: this, );
+
+
+ Future<List<Totem>>  getAllTotems()=>RustLib.instance.api.crateApiSimpleAppDatabaseGetAllTotems(that: this, );
+
+
+/// All users, without the profiles of random IDs that moved to another one
+ Future<List<User>>  getAllUsers()=>RustLib.instance.api.crateApiSimpleAppDatabaseGetAllUsers(that: this, );
+
+
+/// Where and when a user was seen last, for "seen at Totem X, 2 hours ago"
+ Future<LastSeen?>  getLatestLastSeen({required String userId })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetLatestLastSeen(that: this, userId: userId);
+
+
+/// Where the blob with the given content hash is stored on this phone, if it is
+ Future<String?>  getMediaPath({required String hash })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetMediaPath(that: this, hash: hash);
+
+
+/// The attachments of a post, in the order they are shown
+ Future<List<Attachment>>  getPostAttachments({required String uuid })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetPostAttachments(that: this, uuid: uuid);
+
+
+ Future<Post>  getPostById({required String uuid })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetPostById(that: this, uuid: uuid);
+
+
+ Future<List<String>>  getPostIdsInRange({required DateTime start , required DateTime end })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetPostIdsInRange(that: this, start: start, end: end);
+
+
+/// The picture of a post with its thumbnail and placeholder, if it has one
+ Future<PostImage?>  getPostImage({required String uuid })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetPostImage(that: this, uuid: uuid);
+
+
+/// Whether a post was made by its author, checked against the stored author
+ Future<Verification>  getPostVerification({required String uuid })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetPostVerification(that: this, uuid: uuid);
+
+
+/// Placeholder of a user's profile picture, if they have one
+ Future<ImagePreview?>  getProfilePicturePreview({required String uuid })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetProfilePicturePreview(that: this, uuid: uuid);
+
+
+/// Users that visited a totem since the given time, newest first
+ Future<List<LastSeen>>  getRecentVisitors({required String totemId , required DateTime since })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetRecentVisitors(that: this, totemId: totemId, since: since);
+
+
+/// The profile behind `uuid`, the one a random ID from before identities moved to
+ Future<User>  getUserById({required String uuid })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetUserById(that: this, uuid: uuid);
+
+
+/// Random IDs from before identities, with the ID their user moved to
+/// Records under a random ID belong to the profile of the ID it moved to.
+ Future<List<UserLink>>  getUserLinks()=>RustLib.instance.api.crateApiSimpleAppDatabaseGetUserLinks(that: this, );
+
+
+/// Whether a user's profile was made by the owner of their ID
+ Future<Verification>  getUserVerification({required String uuid })=>RustLib.instance.api.crateApiSimpleAppDatabaseGetUserVerification(that: this, uuid: uuid);
+
+
+/// Record where the blob with the given content hash is stored on this phone
+ Future<void>  setMediaPath({required String hash , required String localPath })=>RustLib.instance.api.crateApiSimpleAppDatabaseSetMediaPath(that: this, hash: hash, localPath: localPath);
+
+
+/// Show a picture from [`crate::api::media::prepare_image`] on a user's profile
+ Future<void>  setProfilePicture({required String uuid , required PreparedImage image })=>RustLib.instance.api.crateApiSimpleAppDatabaseSetProfilePicture(that: this, uuid: uuid, image: image);
+
+
+ Future<void>  updateTotemLastContact({required String uuid , required DateTime lastContact })=>RustLib.instance.api.crateApiSimpleAppDatabaseUpdateTotemLastContact(that: this, uuid: uuid, lastContact: lastContact);
+
+
+ Future<void>  updateUser({required User user })=>RustLib.instance.api.crateApiSimpleAppDatabaseUpdateUser(that: this, user: user);
+
+
+            }
+            @sealed class LinkOfferImpl extends RustOpaque implements LinkOffer {
+                // Not to be used by end users
+                LinkOfferImpl.frbInternalDcoDecode(List<dynamic> wire):
+                    super.frbInternalDcoDecode(wire, _kStaticData);
+
+                // Not to be used by end users
+                LinkOfferImpl.frbInternalSseDecode(BigInt ptr, int externalSizeOnNative):
+                    super.frbInternalSseDecode(ptr, externalSizeOnNative, _kStaticData);
+
+                static final _kStaticData = RustArcStaticData(
+                    rustArcIncrementStrongCount: RustLib.instance.api.rust_arc_increment_strong_count_LinkOffer,
+                    rustArcDecrementStrongCount: RustLib.instance.api.rust_arc_decrement_strong_count_LinkOffer,
+                    rustArcDecrementStrongCountPtr: RustLib.instance.api.rust_arc_decrement_strong_count_LinkOfferPtr,
+                );
+
+                /// Stop waiting in [`LinkOffer::send`]
+ void  cancel()=>RustLib.instance.api.crateApiLinkLinkOfferCancel(that: this, );
+
+
+/// The identity sealed for the scanning device, to be passed on as a file
+ Future<Uint8List>  export_({required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase })=>RustLib.instance.api.crateApiLinkLinkOfferExport(that: this, db: db, keyDir: keyDir, cacheDir: cacheDir, includeDatabase: includeDatabase);
+
+
+/// The text of the QR code to show, it must not be shown to anyone else
+ String  qrCode()=>RustLib.instance.api.crateApiLinkLinkOfferQrCode(that: this, );
+
+
+/// Wait up to `timeout_secs` for the scanning device and hand it the identity
+///
+/// With `include_database` it also gets a copy of this phone's posts and profiles.
+/// `cache_dir` holds the copy while it is being sent.
+ Future<void>  send({required AppDatabase db , required String keyDir , required String cacheDir , required bool includeDatabase , required int timeoutSecs })=>RustLib.instance.api.crateApiLinkLinkOfferSend(that: this, db: db, keyDir: keyDir, cacheDir: cacheDir, includeDatabase: includeDatabase, timeoutSecs: timeoutSecs);
+
+
+            }
\ No newline at end of file
@@ -3,317 +3,644 @@
 
 // ignore_for_file: unused_import, unused_element, unnecessary_import, duplicate_ignore, invalid_use_of_internal_member, annotate_overrides, non_constant_identifier_names, curly_braces_in_flow_control_structures, prefer_const_literals_to_create_immutables, unused_field
 
+import 'api/bundles.dart';
+import 'api/deletions.dart';
+import 'api/edits.dart';
+import 'api/identity.dart';
+import 'api/link.dart';
+import 'api/media.dart';
+import 'api/messages.dart';
+import 'api/reactions.dart';
+import 'api/replies.dart';
+import 'api/reposts.dart';
 import 'api/simple.dart';
+import 'api/sync.dart';
+import 'api/tags.dart';
+import 'api/trending.dart';
 import 'dart:async';
 import 'dart:convert';
 import 'dart:ffi' as ffi;
 import 'frb_generated.dart';
 import 'package:flutter_rust_bridge/flutter_rust_bridge_for_generated_io.dart';
 
-abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
-  RustLibApiImplPlatform({
-    required super.handler,
-    required super.wire,
-    required super.generalizedFrbRustBinding,
-    required super.portManager,
-  });
 
-  CrossPlatformFinalizerArg
-  get rust_arc_decrement_strong_count_AppDatabasePtr => wire
-      ._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr;
 
-  @protected
-  AnyhowException dco_decode_AnyhowException(dynamic raw);
 
-  @protected
-  AppDatabase
-  dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    dynamic raw,
-  );
+                abstract class RustLibApiImplPlatform extends BaseApiImpl<RustLibWire> {
+                  RustLibApiImplPlatform({
+                    required super.handler,
+                    required super.wire,
+                    required super.generalizedFrbRustBinding,
+                    required super.portManager,
+                  });
 
-  @protected
-  AppDatabase
-  dco_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    dynamic raw,
-  );
+                  CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_AppDatabasePtr => wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr;
 
-  @protected
-  DateTime dco_decode_Chrono_Utc(dynamic raw);
+CrossPlatformFinalizerArg get rust_arc_decrement_strong_count_LinkOfferPtr => wire._rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOfferPtr;
 
-  @protected
-  AppDatabase
-  dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    dynamic raw,
-  );
 
-  @protected
-  String dco_decode_String(dynamic raw);
 
-  @protected
-  Post dco_decode_box_autoadd_post(dynamic raw);
+                  @protected AnyhowException dco_decode_AnyhowException(dynamic raw);
 
-  @protected
-  Totem dco_decode_box_autoadd_totem(dynamic raw);
+@protected AppDatabase dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(dynamic raw);
 
-  @protected
-  User dco_decode_box_autoadd_user(dynamic raw);
+@protected LinkOffer dco_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(dynamic raw);
 
-  @protected
-  PlatformInt64 dco_decode_i_64(dynamic raw);
+@protected AppDatabase dco_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(dynamic raw);
 
-  @protected
-  List<String> dco_decode_list_String(dynamic raw);
+@protected LinkOffer dco_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(dynamic raw);
 
-  @protected
-  List<Post> dco_decode_list_post(dynamic raw);
+@protected DateTime dco_decode_Chrono_Utc(dynamic raw);
 
-  @protected
-  Uint8List dco_decode_list_prim_u_8_strict(dynamic raw);
+@protected FutureOr<void> Function(MediaSyncProgress) dco_decode_DartFn_Inputs_media_sync_progress_Output_unit_AnyhowException(dynamic raw);
 
-  @protected
-  List<Totem> dco_decode_list_totem(dynamic raw);
+@protected Object dco_decode_DartOpaque(dynamic raw);
 
-  @protected
-  List<User> dco_decode_list_user(dynamic raw);
+@protected AppDatabase dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(dynamic raw);
 
-  @protected
-  String? dco_decode_opt_String(dynamic raw);
+@protected LinkOffer dco_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(dynamic raw);
 
-  @protected
-  Post dco_decode_post(dynamic raw);
+@protected String dco_decode_String(dynamic raw);
 
-  @protected
-  Totem dco_decode_totem(dynamic raw);
+@protected Attachment dco_decode_attachment(dynamic raw);
 
-  @protected
-  int dco_decode_u_8(dynamic raw);
+@protected AttachmentKind dco_decode_attachment_kind(dynamic raw);
 
-  @protected
-  void dco_decode_unit(dynamic raw);
+@protected bool dco_decode_bool(dynamic raw);
 
-  @protected
-  User dco_decode_user(dynamic raw);
+@protected DateTime dco_decode_box_autoadd_Chrono_Utc(dynamic raw);
 
-  @protected
-  BigInt dco_decode_usize(dynamic raw);
+@protected IdentityInfo dco_decode_box_autoadd_identity_info(dynamic raw);
 
-  @protected
-  AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer);
+@protected ImageOptions dco_decode_box_autoadd_image_options(dynamic raw);
 
-  @protected
-  AppDatabase
-  sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    SseDeserializer deserializer,
-  );
+@protected ImagePreview dco_decode_box_autoadd_image_preview(dynamic raw);
 
-  @protected
-  AppDatabase
-  sse_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    SseDeserializer deserializer,
-  );
+@protected LastSeen dco_decode_box_autoadd_last_seen(dynamic raw);
 
-  @protected
-  DateTime sse_decode_Chrono_Utc(SseDeserializer deserializer);
+@protected Post dco_decode_box_autoadd_post(dynamic raw);
 
-  @protected
-  AppDatabase
-  sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    SseDeserializer deserializer,
-  );
+@protected PostImage dco_decode_box_autoadd_post_image(dynamic raw);
 
-  @protected
-  String sse_decode_String(SseDeserializer deserializer);
+@protected PreparedImage dco_decode_box_autoadd_prepared_image(dynamic raw);
 
-  @protected
-  Post sse_decode_box_autoadd_post(SseDeserializer deserializer);
+@protected Totem dco_decode_box_autoadd_totem(dynamic raw);
 
-  @protected
-  Totem sse_decode_box_autoadd_totem(SseDeserializer deserializer);
+@protected int dco_decode_box_autoadd_u_32(dynamic raw);
 
-  @protected
-  User sse_decode_box_autoadd_user(SseDeserializer deserializer);
+@protected User dco_decode_box_autoadd_user(dynamic raw);
 
-  @protected
-  PlatformInt64 sse_decode_i_64(SseDeserializer deserializer);
+@protected BundleSyncSummary dco_decode_bundle_sync_summary(dynamic raw);
 
-  @protected
-  List<String> sse_decode_list_String(SseDeserializer deserializer);
+@protected Conversation dco_decode_conversation(dynamic raw);
 
-  @protected
-  List<Post> sse_decode_list_post(SseDeserializer deserializer);
+@protected DirectMessage dco_decode_direct_message(dynamic raw);
 
-  @protected
-  Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer);
+@protected FeedItem dco_decode_feed_item(dynamic raw);
 
-  @protected
-  List<Totem> sse_decode_list_totem(SseDeserializer deserializer);
+@protected int dco_decode_i_32(dynamic raw);
 
-  @protected
-  List<User> sse_decode_list_user(SseDeserializer deserializer);
+@protected PlatformInt64 dco_decode_i_64(dynamic raw);
 
-  @protected
-  String? sse_decode_opt_String(SseDeserializer deserializer);
+@protected IdentityInfo dco_decode_identity_info(dynamic raw);
 
-  @protected
-  Post sse_decode_post(SseDeserializer deserializer);
+@protected ImageOptions dco_decode_image_options(dynamic raw);
 
-  @protected
-  Totem sse_decode_totem(SseDeserializer deserializer);
+@protected ImagePreview dco_decode_image_preview(dynamic raw);
 
-  @protected
-  int sse_decode_u_8(SseDeserializer deserializer);
+@protected PlatformInt64 dco_decode_isize(dynamic raw);
 
-  @protected
-  void sse_decode_unit(SseDeserializer deserializer);
+@protected LastSeen dco_decode_last_seen(dynamic raw);
 
-  @protected
-  User sse_decode_user(SseDeserializer deserializer);
+@protected LinkSummary dco_decode_link_summary(dynamic raw);
 
-  @protected
-  BigInt sse_decode_usize(SseDeserializer deserializer);
+@protected List<String> dco_decode_list_String(dynamic raw);
 
-  @protected
-  int sse_decode_i_32(SseDeserializer deserializer);
+@protected List<Attachment> dco_decode_list_attachment(dynamic raw);
 
-  @protected
-  bool sse_decode_bool(SseDeserializer deserializer);
+@protected List<Conversation> dco_decode_list_conversation(dynamic raw);
 
-  @protected
-  void sse_encode_AnyhowException(
-    AnyhowException self,
-    SseSerializer serializer,
-  );
+@protected List<DirectMessage> dco_decode_list_direct_message(dynamic raw);
 
-  @protected
-  void
-  sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    AppDatabase self,
-    SseSerializer serializer,
-  );
+@protected List<FeedItem> dco_decode_list_feed_item(dynamic raw);
 
-  @protected
-  void
-  sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    AppDatabase self,
-    SseSerializer serializer,
-  );
+@protected List<LastSeen> dco_decode_list_last_seen(dynamic raw);
 
-  @protected
-  void sse_encode_Chrono_Utc(DateTime self, SseSerializer serializer);
+@protected List<Post> dco_decode_list_post(dynamic raw);
 
-  @protected
-  void
-  sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    AppDatabase self,
-    SseSerializer serializer,
-  );
+@protected List<PostRevision> dco_decode_list_post_revision(dynamic raw);
 
-  @protected
-  void sse_encode_String(String self, SseSerializer serializer);
+@protected List<int> dco_decode_list_prim_u_8_loose(dynamic raw);
 
-  @protected
-  void sse_encode_box_autoadd_post(Post self, SseSerializer serializer);
+@protected Uint8List dco_decode_list_prim_u_8_strict(dynamic raw);
 
-  @protected
-  void sse_encode_box_autoadd_totem(Totem self, SseSerializer serializer);
+@protected List<ThreadReply> dco_decode_list_thread_reply(dynamic raw);
 
-  @protected
-  void sse_encode_box_autoadd_user(User self, SseSerializer serializer);
+@protected List<Totem> dco_decode_list_totem(dynamic raw);
 
-  @protected
-  void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer);
+@protected List<TrendingTag> dco_decode_list_trending_tag(dynamic raw);
 
-  @protected
-  void sse_encode_list_String(List<String> self, SseSerializer serializer);
+@protected List<TrendingThread> dco_decode_list_trending_thread(dynamic raw);
 
-  @protected
-  void sse_encode_list_post(List<Post> self, SseSerializer serializer);
+@protected List<User> dco_decode_list_user(dynamic raw);
 
-  @protected
-  void sse_encode_list_prim_u_8_strict(
-    Uint8List self,
-    SseSerializer serializer,
-  );
+@protected List<UserLink> dco_decode_list_user_link(dynamic raw);
 
-  @protected
-  void sse_encode_list_totem(List<Totem> self, SseSerializer serializer);
+@protected List<ValidationIssue> dco_decode_list_validation_issue(dynamic raw);
 
-  @protected
-  void sse_encode_list_user(List<User> self, SseSerializer serializer);
+@protected MediaDirection dco_decode_media_direction(dynamic raw);
 
-  @protected
-  void sse_encode_opt_String(String? self, SseSerializer serializer);
+@protected MediaSyncProgress dco_decode_media_sync_progress(dynamic raw);
 
-  @protected
-  void sse_encode_post(Post self, SseSerializer serializer);
+@protected MediaSyncSummary dco_decode_media_sync_summary(dynamic raw);
 
-  @protected
-  void sse_encode_totem(Totem self, SseSerializer serializer);
+@protected MessageSyncSummary dco_decode_message_sync_summary(dynamic raw);
 
-  @protected
-  void sse_encode_u_8(int self, SseSerializer serializer);
+@protected String? dco_decode_opt_String(dynamic raw);
 
-  @protected
-  void sse_encode_unit(void self, SseSerializer serializer);
+@protected DateTime? dco_decode_opt_box_autoadd_Chrono_Utc(dynamic raw);
 
-  @protected
-  void sse_encode_user(User self, SseSerializer serializer);
+@protected IdentityInfo? dco_decode_opt_box_autoadd_identity_info(dynamic raw);
 
-  @protected
-  void sse_encode_usize(BigInt self, SseSerializer serializer);
+@protected ImageOptions? dco_decode_opt_box_autoadd_image_options(dynamic raw);
 
-  @protected
-  void sse_encode_i_32(int self, SseSerializer serializer);
+@protected ImagePreview? dco_decode_opt_box_autoadd_image_preview(dynamic raw);
+
+@protected LastSeen? dco_decode_opt_box_autoadd_last_seen(dynamic raw);
+
+@protected Post? dco_decode_opt_box_autoadd_post(dynamic raw);
+
+@protected PostImage? dco_decode_opt_box_autoadd_post_image(dynamic raw);
+
+@protected int? dco_decode_opt_box_autoadd_u_32(dynamic raw);
+
+@protected Post dco_decode_post(dynamic raw);
+
+@protected PostEntities dco_decode_post_entities(dynamic raw);
+
+@protected PostImage dco_decode_post_image(dynamic raw);
+
+@protected PostReactions dco_decode_post_reactions(dynamic raw);
+
+@protected PostRevision dco_decode_post_revision(dynamic raw);
+
+@protected PreparedImage dco_decode_prepared_image(dynamic raw);
+
+@protected ReactionKind dco_decode_reaction_kind(dynamic raw);
+
+@protected RecordSyncSummary dco_decode_record_sync_summary(dynamic raw);
+
+@protected StoredImage dco_decode_stored_image(dynamic raw);
+
+@protected ThreadReply dco_decode_thread_reply(dynamic raw);
+
+@protected Totem dco_decode_totem(dynamic raw);
+
+@protected TrendingTag dco_decode_trending_tag(dynamic raw);
+
+@protected TrendingThread dco_decode_trending_thread(dynamic raw);
+
+@protected TrendingTopics dco_decode_trending_topics(dynamic raw);
+
+@protected int dco_decode_u_32(dynamic raw);
+
+@protected BigInt dco_decode_u_64(dynamic raw);
+
+@protected int dco_decode_u_8(dynamic raw);
+
+@protected void dco_decode_unit(dynamic raw);
+
+@protected User dco_decode_user(dynamic raw);
+
+@protected UserLink dco_decode_user_link(dynamic raw);
+
+@protected BigInt dco_decode_usize(dynamic raw);
+
+@protected ValidationIssue dco_decode_validation_issue(dynamic raw);
+
+@protected Verification dco_decode_verification(dynamic raw);
+
+@protected AnyhowException sse_decode_AnyhowException(SseDeserializer deserializer);
+
+@protected AppDatabase sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(SseDeserializer deserializer);
+
+@protected LinkOffer sse_decode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(SseDeserializer deserializer);
+
+@protected AppDatabase sse_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(SseDeserializer deserializer);
+
+@protected LinkOffer sse_decode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(SseDeserializer deserializer);
+
+@protected DateTime sse_decode_Chrono_Utc(SseDeserializer deserializer);
+
+@protected Object sse_decode_DartOpaque(SseDeserializer deserializer);
+
+@protected AppDatabase sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(SseDeserializer deserializer);
+
+@protected LinkOffer sse_decode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(SseDeserializer deserializer);
+
+@protected String sse_decode_String(SseDeserializer deserializer);
+
+@protected Attachment sse_decode_attachment(SseDeserializer deserializer);
+
+@protected AttachmentKind sse_decode_attachment_kind(SseDeserializer deserializer);
+
+@protected bool sse_decode_bool(SseDeserializer deserializer);
+
+@protected DateTime sse_decode_box_autoadd_Chrono_Utc(SseDeserializer deserializer);
+
+@protected IdentityInfo sse_decode_box_autoadd_identity_info(SseDeserializer deserializer);
+
+@protected ImageOptions sse_decode_box_autoadd_image_options(SseDeserializer deserializer);
+
+@protected ImagePreview sse_decode_box_autoadd_image_preview(SseDeserializer deserializer);
+
+@protected LastSeen sse_decode_box_autoadd_last_seen(SseDeserializer deserializer);
+
+@protected Post sse_decode_box_autoadd_post(SseDeserializer deserializer);
+
+@protected PostImage sse_decode_box_autoadd_post_image(SseDeserializer deserializer);
+
+@protected PreparedImage sse_decode_box_autoadd_prepared_image(SseDeserializer deserializer);
+
+@protected Totem sse_decode_box_autoadd_totem(SseDeserializer deserializer);
+
+@protected int sse_decode_box_autoadd_u_32(SseDeserializer deserializer);
+
+@protected User sse_decode_box_autoadd_user(SseDeserializer deserializer);
+
+@protected BundleSyncSummary sse_decode_bundle_sync_summary(SseDeserializer deserializer);
+
+@protected Conversation sse_decode_conversation(SseDeserializer deserializer);
+
+@protected DirectMessage sse_decode_direct_message(SseDeserializer deserializer);
+
+@protected FeedItem sse_decode_feed_item(SseDeserializer deserializer);
+
+@protected int sse_decode_i_32(SseDeserializer deserializer);
+
+@protected PlatformInt64 sse_decode_i_64(SseDeserializer deserializer);
+
+@protected IdentityInfo sse_decode_identity_info(SseDeserializer deserializer);
+
+@protected ImageOptions sse_decode_image_options(SseDeserializer deserializer);
+
+@protected ImagePreview sse_decode_image_preview(SseDeserializer deserializer);
+
+@protected PlatformInt64 sse_decode_isize(SseDeserializer deserializer);
+
+@protected LastSeen sse_decode_last_seen(SseDeserializer deserializer);
+
+@protected LinkSummary sse_decode_link_summary(SseDeserializer deserializer);
+
+@protected List<String> sse_decode_list_String(SseDeserializer deserializer);
+
+@protected List<Attachment> sse_decode_list_attachment(SseDeserializer deserializer);
+
+@protected List<Conversation> sse_decode_list_conversation(SseDeserializer deserializer);
+
+@protected List<DirectMessage> sse_decode_list_direct_message(SseDeserializer deserializer);
+
+@protected List<FeedItem> sse_decode_list_feed_item(SseDeserializer deserializer);
+
+@protected List<LastSeen> sse_decode_list_last_seen(SseDeserializer deserializer);
+
+@protected List<Post> sse_decode_list_post(SseDeserializer deserializer);
+
+@protected List<PostRevision> sse_decode_list_post_revision(SseDeserializer deserializer);
+
+@protected List<int> sse_decode_list_prim_u_8_loose(SseDeserializer deserializer);
+
+@protected Uint8List sse_decode_list_prim_u_8_strict(SseDeserializer deserializer);
+
+@protected List<ThreadReply> sse_decode_list_thread_reply(SseDeserializer deserializer);
+
+@protected List<Totem> sse_decode_list_totem(SseDeserializer deserializer);
+
+@protected List<TrendingTag> sse_decode_list_trending_tag(SseDeserializer deserializer);
+
+@protected List<TrendingThread> sse_decode_list_trending_thread(SseDeserializer deserializer);
+
+@protected List<User> sse_decode_list_user(SseDeserializer deserializer);
+
+@protected List<UserLink> sse_decode_list_user_link(SseDeserializer deserializer);
+
+@protected List<ValidationIssue> sse_decode_list_validation_issue(SseDeserializer deserializer);
+
+@protected MediaDirection sse_decode_media_direction(SseDeserializer deserializer);
+
+@protected MediaSyncProgress sse_decode_media_sync_progress(SseDeserializer deserializer);
+
+@protected MediaSyncSummary sse_decode_media_sync_summary(SseDeserializer deserializer);
+
+@protected MessageSyncSummary sse_decode_message_sync_summary(SseDeserializer deserializer);
+
+@protected String? sse_decode_opt_String(SseDeserializer deserializer);
+
+@protected DateTime? sse_decode_opt_box_autoadd_Chrono_Utc(SseDeserializer deserializer);
+
+@protected IdentityInfo? sse_decode_opt_box_autoadd_identity_info(SseDeserializer deserializer);
+
+@protected ImageOptions? sse_decode_opt_box_autoadd_image_options(SseDeserializer deserializer);
+
+@protected ImagePreview? sse_decode_opt_box_autoadd_image_preview(SseDeserializer deserializer);
+
+@protected LastSeen? sse_decode_opt_box_autoadd_last_seen(SseDeserializer deserializer);
+
+@protected Post? sse_decode_opt_box_autoadd_post(SseDeserializer deserializer);
+
+@protected PostImage? sse_decode_opt_box_autoadd_post_image(SseDeserializer deserializer);
+
+@protected int? sse_decode_opt_box_autoadd_u_32(SseDeserializer deserializer);
+
+@protected Post sse_decode_post(SseDeserializer deserializer);
+
+@protected PostEntities sse_decode_post_entities(SseDeserializer deserializer);
+
+@protected PostImage sse_decode_post_image(SseDeserializer deserializer);
+
+@protected PostReactions sse_decode_post_reactions(SseDeserializer deserializer);
+
+@protected PostRevision sse_decode_post_revision(SseDeserializer deserializer);
+
+@protected PreparedImage sse_decode_prepared_image(SseDeserializer deserializer);
+
+@protected ReactionKind sse_decode_reaction_kind(SseDeserializer deserializer);
+
+@protected RecordSyncSummary sse_decode_record_sync_summary(SseDeserializer deserializer);
+
+@protected StoredImage sse_decode_stored_image(SseDeserializer deserializer);
+
+@protected ThreadReply sse_decode_thread_reply(SseDeserializer deserializer);
+
+@protected Totem sse_decode_totem(SseDeserializer deserializer);
+
+@protected TrendingTag sse_decode_trending_tag(SseDeserializer deserializer);
+
+@protected TrendingThread sse_decode_trending_thread(SseDeserializer deserializer);
+
+@protected TrendingTopics sse_decode_trending_topics(SseDeserializer deserializer);
+
+@protected int sse_decode_u_32(SseDeserializer deserializer);
+
+@protected BigInt sse_decode_u_64(SseDeserializer deserializer);
+
+@protected int sse_decode_u_8(SseDeserializer deserializer);
+
+@protected void sse_decode_unit(SseDeserializer deserializer);
+
+@protected User sse_decode_user(SseDeserializer deserializer);
+
+@protected UserLink sse_decode_user_link(SseDeserializer deserializer);
+
+@protected BigInt sse_decode_usize(SseDeserializer deserializer);
+
+@protected ValidationIssue sse_decode_validation_issue(SseDeserializer deserializer);
+
+@protected Verification sse_decode_verification(SseDeserializer deserializer);
+
+@protected void sse_encode_AnyhowException(AnyhowException self, SseSerializer serializer);
+
+@protected void sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(AppDatabase self, SseSerializer serializer);
+
+@protected void sse_encode_Auto_Owned_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(LinkOffer self, SseSerializer serializer);
+
+@protected void sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(AppDatabase self, SseSerializer serializer);
+
+@protected void sse_encode_Auto_Ref_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(LinkOffer self, SseSerializer serializer);
+
+@protected void sse_encode_Chrono_Utc(DateTime self, SseSerializer serializer);
+
+@protected void sse_encode_DartFn_Inputs_media_sync_progress_Output_unit_AnyhowException(FutureOr<void> Function(MediaSyncProgress) self, SseSerializer serializer);
+
+@protected void sse_encode_DartOpaque(Object self, SseSerializer serializer);
+
+@protected void sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(AppDatabase self, SseSerializer serializer);
+
+@protected void sse_encode_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(LinkOffer self, SseSerializer serializer);
+
+@protected void sse_encode_String(String self, SseSerializer serializer);
+
+@protected void sse_encode_attachment(Attachment self, SseSerializer serializer);
+
+@protected void sse_encode_attachment_kind(AttachmentKind self, SseSerializer serializer);
+
+@protected void sse_encode_bool(bool self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_Chrono_Utc(DateTime self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_identity_info(IdentityInfo self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_image_options(ImageOptions self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_image_preview(ImagePreview self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_last_seen(LastSeen self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_post(Post self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_post_image(PostImage self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_prepared_image(PreparedImage self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_totem(Totem self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_u_32(int self, SseSerializer serializer);
+
+@protected void sse_encode_box_autoadd_user(User self, SseSerializer serializer);
+
+@protected void sse_encode_bundle_sync_summary(BundleSyncSummary self, SseSerializer serializer);
+
+@protected void sse_encode_conversation(Conversation self, SseSerializer serializer);
+
+@protected void sse_encode_direct_message(DirectMessage self, SseSerializer serializer);
+
+@protected void sse_encode_feed_item(FeedItem self, SseSerializer serializer);
+
+@protected void sse_encode_i_32(int self, SseSerializer serializer);
+
+@protected void sse_encode_i_64(PlatformInt64 self, SseSerializer serializer);
+
+@protected void sse_encode_identity_info(IdentityInfo self, SseSerializer serializer);
+
+@protected void sse_encode_image_options(ImageOptions self, SseSerializer serializer);
+
+@protected void sse_encode_image_preview(ImagePreview self, SseSerializer serializer);
+
+@protected void sse_encode_isize(PlatformInt64 self, SseSerializer serializer);
+
+@protected void sse_encode_last_seen(LastSeen self, SseSerializer serializer);
+
+@protected void sse_encode_link_summary(LinkSummary self, SseSerializer serializer);
+
+@protected void sse_encode_list_String(List<String> self, SseSerializer serializer);
+
+@protected void sse_encode_list_attachment(List<Attachment> self, SseSerializer serializer);
+
+@protected void sse_encode_list_conversation(List<Conversation> self, SseSerializer serializer);
+
+@protected void sse_encode_list_direct_message(List<DirectMessage> self, SseSerializer serializer);
+
+@protected void sse_encode_list_feed_item(List<FeedItem> self, SseSerializer serializer);
+
+@protected void sse_encode_list_last_seen(List<LastSeen> self, SseSerializer serializer);
+
+@protected void sse_encode_list_post(List<Post> self, SseSerializer serializer);
+
+@protected void sse_encode_list_post_revision(List<PostRevision> self, SseSerializer serializer);
+
+@protected void sse_encode_list_prim_u_8_loose(List<int> self, SseSerializer serializer);
+
+@protected void sse_encode_list_prim_u_8_strict(Uint8List self, SseSerializer serializer);
+
+@protected void sse_encode_list_thread_reply(List<ThreadReply> self, SseSerializer serializer);
+
+@protected void sse_encode_list_totem(List<Totem> self, SseSerializer serializer);
+
+@protected void sse_encode_list_trending_tag(List<TrendingTag> self, SseSerializer serializer);
+
+@protected void sse_encode_list_trending_thread(List<TrendingThread> self, SseSerializer serializer);
+
+@protected void sse_encode_list_user(List<User> self, SseSerializer serializer);
+
+@protected void sse_encode_list_user_link(List<UserLink> self, SseSerializer serializer);
+
+@protected void sse_encode_list_validation_issue(List<ValidationIssue> self, SseSerializer serializer);
+
+@protected void sse_encode_media_direction(MediaDirection self, SseSerializer serializer);
+
+@protected void sse_encode_media_sync_progress(MediaSyncProgress self, SseSerializer serializer);
+
+@protected void sse_encode_media_sync_summary(MediaSyncSummary self, SseSerializer serializer);
+
+@protected void sse_encode_message_sync_summary(MessageSyncSummary self, SseSerializer serializer);
+
+@protected void sse_encode_opt_String(String? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_Chrono_Utc(DateTime? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_identity_info(IdentityInfo? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_image_options(ImageOptions? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_image_preview(ImagePreview? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_last_seen(LastSeen? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_post(Post? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_post_image(PostImage? self, SseSerializer serializer);
+
+@protected void sse_encode_opt_box_autoadd_u_32(int? self, SseSerializer serializer);
+
+@protected void sse_encode_post(Post self, SseSerializer serializer);
+
+@protected void sse_encode_post_entities(PostEntities self, SseSerializer serializer);
+
+@protected void sse_encode_post_image(PostImage self, SseSerializer serializer);
+
+@protected void sse_encode_post_reactions(PostReactions self, SseSerializer serializer);
+
+@protected void sse_encode_post_revision(PostRevision self, SseSerializer serializer);
+
+@protected void sse_encode_prepared_image(PreparedImage self, SseSerializer serializer);
+
+@protected void sse_encode_reaction_kind(ReactionKind self, SseSerializer serializer);
+
+@protected void sse_encode_record_sync_summary(RecordSyncSummary self, SseSerializer serializer);
+
+@protected void sse_encode_stored_image(StoredImage self, SseSerializer serializer);
+
+@protected void sse_encode_thread_reply(ThreadReply self, SseSerializer serializer);
+
+@protected void sse_encode_totem(Totem self, SseSerializer serializer);
+
+@protected void sse_encode_trending_tag(TrendingTag self, SseSerializer serializer);
+
+@protected void sse_encode_trending_thread(TrendingThread self, SseSerializer serializer);
+
+@protected void sse_encode_trending_topics(TrendingTopics self, SseSerializer serializer);
+
+@protected void sse_encode_u_32(int self, SseSerializer serializer);
+
+@protected void sse_encode_u_64(BigInt self, SseSerializer serializer);
+
+@protected void sse_encode_u_8(int self, SseSerializer serializer);
+
+@protected void sse_encode_unit(void self, SseSerializer serializer);
+
+@protected void sse_encode_user(User self, SseSerializer serializer);
+
+@protected void sse_encode_user_link(UserLink self, SseSerializer serializer);
+
+@protected void sse_encode_usize(BigInt self, SseSerializer serializer);
+
+@protected void sse_encode_validation_issue(ValidationIssue self, SseSerializer serializer);
+
+@protected void sse_encode_verification(Verification self, SseSerializer serializer);
+                }
+                
 
-  @protected
-  void sse_encode_bool(bool self, SseSerializer serializer);
-}
 
 // Section: wire_class
 
-class RustLibWire implements BaseWire {
-  factory RustLibWire.fromExternalLibrary(ExternalLibrary lib) =>
-      RustLibWire(lib.ffiDynamicLibrary);
-
-  /// Holds the symbol lookup function.
-  final ffi.Pointer<T> Function<T extends ffi.NativeType>(String symbolName)
-  _lookup;
-
-  /// The symbols are looked up in [dynamicLibrary].
-  RustLibWire(ffi.DynamicLibrary dynamicLibrary)
-    : _lookup = dynamicLibrary.lookup;
-
-  void
-  rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_loom_app_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase',
-      );
-  late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase =
-      _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-
-  void
-  rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-    ffi.Pointer<ffi.Void> ptr,
-  ) {
-    return _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
-      ptr,
-    );
-  }
-
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr =
-      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>(
-        'frbgen_loom_app_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase',
-      );
-  late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase =
-      _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr
-          .asFunction<void Function(ffi.Pointer<ffi.Void>)>();
-}
+
+        class RustLibWire implements BaseWire {
+
+            factory RustLibWire.fromExternalLibrary(ExternalLibrary lib) =>
+              RustLibWire(lib.ffiDynamicLibrary);
+        
+            /// Holds the symbol lookup function.
+            final ffi.Pointer<T> Function<T extends ffi.NativeType>(String symbolName)
+                _lookup;
+  
+            /// The symbols are looked up in [dynamicLibrary].
+            RustLibWire(ffi.DynamicLibrary dynamicLibrary)
+                : _lookup = dynamicLibrary.lookup;
+
+            
+            void rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
+              ffi.Pointer<ffi.Void> ptr,
+            ) {
+              return _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
+                ptr,
+              );
+            }
+
+            late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>('frbgen_loom_app_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase');
+            late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase = _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr.asFunction<void Function(ffi.Pointer<ffi.Void>)>();
+            
+            void rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
+              ffi.Pointer<ffi.Void> ptr,
+            ) {
+              return _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase(
+                ptr,
+              );
+            }
+
+            late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>('frbgen_loom_app_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase');
+            late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabase = _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerAppDatabasePtr.asFunction<void Function(ffi.Pointer<ffi.Void>)>();
+            
+            void rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(
+              ffi.Pointer<ffi.Void> ptr,
+            ) {
+              return _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(
+                ptr,
+              );
+            }
+
+            late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOfferPtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>('frbgen_loom_app_rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer');
+            late final _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer = _rust_arc_increment_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOfferPtr.asFunction<void Function(ffi.Pointer<ffi.Void>)>();
+            
+            void rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(
+              ffi.Pointer<ffi.Void> ptr,
+            ) {
+              return _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer(
+                ptr,
+              );
+            }
+
+            late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOfferPtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Pointer<ffi.Void>)>>('frbgen_loom_app_rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer');
+            late final _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOffer = _rust_arc_decrement_strong_count_RustOpaque_flutter_rust_bridgefor_generatedRustAutoOpaqueInnerLinkOfferPtr.asFunction<void Function(ffi.Pointer<ffi.Void>)>();
+            
+        }
+        
\ No newline at end of file
@@ -0,0 +1,13 @@
+import 'package:loom_app/src/rust/api/simple.dart' as rust;
+
+final RegExp _contentHash = RegExp(r'^[0-9a-fA-F]{64}$');
+
+/// The file to show for the picture a post or profile references.
+///
+/// Synced pictures are referenced by their content hash and null until they
+/// have been downloaded. Older records reference a file on this phone.
+Future<String?> resolveMediaPath(rust.AppDatabase db, String? image) async {
+  if (image == null || image.trim().isEmpty) return null;
+  if (!_contentHash.hasMatch(image)) return image;
+  return db.getMediaPath(hash: image.toLowerCase());
+}
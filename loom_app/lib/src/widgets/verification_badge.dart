@@ -0,0 +1,46 @@
+import 'package:flutter/material.dart';
+import 'package:loom_app/src/rust/api/simple.dart' as rust;
+
+/// Small icon telling whether a post or profile was signed by its author.
+///
+/// Shows nothing while the check hasn't finished.
+class VerificationBadge extends StatelessWidget {
+  const VerificationBadge({super.key, required this.verification});
+
+  final rust.Verification? verification;
+
+  @override
+  Widget build(BuildContext context) {
+    final rust.Verification? verification = this.verification;
+    if (verification == null) return const SizedBox.shrink();
+
+    final ColorScheme colors = Theme.of(context).colorScheme;
+    final (IconData icon, Color color, String message) = switch (verification) {
+      rust.Verification.verified => (
+        Icons.verified_rounded,
+        colors.primary,
+        'Signed by its author',
+      ),
+      rust.Verification.unsigned => (
+        Icons.help_outline_rounded,
+        colors.onSurfaceVariant,
+        'Made before signatures, can\'t be verified',
+      ),
+      rust.Verification.unknownAuthor => (
+        Icons.person_off_outlined,
+        colors.onSurfaceVariant,
+        'The author\'s profile hasn\'t been synced yet',
+      ),
+      rust.Verification.invalid => (
+        Icons.gpp_bad_outlined,
+        colors.error,
+        'The signature doesn\'t match, this may be forged',
+      ),
+    };
+
+    return Tooltip(
+      message: message,
+      child: Icon(icon, size: 16, color: color),
+    );
+  }
+}
@@ -0,0 +1,78 @@
+use chrono::Utc;
+use shared::identity::Identity;
+use shared::model::{Bundle, BundleHeader, BundlePayload, Destination};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use crate::api::simple::AppDatabase;
+use crate::bundle_sync::BundleSync;
+use crate::media_sync::lock;
+use crate::totem::{TotemClient, DEFAULT_TOTEM_URL};
+
+#[derive(Debug, Clone)]
+pub struct BundleSyncSummary {
+    /// Bundles addressed to this phone's user
+    pub delivered: u32,
+    /// Bundles taken to be carried for others
+    pub taken: u32,
+    /// Bundles handed over to the totem
+    pub handed_over: u32,
+    /// Bundles the totem didn't take, they are kept for the next one
+    pub refused: u32,
+    /// Bundles that failed validation, or whose content did
+    pub rejected: u32,
+    /// Carried bundles that expired or were used up
+    pub dropped: u32,
+}
+
+/// Exchange bundles with a totem
+///
+/// With `courier` set the phone carries bundles addressed to others, too. Bundles addressed
+/// to this phone's user are only taken while the identity is unlocked.
+/// `totem_url` defaults to the address of a totem on its own access point.
+pub fn sync_bundles(
+    db: &AppDatabase,
+    totem_url: Option<String>,
+    courier: bool,
+) -> anyhow::Result<BundleSyncSummary> {
+    // A copy, so the identity isn't locked while the totem is talked to
+    let identity = db
+        .with_identity(|identity| {
+            Ok(Identity::from_secret_bytes(&Zeroizing::new(
+                identity.secret_bytes(),
+            )))
+        })
+        .ok();
+    let client = TotemClient::new(totem_url.as_deref().unwrap_or(DEFAULT_TOTEM_URL));
+    let summary = BundleSync::new(db.database(), &client, identity.as_ref(), courier).run()?;
+
+    Ok(BundleSyncSummary {
+        delivered: summary.delivered,
+        taken: summary.taken,
+        handed_over: summary.handed_over,
+        refused: summary.refused,
+        rejected: summary.rejected,
+        dropped: summary.dropped,
+    })
+}
+
+/// Have phones carry a stored post to a totem that is out of reach
+///
+/// The post is handed over to couriers at the next syncs until one drops it off there.
+/// Returns the UUID of the bundle.
+pub fn send_post_to_totem(
+    db: &AppDatabase,
+    post_uuid: String,
+    totem_id: String,
+) -> anyhow::Result<String> {
+    let database = lock(db.database())?;
+    let post = database.get_post_by_id(&post_uuid)?;
+    let bundle = Bundle {
+        uuid: Uuid::new_v4().to_string(),
+        header: BundleHeader::new(Destination::Totem(totem_id), Utc::now()),
+        payload: BundlePayload::Post(post),
+    };
+    bundle.validate()?;
+    database.store_bundle(&bundle)?;
+    Ok(bundle.uuid)
+}
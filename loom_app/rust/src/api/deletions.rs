@@ -0,0 +1,56 @@
+use std::io;
+
+use chrono::Utc;
+use shared::db::Database;
+use shared::model::Tombstone;
+use uuid::Uuid;
+
+use crate::api::simple::AppDatabase;
+use crate::media_sync::lock;
+
+/// Delete a post written by this phone's user, here and on every totem and phone the
+/// deletion reaches, see `shared::model::tombstone`
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+pub fn delete_post(db: &AppDatabase, uuid: String) -> anyhow::Result<()> {
+    let database = lock(db.database())?;
+    let post = database.get_post_by_id(&uuid)?;
+
+    db.with_identity(|identity| {
+        if post.user_id != identity.user_id() {
+            anyhow::bail!("Only the author can delete a post");
+        }
+        let mut tombstone = Tombstone::of(&post, Uuid::new_v4().to_string(), Utc::now());
+        tombstone.validate()?;
+        tombstone.sign(identity);
+        bury(&database, &tombstone)?;
+        Ok(())
+    })
+}
+
+/// Store a checked tombstone and delete its post, along with the blobs nothing else uses
+/// Returns whether the tombstone was new.
+pub(crate) fn bury(db: &Database, tombstone: &Tombstone) -> anyhow::Result<bool> {
+    let versions = db.get_post_history(&tombstone.post_id)?;
+    let mut hashes: Vec<String> = Vec::new();
+    for post in versions.iter().filter(|post| tombstone.buries(post)) {
+        hashes.extend(post.media_hashes().into_iter().map(str::to_string));
+    }
+
+    let created = db.create_tombstone(tombstone)?;
+
+    let referenced = db.get_referenced_media()?;
+    for hash in hashes.iter().filter(|hash| !referenced.contains(hash)) {
+        let Some(path) = db.get_media_path(hash)? else {
+            continue;
+        };
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        db.delete_media_path(hash)?;
+    }
+
+    Ok(created)
+}
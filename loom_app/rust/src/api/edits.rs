@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use shared::model::Post as SharedPost;
+
+use crate::api::simple::{AppDatabase, Post};
+use crate::media_sync::lock;
+
+/// A version of a post, see `shared::model::edit`
+#[derive(Debug, Clone)]
+pub struct PostRevision {
+    /// 0 for the post as first written
+    pub revision: u32,
+    /// `None` for the post as first written
+    pub edited_at: Option<DateTime<Utc>>,
+    pub title: String,
+    pub body: String,
+}
+
+impl From<SharedPost> for PostRevision {
+    fn from(post: SharedPost) -> Self {
+        PostRevision {
+            revision: post.revision,
+            edited_at: post.edited_at,
+            title: post.title,
+            body: post.body,
+        }
+    }
+}
+
+/// Change the title and body of a post written by this phone's user
+/// The edit is synced like a new post, the versions it replaces stay in the history.
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+pub fn edit_post(
+    db: &AppDatabase,
+    uuid: String,
+    title: String,
+    body: String,
+) -> anyhow::Result<Post> {
+    let database = lock(db.database())?;
+    let post = database.get_post_by_id(&uuid)?;
+
+    db.with_identity(|identity| {
+        if post.user_id != identity.user_id() {
+            anyhow::bail!("Only the author can edit a post");
+        }
+        let mut edit = post.edited(title, body, Utc::now());
+        edit.validate()?;
+        edit.sign(identity);
+        database.merge_post(&edit)?;
+        Ok(edit.into())
+    })
+}
+
+/// Every known version of a post, oldest first
+/// Posts that were never edited have a single one.
+pub fn get_post_history(db: &AppDatabase, uuid: String) -> anyhow::Result<Vec<PostRevision>> {
+    let versions = lock(db.database())?.get_post_history(&uuid)?;
+    Ok(versions.into_iter().map(Into::into).collect())
+}
@@ -0,0 +1,220 @@
+use chrono::Utc;
+use flutter_rust_bridge::frb;
+use shared::identity::Identity;
+use shared::model::rotation::KeyChain;
+use shared::model::{KeyRotation, RotationKind, User as SharedUser};
+use uuid::Uuid;
+
+use crate::api::simple::{AppDatabase, User};
+use crate::backup;
+use crate::keystore::KeyStore;
+use crate::media_sync::lock;
+
+/// The public part of this phone's identity
+#[derive(Debug, Clone)]
+pub struct IdentityInfo {
+    /// Derived from the public key, used as the user's UUID everywhere
+    pub user_id: String,
+    pub public_key: String,
+}
+
+impl From<&Identity> for IdentityInfo {
+    fn from(identity: &Identity) -> Self {
+        IdentityInfo {
+            user_id: identity.user_id(),
+            public_key: identity.public_key().to_string(),
+        }
+    }
+}
+
+/// The identity stored in `key_dir`, if this phone has one
+///
+/// `key_dir` has to be private to the app, like the application support directory.
+pub fn load_identity(key_dir: String) -> anyhow::Result<Option<IdentityInfo>> {
+    let identity = KeyStore::new(&key_dir).load()?;
+    Ok(identity.as_ref().map(Into::into))
+}
+
+/// Load the identity stored in `key_dir` to sign this user's records with
+///
+/// Has to be called on every start before posts are created. Returns `None` if the phone
+/// has no identity yet, its user's records stay unsigned then.
+pub fn unlock_identity(db: &AppDatabase, key_dir: String) -> anyhow::Result<Option<IdentityInfo>> {
+    let Some(identity) = KeyStore::new(&key_dir).load()? else {
+        return Ok(None);
+    };
+    let info = (&identity).into();
+    db.set_identity(identity)?;
+    Ok(Some(info))
+}
+
+/// The identity stored in `key_dir`, created on first use
+fn load_or_create(key_dir: &str) -> anyhow::Result<Identity> {
+    let store = KeyStore::new(key_dir);
+    if let Some(identity) = store.load()? {
+        return Ok(identity);
+    }
+
+    let identity = Identity::generate();
+    store.save(&identity)?;
+    Ok(identity)
+}
+
+/// Create the profile of this phone's user, under the ID of their identity
+///
+/// The `uuid` of `user` is replaced by the ID derived from the identity's public key.
+pub fn register_user(
+    db: &AppDatabase,
+    key_dir: String,
+    user: User,
+) -> anyhow::Result<IdentityInfo> {
+    let identity = load_or_create(&key_dir)?;
+    let mut user: SharedUser = user.into();
+    user.uuid = identity.user_id();
+    user.public_key = Some(identity.public_key().to_string());
+    user.sign(&identity);
+    user.validate()?;
+
+    let info = (&identity).into();
+    {
+        let db = db
+            .database()
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        db.create_user(&user);
+    }
+    db.set_identity(identity)?;
+    Ok(info)
+}
+
+/// Move a user with a random ID from before identities existed to a derived ID
+///
+/// Their profile is taken over by the new ID and signed with the identity. Their posts
+/// and sightings stay with the random ID, like the copies on totems and other phones: the
+/// UUIDs of the posts are known there already, signed under the new ID they would be
+/// versions of the same post by another author. The random ID is linked to the new one
+/// instead, so the old records lead to the new profile on this phone.
+pub fn migrate_identity(
+    db: &AppDatabase,
+    key_dir: String,
+    legacy_user_id: String,
+) -> anyhow::Result<IdentityInfo> {
+    let identity = load_or_create(&key_dir)?;
+    let info = (&identity).into();
+    {
+        let db = db
+            .database()
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+
+        let mut user = db.get_user_by_id(&legacy_user_id)?;
+        user.uuid = identity.user_id();
+        user.public_key = Some(identity.public_key().to_string());
+        user.sign(&identity);
+        user.validate()?;
+        db.migrate_user(&legacy_user_id, &user)?;
+    }
+
+    db.set_identity(identity)?;
+    Ok(info)
+}
+
+/// The identity stored in `key_dir`, an error if there is none
+#[frb(ignore)]
+pub(crate) fn load_existing(key_dir: &str) -> anyhow::Result<Identity> {
+    KeyStore::new(key_dir)
+        .load()?
+        .ok_or_else(|| anyhow::anyhow!("This phone has no identity yet"))
+}
+
+/// Store an identity from a backup or another device and sign with it from now on
+#[frb(ignore)]
+pub(crate) fn install(
+    db: &AppDatabase,
+    key_dir: &str,
+    identity: Identity,
+) -> anyhow::Result<IdentityInfo> {
+    KeyStore::new(key_dir).install(&identity)?;
+    let info = (&identity).into();
+    db.set_identity(identity)?;
+    Ok(info)
+}
+
+/// The secret key as 24 words to write down
+///
+/// Anyone who has the words can post as the user, they must only be shown on request.
+pub fn export_mnemonic(key_dir: String) -> anyhow::Result<String> {
+    let identity = load_existing(&key_dir)?;
+    Ok(backup::to_mnemonic(&identity).to_string())
+}
+
+/// Restore the identity from the words of [`export_mnemonic`], on a new phone
+///
+/// The user's profile and posts come back with the next sync, they are stored on totems
+/// under the same ID.
+pub fn restore_from_mnemonic(
+    db: &AppDatabase,
+    key_dir: String,
+    phrase: String,
+) -> anyhow::Result<IdentityInfo> {
+    let identity = backup::from_mnemonic(&phrase)?;
+    install(db, &key_dir, identity)
+}
+
+/// The secret key encrypted with `passphrase`, to be saved as a file
+///
+/// Takes a moment on purpose, the passphrase goes through a memory-hard key derivation.
+pub fn export_backup(key_dir: String, passphrase: String) -> anyhow::Result<Vec<u8>> {
+    let identity = load_existing(&key_dir)?;
+    Ok(backup::encrypt(&identity, &passphrase)?)
+}
+
+/// Restore the identity from a file of [`export_backup`], on a new phone
+pub fn restore_from_backup(
+    db: &AppDatabase,
+    key_dir: String,
+    file: Vec<u8>,
+    passphrase: String,
+) -> anyhow::Result<IdentityInfo> {
+    let identity = backup::decrypt(&file, &passphrase)?;
+    install(db, &key_dir, identity)
+}
+
+/// Register a new recovery key for this phone's user, returned as 24 words to write down
+///
+/// The words can revoke the user's key if the phone is stolen, they are never stored on
+/// the phone. The registration reaches the totems with the next sync.
+pub fn create_recovery_key(db: &AppDatabase, key_dir: String) -> anyhow::Result<String> {
+    let identity = load_existing(&key_dir)?;
+    let user_id = identity.user_id();
+    let db = lock(db.database())?;
+
+    let rotations = db.get_key_rotations(&user_id)?;
+    let chain = KeyChain::resolve(&user_id, identity.public_key(), &rotations);
+    if chain.current_key() != identity.public_key() {
+        anyhow::bail!("The key of this phone was replaced");
+    }
+    if chain.recovery_key().is_some() {
+        anyhow::bail!("A recovery key is registered already");
+    }
+
+    // An endorsement of the key in use, which only adds the recovery key
+    let recovery = Identity::generate();
+    let mut rotation = KeyRotation {
+        uuid: Uuid::new_v4().to_string(),
+        user_id,
+        identity_key: identity.public_key().to_string(),
+        sequence: chain.sequence() + 1,
+        kind: RotationKind::Endorsement,
+        new_key: identity.public_key().to_string(),
+        recovery_key: Some(recovery.public_key().to_string()),
+        timestamp: Utc::now(),
+        signature: String::new(),
+    };
+    rotation.sign(&identity);
+    rotation.validate()?;
+    rotation.check_chain(&rotations)?;
+    db.create_key_rotation(&rotation)?;
+
+    Ok(backup::to_mnemonic(&recovery).to_string())
+}
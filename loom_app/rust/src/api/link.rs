@@ -0,0 +1,209 @@
+use std::fs;
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use flutter_rust_bridge::frb;
+use shared::db::Database as SharedDatabase;
+
+use crate::api::identity::{install, load_existing, IdentityInfo};
+use crate::api::simple::AppDatabase;
+use crate::link::{self, Invitation, Payload};
+use crate::media_sync::lock;
+
+/// Result of linking this phone to the identity of another device
+#[derive(Debug, Clone)]
+pub struct LinkSummary {
+    pub identity: IdentityInfo,
+    pub users_imported: u32,
+    pub posts_imported: u32,
+    /// Records of the other device's database that failed validation
+    pub rejected: u32,
+}
+
+/// An offer to hand this phone's identity to one other device
+///
+/// Show [`LinkOffer::qr_code`] and call either [`LinkOffer::send`] or [`LinkOffer::export`].
+/// The pairing secret is used for a single transfer, start a new offer for the next device.
+pub struct LinkOffer {
+    invitation: Invitation,
+    listener: Option<TcpListener>,
+    used: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+impl LinkOffer {
+    /// `local_ip` is this phone's address on the local network
+    /// Without one the QR code has no address and the transfer has to go through a file.
+    #[frb(sync)]
+    pub fn new(local_ip: Option<String>) -> anyhow::Result<LinkOffer> {
+        let (listener, address) = match local_ip {
+            Some(ip) => {
+                let ip: IpAddr = ip.parse()?;
+                let listener = TcpListener::bind(SocketAddr::new(ip, 0))?;
+                let address = listener.local_addr()?;
+                (Some(listener), Some(address))
+            }
+            None => (None, None),
+        };
+
+        Ok(LinkOffer {
+            invitation: Invitation::new(address),
+            listener,
+            used: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+        })
+    }
+
+    /// The text of the QR code to show, it must not be shown to anyone else
+    #[frb(sync)]
+    pub fn qr_code(&self) -> String {
+        self.invitation.to_qr()
+    }
+
+    /// Wait up to `timeout_secs` for the scanning device and hand it the identity
+    ///
+    /// With `include_database` it also gets a copy of this phone's posts and profiles.
+    /// `cache_dir` holds the copy while it is being sent.
+    pub fn send(
+        &self,
+        db: &AppDatabase,
+        key_dir: String,
+        cache_dir: String,
+        include_database: bool,
+        timeout_secs: u32,
+    ) -> anyhow::Result<()> {
+        let listener = self.listener.as_ref().ok_or(link::LinkError::NoAddress)?;
+        let sealed = self.seal(db, &key_dir, &cache_dir, include_database)?;
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs.into());
+        link::serve(
+            listener,
+            &self.invitation,
+            &sealed,
+            deadline,
+            &self.cancelled,
+        )?;
+        Ok(())
+    }
+
+    /// The identity sealed for the scanning device, to be passed on as a file
+    pub fn export(
+        &self,
+        db: &AppDatabase,
+        key_dir: String,
+        cache_dir: String,
+        include_database: bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        self.seal(db, &key_dir, &cache_dir, include_database)
+    }
+
+    /// Stop waiting in [`LinkOffer::send`]
+    #[frb(sync)]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn seal(
+        &self,
+        db: &AppDatabase,
+        key_dir: &str,
+        cache_dir: &str,
+        include_database: bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        if self.used.swap(true, Ordering::Relaxed) {
+            anyhow::bail!("This pairing code was used already");
+        }
+
+        let identity = load_existing(key_dir)?;
+        let database = if include_database {
+            let file = TempFile::new(cache_dir)?;
+            lock(db.database())?.snapshot(file.path_str()?)?;
+            Some(fs::read(&file.0)?)
+        } else {
+            None
+        };
+
+        Ok(self.invitation.seal(&Payload { identity, database }))
+    }
+}
+
+/// Take over the identity of the device showing `qr_code`, over the local network
+///
+/// Its posts and profiles are added to this phone's if it sent them along. `cache_dir`
+/// holds them while they are read.
+pub fn link_over_network(
+    db: &AppDatabase,
+    key_dir: String,
+    cache_dir: String,
+    qr_code: String,
+) -> anyhow::Result<LinkSummary> {
+    let invitation = Invitation::parse(&qr_code)?;
+    let sealed = link::fetch(&invitation)?;
+    accept(db, &key_dir, &cache_dir, &invitation, &sealed)
+}
+
+/// Take over the identity of the device showing `qr_code`, from a file of [`LinkOffer::export`]
+pub fn link_from_file(
+    db: &AppDatabase,
+    key_dir: String,
+    cache_dir: String,
+    qr_code: String,
+    file: Vec<u8>,
+) -> anyhow::Result<LinkSummary> {
+    let invitation = Invitation::parse(&qr_code)?;
+    accept(db, &key_dir, &cache_dir, &invitation, &file)
+}
+
+fn accept(
+    db: &AppDatabase,
+    key_dir: &str,
+    cache_dir: &str,
+    invitation: &Invitation,
+    sealed: &[u8],
+) -> anyhow::Result<LinkSummary> {
+    let payload = invitation.open(sealed)?;
+    let identity = install(db, key_dir, payload.identity)?;
+
+    let mut summary = LinkSummary {
+        identity,
+        users_imported: 0,
+        posts_imported: 0,
+        rejected: 0,
+    };
+    if let Some(database) = payload.database {
+        let file = TempFile::new(cache_dir)?;
+        fs::write(&file.0, database)?;
+        let other = SharedDatabase::new(file.path_str()?.to_string());
+        let imported = link::import(db.database(), &other)?;
+        summary.users_imported = imported.users;
+        summary.posts_imported = imported.posts;
+        summary.rejected = imported.rejected;
+    }
+    Ok(summary)
+}
+
+/// A file with a random name in `cache_dir`, removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(cache_dir: &str) -> anyhow::Result<Self> {
+        fs::create_dir_all(cache_dir)?;
+        let name = format!("loom-link-{:016x}.db", OsRng.next_u64());
+        Ok(TempFile(Path::new(cache_dir).join(name)))
+    }
+
+    fn path_str(&self) -> anyhow::Result<&str> {
+        self.0
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("Cache directory is not valid UTF-8"))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
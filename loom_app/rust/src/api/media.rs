@@ -0,0 +1,230 @@
+use flutter_rust_bridge::{frb, DartFnFuture};
+use shared::media::{ContentHash, MediaError, MediaStore, MediaType};
+use shared::model::validation::check_attachments;
+
+use crate::api::simple::{AppDatabase, Attachment, AttachmentKind};
+use crate::media_sync::{self, MediaSync};
+use crate::totem::{TotemClient, DEFAULT_TOTEM_URL};
+use crate::transcode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaDirection {
+    Upload,
+    Download,
+}
+
+/// Progress of the transfer of a single blob, reported after every piece
+#[derive(Debug, Clone)]
+pub struct MediaSyncProgress {
+    pub hash: String,
+    pub direction: MediaDirection,
+    pub transferred: u64,
+    /// Size of the blob, 0 until it is known
+    pub total: u64,
+    /// The transfer is over, successfully unless `error` is set
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+impl From<media_sync::Progress> for MediaSyncProgress {
+    fn from(p: media_sync::Progress) -> Self {
+        MediaSyncProgress {
+            hash: p.hash.to_string(),
+            direction: match p.direction {
+                media_sync::Direction::Upload => MediaDirection::Upload,
+                media_sync::Direction::Download => MediaDirection::Download,
+            },
+            transferred: p.transferred,
+            total: p.total,
+            done: p.done,
+            error: p.error,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MediaSyncSummary {
+    pub uploaded: u32,
+    pub downloaded: u32,
+    pub failed: u32,
+}
+
+/// Transfer the pictures referenced by posts and users that are missing on either side
+///
+/// Run this after posts and users have been reconciled with the totem. Downloaded blobs
+/// are stored in `media_dir` and recorded in the database, see [`AppDatabase::get_media_path`].
+/// `totem_url` defaults to the address of a totem on its own access point.
+pub async fn sync_media(
+    db: &AppDatabase,
+    media_dir: String,
+    totem_url: Option<String>,
+    on_progress: impl Fn(MediaSyncProgress) -> DartFnFuture<()>,
+) -> anyhow::Result<MediaSyncSummary> {
+    let store = MediaStore::init(&media_dir)?;
+    let client = TotemClient::new(totem_url.as_deref().unwrap_or(DEFAULT_TOTEM_URL));
+
+    let mut sync = MediaSync::start(db.database(), client, store)?;
+    while let Some(progress) = sync.step() {
+        on_progress(progress.into()).await;
+    }
+
+    let summary = sync.summary();
+    Ok(MediaSyncSummary {
+        uploaded: summary.uploaded,
+        downloaded: summary.downloaded,
+        failed: summary.failed,
+    })
+}
+
+/// How pictures are downscaled and compressed before they are stored
+#[derive(Debug, Clone, Copy)]
+pub struct ImageOptions {
+    /// Longest side of the stored picture in pixels
+    pub max_dimension: u32,
+    /// Longest side of the thumbnail shown in feeds
+    pub thumbnail_dimension: u32,
+    /// JPEG quality from 1 to 100
+    pub quality: u8,
+}
+
+impl From<ImageOptions> for transcode::Options {
+    fn from(o: ImageOptions) -> Self {
+        transcode::Options {
+            max_dimension: o.max_dimension,
+            thumbnail_dimension: o.thumbnail_dimension,
+            quality: o.quality,
+        }
+    }
+}
+
+#[frb(sync)]
+pub fn default_image_options() -> ImageOptions {
+    let options = transcode::Options::default();
+    ImageOptions {
+        max_dimension: options.max_dimension,
+        thumbnail_dimension: options.thumbnail_dimension,
+        quality: options.quality,
+    }
+}
+
+/// A picture in the media store of this phone
+#[derive(Debug, Clone)]
+pub struct StoredImage {
+    pub hash: String,
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub size: u64,
+}
+
+/// A picture ready to be referenced by a post or profile
+/// See [`AppDatabase::create_post_with_image`] and [`AppDatabase::set_profile_picture`].
+#[derive(Debug, Clone)]
+pub struct PreparedImage {
+    pub image: StoredImage,
+    pub thumbnail: StoredImage,
+    /// Blurred placeholder shown until the picture has been downloaded
+    pub blurhash: String,
+}
+
+impl PreparedImage {
+    /// The stored picture as an attachment of a post
+    #[frb(sync)]
+    pub fn attachment(&self, alt_text: Option<String>) -> Attachment {
+        Attachment {
+            kind: AttachmentKind::Image,
+            mime: "image/jpeg".to_string(),
+            hash: self.image.hash.clone(),
+            size: self.image.size,
+            duration_ms: None,
+            width: Some(self.image.width),
+            height: Some(self.image.height),
+            alt_text,
+        }
+    }
+}
+
+/// Downscale a picture picked by the user, strip its metadata and store it with a thumbnail
+///
+/// `data` may be a JPEG, PNG or WebP file. Both variants are re-encoded as JPEG, stored
+/// in `media_dir` under their content hash and recorded in the database.
+pub fn prepare_image(
+    db: &AppDatabase,
+    data: Vec<u8>,
+    media_dir: String,
+    options: Option<ImageOptions>,
+) -> anyhow::Result<PreparedImage> {
+    let options = options.map(Into::into).unwrap_or_default();
+    let transcoded = transcode::transcode(&data, &options)?;
+    let store = MediaStore::init(&media_dir)?;
+
+    Ok(PreparedImage {
+        image: store_image(db, &store, transcoded.image)?,
+        thumbnail: store_image(db, &store, transcoded.thumbnail)?,
+        blurhash: transcoded.blurhash,
+    })
+}
+
+fn store_image(
+    db: &AppDatabase,
+    store: &MediaStore,
+    encoded: transcode::Encoded,
+) -> anyhow::Result<StoredImage> {
+    let info = match store.put(&encoded.data) {
+        // The same picture was posted before
+        Err(MediaError::AlreadyExists) => store.info(&ContentHash::of(&encoded.data))?,
+        result => result?,
+    };
+    let path = store.blob_path(&info.hash).to_string_lossy().into_owned();
+    db.set_media_path(info.hash.to_string(), path.clone())?;
+
+    Ok(StoredImage {
+        hash: info.hash.to_string(),
+        path,
+        width: encoded.width,
+        height: encoded.height,
+        size: info.size,
+    })
+}
+
+/// Store a clip, voice note or file picked by the user, to be attached to a post
+///
+/// Pictures go through [`prepare_image`] first, so they lose their metadata like everywhere
+/// else. The MIME type is recognised from the content, unknown types are rejected.
+pub fn store_attachment(
+    db: &AppDatabase,
+    data: Vec<u8>,
+    media_dir: String,
+    kind: AttachmentKind,
+    duration_ms: Option<u32>,
+    alt_text: Option<String>,
+) -> anyhow::Result<Attachment> {
+    if kind == AttachmentKind::Image {
+        let image = prepare_image(db, data, media_dir, None)?;
+        return Ok(image.attachment(alt_text));
+    }
+
+    let media_type = MediaType::sniff(&data).ok_or(MediaError::UnsupportedType)?;
+    let attachment = Attachment {
+        kind,
+        mime: media_type.mime().to_string(),
+        hash: ContentHash::of(&data).to_string(),
+        size: data.len() as u64,
+        duration_ms,
+        width: None,
+        height: None,
+        alt_text,
+    };
+    // Don't store what no totem would accept
+    check_attachments(&[attachment.clone().into()]).map_err(|e| anyhow::anyhow!(e))?;
+
+    let store = MediaStore::init(&media_dir)?;
+    let info = match store.put(&data) {
+        Err(MediaError::AlreadyExists) => store.info(&ContentHash::of(&data))?,
+        result => result?,
+    };
+    let path = store.blob_path(&info.hash).to_string_lossy().into_owned();
+    db.set_media_path(info.hash.to_string(), path)?;
+
+    Ok(attachment)
+}
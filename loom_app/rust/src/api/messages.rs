@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use shared::db::Database as SharedDatabase;
+use shared::identity::Identity;
+use shared::model::{Bundle, BundleHeader, BundlePayload, Destination, Message};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use crate::api::simple::{AppDatabase, Verification};
+use crate::mailbox_sync::MailboxSync;
+use crate::media_sync::lock;
+use crate::messaging;
+use crate::totem::{TotemClient, DEFAULT_TOTEM_URL};
+
+/// A direct message, decrypted if this phone can read it
+#[derive(Debug, Clone)]
+pub struct DirectMessage {
+    pub uuid: String,
+    pub sender_id: String,
+    pub recipient_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// `None` if it was encrypted to a key this phone doesn't have, or was altered
+    pub text: Option<String>,
+    /// Whether the sender wrote it with their own key
+    pub verification: Verification,
+    /// Whether the recipient collected it, only known for messages of this phone's user
+    pub delivered: bool,
+}
+
+/// The latest message exchanged with another user
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    pub user_id: String,
+    pub last_message: DirectMessage,
+    pub message_count: u32,
+}
+
+fn decrypt(
+    db: &SharedDatabase,
+    identity: &Identity,
+    message: Message,
+) -> anyhow::Result<DirectMessage> {
+    let text = messaging::open(identity, &message).ok();
+    let sender = db.get_user_by_id(&message.sender_id).ok();
+    let verification = match text {
+        // Nobody else could have encrypted it, but it may claim a key the sender never had
+        Some(_) => message
+            .sender_verification(sender.as_ref(), &db.get_key_rotations(&message.sender_id)?)
+            .into(),
+        None => Verification::Invalid,
+    };
+    let delivered = db.get_delivery_receipt(&message)?.is_some();
+
+    Ok(DirectMessage {
+        uuid: message.uuid,
+        sender_id: message.sender_id,
+        recipient_id: message.recipient_id,
+        timestamp: message.timestamp,
+        text,
+        verification,
+        delivered,
+    })
+}
+
+/// Encrypt `text` to another user and store it until it is synced
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+pub fn send_message(
+    db: &AppDatabase,
+    recipient_id: String,
+    text: String,
+) -> anyhow::Result<DirectMessage> {
+    let database = lock(db.database())?;
+    let recipient = database.get_user_by_id(&recipient_id)?;
+    let chain = recipient
+        .key_chain(&database.get_key_rotations(&recipient_id)?)
+        .ok_or_else(|| anyhow::anyhow!("This user has no key to send messages to"))?;
+
+    db.with_identity(|identity| {
+        let message = messaging::seal(
+            identity,
+            Uuid::new_v4().to_string(),
+            &recipient_id,
+            &chain.current_key(),
+            &text,
+            Utc::now(),
+        )?;
+        message.validate()?;
+        database.create_message(&message)?;
+        // Besides the mailboxes, couriers carry it towards the recipient
+        let bundle = Bundle {
+            uuid: Uuid::new_v4().to_string(),
+            header: BundleHeader::new(Destination::User(recipient_id.clone()), Utc::now()),
+            payload: BundlePayload::Message(message.clone()),
+        };
+        database.store_bundle(&bundle)?;
+        decrypt(&database, identity, message)
+    })
+}
+
+/// The messages exchanged with another user, oldest first
+pub fn get_conversation(db: &AppDatabase, user_id: String) -> anyhow::Result<Vec<DirectMessage>> {
+    let database = lock(db.database())?;
+    db.with_identity(|identity| {
+        database
+            .get_messages_between(&identity.user_id(), &user_id)?
+            .into_iter()
+            .map(|m| decrypt(&database, identity, m))
+            .collect()
+    })
+}
+
+/// Every user messages were exchanged with, most recent conversation first
+pub fn list_conversations(db: &AppDatabase) -> anyhow::Result<Vec<Conversation>> {
+    let database = lock(db.database())?;
+    db.with_identity(|identity| {
+        let own_id = identity.user_id();
+        let mut latest: HashMap<String, (Message, u32)> = HashMap::new();
+        // Oldest first, so the last one seen is the latest
+        for message in database.get_messages_of(&own_id)? {
+            let other = if message.sender_id == own_id {
+                message.recipient_id.clone()
+            } else {
+                message.sender_id.clone()
+            };
+            let count = latest.get(&other).map_or(0, |(_, count)| *count);
+            latest.insert(other, (message, count + 1));
+        }
+
+        let mut conversations = latest
+            .into_iter()
+            .map(|(user_id, (message, message_count))| {
+                Ok(Conversation {
+                    user_id,
+                    last_message: decrypt(&database, identity, message)?,
+                    message_count,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        conversations.sort_by_key(|c| std::cmp::Reverse(c.last_message.timestamp));
+        Ok(conversations)
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageSyncSummary {
+    pub received: u32,
+    pub sent: u32,
+    /// Messages of this phone's user that turned out to be delivered
+    pub delivered: u32,
+    /// Messages the totem had no room for, they are tried again at the next totem
+    pub refused: u32,
+    /// Messages and receipts that failed validation or decryption
+    pub rejected: u32,
+}
+
+/// Collect the messages waiting at a totem and leave the undelivered ones for their recipients
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+/// `totem_url` defaults to the address of a totem on its own access point.
+pub fn sync_messages(
+    db: &AppDatabase,
+    totem_url: Option<String>,
+) -> anyhow::Result<MessageSyncSummary> {
+    // A copy, so the identity isn't locked while the totem is talked to
+    let identity = db.with_identity(|identity| {
+        Ok(Identity::from_secret_bytes(&Zeroizing::new(
+            identity.secret_bytes(),
+        )))
+    })?;
+    let client = TotemClient::new(totem_url.as_deref().unwrap_or(DEFAULT_TOTEM_URL));
+    let summary = MailboxSync::new(db.database(), &client, &identity).run()?;
+
+    Ok(MessageSyncSummary {
+        received: summary.received,
+        sent: summary.sent,
+        delivered: summary.delivered,
+        refused: summary.refused,
+        rejected: summary.rejected,
+    })
+}
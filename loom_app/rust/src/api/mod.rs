@@ -1 +1,14 @@
-pub mod simple;
\ No newline at end of file
+pub mod bundles;
+pub mod deletions;
+pub mod edits;
+pub mod identity;
+pub mod link;
+pub mod media;
+pub mod messages;
+pub mod reactions;
+pub mod replies;
+pub mod reposts;
+pub mod simple;
+pub mod sync;
+pub mod tags;
+pub mod trending;
@@ -0,0 +1,101 @@
+use chrono::Utc;
+use shared::db::Database as SharedDatabase;
+use shared::model::{Reaction, ReactionKind as SharedReactionKind};
+use shared::reactions::next_timestamp;
+use uuid::Uuid;
+
+use crate::api::simple::AppDatabase;
+use crate::media_sync::lock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionKind {
+    Like,
+    Share,
+}
+
+impl From<SharedReactionKind> for ReactionKind {
+    fn from(s: SharedReactionKind) -> Self {
+        match s {
+            SharedReactionKind::Like => ReactionKind::Like,
+            SharedReactionKind::Share => ReactionKind::Share,
+        }
+    }
+}
+
+impl From<ReactionKind> for SharedReactionKind {
+    fn from(k: ReactionKind) -> Self {
+        match k {
+            ReactionKind::Like => SharedReactionKind::Like,
+            ReactionKind::Share => SharedReactionKind::Share,
+        }
+    }
+}
+
+/// Reactions to a post as far as this phone knows them
+#[derive(Debug, Clone)]
+pub struct PostReactions {
+    pub likes: u32,
+    pub shares: u32,
+    /// Whether this phone's user likes the post, false while the identity is locked
+    pub liked: bool,
+    pub shared: bool,
+}
+
+fn post_reactions(
+    db: &SharedDatabase,
+    post_id: &str,
+    user_id: Option<&str>,
+) -> anyhow::Result<PostReactions> {
+    let counts = db.get_reaction_counts(post_id)?;
+    let reacted = |kind| -> anyhow::Result<bool> {
+        let Some(user_id) = user_id else {
+            return Ok(false);
+        };
+        Ok(db
+            .get_reaction(user_id, post_id, kind)?
+            .is_some_and(|reaction| reaction.active))
+    };
+
+    Ok(PostReactions {
+        likes: counts.likes,
+        shares: counts.shares,
+        liked: reacted(SharedReactionKind::Like)?,
+        shared: reacted(SharedReactionKind::Share)?,
+    })
+}
+
+/// Like or share a post as this phone's user, or take it back with `active` unset
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+pub fn react(
+    db: &AppDatabase,
+    post_id: String,
+    kind: ReactionKind,
+    active: bool,
+) -> anyhow::Result<PostReactions> {
+    let database = lock(db.database())?;
+    db.with_identity(|identity| {
+        let user_id = identity.user_id();
+        let previous = database.get_reaction(&user_id, &post_id, kind.into())?;
+        let mut reaction = Reaction {
+            uuid: Uuid::new_v4().to_string(),
+            user_id: user_id.clone(),
+            post_id: post_id.clone(),
+            kind: kind.into(),
+            active,
+            timestamp: next_timestamp(previous.as_ref(), Utc::now()),
+            signature: None,
+        };
+        reaction.validate()?;
+        reaction.sign(identity);
+        database.merge_reaction(&reaction)?;
+
+        post_reactions(&database, &post_id, Some(&user_id))
+    })
+}
+
+pub fn get_post_reactions(db: &AppDatabase, post_id: String) -> anyhow::Result<PostReactions> {
+    let database = lock(db.database())?;
+    let user_id = db.with_identity(|identity| Ok(identity.user_id())).ok();
+    post_reactions(&database, &post_id, user_id.as_deref())
+}
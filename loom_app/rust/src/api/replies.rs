@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use shared::db::Database as SharedDatabase;
+use shared::model::reply::{thread, ThreadEntry};
+use shared::model::Reply;
+use uuid::Uuid;
+
+use crate::api::simple::{AppDatabase, Verification};
+use crate::media_sync::lock;
+
+/// A reply in its place in the thread of a post
+#[derive(Debug, Clone)]
+pub struct ThreadReply {
+    pub uuid: String,
+    pub user_id: String,
+    pub parent_id: String,
+    pub root_id: String,
+    pub body: String,
+    pub timestamp: DateTime<Utc>,
+    /// 1 for replies to the post, 2 for replies to those and so on
+    pub depth: u32,
+    /// The reply it answers didn't arrive yet, it is shown as a reply to the post until then
+    pub parent_missing: bool,
+    /// Whether the author wrote it with their own key
+    pub verification: Verification,
+}
+
+fn thread_reply(db: &SharedDatabase, entry: ThreadEntry) -> anyhow::Result<ThreadReply> {
+    let reply = entry.reply;
+    let (author, rotations) = db.get_signer(&reply.user_id)?;
+    let verification = reply.verification(author.as_ref(), &rotations).into();
+
+    Ok(ThreadReply {
+        uuid: reply.uuid,
+        user_id: reply.user_id,
+        parent_id: reply.parent_id,
+        root_id: reply.root_id,
+        body: reply.body,
+        timestamp: reply.timestamp,
+        depth: entry.depth,
+        parent_missing: entry.parent_missing,
+        verification,
+    })
+}
+
+/// Answer a post or a reply as this phone's user
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+pub fn reply_to(db: &AppDatabase, parent_id: String, body: String) -> anyhow::Result<ThreadReply> {
+    let database = lock(db.database())?;
+    // Replies to replies belong to the thread of the post the parent answers
+    let root_id = match database.get_reply_by_id(&parent_id) {
+        Ok(parent) => parent.root_id,
+        Err(_) => database.get_post_by_id(&parent_id)?.uuid,
+    };
+
+    db.with_identity(|identity| {
+        let mut reply = Reply {
+            uuid: Uuid::new_v4().to_string(),
+            user_id: identity.user_id(),
+            parent_id,
+            root_id,
+            body,
+            timestamp: Utc::now(),
+            signature: None,
+        };
+        reply.validate()?;
+        reply.sign(identity);
+        database.create_reply(&reply)?;
+
+        let entry = thread(&reply.root_id, database.get_replies_to(&reply.root_id)?)
+            .into_iter()
+            .find(|entry| entry.reply.uuid == reply.uuid)
+            .ok_or_else(|| anyhow::anyhow!("The reply wasn't stored"))?;
+        thread_reply(&database, entry)
+    })
+}
+
+/// The replies to a post in thread order, each followed by its answers
+pub fn get_thread(db: &AppDatabase, post_id: String) -> anyhow::Result<Vec<ThreadReply>> {
+    let database = lock(db.database())?;
+    let replies = database.get_replies_to(&post_id)?;
+    thread(&post_id, replies)
+        .into_iter()
+        .map(|entry| thread_reply(&database, entry))
+        .collect()
+}
+
+/// Number of replies in the thread of a post, including ones whose parent didn't arrive yet
+pub fn get_reply_count(db: &AppDatabase, post_id: String) -> anyhow::Result<u32> {
+    Ok(lock(db.database())?.get_reply_count(&post_id)?)
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use shared::model::Repost;
+use uuid::Uuid;
+
+use crate::api::simple::{AppDatabase, Post};
+use crate::media_sync::lock;
+
+/// A post in the feed, either as written or as passed on by someone
+#[derive(Debug, Clone)]
+pub struct FeedItem {
+    pub post_id: String,
+    /// `None` for a repost whose post didn't arrive yet, it is fetched at the next syncs
+    pub post: Option<Post>,
+    /// Who wrote the post
+    pub author_id: String,
+    /// Who reposted it, `None` for the post itself
+    pub reposted_by: Option<String>,
+    pub repost_uuid: Option<String>,
+    /// When it was posted or reposted
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Pass someone's post on as this phone's user
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+pub fn repost(db: &AppDatabase, post_id: String) -> anyhow::Result<FeedItem> {
+    let database = lock(db.database())?;
+    let post = database.get_post_by_id(&post_id)?;
+
+    db.with_identity(|identity| {
+        let mut repost = Repost {
+            uuid: Uuid::new_v4().to_string(),
+            user_id: identity.user_id(),
+            post_id: post.uuid.clone(),
+            author_id: post.user_id.clone(),
+            timestamp: Utc::now(),
+            signature: None,
+        };
+        repost.validate()?;
+        repost.sign(identity);
+        database.create_repost(&repost)?;
+
+        Ok(FeedItem {
+            post_id: repost.post_id,
+            post: Some(post.into()),
+            author_id: repost.author_id,
+            reposted_by: Some(repost.user_id),
+            repost_uuid: Some(repost.uuid),
+            timestamp: repost.timestamp,
+        })
+    })
+}
+
+/// Posts and reposts, newest first
+/// Reposts that attribute a post to someone who didn't write it, or of posts their
+/// author deleted, are left out.
+pub fn get_feed(db: &AppDatabase) -> anyhow::Result<Vec<FeedItem>> {
+    let database = lock(db.database())?;
+    let mut feed: Vec<FeedItem> = database
+        .get_all_posts()?
+        .into_iter()
+        .map(|post| FeedItem {
+            post_id: post.uuid.clone(),
+            author_id: post.user_id.clone(),
+            reposted_by: None,
+            repost_uuid: None,
+            timestamp: post.timestamp,
+            post: Some(post.into()),
+        })
+        .collect();
+
+    for repost in database.get_all_reposts()? {
+        let post = database.get_post_by_id(&repost.post_id).ok();
+        if post
+            .as_ref()
+            .is_some_and(|post| repost.check_original(post).is_err())
+        {
+            continue;
+        }
+        if post.is_none() && database.is_post_deleted(&repost.post_id, &repost.author_id)? {
+            continue;
+        }
+        feed.push(FeedItem {
+            post_id: repost.post_id,
+            post: post.map(Into::into),
+            author_id: repost.author_id,
+            reposted_by: Some(repost.user_id),
+            repost_uuid: Some(repost.uuid),
+            timestamp: repost.timestamp,
+        });
+    }
+
+    feed.sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+    Ok(feed)
+}
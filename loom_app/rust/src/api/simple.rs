@@ -1,10 +1,20 @@
 use chrono::{DateTime, Utc};
+use std::collections::HashSet;
 use std::sync::Mutex;
 use flutter_rust_bridge::frb;
 
+use crate::api::media::PreparedImage;
+
 // Import the internal types from the shared crate
 use shared::db::Database as SharedDatabase;
-use shared::model::{Post as SharedPost, Totem as SharedTotem, User as SharedUser};
+use shared::identity::Identity;
+use shared::model::signing::Verification as SharedVerification;
+use shared::model::validation::ValidationErrors;
+use shared::model::{
+    Attachment as SharedAttachment, AttachmentKind as SharedAttachmentKind,
+    ImagePreview as SharedImagePreview, LastSeen as SharedLastSeen, Post as SharedPost,
+    Totem as SharedTotem, User as SharedUser,
+};
 
 // --- Models ---
 // We redefine the structs here so FRB can generate the Dart classes.
@@ -33,15 +43,20 @@ impl From<SharedUser> for User {
     }
 }
 
-impl Into<SharedUser> for User {
-    fn into(self) -> SharedUser {
+impl From<User> for SharedUser {
+    fn from(u: User) -> Self {
         SharedUser {
-            uuid: self.uuid,
-            username: self.username,
-            status: self.status,
-            bio: self.bio,
-            profile_picture: self.profile_picture,
-            last_contact: self.last_contact,
+            uuid: u.uuid,
+            username: u.username,
+            status: u.status,
+            bio: u.bio,
+            profile_picture: u.profile_picture,
+            last_contact: u.last_contact,
+            // Kept by `AppDatabase::update_user` while the picture doesn't change
+            profile_picture_preview: None,
+            // Set by `crate::api::identity::register_user`, never changed afterwards
+            public_key: None,
+            signature: None,
         }
     }
 }
@@ -71,20 +86,26 @@ impl From<SharedPost> for Post {
     }
 }
 
-impl Into<SharedPost> for Post {
-    fn into(self) -> SharedPost {
+impl From<Post> for SharedPost {
+    fn from(p: Post) -> Self {
         SharedPost {
-            uuid: self.uuid,
-            user_id: self.user_id,
-            title: self.title,
-            body: self.body,
-            timestamp: self.timestamp,
-            image: self.image,
-            source_totem: if self.source_totem.is_empty() {
+            uuid: p.uuid,
+            user_id: p.user_id,
+            title: p.title,
+            body: p.body,
+            timestamp: p.timestamp,
+            image: p.image,
+            source_totem: if p.source_totem.is_empty() {
                 None
             } else {
-                Some(self.source_totem)
+                Some(p.source_totem)
             },
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         }
     }
 }
@@ -108,23 +129,212 @@ impl From<SharedTotem> for Totem {
     }
 }
 
-impl Into<SharedTotem> for Totem {
-    fn into(self) -> SharedTotem {
+impl From<Totem> for SharedTotem {
+    fn from(t: Totem) -> Self {
         SharedTotem {
-            uuid: self.uuid,
-            name: self.name,
-            location: self.location,
-            last_contact: self.last_contact,
+            uuid: t.uuid,
+            name: t.name,
+            location: t.location,
+            last_contact: t.last_contact,
+        }
+    }
+}
+
+/// Blurred placeholder of a picture and its size, to lay out the feed before it is downloaded
+#[derive(Debug, Clone)]
+pub struct ImagePreview {
+    pub blurhash: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl From<SharedImagePreview> for ImagePreview {
+    fn from(s: SharedImagePreview) -> Self {
+        ImagePreview {
+            blurhash: s.blurhash,
+            width: s.width,
+            height: s.height,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentKind {
+    Image,
+    Clip,
+    Voice,
+    File,
+}
+
+impl From<SharedAttachmentKind> for AttachmentKind {
+    fn from(s: SharedAttachmentKind) -> Self {
+        match s {
+            SharedAttachmentKind::Image => AttachmentKind::Image,
+            SharedAttachmentKind::Clip => AttachmentKind::Clip,
+            SharedAttachmentKind::Voice => AttachmentKind::Voice,
+            SharedAttachmentKind::File => AttachmentKind::File,
+        }
+    }
+}
+
+impl From<AttachmentKind> for SharedAttachmentKind {
+    fn from(k: AttachmentKind) -> Self {
+        match k {
+            AttachmentKind::Image => SharedAttachmentKind::Image,
+            AttachmentKind::Clip => SharedAttachmentKind::Clip,
+            AttachmentKind::Voice => SharedAttachmentKind::Voice,
+            AttachmentKind::File => SharedAttachmentKind::File,
+        }
+    }
+}
+
+/// A blob attached to a post, by content hash, see [`AppDatabase::get_media_path`]
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    pub mime: String,
+    pub hash: String,
+    pub size: u64,
+    pub duration_ms: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub alt_text: Option<String>,
+}
+
+impl From<SharedAttachment> for Attachment {
+    fn from(s: SharedAttachment) -> Self {
+        Attachment {
+            kind: s.kind.into(),
+            mime: s.mime,
+            hash: s.hash,
+            size: s.size,
+            duration_ms: s.duration_ms,
+            width: s.width,
+            height: s.height,
+            alt_text: s.alt_text,
+        }
+    }
+}
+
+impl From<Attachment> for SharedAttachment {
+    fn from(a: Attachment) -> Self {
+        SharedAttachment {
+            kind: a.kind.into(),
+            mime: a.mime,
+            hash: a.hash,
+            size: a.size,
+            duration_ms: a.duration_ms,
+            width: a.width,
+            height: a.height,
+            alt_text: a.alt_text,
+        }
+    }
+}
+
+/// The picture of a post, by content hash, see [`AppDatabase::get_media_path`]
+#[derive(Debug, Clone)]
+pub struct PostImage {
+    pub image: String,
+    pub thumbnail: Option<String>,
+    pub preview: Option<ImagePreview>,
+}
+
+/// When a user last synced with a totem
+#[derive(Debug, Clone)]
+pub struct LastSeen {
+    pub user_id: String,
+    pub totem_id: String,
+    pub seen_at: DateTime<Utc>,
+}
+
+impl From<SharedLastSeen> for LastSeen {
+    fn from(s: SharedLastSeen) -> Self {
+        LastSeen {
+            user_id: s.user_id,
+            totem_id: s.totem_id,
+            seen_at: s.seen_at,
+        }
+    }
+}
+
+/// A random user ID from before identities, and the derived ID its user moved to
+#[derive(Debug, Clone)]
+pub struct UserLink {
+    pub legacy_id: String,
+    pub user_id: String,
+}
+
+/// Whether a record was made by its author, see `shared::model::signing`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    Verified,
+    /// From before identities existed, the UI should say that it can't be verified
+    Unsigned,
+    UnknownAuthor,
+    /// Forged or altered, only records created before verification existed can be like this
+    Invalid,
+}
+
+impl From<SharedVerification> for Verification {
+    fn from(s: SharedVerification) -> Self {
+        match s {
+            SharedVerification::Verified => Verification::Verified,
+            SharedVerification::Unsigned => Verification::Unsigned,
+            SharedVerification::UnknownAuthor => Verification::UnknownAuthor,
+            SharedVerification::Invalid => Verification::Invalid,
+        }
+    }
+}
+
+/// A field that failed validation, see `shared::model::validation` for the rules
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub field: String,
+    pub message: String,
+}
+
+fn preview_of(image: &PreparedImage) -> SharedImagePreview {
+    SharedImagePreview {
+        blurhash: image.blurhash.clone(),
+        width: image.image.width,
+        height: image.image.height,
+    }
+}
+
+fn validation_issues(result: Result<(), ValidationErrors>) -> Vec<ValidationIssue> {
+    match result {
+        Ok(()) => Vec::new(),
+        Err(e) => e
+            .errors
+            .into_iter()
+            .map(|e| ValidationIssue {
+                field: e.field.to_string(),
+                message: e.message,
+            })
+            .collect(),
+    }
+}
+
+/// Check a user before saving it, so forms can point at the offending fields
+#[frb(sync)]
+pub fn validate_user(user: User) -> Vec<ValidationIssue> {
+    validation_issues(SharedUser::from(user).validate())
+}
+
+/// Check a post before saving it, so forms can point at the offending fields
+#[frb(sync)]
+pub fn validate_post(post: Post) -> Vec<ValidationIssue> {
+    validation_issues(SharedPost::from(post).validate())
+}
+
 // --- Database Wrapper ---
 
 // We wrap the SharedDatabase in a Mutex to make it thread-safe (Sync).
 // This allows FRB to pass the 'AppDatabase' handle safely between Rust threads.
 pub struct AppDatabase {
     inner: Mutex<SharedDatabase>,
+    /// Signs the records of this phone's user, see [`crate::api::identity::unlock_identity`]
+    identity: Mutex<Option<Identity>>,
 }
 
 impl AppDatabase {
@@ -133,7 +343,73 @@ impl AppDatabase {
         let db = SharedDatabase::new(path);
         AppDatabase {
             inner: Mutex::new(db),
+            identity: Mutex::new(None),
+        }
+    }
+
+    /// The database shared with the sync code
+    #[frb(ignore)]
+    pub(crate) fn database(&self) -> &Mutex<SharedDatabase> {
+        &self.inner
+    }
+
+    /// Sign records of this identity's user from now on
+    #[frb(ignore)]
+    pub(crate) fn set_identity(&self, identity: Identity) -> anyhow::Result<()> {
+        *self
+            .identity
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))? = Some(identity);
+        Ok(())
+    }
+
+    /// Run `f` with the unlocked identity of this phone's user
+    /// Lock the database before, never while `f` runs, the signing code locks them in that order.
+    #[frb(ignore)]
+    pub(crate) fn with_identity<T>(
+        &self,
+        f: impl FnOnce(&Identity) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let identity = self
+            .identity
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let identity = identity
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("The identity isn't unlocked"))?;
+        f(identity)
+    }
+
+    /// Sign a user if they are this phone's user
+    /// Anyone else's record is left alone, validation catches it if it needs a signature.
+    fn sign_user(&self, user: &mut SharedUser) -> anyhow::Result<()> {
+        let identity = self
+            .identity
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        match identity.as_ref() {
+            Some(identity) if identity.user_id() == user.uuid => user.sign(identity),
+            _ => user.signature = None,
         }
+        Ok(())
+    }
+
+    /// Sign a post by this phone's user and check it against the stored author
+    /// Posts of users with a key can't be created while their identity isn't unlocked.
+    fn sign_post(&self, db: &SharedDatabase, post: &mut SharedPost) -> anyhow::Result<()> {
+        {
+            let identity = self
+                .identity
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+            if let Some(identity) = identity.as_ref().filter(|i| i.user_id() == post.user_id) {
+                post.sign(identity);
+            }
+        }
+
+        let (author, rotations) = db.get_signer(&post.user_id)?;
+        post.check_signature(author.as_ref(), &rotations)?;
+        Ok(())
     }
 
     // --- User Methods ---
@@ -145,36 +421,197 @@ impl AppDatabase {
         // Assuming shared::create_user panics on failure (based on your code),
         // we wrap it here. Note: If shared::create_user panics, the Rust side might crash.
         // Ideally refactor 'shared' to return Result, but this works for now.
-        db.create_user(&user.into());
+        // Invalid users would be rejected by every totem, so don't let them in at all.
+        let user: SharedUser = user.into();
+        user.validate()?;
+        user.check_signature(&db.get_key_rotations(&user.uuid)?)?;
+        db.create_user(&user);
         Ok(())
     }
 
+    /// The profile behind `uuid`, the one a random ID from before identities moved to
     pub fn get_user_by_id(&self, uuid: String) -> anyhow::Result<User> {
         let db = self.inner.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-        let user = db.get_user_by_id(&uuid)?;
+        let user = db.get_author(&uuid)?;
         Ok(user.into())
     }
 
+    /// All users, without the profiles of random IDs that moved to another one
     pub fn get_all_users(&self) -> anyhow::Result<Vec<User>> {
         let db = self.inner.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let moved: HashSet<String> = db
+            .get_user_links()?
+            .into_iter()
+            .map(|(legacy_id, _)| legacy_id)
+            .collect();
         let users = db.get_all_users()?;
-        Ok(users.into_iter().map(Into::into).collect())
+        Ok(users
+            .into_iter()
+            .filter(|user| !moved.contains(&user.uuid))
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Random IDs from before identities, with the ID their user moved to
+    /// Records under a random ID belong to the profile of the ID it moved to.
+    pub fn get_user_links(&self) -> anyhow::Result<Vec<UserLink>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let links = db.get_user_links()?;
+        Ok(links
+            .into_iter()
+            .map(|(legacy_id, user_id)| UserLink { legacy_id, user_id })
+            .collect())
     }
 
     pub fn update_user(&self, user: User) -> anyhow::Result<()> {
         let db = self.inner.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-        db.update_user(&user.into())?;
+        let mut user: SharedUser = user.into();
+        let stored = db.get_user_by_id(&user.uuid)?;
+        if stored.profile_picture == user.profile_picture {
+            user.profile_picture_preview = stored.profile_picture_preview;
+        }
+        user.public_key = stored.public_key;
+        self.sign_user(&mut user)?;
+        user.validate()?;
+        user.check_signature(&db.get_key_rotations(&user.uuid)?)?;
+        db.update_user(&user)?;
+        Ok(())
+    }
+
+    /// Whether a user's profile was made by the owner of their ID
+    pub fn get_user_verification(&self, uuid: String) -> anyhow::Result<Verification> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let user = db.get_author(&uuid)?;
+        Ok(user.verification(&db.get_key_rotations(&user.uuid)?).into())
+    }
+
+    /// Show a picture from [`crate::api::media::prepare_image`] on a user's profile
+    pub fn set_profile_picture(&self, uuid: String, image: PreparedImage) -> anyhow::Result<()> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let mut user = db.get_user_by_id(&uuid)?;
+        user.profile_picture_preview = Some(preview_of(&image));
+        user.profile_picture = Some(image.image.hash);
+        self.sign_user(&mut user)?;
+        user.validate()?;
+        user.check_signature(&db.get_key_rotations(&user.uuid)?)?;
+        db.update_user(&user)?;
         Ok(())
     }
 
+    /// Placeholder of a user's profile picture, if they have one
+    pub fn get_profile_picture_preview(
+        &self,
+        uuid: String,
+    ) -> anyhow::Result<Option<ImagePreview>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let user = db.get_user_by_id(&uuid)?;
+        Ok(user.profile_picture_preview.map(Into::into))
+    }
+
     // --- Post Methods ---
 
     pub fn create_post(&self, post: Post) -> anyhow::Result<()> {
         let db = self.inner.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-        db.create_post(&post.into());
+        let mut post: SharedPost = post.into();
+        post.validate()?;
+        self.sign_post(&db, &mut post)?;
+        db.create_post(&post);
+        Ok(())
+    }
+
+    /// Create a post showing a picture from [`crate::api::media::prepare_image`]
+    pub fn create_post_with_image(
+        &self,
+        post: Post,
+        image: PreparedImage,
+        alt_text: Option<String>,
+    ) -> anyhow::Result<()> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let mut post: SharedPost = post.into();
+        post.image_preview = Some(preview_of(&image));
+        post.attachments = vec![image.attachment(alt_text).into()];
+        post.image = Some(image.image.hash);
+        post.thumbnail = Some(image.thumbnail.hash);
+        post.validate()?;
+        self.sign_post(&db, &mut post)?;
+        db.create_post(&post);
         Ok(())
     }
 
+    /// Create a post with attachments from [`crate::api::media::store_attachment`]
+    pub fn create_post_with_attachments(
+        &self,
+        post: Post,
+        attachments: Vec<Attachment>,
+    ) -> anyhow::Result<()> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let mut post: SharedPost = post.into();
+        post.attachments = attachments.into_iter().map(Into::into).collect();
+        // Apps and totems that don't know attachments still show the first picture
+        post.image = post
+            .attachments
+            .iter()
+            .find(|a| a.kind == SharedAttachmentKind::Image)
+            .map(|a| a.hash.clone());
+        post.validate()?;
+        self.sign_post(&db, &mut post)?;
+        db.create_post(&post);
+        Ok(())
+    }
+
+    /// The attachments of a post, in the order they are shown
+    pub fn get_post_attachments(&self, uuid: String) -> anyhow::Result<Vec<Attachment>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let attachments = db.get_attachments(&uuid)?;
+        Ok(attachments.into_iter().map(Into::into).collect())
+    }
+
+    /// The picture of a post with its thumbnail and placeholder, if it has one
+    pub fn get_post_image(&self, uuid: String) -> anyhow::Result<Option<PostImage>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let post = db.get_post_by_id(&uuid)?;
+        Ok(post.image.map(|image| PostImage {
+            image,
+            thumbnail: post.thumbnail,
+            preview: post.image_preview.map(Into::into),
+        }))
+    }
+
+    /// Whether a post was made by its author, checked against the stored author
+    pub fn get_post_verification(&self, uuid: String) -> anyhow::Result<Verification> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let post = db.get_post_by_id(&uuid)?;
+        let (author, rotations) = db.get_signer(&post.user_id)?;
+        Ok(post.verification(author.as_ref(), &rotations).into())
+    }
+
     pub fn get_post_by_id(&self, uuid: String) -> anyhow::Result<Post> {
         let db = self.inner.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
         let post = db.get_post_by_id(&uuid)?;
@@ -212,6 +649,63 @@ impl AppDatabase {
         db.update_totem_last_contact(&uuid, last_contact)?;
         Ok(())
     }
+
+    // --- Media Methods ---
+
+    /// Where the blob with the given content hash is stored on this phone, if it is
+    pub fn get_media_path(&self, hash: String) -> anyhow::Result<Option<String>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        Ok(db.get_media_path(&hash)?)
+    }
+
+    /// Record where the blob with the given content hash is stored on this phone
+    pub fn set_media_path(&self, hash: String, local_path: String) -> anyhow::Result<()> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        db.set_media_path(&hash, &local_path)?;
+        Ok(())
+    }
+
+    // --- Presence Methods ---
+
+    /// All known sightings, newest first
+    pub fn get_all_last_seen(&self) -> anyhow::Result<Vec<LastSeen>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let entries = db.get_all_last_seen()?;
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
+    /// Where and when a user was seen last, for "seen at Totem X, 2 hours ago"
+    pub fn get_latest_last_seen(&self, user_id: String) -> anyhow::Result<Option<LastSeen>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let entry = db.get_latest_last_seen(&user_id)?;
+        Ok(entry.map(Into::into))
+    }
+
+    /// Users that visited a totem since the given time, newest first
+    pub fn get_recent_visitors(
+        &self,
+        totem_id: String,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<LastSeen>> {
+        let db = self
+            .inner
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        let entries = db.get_recent_visitors(&totem_id, since)?;
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
 }
 
 // Keep the original greeting for testing
@@ -0,0 +1,70 @@
+use shared::identity::Identity;
+use zeroize::Zeroizing;
+
+use crate::api::simple::AppDatabase;
+use crate::record_sync::RecordSync;
+use crate::totem::{TotemClient, DEFAULT_TOTEM_URL};
+
+#[derive(Debug, Clone)]
+pub struct RecordSyncSummary {
+    pub rotations_downloaded: u32,
+    pub rotations_uploaded: u32,
+    pub users_downloaded: u32,
+    pub users_uploaded: u32,
+    /// Sightings of users at totems that were new to the phone
+    pub last_seen_downloaded: u32,
+    pub tombstones_downloaded: u32,
+    pub tombstones_uploaded: u32,
+    pub posts_downloaded: u32,
+    pub posts_uploaded: u32,
+    pub replies_downloaded: u32,
+    pub replies_uploaded: u32,
+    pub reposts_downloaded: u32,
+    pub reposts_uploaded: u32,
+    pub reactions_downloaded: u32,
+    pub reactions_uploaded: u32,
+    /// Records that failed validation on either side
+    pub rejected: u32,
+}
+
+/// Exchange the key rotations, users, tombstones and recent posts, replies, reposts and reactions
+/// missing on either side with a totem
+///
+/// Sightings of the known users are exchanged too. While the identity is unlocked, the
+/// totem records the phone's user as seen there.
+/// Run [`crate::api::media::sync_media`] afterwards to transfer the pictures they reference.
+/// `totem_url` defaults to the address of a totem on its own access point.
+pub fn sync_records(
+    db: &AppDatabase,
+    totem_url: Option<String>,
+) -> anyhow::Result<RecordSyncSummary> {
+    // A copy, so the identity isn't locked while the totem is talked to
+    let identity = db
+        .with_identity(|identity| {
+            Ok(Identity::from_secret_bytes(&Zeroizing::new(
+                identity.secret_bytes(),
+            )))
+        })
+        .ok();
+    let client = TotemClient::new(totem_url.as_deref().unwrap_or(DEFAULT_TOTEM_URL));
+    let summary = RecordSync::new(db.database(), &client, identity.as_ref()).run()?;
+
+    Ok(RecordSyncSummary {
+        rotations_downloaded: summary.rotations_downloaded,
+        rotations_uploaded: summary.rotations_uploaded,
+        users_downloaded: summary.users_downloaded,
+        users_uploaded: summary.users_uploaded,
+        last_seen_downloaded: summary.last_seen_downloaded,
+        tombstones_downloaded: summary.tombstones_downloaded,
+        tombstones_uploaded: summary.tombstones_uploaded,
+        posts_downloaded: summary.posts_downloaded,
+        posts_uploaded: summary.posts_uploaded,
+        replies_downloaded: summary.replies_downloaded,
+        replies_uploaded: summary.replies_uploaded,
+        reposts_downloaded: summary.reposts_downloaded,
+        reposts_uploaded: summary.reposts_uploaded,
+        reactions_downloaded: summary.reactions_downloaded,
+        reactions_uploaded: summary.reactions_uploaded,
+        rejected: summary.rejected,
+    })
+}
@@ -0,0 +1,63 @@
+use flutter_rust_bridge::frb;
+use shared::model::entities::{Entities, Mention};
+
+use crate::api::simple::{AppDatabase, Post};
+use crate::media_sync::lock;
+
+/// The hashtags and mentions in the body of a post, see `shared::model::entities`
+#[derive(Debug, Clone)]
+pub struct PostEntities {
+    /// In lowercase, without `#`
+    pub tags: Vec<String>,
+    /// Usernames, in lowercase
+    pub handles: Vec<String>,
+    /// Key fingerprints of the users mentioned that way
+    pub fingerprints: Vec<String>,
+}
+
+impl From<Entities> for PostEntities {
+    fn from(entities: Entities) -> Self {
+        let mut handles = Vec::new();
+        let mut fingerprints = Vec::new();
+        for mention in entities.mentions {
+            match mention {
+                Mention::Handle(handle) => handles.push(handle),
+                Mention::Key(fingerprint) => fingerprints.push(fingerprint),
+            }
+        }
+        PostEntities {
+            tags: entities.tags,
+            handles,
+            fingerprints,
+        }
+    }
+}
+
+/// Read the hashtags and mentions from a body, as they will be indexed
+#[frb(sync)]
+pub fn parse_entities(body: String) -> PostEntities {
+    Entities::parse(&body).into()
+}
+
+pub fn get_post_entities(db: &AppDatabase, post_id: String) -> anyhow::Result<PostEntities> {
+    let post = lock(db.database())?.get_post_by_id(&post_id)?;
+    Ok(post.entities().into())
+}
+
+/// Posts tagged with `tag`, newest first
+/// The tag may be given as typed, with `#` or in another case.
+pub fn get_posts_with_tag(db: &AppDatabase, tag: String) -> anyhow::Result<Vec<Post>> {
+    let posts = lock(db.database())?.get_posts_with_tag(&tag)?;
+    Ok(posts.into_iter().map(Into::into).collect())
+}
+
+/// Posts mentioning this phone's user by username or key fingerprint, newest first
+///
+/// Needs the unlocked identity, see [`crate::api::identity::unlock_identity`].
+pub fn get_mentions(db: &AppDatabase) -> anyhow::Result<Vec<Post>> {
+    let database = lock(db.database())?;
+    let user_id = db.with_identity(|identity| Ok(identity.user_id()))?;
+    let user = database.get_user_by_id(&user_id)?;
+    let posts = database.get_posts_mentioning(&user)?;
+    Ok(posts.into_iter().map(Into::into).collect())
+}
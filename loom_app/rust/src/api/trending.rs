@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+
+use crate::api::simple::{AppDatabase, Post};
+use crate::media_sync::lock;
+use crate::totem::{TotemClient, DEFAULT_TOTEM_URL};
+
+/// What is talked about at a totem, see `shared::trending`
+#[derive(Debug, Clone)]
+pub struct TrendingTopics {
+    /// The totem that answered, empty for totems from before it was sent
+    pub totem_id: String,
+    /// Start of the counted time range
+    pub since: DateTime<Utc>,
+    /// Most used hashtags first
+    pub tags: Vec<TrendingTag>,
+    /// Threads with the most replies first
+    pub threads: Vec<TrendingThread>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrendingTag {
+    /// In lowercase, without `#`
+    pub tag: String,
+    /// Posts the totem received with it
+    pub posts: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrendingThread {
+    pub post_id: String,
+    /// Replies the totem received in the thread
+    pub replies: u32,
+    /// `None` until the post has been synced to this phone
+    pub post: Option<Post>,
+}
+
+/// Ask a totem what is talked about there lately
+/// `totem_url` defaults to the address of a totem on its own access point.
+pub fn get_trending(db: &AppDatabase, totem_url: Option<String>) -> anyhow::Result<TrendingTopics> {
+    let client = TotemClient::new(totem_url.as_deref().unwrap_or(DEFAULT_TOTEM_URL));
+    let trending = client.get_trending()?;
+
+    let database = lock(db.database())?;
+    let threads = trending
+        .threads
+        .into_iter()
+        .map(|thread| TrendingThread {
+            post: database
+                .get_post_by_id(&thread.post_id)
+                .ok()
+                .map(Into::into),
+            post_id: thread.post_id,
+            replies: thread.replies,
+        })
+        .collect();
+
+    Ok(TrendingTopics {
+        totem_id: trending.totem_id,
+        since: DateTime::parse_from_rfc3339(&trending.since)?.with_timezone(&Utc),
+        tags: trending
+            .tags
+            .into_iter()
+            .map(|tag| TrendingTag {
+                tag: tag.tag,
+                posts: tag.posts,
+            })
+            .collect(),
+        threads,
+    })
+}
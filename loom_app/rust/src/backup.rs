@@ -0,0 +1,301 @@
+//! Backups of the user's secret key, to take their identity to a new phone
+//!
+//! There are two forms. A mnemonic is the secret key as 24 words of the BIP-39 English
+//! wordlist, with a checksum that catches typos, for writing down on paper. A backup file
+//! holds the secret key encrypted with XChaCha20-Poly1305, under a key derived from a
+//! passphrase with Argon2id, so it can be kept in cloud storage.
+//!
+//! Backup file layout, all integers little endian:
+//!
+//! | Bytes | Content                                   |
+//! |-------|-------------------------------------------|
+//! | 8     | `LOOMKEY1`                                |
+//! | 12    | Argon2 memory in KiB, iterations, lanes   |
+//! | 16    | salt                                      |
+//! | 24    | nonce                                     |
+//! | 48    | encrypted secret key and tag              |
+//!
+//! The header is authenticated along with the key, so a file that was tampered with
+//! fails to decrypt like one with the wrong passphrase.
+
+use std::fmt;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use bip39::{Language, Mnemonic};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng, Payload};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305, XNonce};
+use shared::identity::{Identity, KEY_LEN};
+use zeroize::Zeroizing;
+
+/// Words of a mnemonic of a 32 byte key
+const WORD_COUNT: usize = 24;
+const MAGIC: &[u8; 8] = b"LOOMKEY1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 12 + SALT_LEN + NONCE_LEN;
+const FILE_LEN: usize = HEADER_LEN + KEY_LEN + TAG_LEN;
+
+/// Argon2id memory cost in KiB, phones have to be able to spare it
+const MEMORY_KIB: u32 = 64 * 1024;
+const ITERATIONS: u32 = 3;
+const LANES: u32 = 1;
+/// Files asking for more are refused, so a crafted file can't exhaust the phone's memory
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_LANES: u32 = 8;
+
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+#[derive(Debug)]
+pub enum BackupError {
+    /// The words aren't a mnemonic of a secret key, or a word is misspelt
+    InvalidMnemonic(bip39::Error),
+    /// Not a backup file, or one from a newer version of the app
+    InvalidFile,
+    /// The passphrase is wrong or the file was damaged
+    Decryption,
+    WeakPassphrase,
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::InvalidMnemonic(e) => write!(f, "Invalid recovery phrase: {}", e),
+            BackupError::InvalidFile => write!(f, "Not a Loom key backup"),
+            BackupError::Decryption => write!(f, "Wrong passphrase or damaged backup"),
+            BackupError::WeakPassphrase => {
+                write!(
+                    f,
+                    "Passphrase must be at least {} characters",
+                    MIN_PASSPHRASE_LEN
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+/// The secret key as 24 words
+pub fn to_mnemonic(identity: &Identity) -> Zeroizing<String> {
+    let secret = Zeroizing::new(identity.secret_bytes());
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, secret.as_slice())
+        .expect("32 bytes are valid entropy");
+    Zeroizing::new(mnemonic.to_string())
+}
+
+/// Restore an identity from the words of [`to_mnemonic`]
+/// Case and surrounding whitespace don't matter, people copy the words by hand.
+pub fn from_mnemonic(phrase: &str) -> Result<Identity, BackupError> {
+    let normalized = Zeroizing::new(
+        phrase
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase(),
+    );
+    let mnemonic = Mnemonic::parse_in(Language::English, normalized.as_str())
+        .map_err(BackupError::InvalidMnemonic)?;
+
+    // Shorter mnemonics are valid BIP-39, but don't hold a whole key
+    if mnemonic.word_count() != WORD_COUNT {
+        return Err(BackupError::InvalidMnemonic(bip39::Error::BadWordCount(
+            mnemonic.word_count(),
+        )));
+    }
+
+    let entropy = Zeroizing::new(mnemonic.to_entropy_array().0);
+    let mut secret = Zeroizing::new([0u8; KEY_LEN]);
+    secret.copy_from_slice(&entropy[..KEY_LEN]);
+    Ok(Identity::from_secret_bytes(&secret))
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    memory_kib: u32,
+    iterations: u32,
+    lanes: u32,
+) -> Result<Zeroizing<[u8; 32]>, BackupError> {
+    let params = Params::new(memory_kib, iterations, lanes, Some(32))
+        .map_err(|_| BackupError::InvalidFile)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut_slice())
+        .map_err(|_| BackupError::InvalidFile)?;
+    Ok(key)
+}
+
+/// The secret key encrypted under `passphrase`
+pub fn encrypt(identity: &Identity, passphrase: &str) -> Result<Vec<u8>, BackupError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(BackupError::WeakPassphrase);
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut file = Vec::with_capacity(FILE_LEN);
+    file.extend_from_slice(MAGIC);
+    for value in [MEMORY_KIB, ITERATIONS, LANES] {
+        file.extend_from_slice(&value.to_le_bytes());
+    }
+    file.extend_from_slice(&salt);
+    file.extend_from_slice(&nonce);
+
+    let key = derive_key(passphrase, &salt, MEMORY_KIB, ITERATIONS, LANES)?;
+    let secret = Zeroizing::new(identity.secret_bytes());
+    let sealed = XChaCha20Poly1305::new(key.as_slice().into())
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: secret.as_slice(),
+                aad: &file,
+            },
+        )
+        .expect("encrypting into a Vec can't fail");
+    file.extend_from_slice(&sealed);
+
+    Ok(file)
+}
+
+/// Restore an identity from a file of [`encrypt`]
+pub fn decrypt(file: &[u8], passphrase: &str) -> Result<Identity, BackupError> {
+    if file.len() != FILE_LEN || !file.starts_with(MAGIC) {
+        return Err(BackupError::InvalidFile);
+    }
+
+    let (header, sealed) = file.split_at(HEADER_LEN);
+    let param = |i: usize| {
+        let start = MAGIC.len() + i * 4;
+        u32::from_le_bytes(header[start..start + 4].try_into().expect("4 bytes"))
+    };
+    let (memory_kib, iterations, lanes) = (param(0), param(1), param(2));
+    if memory_kib > MAX_MEMORY_KIB || iterations > MAX_ITERATIONS || lanes > MAX_LANES {
+        return Err(BackupError::InvalidFile);
+    }
+    let salt = &header[MAGIC.len() + 12..MAGIC.len() + 12 + SALT_LEN];
+    let nonce = XNonce::from_slice(&header[HEADER_LEN - NONCE_LEN..]);
+
+    let key = derive_key(passphrase, salt, memory_kib, iterations, lanes)?;
+    let secret = Zeroizing::new(
+        XChaCha20Poly1305::new(key.as_slice().into())
+            .decrypt(
+                nonce,
+                Payload {
+                    msg: sealed,
+                    aad: header,
+                },
+            )
+            .map_err(|_| BackupError::Decryption)?,
+    );
+    let secret: Zeroizing<[u8; KEY_LEN]> = Zeroizing::new(
+        secret
+            .as_slice()
+            .try_into()
+            .map_err(|_| BackupError::InvalidFile)?,
+    );
+
+    Ok(Identity::from_secret_bytes(&secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mnemonic() {
+        let identity = Identity::generate();
+        let phrase = to_mnemonic(&identity);
+        assert_eq!(phrase.split(' ').count(), WORD_COUNT);
+        assert_eq!(
+            from_mnemonic(&phrase).unwrap().user_id(),
+            identity.user_id()
+        );
+
+        // Copied by hand
+        let sloppy = format!("  {}\n", phrase.to_uppercase().replace(' ', "   "));
+        assert_eq!(
+            from_mnemonic(&sloppy).unwrap().user_id(),
+            identity.user_id()
+        );
+
+        // A misspelt word isn't in the wordlist
+        let mut words: Vec<_> = phrase.split(' ').collect();
+        words[0] = "loomx";
+        assert!(matches!(
+            from_mnemonic(&words.join(" ")),
+            Err(BackupError::InvalidMnemonic(_))
+        ));
+
+        // Valid BIP-39, but too short for a key
+        let short = Mnemonic::from_entropy_in(Language::English, &[7u8; 16])
+            .unwrap()
+            .to_string();
+        assert!(matches!(
+            from_mnemonic(&short),
+            Err(BackupError::InvalidMnemonic(_))
+        ));
+    }
+
+    #[test]
+    fn test_file() {
+        let identity = Identity::generate();
+        assert!(matches!(
+            encrypt(&identity, "short"),
+            Err(BackupError::WeakPassphrase)
+        ));
+
+        let file = encrypt(&identity, "correct horse").unwrap();
+        assert_eq!(file.len(), FILE_LEN);
+        assert_eq!(
+            decrypt(&file, "correct horse").unwrap().user_id(),
+            identity.user_id()
+        );
+        assert!(matches!(
+            decrypt(&file, "wrong horse"),
+            Err(BackupError::Decryption)
+        ));
+
+        // The header is authenticated
+        let mut tampered = file.clone();
+        tampered[HEADER_LEN - 1] ^= 1;
+        assert!(matches!(
+            decrypt(&tampered, "correct horse"),
+            Err(BackupError::Decryption)
+        ));
+
+        assert!(matches!(
+            decrypt(&file[1..], "correct horse"),
+            Err(BackupError::InvalidFile)
+        ));
+        let mut wrong_magic = file.clone();
+        wrong_magic[7] = b'9';
+        assert!(matches!(
+            decrypt(&wrong_magic, "correct horse"),
+            Err(BackupError::InvalidFile)
+        ));
+    }
+
+    #[test]
+    fn test_refuses_expensive_files() {
+        let identity = Identity::generate();
+        let file = encrypt(&identity, "correct horse").unwrap();
+
+        for (i, value) in [MAX_MEMORY_KIB + 1, MAX_ITERATIONS + 1, MAX_LANES + 1]
+            .into_iter()
+            .enumerate()
+        {
+            let mut crafted = file.clone();
+            let start = MAGIC.len() + i * 4;
+            crafted[start..start + 4].copy_from_slice(&value.to_le_bytes());
+            assert!(matches!(
+                decrypt(&crafted, "correct horse"),
+                Err(BackupError::InvalidFile)
+            ));
+        }
+    }
+}
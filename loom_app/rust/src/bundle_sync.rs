@@ -0,0 +1,225 @@
+//! Exchange of bundles with a totem, see `shared::routing`
+//!
+//! The phone offers the bundles it carries and takes the ones the totem hands out: those
+//! addressed to its user are delivered right away, the others are carried if the phone is
+//! a courier. Then it hands the totem its share of the bundles the totem asked for. Copies
+//! the totem refused stay with the phone.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use shared::api::BundlesOfferRequest;
+use shared::db::Database;
+use shared::identity::Identity;
+use shared::model::{Bundle, BundlePayload, DeliveryReceipt};
+use shared::routing::{Node, MAX_BUNDLE_BODY};
+
+use crate::media_sync::lock;
+use crate::messaging;
+use crate::totem::TotemClient;
+
+/// Room left in a delivery for the JSON around the bundles
+const DELIVER_OVERHEAD: usize = 256;
+
+/// Max number of bundles a phone carries for others
+/// A full phone stops taking bundles until it handed some over.
+const MAX_CARRIED_BUNDLES: usize = 500;
+
+/// Counts of a finished sync
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Summary {
+    /// Bundles addressed to this phone's user
+    pub delivered: u32,
+    /// Bundles taken to be carried for others
+    pub taken: u32,
+    /// Bundles handed over to the totem
+    pub handed_over: u32,
+    /// Bundles the totem didn't take, they are kept for the next one
+    pub refused: u32,
+    /// Bundles that failed validation, or whose content did
+    pub rejected: u32,
+    /// Carried bundles that expired or were used up
+    pub dropped: u32,
+}
+
+pub struct BundleSync<'a> {
+    db: &'a Mutex<Database>,
+    client: &'a TotemClient,
+    /// `None` while the identity is locked, bundles addressed to the user wait then
+    identity: Option<&'a Identity>,
+    courier: bool,
+    summary: Summary,
+}
+
+impl<'a> BundleSync<'a> {
+    pub fn new(
+        db: &'a Mutex<Database>,
+        client: &'a TotemClient,
+        identity: Option<&'a Identity>,
+        courier: bool,
+    ) -> Self {
+        BundleSync {
+            db,
+            client,
+            identity,
+            courier,
+            summary: Summary::default(),
+        }
+    }
+
+    /// Take the bundles the totem hands out, then hand over the ones it wants
+    pub fn run(mut self) -> anyhow::Result<Summary> {
+        let now = Utc::now();
+        let carried = self.drop_spent(now)?;
+        let user_id = self.identity.map(Identity::user_id);
+
+        let offer = self.client.offer_bundles(&BundlesOfferRequest {
+            user_id: user_id.clone(),
+            courier: self.courier && carried.len() < MAX_CARRIED_BUNDLES,
+            carried,
+        })?;
+
+        self.take(offer.bundles, user_id.as_deref(), now)?;
+        self.hand_over(&offer.totem_id, offer.wanted, now)?;
+        Ok(self.summary)
+    }
+
+    /// Drop the bundles nobody needs anymore, returns the UUIDs of the others
+    fn drop_spent(&mut self, now: DateTime<Utc>) -> anyhow::Result<Vec<String>> {
+        let db = lock(self.db)?;
+        let mut carried = Vec::new();
+        for bundle in db.get_bundles()? {
+            if bundle.is_spent(now) {
+                db.delete_bundle(&bundle.uuid)?;
+                self.summary.dropped += 1;
+            } else {
+                carried.push(bundle.uuid);
+            }
+        }
+        Ok(carried)
+    }
+
+    fn take(
+        &mut self,
+        bundles: Vec<Bundle>,
+        user_id: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let db = lock(self.db)?;
+        for bundle in bundles {
+            if bundle.validate_at(now).is_err() {
+                self.summary.rejected += 1;
+                continue;
+            }
+
+            let phone = Node::Phone {
+                user: user_id,
+                courier: self.courier,
+            };
+            if user_id.is_some() && bundle.header.is_for(phone) {
+                if self.deliver(&db, &bundle.payload)? {
+                    self.summary.delivered += 1;
+                } else {
+                    self.summary.rejected += 1;
+                }
+            } else if self.courier {
+                db.store_bundle(&bundle)?;
+                self.summary.taken += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store the content of a bundle addressed to this phone's user
+    /// Returns false if it isn't valid.
+    fn deliver(&self, db: &Database, payload: &BundlePayload) -> anyhow::Result<bool> {
+        let Some(identity) = self.identity else {
+            return Ok(false);
+        };
+
+        match payload {
+            BundlePayload::Message(message) => {
+                // Only messages that decrypt are from someone who knows the key they claim
+                let addressed = message.recipient_id == identity.user_id()
+                    && message.recipient_key == identity.public_key().to_string();
+                if !addressed
+                    || message.validate().is_err()
+                    || messaging::open(identity, message).is_err()
+                {
+                    return Ok(false);
+                }
+                db.create_message(message)?;
+                db.create_delivery_receipt(&DeliveryReceipt::new(message, identity, Utc::now()))?;
+            }
+            BundlePayload::Post(post) => {
+                let (author, rotations) = db.get_signer(&post.user_id)?;
+                if post.validate().is_err()
+                    || post.check_signature(author.as_ref(), &rotations).is_err()
+                {
+                    return Ok(false);
+                }
+                db.create_post(post);
+            }
+        }
+        Ok(true)
+    }
+
+    fn hand_over(
+        &mut self,
+        totem_id: &str,
+        wanted: Vec<String>,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        // The copies handed out, with the number of copies left to the phone
+        let mut batch: Vec<(Bundle, u32)> = Vec::new();
+        let mut size = DELIVER_OVERHEAD;
+        for uuid in wanted {
+            let Some(mut bundle) = lock(self.db)?.get_bundle(&uuid)? else {
+                continue;
+            };
+            let Some(handed) = bundle.hand_to(Node::Totem(totem_id), now) else {
+                continue;
+            };
+
+            let len = serde_json::to_vec(&handed)?.len() + 1;
+            if size + len > MAX_BUNDLE_BODY && !batch.is_empty() {
+                self.send(std::mem::take(&mut batch))?;
+                size = DELIVER_OVERHEAD;
+            }
+            size += len;
+            batch.push((handed, bundle.header.copies));
+        }
+        if !batch.is_empty() {
+            self.send(batch)?;
+        }
+
+        Ok(())
+    }
+
+    fn send(&mut self, batch: Vec<(Bundle, u32)>) -> anyhow::Result<()> {
+        let left: HashMap<String, u32> = batch
+            .iter()
+            .map(|(bundle, copies)| (bundle.uuid.clone(), *copies))
+            .collect();
+        let response = self
+            .client
+            .deliver_bundles(batch.into_iter().map(|(bundle, _)| bundle).collect())?;
+
+        // Only the copies the totem took are given up
+        let db = lock(self.db)?;
+        for uuid in response.accepted {
+            let Some(&copies) = left.get(&uuid) else {
+                continue;
+            };
+            if copies == 0 {
+                db.delete_bundle(&uuid)?;
+            } else {
+                db.set_bundle_copies(&uuid, copies)?;
+            }
+            self.summary.handed_over += 1;
+        }
+        self.summary.refused += response.refused.len() as u32;
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! Storage of the user's secret key on the phone
+//!
+//! The key lives in a file in the app's private directory, which other apps can't read
+//! on Android and iOS. On top of that the file is only readable by its owner where the
+//! platform has Unix permissions. It is written to a temporary file first and then
+//! renamed, so a crash can't leave a truncated key behind.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use shared::identity::{Identity, KEY_LEN};
+
+const KEY_FILE: &str = "identity.key";
+
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// `dir` should be private to the app, like the application support directory
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        KeyStore {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(KEY_FILE)
+    }
+
+    /// The identity of this phone's user, if one was created
+    pub fn load(&self) -> io::Result<Option<Identity>> {
+        let mut file = match File::open(self.path()) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut secret = [0u8; KEY_LEN];
+        file.read_exact(&mut secret)?;
+        Ok(Some(Identity::from_secret_bytes(&secret)))
+    }
+
+    /// Store a new identity, an existing one is never replaced
+    pub fn save(&self, identity: &Identity) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path();
+        if path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "An identity exists already",
+            ));
+        }
+
+        let tmp_path = self.dir.join(format!("{KEY_FILE}.tmp"));
+        let mut file = private_file(&tmp_path)?;
+        file.write_all(&identity.secret_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        fs::rename(&tmp_path, &path)
+    }
+
+    /// Store an identity brought over from another device
+    /// Installing the identity that is stored already is fine, replacing another one isn't.
+    pub fn install(&self, identity: &Identity) -> io::Result<()> {
+        match self.load()? {
+            Some(existing) if existing.public_key() == identity.public_key() => Ok(()),
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "This phone has a different identity already",
+            )),
+            None => self.save(identity),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn private_file(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn private_file(path: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+}
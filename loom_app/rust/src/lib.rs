@@ -1,2 +1,12 @@
 pub mod api;
+mod backup;
+mod bundle_sync;
 mod frb_generated;
+mod keystore;
+mod link;
+mod mailbox_sync;
+mod media_sync;
+mod messaging;
+mod record_sync;
+mod totem;
+mod transcode;
@@ -0,0 +1,498 @@
+//! Linking another device to the identity of this one
+//!
+//! The device that has the identity shows a QR code with a one-time pairing secret and,
+//! if it is reachable over the local network, its address. The new device scans it. Both
+//! derive an encryption key and an access token from the secret with HKDF, so the secret
+//! itself never goes over the network.
+//!
+//! The existing device seals a payload of the secret key, optionally followed by a copy of
+//! its database, with XChaCha20-Poly1305. The new device gets it over TCP or as a file.
+//! Over TCP it first sends the access token, connections without it are dropped, so a
+//! neighbour on the network can't take the one transfer that is offered.
+//!
+//! TCP transfer, after connecting:
+//!
+//! 1. The new device sends the 32 byte access token.
+//! 2. The existing device answers with the length of the sealed payload as a little
+//!    endian `u32`, followed by the payload, and closes the connection.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use shared::db::Database;
+use shared::identity::{is_derived_user_id, Identity, KEY_LEN};
+use zeroize::Zeroizing;
+
+use crate::media_sync::lock;
+
+const QR_PREFIX: &str = "loom-link:";
+const SECRET_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const KEY_INFO: &[u8] = b"loom link v1 key";
+const TOKEN_INFO: &[u8] = b"loom link v1 token";
+
+/// Databases are small, anything bigger is refused before it is read into memory
+pub const MAX_PAYLOAD_LEN: usize = 256 * 1024 * 1024;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+/// Connections that don't send a token right away are dropped, they hold up the others
+const TOKEN_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub enum LinkError {
+    /// The QR code isn't one of a Loom device
+    InvalidCode,
+    /// The QR code has no address, the transfer has to go through a file
+    NoAddress,
+    /// The payload doesn't belong to the QR code or was damaged
+    Decryption,
+    TooLarge,
+    TimedOut,
+    Cancelled,
+    Io(io::Error),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::InvalidCode => write!(f, "Not a Loom pairing code"),
+            LinkError::NoAddress => write!(f, "The other device isn't reachable, use a file"),
+            LinkError::Decryption => write!(f, "The transfer doesn't match the pairing code"),
+            LinkError::TooLarge => write!(f, "The transfer is too large"),
+            LinkError::TimedOut => write!(f, "No device connected in time"),
+            LinkError::Cancelled => write!(f, "Linking was cancelled"),
+            LinkError::Io(e) => write!(f, "Network error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+impl From<io::Error> for LinkError {
+    fn from(e: io::Error) -> Self {
+        LinkError::Io(e)
+    }
+}
+
+/// What the existing device hands over
+pub struct Payload {
+    pub identity: Identity,
+    /// A snapshot of the existing device's database, see `shared::db::Database::snapshot`
+    pub database: Option<Vec<u8>>,
+}
+
+/// The contents of the QR code
+pub struct Invitation {
+    secret: Zeroizing<[u8; SECRET_LEN]>,
+    pub address: Option<SocketAddr>,
+}
+
+impl Invitation {
+    /// A new invitation with a fresh secret, to be shown once
+    pub fn new(address: Option<SocketAddr>) -> Self {
+        let mut secret = Zeroizing::new([0u8; SECRET_LEN]);
+        OsRng.fill_bytes(secret.as_mut_slice());
+        Invitation { secret, address }
+    }
+
+    /// `loom-link:<secret as hex>`, followed by `@<address>` if there is one
+    pub fn to_qr(&self) -> String {
+        let mut qr = String::from(QR_PREFIX);
+        for b in self.secret.iter() {
+            qr.push_str(&format!("{b:02x}"));
+        }
+        if let Some(address) = self.address {
+            qr.push_str(&format!("@{}", address));
+        }
+        qr
+    }
+
+    pub fn parse(qr: &str) -> Result<Self, LinkError> {
+        let rest = qr
+            .trim()
+            .strip_prefix(QR_PREFIX)
+            .ok_or(LinkError::InvalidCode)?;
+        let (hex, address) = match rest.split_once('@') {
+            Some((hex, address)) => (
+                hex,
+                Some(address.parse().map_err(|_| LinkError::InvalidCode)?),
+            ),
+            None => (rest, None),
+        };
+
+        if hex.len() != SECRET_LEN * 2 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(LinkError::InvalidCode);
+        }
+        let mut secret = Zeroizing::new([0u8; SECRET_LEN]);
+        for (i, byte) in secret.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| LinkError::InvalidCode)?;
+        }
+
+        Ok(Invitation { secret, address })
+    }
+
+    fn derive(&self, info: &[u8]) -> Zeroizing<[u8; 32]> {
+        let mut output = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha256>::new(None, self.secret.as_slice())
+            .expand(info, output.as_mut_slice())
+            .expect("32 bytes is a valid output length");
+        output
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(self.derive(KEY_INFO).as_slice().into())
+    }
+
+    /// Encrypt a payload for the device that scans this invitation
+    pub fn seal(&self, payload: &Payload) -> Vec<u8> {
+        let mut plaintext = Zeroizing::new(payload.identity.secret_bytes().to_vec());
+        if let Some(database) = &payload.database {
+            plaintext.extend_from_slice(database);
+        }
+
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut sealed = nonce.to_vec();
+        sealed.extend(
+            self.cipher()
+                .encrypt(&nonce, plaintext.as_slice())
+                .expect("encrypting into a Vec can't fail"),
+        );
+        sealed
+    }
+
+    pub fn open(&self, sealed: &[u8]) -> Result<Payload, LinkError> {
+        if sealed.len() < NONCE_LEN {
+            return Err(LinkError::Decryption);
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let plaintext = Zeroizing::new(
+            self.cipher()
+                .decrypt(XNonce::from_slice(nonce), ciphertext)
+                .map_err(|_| LinkError::Decryption)?,
+        );
+        if plaintext.len() < KEY_LEN {
+            return Err(LinkError::Decryption);
+        }
+
+        let (secret, database) = plaintext.split_at(KEY_LEN);
+        let secret: Zeroizing<[u8; KEY_LEN]> =
+            Zeroizing::new(secret.try_into().expect("split at the key length"));
+        Ok(Payload {
+            identity: Identity::from_secret_bytes(&secret),
+            database: (!database.is_empty()).then(|| database.to_vec()),
+        })
+    }
+
+    fn token(&self) -> Zeroizing<[u8; 32]> {
+        self.derive(TOKEN_INFO)
+    }
+
+    /// Whether `token` was derived from this invitation, in constant time
+    fn accepts(&self, token: &[u8; 32]) -> bool {
+        self.token()
+            .iter()
+            .zip(token)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+}
+
+/// Hand `sealed` to the first device that connects with the token of `invitation`
+/// Gives up at `deadline` or once `cancelled` is set.
+pub fn serve(
+    listener: &TcpListener,
+    invitation: &Invitation,
+    sealed: &[u8],
+    deadline: Instant,
+    cancelled: &AtomicBool,
+) -> Result<(), LinkError> {
+    listener.set_nonblocking(true)?;
+
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(LinkError::Cancelled);
+        }
+        if Instant::now() >= deadline {
+            return Err(LinkError::TimedOut);
+        }
+
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Anyone on the network can connect, only the scanning device gets the payload
+        match send(stream, invitation, sealed) {
+            Ok(true) => return Ok(()),
+            Ok(false) | Err(_) => continue,
+        }
+    }
+}
+
+fn send(mut stream: TcpStream, invitation: &Invitation, sealed: &[u8]) -> io::Result<bool> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(TOKEN_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let mut token = [0u8; 32];
+    stream.read_exact(&mut token)?;
+    if !invitation.accepts(&token) {
+        return Ok(false);
+    }
+
+    let len = u32::try_from(sealed.len()).map_err(|_| io::Error::other("payload too large"))?;
+    stream.write_all(&len.to_le_bytes())?;
+    stream.write_all(sealed)?;
+    stream.flush()?;
+    Ok(true)
+}
+
+/// Fetch the sealed payload from the device that showed `invitation`
+pub fn fetch(invitation: &Invitation) -> Result<Vec<u8>, LinkError> {
+    let address = invitation.address.ok_or(LinkError::NoAddress)?;
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    stream.write_all(invitation.token().as_slice())?;
+
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_PAYLOAD_LEN {
+        return Err(LinkError::TooLarge);
+    }
+
+    let mut sealed = vec![0u8; len];
+    stream.read_exact(&mut sealed)?;
+    Ok(sealed)
+}
+
+/// Counts of a database brought over from another device
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Imported {
+    pub rotations: u32,
+    pub users: u32,
+    pub posts: u32,
+    /// Records that failed validation or had a forged signature
+    pub rejected: u32,
+}
+
+/// Add the records of another device's database that are missing in `db`
+///
+/// They are checked like records from a totem, the other device could run an altered app.
+/// Media files stay behind, they come from the totems with the next media sync.
+pub fn import(db: &Mutex<Database>, other: &Database) -> anyhow::Result<Imported> {
+    let mut imported = Imported::default();
+    let db = lock(db)?;
+
+    // In the order of the chain, so each is checked against the ones before it
+    for rotation in other.get_all_key_rotations()? {
+        let known = db.get_key_rotations(&rotation.user_id)?;
+        if known.iter().any(|r| r.uuid == rotation.uuid) {
+            continue;
+        }
+        if rotation.validate().is_err() || rotation.check_chain(&known).is_err() {
+            imported.rejected += 1;
+            continue;
+        }
+        db.create_key_rotation(&rotation)?;
+        imported.rotations += 1;
+    }
+
+    let known_users: HashSet<String> = db.get_all_user_ids().into_iter().collect();
+    for user in other.get_all_users()? {
+        if known_users.contains(&user.uuid) {
+            continue;
+        }
+        if user.validate().is_err()
+            || user
+                .check_signature(&db.get_key_rotations(&user.uuid)?)
+                .is_err()
+        {
+            imported.rejected += 1;
+            continue;
+        }
+        db.create_user(&user);
+        imported.users += 1;
+    }
+
+    // The random IDs its user moved from, so their old posts lead to them here too
+    for (legacy_id, user_id) in other.get_user_links()? {
+        if is_derived_user_id(&user_id) && !is_derived_user_id(&legacy_id) {
+            db.link_user(&legacy_id, &user_id)?;
+        }
+    }
+
+    let known_posts: HashSet<String> = db.get_all_post_ids().into_iter().collect();
+    for post in other.get_all_posts()? {
+        if known_posts.contains(&post.uuid) {
+            continue;
+        }
+        let (author, rotations) = db.get_signer(&post.user_id)?;
+        if post.validate().is_err() || post.check_signature(author.as_ref(), &rotations).is_err() {
+            imported.rejected += 1;
+            continue;
+        }
+        db.create_post(&post);
+        imported.posts += 1;
+    }
+
+    for totem in other.get_all_totems()? {
+        db.create_totem(&totem);
+    }
+    db.merge_last_seen(&other.get_all_last_seen()?)?;
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr() {
+        let address: SocketAddr = "192.168.4.2:7070".parse().unwrap();
+        let invitation = Invitation::new(Some(address));
+        let qr = invitation.to_qr();
+        assert!(qr.starts_with(QR_PREFIX));
+
+        let parsed = Invitation::parse(&format!(" {qr}\n")).unwrap();
+        assert_eq!(parsed.address, Some(address));
+        assert_eq!(*parsed.secret, *invitation.secret);
+        assert!(Invitation::parse(qr.split('@').next().unwrap())
+            .unwrap()
+            .address
+            .is_none());
+
+        for invalid in [
+            "",
+            "loom-link:",
+            &qr[QR_PREFIX.len()..],
+            &qr[..qr.find('@').unwrap() - 1],
+            &qr.replace('@', "@nowhere"),
+            &format!("{QR_PREFIX}{}", "zz".repeat(SECRET_LEN)),
+        ] {
+            assert!(
+                matches!(Invitation::parse(invalid), Err(LinkError::InvalidCode)),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_seal() {
+        let invitation = Invitation::new(None);
+        let identity = Identity::generate();
+
+        let sealed = invitation.seal(&Payload {
+            identity: Identity::from_secret_bytes(&identity.secret_bytes()),
+            database: None,
+        });
+        let opened = invitation.open(&sealed).unwrap();
+        assert_eq!(opened.identity.user_id(), identity.user_id());
+        assert!(opened.database.is_none());
+
+        let sealed = invitation.seal(&Payload {
+            identity: Identity::from_secret_bytes(&identity.secret_bytes()),
+            database: Some(b"snapshot".to_vec()),
+        });
+        let opened = invitation.open(&sealed).unwrap();
+        assert_eq!(opened.identity.user_id(), identity.user_id());
+        assert_eq!(opened.database.as_deref(), Some(&b"snapshot"[..]));
+
+        // Only for the device that scanned this invitation, and only as it was sent
+        assert!(matches!(
+            Invitation::new(None).open(&sealed),
+            Err(LinkError::Decryption)
+        ));
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            invitation.open(&tampered),
+            Err(LinkError::Decryption)
+        ));
+        assert!(matches!(
+            invitation.open(&sealed[..NONCE_LEN - 1]),
+            Err(LinkError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn test_token() {
+        let invitation = Invitation::new(None);
+        assert!(invitation.accepts(&invitation.token()));
+        assert!(!invitation.accepts(&Invitation::new(None).token()));
+        assert!(!invitation.accepts(&[0u8; 32]));
+        // The token doesn't give away the key
+        assert_ne!(*invitation.token(), *invitation.derive(KEY_INFO));
+    }
+
+    #[test]
+    fn test_transfer() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let invitation = Invitation::new(Some(address));
+        let qr = invitation.to_qr();
+        let sealed = invitation.seal(&Payload {
+            identity: Identity::generate(),
+            database: None,
+        });
+
+        let cancelled = AtomicBool::new(false);
+        let served = thread::scope(|scope| {
+            let server = scope.spawn(|| {
+                serve(
+                    &listener,
+                    &invitation,
+                    &sealed,
+                    Instant::now() + Duration::from_secs(30),
+                    &cancelled,
+                )
+            });
+
+            // A neighbour without the token is dropped and the transfer stays on offer
+            assert!(fetch(&Invitation::new(Some(address))).is_err());
+
+            let fetched = fetch(&Invitation::parse(&qr).unwrap()).unwrap();
+            assert_eq!(fetched, sealed);
+            server.join().unwrap()
+        });
+        assert!(served.is_ok());
+
+        let later = Instant::now() + Duration::from_secs(30);
+        let result = serve(
+            &listener,
+            &invitation,
+            &sealed,
+            later,
+            &AtomicBool::new(true),
+        );
+        assert!(matches!(result, Err(LinkError::Cancelled)));
+        let result = serve(
+            &listener,
+            &invitation,
+            &sealed,
+            Instant::now(),
+            &AtomicBool::new(false),
+        );
+        assert!(matches!(result, Err(LinkError::TimedOut)));
+    }
+}
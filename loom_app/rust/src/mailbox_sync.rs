@@ -0,0 +1,194 @@
+//! Exchange of direct messages through the mailboxes of a totem, see `shared::mailbox`
+//!
+//! The phone first collects the messages waiting for its user and signs a receipt for
+//! every one it can decrypt. Then it deposits the messages its user sent that weren't
+//! delivered yet, along with the receipts it knows, so the totem drops delivered copies.
+//! The receipts the totem hands out are kept and passed on to the next totem.
+
+use std::sync::Mutex;
+
+use chrono::Utc;
+use shared::api::{MailboxCollectRequest, MailboxDepositRequest};
+use shared::db::Database;
+use shared::identity::Identity;
+use shared::mailbox::{Refusal, MAX_MAILBOX_BODY, MESSAGE_TTL};
+use shared::model::message::mailbox_of;
+use shared::model::{DeliveryReceipt, Message};
+
+use crate::media_sync::lock;
+use crate::messaging;
+use crate::totem::TotemClient;
+
+/// Room left in a deposit for the JSON around the records
+const DEPOSIT_OVERHEAD: usize = 256;
+
+/// Counts of a finished sync
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Summary {
+    pub received: u32,
+    pub sent: u32,
+    /// Messages of this phone's user that turned out to be delivered
+    pub delivered: u32,
+    /// Messages the totem didn't take, because its mailbox for the recipient is full
+    pub refused: u32,
+    /// Messages and receipts that failed validation or decryption
+    pub rejected: u32,
+}
+
+pub struct MailboxSync<'a> {
+    db: &'a Mutex<Database>,
+    client: &'a TotemClient,
+    identity: &'a Identity,
+    summary: Summary,
+}
+
+impl<'a> MailboxSync<'a> {
+    pub fn new(db: &'a Mutex<Database>, client: &'a TotemClient, identity: &'a Identity) -> Self {
+        MailboxSync {
+            db,
+            client,
+            identity,
+            summary: Summary::default(),
+        }
+    }
+
+    /// Collect the messages of this phone's user, then deposit the ones they sent
+    pub fn run(mut self) -> anyhow::Result<Summary> {
+        self.collect()?;
+        self.deposit()?;
+        Ok(self.summary)
+    }
+
+    fn collect(&mut self) -> anyhow::Result<()> {
+        let own_id = self.identity.user_id();
+        let own_key = self.identity.public_key().to_string();
+        let (mut known, pending): (Vec<String>, Vec<String>) = {
+            let db = lock(self.db)?;
+            let known = db
+                .get_messages_of(&own_id)?
+                .into_iter()
+                .filter(|m| m.recipient_key == own_key)
+                .map(|m| m.uuid)
+                .collect();
+            let pending = undelivered(&db, &own_id)?
+                .into_iter()
+                .map(|m| m.uuid)
+                .collect();
+            (known, pending)
+        };
+
+        loop {
+            let response = self.client.collect_mail(&MailboxCollectRequest {
+                mailbox: mailbox_of(&own_key),
+                known: known.clone(),
+                pending: pending.clone(),
+            })?;
+
+            let db = lock(self.db)?;
+            for message in &response.messages {
+                known.push(message.uuid.clone());
+                // Only messages that decrypt are from someone who knows the key they claim
+                let addressed = message.recipient_id == own_id && message.recipient_key == own_key;
+                if !addressed
+                    || message.validate().is_err()
+                    || messaging::open(self.identity, message).is_err()
+                {
+                    self.summary.rejected += 1;
+                    continue;
+                }
+                db.create_message(message)?;
+                db.create_delivery_receipt(&DeliveryReceipt::new(
+                    message,
+                    self.identity,
+                    Utc::now(),
+                ))?;
+                self.summary.received += 1;
+            }
+
+            for receipt in &response.receipts {
+                if receipt.validate().is_err() || receipt.check_signature().is_err() {
+                    self.summary.rejected += 1;
+                    continue;
+                }
+                db.create_delivery_receipt(receipt)?;
+            }
+
+            if !response.more || response.messages.is_empty() {
+                break;
+            }
+        }
+
+        let still_pending = undelivered(&*lock(self.db)?, &own_id)?.len();
+        self.summary.delivered += pending.len().saturating_sub(still_pending) as u32;
+        Ok(())
+    }
+
+    fn deposit(&mut self) -> anyhow::Result<()> {
+        let (messages, receipts) = {
+            let db = lock(self.db)?;
+            let messages = undelivered(&db, &self.identity.user_id())?;
+            (
+                messages,
+                db.get_delivery_receipts_since(Utc::now() - MESSAGE_TTL)?,
+            )
+        };
+
+        // Receipts go first, so the totem drops delivered messages before taking new ones
+        let mut request = MailboxDepositRequest::default();
+        let mut size = DEPOSIT_OVERHEAD;
+        for receipt in receipts {
+            let len = serde_json::to_vec(&receipt)?.len() + 1;
+            if size + len > MAX_MAILBOX_BODY {
+                self.send(std::mem::take(&mut request))?;
+                size = DEPOSIT_OVERHEAD;
+            }
+            size += len;
+            request.receipts.push(receipt);
+        }
+        for message in messages {
+            let len = serde_json::to_vec(&message)?.len() + 1;
+            if size + len > MAX_MAILBOX_BODY
+                && !(request.messages.is_empty() && request.receipts.is_empty())
+            {
+                self.send(std::mem::take(&mut request))?;
+                size = DEPOSIT_OVERHEAD;
+            }
+            size += len;
+            request.messages.push(message);
+        }
+        if !(request.messages.is_empty() && request.receipts.is_empty()) {
+            self.send(request)?;
+        }
+
+        Ok(())
+    }
+
+    fn send(&mut self, request: MailboxDepositRequest) -> anyhow::Result<()> {
+        let response = self.client.deposit_mail(&request)?;
+        self.summary.sent += response.stored.len() as u32;
+        for refused in response.refused {
+            match refused.reason {
+                Refusal::MailboxFull => self.summary.refused += 1,
+                // Delivered through another totem, the receipt will come along
+                Refusal::Delivered | Refusal::Expired => {}
+                Refusal::Invalid | Refusal::Unknown => self.summary.rejected += 1,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Messages sent by the user that have no receipt yet and didn't expire
+fn undelivered(db: &Database, user_id: &str) -> anyhow::Result<Vec<Message>> {
+    let expired_before = Utc::now() - MESSAGE_TTL;
+    let mut undelivered = Vec::new();
+    for message in db.get_messages_of(user_id)? {
+        if message.sender_id == user_id
+            && message.timestamp >= expired_before
+            && db.get_delivery_receipt(&message)?.is_none()
+        {
+            undelivered.push(message);
+        }
+    }
+    Ok(undelivered)
+}
@@ -0,0 +1,335 @@
+//! Synchronisation of the blobs referenced by posts and users
+//!
+//! Posts and users only carry content hashes, the blobs behind them are transferred
+//! separately once the records have been reconciled. The totem tells us which of the
+//! blobs it is missing and which of ours it has, then they are transferred one piece
+//! at a time. Both directions resume where an earlier, interrupted sync stopped.
+//!
+//! Downloads go through a [`MediaStore`] on the phone, which verifies the hash before
+//! the blob is recorded in the app database.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::anyhow;
+use shared::api::ErrorCode;
+use shared::db::Database;
+use shared::media::{ContentHash, MediaStore, Upload};
+
+use crate::totem::{TotemClient, TotemError};
+
+/// Size of the pieces blobs are transferred in
+/// Small enough that little is lost when the phone leaves the totem's range.
+pub const PIECE_LEN: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Upload,
+    Download,
+}
+
+/// Progress of a single transfer, reported after every piece
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub hash: ContentHash,
+    pub direction: Direction,
+    pub transferred: u64,
+    /// Size of the blob, 0 until it is known
+    pub total: u64,
+    /// The transfer is over, successfully unless `error` is set
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+/// Counts of a finished sync
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Summary {
+    pub uploaded: u32,
+    pub downloaded: u32,
+    pub failed: u32,
+}
+
+enum TransferState {
+    /// Nothing has been transferred in this sync yet
+    Pending,
+    Uploading {
+        file: File,
+        offset: u64,
+        total: u64,
+    },
+    Downloading {
+        upload: Box<Upload>,
+    },
+}
+
+struct Transfer {
+    hash: ContentHash,
+    direction: Direction,
+    state: TransferState,
+}
+
+impl Transfer {
+    fn progress(&self, transferred: u64, total: u64, done: bool) -> Progress {
+        Progress {
+            hash: self.hash,
+            direction: self.direction,
+            transferred,
+            total,
+            done,
+            error: None,
+        }
+    }
+
+    /// Keep what has been downloaded, so the next sync can resume
+    fn suspend(self) {
+        if let TransferState::Downloading { upload, .. } = self.state {
+            upload.suspend().ok();
+        }
+    }
+}
+
+/// A media sync with a single totem
+pub struct MediaSync<'a> {
+    db: &'a Mutex<Database>,
+    client: TotemClient,
+    store: MediaStore,
+    queue: VecDeque<Transfer>,
+    current: Option<Transfer>,
+    summary: Summary,
+}
+
+impl<'a> MediaSync<'a> {
+    /// Compare the referenced blobs with the totem and plan the transfers
+    pub fn start(
+        db: &'a Mutex<Database>,
+        client: TotemClient,
+        store: MediaStore,
+    ) -> anyhow::Result<Self> {
+        let (hashes, wanted) = {
+            let db = lock(db)?;
+            let mut hashes = Vec::new();
+            let mut wanted = Vec::new();
+
+            for name in db.get_referenced_media()? {
+                // Older posts reference local files instead of hashes
+                let Ok(hash) = name.parse::<ContentHash>() else {
+                    continue;
+                };
+                match db.get_media_path(&name)? {
+                    Some(_) => hashes.push(hash.to_string()),
+                    None => wanted.push(hash.to_string()),
+                }
+            }
+
+            (hashes, wanted)
+        };
+
+        let compared = client.compare_media(hashes, wanted)?;
+
+        let transfers = |names: Vec<String>, direction| {
+            names
+                .into_iter()
+                .filter_map(|name| name.parse::<ContentHash>().ok())
+                .map(move |hash| Transfer {
+                    hash,
+                    direction,
+                    state: TransferState::Pending,
+                })
+        };
+        let queue = transfers(compared.totem_missing, Direction::Upload)
+            .chain(transfers(compared.client_missing, Direction::Download))
+            .collect();
+
+        Ok(MediaSync {
+            db,
+            client,
+            store,
+            queue,
+            current: None,
+            summary: Summary::default(),
+        })
+    }
+
+    pub fn summary(&self) -> Summary {
+        self.summary
+    }
+
+    /// Transfer the next piece
+    /// Returns `None` once everything has been transferred. A failed transfer is
+    /// reported and skipped, the remaining ones still get their chance.
+    pub fn step(&mut self) -> Option<Progress> {
+        let mut transfer = self.current.take().or_else(|| self.queue.pop_front())?;
+
+        let result = match transfer.direction {
+            Direction::Upload => self.upload_step(&mut transfer),
+            Direction::Download => self.download_step(&mut transfer),
+        };
+
+        match result {
+            Ok(progress) if progress.done => {
+                match transfer.direction {
+                    Direction::Upload => self.summary.uploaded += 1,
+                    Direction::Download => self.summary.downloaded += 1,
+                }
+                Some(progress)
+            }
+            Ok(progress) => {
+                self.current = Some(transfer);
+                Some(progress)
+            }
+            Err(e) => {
+                self.summary.failed += 1;
+                let mut progress = transfer.progress(0, 0, true);
+                progress.error = Some(e.to_string());
+                transfer.suspend();
+                Some(progress)
+            }
+        }
+    }
+
+    fn upload_step(&self, transfer: &mut Transfer) -> anyhow::Result<Progress> {
+        if let TransferState::Pending = transfer.state {
+            let path = lock(self.db)?
+                .get_media_path(&transfer.hash.to_string())?
+                .ok_or_else(|| anyhow!("No local copy of {}", transfer.hash))?;
+
+            let file = File::open(path)?;
+            let total = file.metadata()?.len();
+
+            let status = self.client.upload_status(&transfer.hash)?;
+            if status.complete {
+                return Ok(transfer.progress(total, total, true));
+            }
+
+            transfer.state = TransferState::Uploading {
+                file,
+                offset: status.offset,
+                total,
+            };
+        }
+
+        let TransferState::Uploading {
+            file,
+            offset,
+            total,
+        } = &mut transfer.state
+        else {
+            unreachable!("upload in download state");
+        };
+
+        let mut piece = Vec::with_capacity(PIECE_LEN as usize);
+        file.seek(SeekFrom::Start(*offset))?;
+        Read::by_ref(file).take(PIECE_LEN).read_to_end(&mut piece)?;
+        if piece.is_empty() {
+            return Err(anyhow!(
+                "Local copy of {} is shorter than expected",
+                transfer.hash
+            ));
+        }
+
+        match self
+            .client
+            .upload_piece(&transfer.hash, &piece, *offset, *total)
+        {
+            Ok(saved) => {
+                *offset = saved.size;
+                let (offset, total) = (*offset, *total);
+                Ok(transfer.progress(offset, total, saved.complete))
+            }
+            Err(TotemError::Api { error, .. }) if error.code == ErrorCode::Conflict => {
+                // Either the totem got the blob from someone else in the meantime, or it
+                // lost part of our upload and tells us where to continue
+                let expected = error
+                    .details
+                    .iter()
+                    .find(|detail| detail.field.as_deref() == Some("offset"))
+                    .and_then(|detail| detail.message.parse().ok());
+
+                match expected {
+                    Some(expected) => {
+                        *offset = expected;
+                        let total = *total;
+                        Ok(transfer.progress(expected, total, false))
+                    }
+                    None => {
+                        let total = *total;
+                        Ok(transfer.progress(total, total, true))
+                    }
+                }
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn download_step(&self, transfer: &mut Transfer) -> anyhow::Result<Progress> {
+        if let TransferState::Pending = transfer.state {
+            let state = self.store.upload_state(&transfer.hash)?;
+            if state.complete {
+                // Downloaded before, but not recorded
+                self.record(&transfer.hash)?;
+                return Ok(transfer.progress(state.offset, state.offset, true));
+            }
+
+            transfer.state = TransferState::Downloading {
+                upload: Box::new(self.store.resume_upload(transfer.hash, state.offset)?),
+            };
+        }
+
+        let TransferState::Downloading { upload } = &transfer.state else {
+            unreachable!("download in upload state");
+        };
+        let piece = self
+            .client
+            .download_piece(&transfer.hash, upload.size(), PIECE_LEN)?;
+
+        let TransferState::Downloading { upload } =
+            std::mem::replace(&mut transfer.state, TransferState::Pending)
+        else {
+            unreachable!("download in upload state");
+        };
+
+        let mut upload = if piece.start == upload.size() {
+            upload
+        } else if piece.start == 0 {
+            // The totem ignored the range and sent everything, start over with that.
+            // Both write to the same part file, so the old one has to go first.
+            upload.abort();
+            Box::new(self.store.begin_upload(transfer.hash)?)
+        } else {
+            upload.suspend().ok();
+            return Err(anyhow!(
+                "Totem sent {} from the wrong offset",
+                transfer.hash
+            ));
+        };
+
+        if let Err(e) = upload.write_all(&piece.data) {
+            upload.suspend().ok();
+            return Err(e.into());
+        }
+
+        if upload.size() < piece.total {
+            let transferred = upload.size();
+            transfer.state = TransferState::Downloading { upload };
+            return Ok(transfer.progress(transferred, piece.total, false));
+        }
+
+        let info = upload.finish()?;
+        self.record(&transfer.hash)?;
+
+        Ok(transfer.progress(info.size, info.size, true))
+    }
+
+    /// Record where a downloaded blob is stored
+    fn record(&self, hash: &ContentHash) -> anyhow::Result<()> {
+        let path = self.store.blob_path(hash);
+        lock(self.db)?.set_media_path(&hash.to_string(), &path.to_string_lossy())?;
+        Ok(())
+    }
+}
+
+pub(crate) fn lock(db: &Mutex<Database>) -> anyhow::Result<MutexGuard<'_, Database>> {
+    db.lock().map_err(|e| anyhow!("Lock error: {}", e))
+}
@@ -0,0 +1,268 @@
+//! Encryption of direct messages, see `shared::model::message`
+//!
+//! The key of a conversation is derived with HKDF from the X25519 secret of the two
+//! users' keys. Both sides arrive at the same key, so the sender can read their own
+//! messages again as well. Every message has a random nonce, which is safe with
+//! XChaCha20-Poly1305 however many messages a conversation has.
+
+use std::fmt;
+
+use chacha20poly1305::aead::{Aead, AeadCore, OsRng, Payload};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305, XNonce};
+use chrono::{DateTime, Utc};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use shared::identity::{Identity, PublicKey};
+use shared::model::validation::{MAX_MESSAGE_LEN, MESSAGE_NONCE_LEN};
+use shared::model::Message;
+use zeroize::Zeroizing;
+
+const KEY_INFO: &[u8] = b"loom message v1 key";
+
+#[derive(Debug)]
+pub enum MessageError {
+    Empty,
+    TooLong,
+    /// The other key is of low order, nobody can have a conversation with it
+    UnusableKey,
+    /// This identity is neither the sender nor the recipient of the message
+    NotAParticipant,
+    /// The message was altered or damaged
+    Decryption,
+}
+
+impl fmt::Display for MessageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageError::Empty => write!(f, "The message is empty"),
+            MessageError::TooLong => write!(
+                f,
+                "The message is longer than {} characters",
+                MAX_MESSAGE_LEN
+            ),
+            MessageError::UnusableKey => {
+                write!(f, "The key of the other user can't be used for messages")
+            }
+            MessageError::NotAParticipant => {
+                write!(f, "The message isn't addressed to this identity")
+            }
+            MessageError::Decryption => write!(f, "The message can't be decrypted"),
+        }
+    }
+}
+
+impl std::error::Error for MessageError {}
+
+fn cipher(identity: &Identity, other: &PublicKey) -> Result<XChaCha20Poly1305, MessageError> {
+    let secret = Zeroizing::new(identity.agree(other).ok_or(MessageError::UnusableKey)?);
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(None, secret.as_slice())
+        .expand(KEY_INFO, key.as_mut_slice())
+        .expect("32 bytes is a valid output length");
+    Ok(XChaCha20Poly1305::new(key.as_slice().into()))
+}
+
+/// Encrypt `text` from this identity's user to `recipient_key`
+pub fn seal(
+    identity: &Identity,
+    uuid: String,
+    recipient_id: &str,
+    recipient_key: &PublicKey,
+    text: &str,
+    timestamp: DateTime<Utc>,
+) -> Result<Message, MessageError> {
+    if text.trim().is_empty() {
+        return Err(MessageError::Empty);
+    }
+    if text.chars().count() > MAX_MESSAGE_LEN {
+        return Err(MessageError::TooLong);
+    }
+
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let mut message = Message {
+        uuid,
+        sender_id: identity.user_id(),
+        recipient_id: recipient_id.to_string(),
+        sender_key: identity.public_key().to_string(),
+        recipient_key: recipient_key.to_string(),
+        timestamp,
+        nonce: to_hex(&nonce),
+        ciphertext: String::new(),
+    };
+    let ciphertext = cipher(identity, recipient_key)?
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: text.as_bytes(),
+                aad: &message.associated_data(),
+            },
+        )
+        .expect("encrypting into a Vec can't fail");
+    message.ciphertext = to_hex(&ciphertext);
+    Ok(message)
+}
+
+/// Decrypt a message this identity sent or received
+pub fn open(identity: &Identity, message: &Message) -> Result<String, MessageError> {
+    let own_key = identity.public_key().to_string();
+    let other = if message.recipient_key == own_key {
+        &message.sender_key
+    } else if message.sender_key == own_key {
+        &message.recipient_key
+    } else {
+        return Err(MessageError::NotAParticipant);
+    };
+    let other: PublicKey = other.parse().map_err(|_| MessageError::Decryption)?;
+
+    let nonce = from_hex(&message.nonce).ok_or(MessageError::Decryption)?;
+    if nonce.len() != MESSAGE_NONCE_LEN {
+        return Err(MessageError::Decryption);
+    }
+    let ciphertext = from_hex(&message.ciphertext).ok_or(MessageError::Decryption)?;
+    let plaintext = cipher(identity, &other)?
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: &message.associated_data(),
+            },
+        )
+        .map_err(|_| MessageError::Decryption)?;
+    String::from_utf8(plaintext).map_err(|_| MessageError::Decryption)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(sender: &Identity, recipient: &Identity, text: &str) -> Message {
+        seal(
+            sender,
+            "0b5d9c0e-2f4a-4e8b-9d3c-1a2b3c4d5e6f".to_string(),
+            &recipient.user_id(),
+            &recipient.public_key(),
+            text,
+            Utc::now(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let (alice, bob) = (Identity::generate(), Identity::generate());
+        let sealed = message(&alice, &bob, "See you at the totem");
+        assert!(!sealed.ciphertext.contains(&to_hex(b"totem")));
+        assert_eq!(from_hex(&sealed.nonce).unwrap().len(), MESSAGE_NONCE_LEN);
+
+        // Both sides can read it, nobody else
+        assert_eq!(open(&bob, &sealed).unwrap(), "See you at the totem");
+        assert_eq!(open(&alice, &sealed).unwrap(), "See you at the totem");
+        assert!(matches!(
+            open(&Identity::generate(), &sealed),
+            Err(MessageError::NotAParticipant)
+        ));
+
+        // A fresh nonce every time
+        assert_ne!(
+            message(&alice, &bob, "See you at the totem").nonce,
+            sealed.nonce
+        );
+    }
+
+    #[test]
+    fn test_tampering() {
+        let (alice, bob) = (Identity::generate(), Identity::generate());
+        let sealed = message(&alice, &bob, "See you at the totem");
+
+        let mut ciphertext = from_hex(&sealed.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        let tampered = [
+            Message {
+                ciphertext: to_hex(&ciphertext),
+                ..sealed.clone()
+            },
+            Message {
+                nonce: to_hex(&[0u8; MESSAGE_NONCE_LEN]),
+                ..sealed.clone()
+            },
+            Message {
+                nonce: to_hex(&[0u8; 12]),
+                ..sealed.clone()
+            },
+            Message {
+                nonce: "zz".repeat(MESSAGE_NONCE_LEN),
+                ..sealed.clone()
+            },
+            // The header is authenticated
+            Message {
+                uuid: "6f5e4d3c-2b1a-4c3d-9e8b-4a2f0e9c5d0b".to_string(),
+                ..sealed.clone()
+            },
+            Message {
+                sender_id: Identity::generate().user_id(),
+                ..sealed.clone()
+            },
+            Message {
+                timestamp: sealed.timestamp + chrono::TimeDelta::seconds(1),
+                ..sealed.clone()
+            },
+        ];
+        for message in &tampered {
+            assert!(matches!(open(&bob, message), Err(MessageError::Decryption)));
+        }
+
+        // Swapping in another key makes the reader derive another key
+        let mallory = Identity::generate();
+        let forged = Message {
+            sender_key: mallory.public_key().to_string(),
+            ..sealed.clone()
+        };
+        assert!(matches!(open(&bob, &forged), Err(MessageError::Decryption)));
+    }
+
+    #[test]
+    fn test_refuses_bad_text() {
+        let (alice, bob) = (Identity::generate(), Identity::generate());
+        let seal = |text: &str| {
+            seal(
+                &alice,
+                "0b5d9c0e".to_string(),
+                &bob.user_id(),
+                &bob.public_key(),
+                text,
+                Utc::now(),
+            )
+        };
+        assert!(matches!(seal(" \n"), Err(MessageError::Empty)));
+        assert!(matches!(
+            seal(&"a".repeat(MAX_MESSAGE_LEN + 1)),
+            Err(MessageError::TooLong)
+        ));
+        assert!(seal(&"a".repeat(MAX_MESSAGE_LEN)).is_ok());
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(
+            from_hex(&to_hex(&[0, 1, 0xab, 0xff])).unwrap(),
+            vec![0, 1, 0xab, 0xff]
+        );
+        assert!(from_hex("abc").is_none());
+        assert!(from_hex("zz").is_none());
+        assert!(from_hex("é1").is_none());
+    }
+}
@@ -0,0 +1,552 @@
+//! Synchronisation of users and posts with a totem
+//!
+//! Both sides tell each other which records they have, then the missing ones are
+//! transferred one at a time. Records travel in the JSON form of `shared::model`,
+//! so everything they carry, like picture previews, arrives on the other side.
+//! Key rotations are synced first, profiles and posts are checked against the key their
+//! author used at the time. Then users, totems reject posts by authors they don't know.
+//!
+//! Sightings of the known users are exchanged next, and the totem records this phone's
+//! user as seen there. The sighting is signed, and names the totem the first exchange
+//! told about, so it can't be passed off as a visit elsewhere.
+//!
+//! Tombstones of deleted posts are synced before the posts, so deleted posts aren't offered
+//! to the totem or taken from it. All of them are compared, however old, until they expire.
+//! Edited posts are compared by version, whichever side has the older version gets the newer.
+//!
+//! Replies, reposts and reactions come last. The totem also names the posts and replies its
+//! replies answer and the posts its reposts pass on that it doesn't have, those are uploaded
+//! if the phone has them. Reposted posts the phone lacks are fetched from the totem, however
+//! old they are.
+//!
+//! Downloaded records are checked like a totem checks uploads, including their
+//! signatures, so a totem can't slip altered records onto the phone.
+
+use std::sync::Mutex;
+
+use chrono::{TimeDelta, Utc};
+use shared::api::{
+    PostsCompareRequest, ReactionsCompareRequest, RepliesCompareRequest, RepostsCompareRequest,
+    UsersLastSeenRequest,
+};
+use shared::db::Database;
+use shared::identity::Identity;
+use shared::model::{KeyRotation, LastSeen, Post, Reaction, Reply, Repost, Tombstone, User};
+use shared::reactions::MAX_REACTIONS_PER_REQUEST;
+
+use crate::api::deletions::bury;
+use crate::media_sync::lock;
+use crate::totem::{TotemClient, TotemError};
+
+/// Posts, replies, reposts and reactions made this long before or after now are compared with the totem
+pub const POST_WINDOW: TimeDelta = TimeDelta::days(1);
+
+/// Users asked about and sightings sent, so the request stays within the totem's 10 KB limit
+const LAST_SEEN_USERS: usize = 100;
+const LAST_SEEN_ENTRIES: usize = 20;
+
+/// Counts of a finished sync
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Summary {
+    pub rotations_downloaded: u32,
+    pub rotations_uploaded: u32,
+    pub users_downloaded: u32,
+    pub users_uploaded: u32,
+    /// Sightings that were newer than the ones the phone knew
+    pub last_seen_downloaded: u32,
+    pub tombstones_downloaded: u32,
+    pub tombstones_uploaded: u32,
+    pub posts_downloaded: u32,
+    pub posts_uploaded: u32,
+    pub replies_downloaded: u32,
+    pub replies_uploaded: u32,
+    pub reposts_downloaded: u32,
+    pub reposts_uploaded: u32,
+    pub reactions_downloaded: u32,
+    pub reactions_uploaded: u32,
+    /// Records that failed validation or had a forged signature, on either side
+    pub rejected: u32,
+}
+
+pub struct RecordSync<'a> {
+    db: &'a Mutex<Database>,
+    client: &'a TotemClient,
+    /// `None` while the identity is locked, the user isn't recorded as seen then
+    identity: Option<&'a Identity>,
+    summary: Summary,
+}
+
+impl<'a> RecordSync<'a> {
+    pub fn new(
+        db: &'a Mutex<Database>,
+        client: &'a TotemClient,
+        identity: Option<&'a Identity>,
+    ) -> Self {
+        RecordSync {
+            db,
+            client,
+            identity,
+            summary: Summary::default(),
+        }
+    }
+
+    /// Sync key rotations, users, sightings and tombstones, then the posts, replies, reposts
+    /// and reactions of the last and next [`POST_WINDOW`]
+    pub fn run(mut self) -> anyhow::Result<Summary> {
+        self.sync_rotations()?;
+        self.sync_users()?;
+        self.sync_last_seen()?;
+        self.sync_tombstones()?;
+        self.sync_posts()?;
+        self.sync_replies()?;
+        self.sync_reposts()?;
+        self.sync_reactions()?;
+        Ok(self.summary)
+    }
+
+    fn sync_rotations(&mut self) -> anyhow::Result<()> {
+        let local = lock(self.db)?.get_all_key_rotations()?;
+        let uuids = local.iter().map(|rotation| rotation.uuid.clone()).collect();
+        let compared = self.client.compare_rotations(uuids)?;
+
+        let mut downloaded = Vec::new();
+        for uuid in compared.client_missing {
+            let Some(rotation) = self.download(|client| client.get_rotation(&uuid))? else {
+                continue;
+            };
+            if rotation.validate().is_err() {
+                self.summary.rejected += 1;
+                continue;
+            }
+            downloaded.push(rotation);
+        }
+        // Each rotation is checked against the ones before it, which may arrive in the same sync
+        downloaded.sort_by_key(|rotation| rotation.sequence);
+        for rotation in downloaded {
+            let db = lock(self.db)?;
+            if rotation
+                .check_chain(&db.get_key_rotations(&rotation.user_id)?)
+                .is_err()
+            {
+                self.summary.rejected += 1;
+                continue;
+            }
+            db.create_key_rotation(&rotation)?;
+            self.summary.rotations_downloaded += 1;
+        }
+
+        // In the order of the chain, totems reject rotations that don't follow a known one
+        let missing: Vec<&KeyRotation> = local
+            .iter()
+            .filter(|rotation| compared.totem_missing.contains(&rotation.uuid))
+            .collect();
+        for rotation in missing {
+            if self.upload(|client| client.create_rotation(rotation))? {
+                self.summary.rotations_uploaded += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync_users(&mut self) -> anyhow::Result<()> {
+        let local = lock(self.db)?.get_all_users()?;
+        let uuids = local.iter().map(|user| user.uuid.clone()).collect();
+        let compared = self.client.compare_users(uuids)?;
+
+        for uuid in compared.client_missing {
+            let Some(user) = self.download(|client| client.get_user(&uuid))? else {
+                continue;
+            };
+            let db = lock(self.db)?;
+            if user.validate().is_err()
+                || user
+                    .check_signature(&db.get_key_rotations(&user.uuid)?)
+                    .is_err()
+            {
+                self.summary.rejected += 1;
+                continue;
+            }
+            db.create_user(&user);
+            self.summary.users_downloaded += 1;
+        }
+
+        let missing: Vec<&User> = local
+            .iter()
+            .filter(|user| compared.totem_missing.contains(&user.uuid))
+            .collect();
+        for user in missing {
+            if self.upload(|client| client.create_user(user))? {
+                self.summary.users_uploaded += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync_last_seen(&mut self) -> anyhow::Result<()> {
+        let request = {
+            let db = lock(self.db)?;
+            let mut user_uuids: Vec<String> = db
+                .get_all_users()?
+                .into_iter()
+                .map(|user| user.uuid)
+                .collect();
+            user_uuids.truncate(LAST_SEEN_USERS);
+            let mut entries = db.get_all_last_seen()?;
+            entries.truncate(LAST_SEEN_ENTRIES);
+            UsersLastSeenRequest {
+                sighting: None,
+                user_uuids,
+                entries,
+            }
+        };
+        let response = self.client.users_last_seen(&request)?;
+        self.merge_last_seen(response.entries)?;
+
+        let Some(identity) = self.identity else {
+            return Ok(());
+        };
+        let mut sighting = LastSeen {
+            user_id: identity.user_id(),
+            totem_id: response.totem_id,
+            seen_at: Utc::now(),
+            signature: None,
+        };
+        sighting.sign(identity);
+        let request = UsersLastSeenRequest {
+            sighting: Some(sighting),
+            ..Default::default()
+        };
+        if let Some(response) = self.download(|client| client.users_last_seen(&request))? {
+            self.merge_last_seen(response.entries)?;
+        }
+
+        Ok(())
+    }
+
+    /// Store the downloaded sightings that are valid and signed by their users
+    fn merge_last_seen(&mut self, entries: Vec<LastSeen>) -> anyhow::Result<()> {
+        let db = lock(self.db)?;
+        let mut accepted = Vec::new();
+        for entry in entries {
+            let (user, rotations) = db.get_signer(&entry.user_id)?;
+            if entry.validate().is_err()
+                || entry.check_signature(user.as_ref(), &rotations).is_err()
+            {
+                self.summary.rejected += 1;
+                continue;
+            }
+            accepted.push(entry);
+        }
+        self.summary.last_seen_downloaded += db.merge_last_seen(&accepted)? as u32;
+        Ok(())
+    }
+
+    fn sync_tombstones(&mut self) -> anyhow::Result<()> {
+        let uuids = {
+            let db = lock(self.db)?;
+            db.purge_tombstones(Utc::now())?;
+            db.get_tombstone_ids()?
+        };
+
+        let compared = self.client.compare_tombstones(uuids)?;
+
+        for uuid in compared.client_missing {
+            let Some(tombstone) = self.download(|client| client.get_tombstone(&uuid))? else {
+                continue;
+            };
+            let db = lock(self.db)?;
+            let (author, rotations) = db.get_signer(&tombstone.user_id)?;
+            if tombstone.validate().is_err()
+                || tombstone
+                    .check_signature(author.as_ref(), &rotations)
+                    .is_err()
+            {
+                self.summary.rejected += 1;
+                continue;
+            }
+            if bury(&db, &tombstone)? {
+                self.summary.tombstones_downloaded += 1;
+            }
+        }
+
+        for uuid in compared.totem_missing {
+            let tombstone: Tombstone = lock(self.db)?.get_tombstone_by_id(&uuid)?;
+            if self.upload(|client| client.create_tombstone(&tombstone))? {
+                self.summary.tombstones_uploaded += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync_posts(&mut self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let (start, end) = (now - POST_WINDOW, now + POST_WINDOW);
+        let (uuids, versions) = {
+            let db = lock(self.db)?;
+            (
+                db.get_post_ids_in_range(start, end),
+                db.get_post_versions_in_range(start, end)?,
+            )
+        };
+
+        let compared = self.client.compare_posts(&PostsCompareRequest {
+            time_start: start.to_rfc3339(),
+            time_end: end.to_rfc3339(),
+            post_uuids: uuids,
+            versions,
+        })?;
+
+        for uuid in compared.client_missing {
+            let Some(post) = self.download(|client| client.get_post(&uuid))? else {
+                continue;
+            };
+            self.store_post(post)?;
+        }
+
+        for uuid in compared.totem_missing {
+            let post: Post = lock(self.db)?.get_post_by_id(&uuid)?;
+            if self.upload(|client| client.create_post(&post))? {
+                self.summary.posts_uploaded += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync_replies(&mut self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let (start, end) = (now - POST_WINDOW, now + POST_WINDOW);
+        let uuids = lock(self.db)?.get_reply_ids_in_range(start, end)?;
+
+        let compared = self.client.compare_replies(&RepliesCompareRequest {
+            time_start: start.to_rfc3339(),
+            time_end: end.to_rfc3339(),
+            reply_uuids: uuids,
+        })?;
+
+        for uuid in compared.client_missing {
+            let Some(reply) = self.download(|client| client.get_reply(&uuid))? else {
+                continue;
+            };
+            let db = lock(self.db)?;
+            let (author, rotations) = db.get_signer(&reply.user_id)?;
+            if reply.validate().is_err()
+                || reply.check_signature(author.as_ref(), &rotations).is_err()
+            {
+                self.summary.rejected += 1;
+                continue;
+            }
+            db.create_reply(&reply)?;
+            self.summary.replies_downloaded += 1;
+        }
+
+        for uuid in compared.totem_missing {
+            let reply: Reply = lock(self.db)?.get_reply_by_id(&uuid)?;
+            if self.upload(|client| client.create_reply(&reply))? {
+                self.summary.replies_uploaded += 1;
+            }
+        }
+
+        // What the totem's replies answer may be here, older than the compared range
+        for uuid in compared.unresolved {
+            let (post, reply) = {
+                let db = lock(self.db)?;
+                (
+                    db.get_post_by_id(&uuid).ok(),
+                    db.get_reply_by_id(&uuid).ok(),
+                )
+            };
+            match (post, reply) {
+                (Some(post), _) => {
+                    if self.upload(|client| client.create_post(&post))? {
+                        self.summary.posts_uploaded += 1;
+                    }
+                }
+                (None, Some(reply)) => {
+                    if self.upload(|client| client.create_reply(&reply))? {
+                        self.summary.replies_uploaded += 1;
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync_reposts(&mut self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let (start, end) = (now - POST_WINDOW, now + POST_WINDOW);
+        let uuids = lock(self.db)?.get_repost_ids_in_range(start, end)?;
+
+        let compared = self.client.compare_reposts(&RepostsCompareRequest {
+            time_start: start.to_rfc3339(),
+            time_end: end.to_rfc3339(),
+            repost_uuids: uuids,
+        })?;
+
+        for uuid in compared.client_missing {
+            let Some(repost) = self.download(|client| client.get_repost(&uuid))? else {
+                continue;
+            };
+            let db = lock(self.db)?;
+            let (user, rotations) = db.get_signer(&repost.user_id)?;
+            let original = db.get_post_by_id(&repost.post_id).ok();
+            if repost.validate().is_err()
+                || repost.check_signature(user.as_ref(), &rotations).is_err()
+                || original.is_some_and(|original| repost.check_original(&original).is_err())
+            {
+                self.summary.rejected += 1;
+                continue;
+            }
+            db.create_repost(&repost)?;
+            self.summary.reposts_downloaded += 1;
+        }
+
+        for uuid in compared.totem_missing {
+            let repost: Repost = lock(self.db)?.get_repost_by_id(&uuid)?;
+            if self.upload(|client| client.create_repost(&repost))? {
+                self.summary.reposts_uploaded += 1;
+            }
+        }
+
+        // The posts the totem's reposts pass on may be here, older than the compared range
+        for uuid in &compared.unresolved {
+            let Ok(post) = lock(self.db)?.get_post_by_id(uuid) else {
+                continue;
+            };
+            if self.upload(|client| client.create_post(&post))? {
+                self.summary.posts_uploaded += 1;
+            }
+        }
+
+        // And the totem may have the ones reposted here, unless it asked for them
+        let missing = lock(self.db)?.get_missing_reposted_ids()?;
+        for uuid in missing
+            .into_iter()
+            .filter(|uuid| !compared.unresolved.contains(uuid))
+        {
+            match self.client.get_post(&uuid) {
+                Ok(post) => self.store_post(post)?,
+                Err(TotemError::Api { status: 404, .. }) => {}
+                Err(TotemError::Api { .. } | TotemError::InvalidResponse(_)) => {
+                    self.summary.rejected += 1
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn sync_reactions(&mut self) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let (start, end) = (now - POST_WINDOW, now + POST_WINDOW);
+        let uuids = lock(self.db)?.get_reaction_ids_in_range(start, end)?;
+
+        let compared = self.client.compare_reactions(&ReactionsCompareRequest {
+            time_start: start.to_rfc3339(),
+            time_end: end.to_rfc3339(),
+            reaction_uuids: uuids,
+        })?;
+
+        for chunk in compared.client_missing.chunks(MAX_REACTIONS_PER_REQUEST) {
+            let Some(reactions) = self.download(|client| client.fetch_reactions(chunk.to_vec()))?
+            else {
+                continue;
+            };
+            let db = lock(self.db)?;
+            for reaction in reactions {
+                let (author, rotations) = db.get_signer(&reaction.user_id)?;
+                if reaction.validate().is_err()
+                    || reaction
+                        .check_signature(author.as_ref(), &rotations)
+                        .is_err()
+                {
+                    self.summary.rejected += 1;
+                    continue;
+                }
+                if db.merge_reaction(&reaction)? {
+                    self.summary.reactions_downloaded += 1;
+                }
+            }
+        }
+
+        // Reactions superseded by a downloaded one don't count anymore and stay here
+        let missing: Vec<Reaction> = {
+            let db = lock(self.db)?;
+            let mut missing = Vec::new();
+            for uuid in &compared.totem_missing {
+                missing.extend(db.get_reaction_by_id(uuid)?);
+            }
+            missing
+        };
+        for chunk in missing.chunks(MAX_REACTIONS_PER_REQUEST) {
+            match self.client.create_reactions(chunk.to_vec()) {
+                Ok(response) => {
+                    self.summary.reactions_uploaded += response.accepted.len() as u32;
+                    self.summary.rejected += response.rejected.len() as u32;
+                }
+                Err(TotemError::Api { .. }) => self.summary.rejected += chunk.len() as u32,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Store a downloaded post if it is valid and was written by its author
+    fn store_post(&mut self, post: Post) -> anyhow::Result<()> {
+        let db = lock(self.db)?;
+        // Authors were synced right before, posts of the others stay unverified.
+        // Their edits are refused, those need a verified signature.
+        let (author, rotations) = db.get_signer(&post.user_id)?;
+        // Edits must keep the author and time of the stored version
+        let stored = db.get_post_by_id(&post.uuid).ok();
+        if post.validate().is_err()
+            || stored.is_some_and(|stored| post.check_edit_of(&stored).is_err())
+            || post.check_signature(author.as_ref(), &rotations).is_err()
+        {
+            self.summary.rejected += 1;
+            return Ok(());
+        }
+        if db.merge_post(&post)? {
+            self.summary.posts_downloaded += 1;
+        }
+        Ok(())
+    }
+
+    /// Fetch a record, `None` if the totem refused to hand it out
+    fn download<T>(
+        &mut self,
+        request: impl FnOnce(&TotemClient) -> Result<T, TotemError>,
+    ) -> anyhow::Result<Option<T>> {
+        match request(self.client) {
+            Ok(record) => Ok(Some(record)),
+            // Records that don't parse are as good as invalid ones
+            Err(TotemError::Api { .. } | TotemError::InvalidResponse(_)) => {
+                self.summary.rejected += 1;
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Send a record, `false` if the totem rejected it
+    fn upload<T>(
+        &mut self,
+        request: impl FnOnce(&TotemClient) -> Result<T, TotemError>,
+    ) -> anyhow::Result<bool> {
+        match request(self.client) {
+            Ok(_) => Ok(true),
+            Err(TotemError::Api { .. }) => {
+                self.summary.rejected += 1;
+                Ok(false)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
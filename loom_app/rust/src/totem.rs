@@ -0,0 +1,387 @@
+//! Blocking HTTP client for the API of a totem
+//!
+//! Phones reach the totem over its Wi-Fi access point. Error responses carry the
+//! JSON envelope from `shared::api`, which is turned into an [`ApiError`] here, so
+//! callers can react to the error code instead of the status line.
+
+use std::io::Read;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use shared::api::{
+    ApiError, BundlesDeliverRequest, BundlesDeliverResponse, BundlesOfferRequest,
+    BundlesOfferResponse, CompareResponse, CreatedResponse, ErrorCode, MailboxCollectRequest,
+    MailboxCollectResponse, MailboxDepositRequest, MailboxDepositResponse, MediaCompareRequest,
+    MediaCompareResponse, PicSaved, PostsCompareRequest, ReactionsBatch, ReactionsCompareRequest,
+    ReactionsCreateResponse, ReactionsFetchRequest, RepliesCompareRequest, RepliesCompareResponse,
+    RepostsCompareRequest, RepostsCompareResponse, RotationsCompareRequest,
+    TombstonesCompareRequest, TrendingResponse, UploadStatus, UsersCompareRequest,
+    UsersLastSeenRequest, UsersLastSeenResponse,
+};
+use shared::http::{ByteRange, ContentRange};
+use shared::media::ContentHash;
+use shared::model::{Bundle, KeyRotation, Post, Reaction, Reply, Repost, Tombstone, User};
+
+/// Address of a totem on its own access point
+pub const DEFAULT_TOTEM_URL: &str = "http://192.168.71.1";
+
+/// The totem's AP is weak, but a request that takes longer than this is lost anyway
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Errors of requests to a totem
+#[derive(Debug)]
+pub enum TotemError {
+    /// The totem answered with an error
+    Api { status: u16, error: ApiError },
+    /// The totem couldn't be reached, or the connection dropped
+    Transport(String),
+    /// The totem answered with something we don't understand
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for TotemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TotemError::Api { status, error } => write!(f, "Totem answered {status}: {error}"),
+            TotemError::Transport(e) => write!(f, "Totem not reachable: {e}"),
+            TotemError::InvalidResponse(e) => write!(f, "Invalid response from totem: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TotemError {}
+
+impl From<ureq::Error> for TotemError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, response) => {
+                let body = response.into_string().unwrap_or_default();
+                // Older totems answer with plain text
+                let error = serde_json::from_str(&body)
+                    .unwrap_or_else(|_| ApiError::new(ErrorCode::from_status(status), body));
+                TotemError::Api { status, error }
+            }
+            ureq::Error::Transport(e) => TotemError::Transport(e.to_string()),
+        }
+    }
+}
+
+/// A piece of a blob downloaded with a Range request
+pub struct Piece {
+    pub data: Vec<u8>,
+    /// Offset of the piece within the blob
+    pub start: u64,
+    /// Size of the whole blob
+    pub total: u64,
+}
+
+pub struct TotemClient {
+    base_url: String,
+    agent: ureq::Agent,
+}
+
+impl TotemClient {
+    pub fn new(base_url: &str) -> Self {
+        TotemClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn post_json<B: Serialize, R: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<R, TotemError> {
+        let response = self.agent.post(&self.url(path)).send_json(body)?;
+        response
+            .into_json()
+            .map_err(|e| TotemError::InvalidResponse(e.to_string()))
+    }
+
+    fn get_json<R: DeserializeOwned>(&self, path: &str) -> Result<R, TotemError> {
+        let response = self.agent.get(&self.url(path)).call()?;
+        response
+            .into_json()
+            .map_err(|e| TotemError::InvalidResponse(e.to_string()))
+    }
+
+    /// Find out which users have to be uploaded and which can be downloaded
+    pub fn compare_users(&self, user_uuids: Vec<String>) -> Result<CompareResponse, TotemError> {
+        self.post_json("/users/compare", &UsersCompareRequest { user_uuids })
+    }
+
+    /// Exchange when users were last seen at which totem, see `shared::presence`
+    pub fn users_last_seen(
+        &self,
+        request: &UsersLastSeenRequest,
+    ) -> Result<UsersLastSeenResponse, TotemError> {
+        self.post_json("/users/last_seen", request)
+    }
+
+    /// Find out which posts of a time range have to be uploaded and which can be downloaded
+    pub fn compare_posts(
+        &self,
+        request: &PostsCompareRequest,
+    ) -> Result<CompareResponse, TotemError> {
+        self.post_json("/posts/compare", request)
+    }
+
+    /// Find out which replies of a time range have to be uploaded and which can be downloaded
+    pub fn compare_replies(
+        &self,
+        request: &RepliesCompareRequest,
+    ) -> Result<RepliesCompareResponse, TotemError> {
+        self.post_json("/replies/compare", request)
+    }
+
+    /// Find out which reposts of a time range have to be uploaded and which can be downloaded
+    pub fn compare_reposts(
+        &self,
+        request: &RepostsCompareRequest,
+    ) -> Result<RepostsCompareResponse, TotemError> {
+        self.post_json("/reposts/compare", request)
+    }
+
+    /// Find out which reactions of a time range have to be uploaded and which can be downloaded
+    pub fn compare_reactions(
+        &self,
+        request: &ReactionsCompareRequest,
+    ) -> Result<CompareResponse, TotemError> {
+        self.post_json("/reactions/compare", request)
+    }
+
+    /// Fetch up to `shared::reactions::MAX_REACTIONS_PER_REQUEST` reactions
+    pub fn fetch_reactions(
+        &self,
+        reaction_uuids: Vec<String>,
+    ) -> Result<Vec<Reaction>, TotemError> {
+        let batch: ReactionsBatch = self.post_json(
+            "/reactions/fetch",
+            &ReactionsFetchRequest { reaction_uuids },
+        )?;
+        Ok(batch.reactions)
+    }
+
+    pub fn create_reactions(
+        &self,
+        reactions: Vec<Reaction>,
+    ) -> Result<ReactionsCreateResponse, TotemError> {
+        self.post_json("/reactions/create", &ReactionsBatch { reactions })
+    }
+
+    /// Find out which key rotations have to be uploaded and which can be downloaded
+    pub fn compare_rotations(
+        &self,
+        rotation_uuids: Vec<String>,
+    ) -> Result<CompareResponse, TotemError> {
+        self.post_json(
+            "/rotations/compare",
+            &RotationsCompareRequest { rotation_uuids },
+        )
+    }
+
+    /// Find out which tombstones have to be uploaded and which can be downloaded
+    pub fn compare_tombstones(
+        &self,
+        tombstone_uuids: Vec<String>,
+    ) -> Result<CompareResponse, TotemError> {
+        self.post_json(
+            "/tombstones/compare",
+            &TombstonesCompareRequest { tombstone_uuids },
+        )
+    }
+
+    pub fn get_user(&self, uuid: &str) -> Result<User, TotemError> {
+        self.get_json(&format!("/users/{uuid}"))
+    }
+
+    pub fn get_post(&self, uuid: &str) -> Result<Post, TotemError> {
+        self.get_json(&format!("/posts/{uuid}"))
+    }
+
+    pub fn get_reply(&self, uuid: &str) -> Result<Reply, TotemError> {
+        self.get_json(&format!("/replies/{uuid}"))
+    }
+
+    pub fn get_repost(&self, uuid: &str) -> Result<Repost, TotemError> {
+        self.get_json(&format!("/reposts/{uuid}"))
+    }
+
+    pub fn get_tombstone(&self, uuid: &str) -> Result<Tombstone, TotemError> {
+        self.get_json(&format!("/tombstones/{uuid}"))
+    }
+
+    /// The hashtags and threads most talked about at the totem lately
+    pub fn get_trending(&self) -> Result<TrendingResponse, TotemError> {
+        self.get_json("/trending")
+    }
+
+    pub fn get_rotation(&self, uuid: &str) -> Result<KeyRotation, TotemError> {
+        self.get_json(&format!("/rotations/{uuid}"))
+    }
+
+    pub fn create_user(&self, user: &User) -> Result<CreatedResponse, TotemError> {
+        self.post_json("/users/create", user)
+    }
+
+    pub fn create_post(&self, post: &Post) -> Result<CreatedResponse, TotemError> {
+        self.post_json("/posts/create", post)
+    }
+
+    pub fn create_reply(&self, reply: &Reply) -> Result<CreatedResponse, TotemError> {
+        self.post_json("/replies/create", reply)
+    }
+
+    pub fn create_repost(&self, repost: &Repost) -> Result<CreatedResponse, TotemError> {
+        self.post_json("/reposts/create", repost)
+    }
+
+    pub fn create_tombstone(&self, tombstone: &Tombstone) -> Result<CreatedResponse, TotemError> {
+        self.post_json("/tombstones/create", tombstone)
+    }
+
+    pub fn create_rotation(&self, rotation: &KeyRotation) -> Result<CreatedResponse, TotemError> {
+        self.post_json("/rotations/create", rotation)
+    }
+
+    /// Leave messages and receipts in the totem's mailboxes
+    pub fn deposit_mail(
+        &self,
+        request: &MailboxDepositRequest,
+    ) -> Result<MailboxDepositResponse, TotemError> {
+        self.post_json("/mailbox/deposit", request)
+    }
+
+    /// Fetch the messages waiting in a mailbox
+    pub fn collect_mail(
+        &self,
+        request: &MailboxCollectRequest,
+    ) -> Result<MailboxCollectResponse, TotemError> {
+        self.post_json("/mailbox/collect", request)
+    }
+
+    /// Learn which carried bundles the totem wants and take the ones it hands out
+    pub fn offer_bundles(
+        &self,
+        request: &BundlesOfferRequest,
+    ) -> Result<BundlesOfferResponse, TotemError> {
+        self.post_json("/bundles/offer", request)
+    }
+
+    /// Hand bundles over to the totem
+    pub fn deliver_bundles(
+        &self,
+        bundles: Vec<Bundle>,
+    ) -> Result<BundlesDeliverResponse, TotemError> {
+        self.post_json("/bundles/deliver", &BundlesDeliverRequest { bundles })
+    }
+
+    /// Find out which blobs have to be uploaded and which can be downloaded
+    pub fn compare_media(
+        &self,
+        hashes: Vec<String>,
+        wanted: Vec<String>,
+    ) -> Result<MediaCompareResponse, TotemError> {
+        self.post_json("/media/compare", &MediaCompareRequest { hashes, wanted })
+    }
+
+    /// How much of a blob the totem has received
+    pub fn upload_status(&self, hash: &ContentHash) -> Result<UploadStatus, TotemError> {
+        let response = self
+            .agent
+            .get(&self.url(&format!("/uploads/{hash}")))
+            .call()?;
+        response
+            .into_json()
+            .map_err(|e| TotemError::InvalidResponse(e.to_string()))
+    }
+
+    /// Upload a piece of a blob, starting at `start`, of a blob with `total` bytes
+    /// An empty piece can't be named by a Content-Range, the upload's state is returned instead.
+    pub fn upload_piece(
+        &self,
+        hash: &ContentHash,
+        data: &[u8],
+        start: u64,
+        total: u64,
+    ) -> Result<PicSaved, TotemError> {
+        if data.is_empty() {
+            let status = self.upload_status(hash)?;
+            return Ok(PicSaved {
+                name: status.name,
+                size: status.offset,
+                complete: status.complete,
+            });
+        }
+        let end = start + data.len() as u64 - 1;
+        let response = self
+            .agent
+            .post(&self.url(&format!("/pic/{hash}")))
+            .set("Content-Range", &format!("bytes {start}-{end}/{total}"))
+            .send_bytes(data)?;
+
+        response
+            .into_json()
+            .map_err(|e| TotemError::InvalidResponse(e.to_string()))
+    }
+
+    /// Download up to `max_len` bytes of a blob, starting at `start`
+    /// With a `max_len` of 0 nothing is downloaded, only the size of the blob is looked up.
+    pub fn download_piece(
+        &self,
+        hash: &ContentHash,
+        start: u64,
+        max_len: u64,
+    ) -> Result<Piece, TotemError> {
+        if max_len == 0 {
+            let status = self.upload_status(hash)?;
+            return Ok(Piece {
+                data: Vec::new(),
+                start,
+                total: status.offset,
+            });
+        }
+        let range = ByteRange {
+            start,
+            end: start + max_len - 1,
+        };
+        let response = self
+            .agent
+            .get(&self.url(&format!("/pic/{hash}")))
+            .set("Range", &format!("bytes={}-{}", range.start, range.end))
+            .call()?;
+
+        // A totem that doesn't know about ranges sends the whole blob
+        let (start, total) = match response.status() {
+            206 => response
+                .header("Content-Range")
+                .and_then(ContentRange::parse)
+                .map(|range| (range.range.start, range.total))
+                .ok_or_else(|| TotemError::InvalidResponse("missing Content-Range".to_string()))?,
+            _ => (
+                0,
+                response
+                    .header("Content-Length")
+                    .and_then(|len| len.parse().ok())
+                    .ok_or_else(|| {
+                        TotemError::InvalidResponse("missing Content-Length".to_string())
+                    })?,
+            ),
+        };
+
+        let mut data = Vec::new();
+        response
+            .into_reader()
+            .take(total)
+            .read_to_end(&mut data)
+            .map_err(|e| TotemError::Transport(e.to_string()))?;
+
+        Ok(Piece { data, start, total })
+    }
+}
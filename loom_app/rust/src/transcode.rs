@@ -0,0 +1,253 @@
+//! Preparing pictures before they are posted
+//!
+//! Photos straight from the camera are several megabytes and carry EXIF metadata, often
+//! including the GPS position they were taken at. Every picture is therefore decoded,
+//! turned upright, downscaled and re-encoded as a plain JPEG before it is stored. Only
+//! the pixels survive the round trip, so no metadata of the original ends up on a totem.
+
+use std::io::Cursor;
+
+use anyhow::Context;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader, Limits, RgbImage};
+
+/// Largest width or height accepted from the picker, anything bigger is not a photo
+const MAX_SOURCE_DIMENSION: u32 = 16384;
+
+/// Size the picture is shrunk to before its blurhash is computed
+const BLURHASH_SOURCE_DIMENSION: u32 = 64;
+
+/// How a picture is downscaled and compressed
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Longest side of the stored picture in pixels
+    pub max_dimension: u32,
+    /// Longest side of the thumbnail shown in feeds
+    pub thumbnail_dimension: u32,
+    /// JPEG quality from 1 to 100
+    pub quality: u8,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            max_dimension: 1600,
+            thumbnail_dimension: 320,
+            quality: 80,
+        }
+    }
+}
+
+/// A re-encoded JPEG
+pub struct Encoded {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The picture to store and its thumbnail
+pub struct Transcoded {
+    pub image: Encoded,
+    pub thumbnail: Encoded,
+    /// Blurred placeholder shown until the picture has been downloaded
+    pub blurhash: String,
+}
+
+/// Decode a JPEG, PNG or WebP picture, upright according to its EXIF orientation
+pub fn decode(data: &[u8]) -> anyhow::Result<DynamicImage> {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+
+    let mut reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+    reader.limits(limits);
+    let mut decoder = reader
+        .into_decoder()
+        .context("Unsupported picture format")?;
+    let orientation = decoder.orientation()?;
+
+    let mut image = DynamicImage::from_decoder(decoder).context("Failed to decode picture")?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+/// Downscale a picture and produce its thumbnail, both stripped of any metadata
+pub fn transcode(data: &[u8], options: &Options) -> anyhow::Result<Transcoded> {
+    let image = flatten(decode(data)?);
+    let image = fit(image, options.max_dimension, FilterType::CatmullRom);
+    let thumbnail = fit(
+        image.clone(),
+        options.thumbnail_dimension,
+        FilterType::Triangle,
+    );
+    let blurhash = blurhash(&thumbnail)?;
+
+    Ok(Transcoded {
+        image: encode(image, options.quality)?,
+        thumbnail: encode(thumbnail, options.quality)?,
+        blurhash,
+    })
+}
+
+/// Compute the blurhash of a picture, more components along its longer side
+fn blurhash(image: &RgbImage) -> anyhow::Result<String> {
+    // Computing the hash is quadratic in the pixels, a tiny version looks just the same
+    let small = fit(
+        image.clone(),
+        BLURHASH_SOURCE_DIMENSION,
+        FilterType::Triangle,
+    );
+    let (x, y) = if small.width() >= small.height() {
+        (4, 3)
+    } else {
+        (3, 4)
+    };
+    let rgba = DynamicImage::ImageRgb8(small).into_rgba8();
+
+    blurhash::encode(x, y, rgba.width(), rgba.height(), rgba.as_raw())
+        .map_err(|e| anyhow::anyhow!("Failed to compute blurhash: {e}"))
+}
+
+/// Shrink a picture so that its longer side is at most `max_dimension`, keeping its aspect ratio
+fn fit(image: RgbImage, max_dimension: u32, filter: FilterType) -> RgbImage {
+    let max_dimension = max_dimension.max(1);
+    if image.width() <= max_dimension && image.height() <= max_dimension {
+        return image;
+    }
+
+    DynamicImage::ImageRgb8(image)
+        .resize(max_dimension, max_dimension, filter)
+        .into_rgb8()
+}
+
+/// JPEG has no transparency, put transparent pictures on a white background
+fn flatten(image: DynamicImage) -> RgbImage {
+    if !image.color().has_alpha() {
+        return image.into_rgb8();
+    }
+
+    let rgba = image.into_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+fn encode(image: RgbImage, quality: u8) -> anyhow::Result<Encoded> {
+    let mut data = Vec::new();
+    JpegEncoder::new_with_quality(&mut data, quality.clamp(1, 100))
+        .encode_image(&image)
+        .context("Failed to encode picture")?;
+
+    Ok(Encoded {
+        data,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::png::PngEncoder;
+    use image::{ImageEncoder, RgbaImage};
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| image::Rgb([x as u8, y as u8, 128]));
+        encode(image, 90).unwrap().data
+    }
+
+    /// Insert an EXIF segment with the given orientation and a GPS tag into a JPEG
+    fn with_exif(jpeg: &[u8], orientation: u16) -> Vec<u8> {
+        let mut tiff = b"MM\0\x2a\0\0\0\x08".to_vec();
+        // One entry: orientation, a SHORT
+        tiff.extend_from_slice(&[0, 1, 0x01, 0x12, 0, 3, 0, 0, 0, 1]);
+        tiff.extend_from_slice(&orientation.to_be_bytes());
+        tiff.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        tiff.extend_from_slice(b"GPS 52.5200N 13.4050E");
+
+        let mut segment = b"Exif\0\0".to_vec();
+        segment.extend_from_slice(&tiff);
+        let len = (segment.len() + 2) as u16;
+
+        let mut data = jpeg[..2].to_vec();
+        data.extend_from_slice(&[0xff, 0xe1]);
+        data.extend_from_slice(&len.to_be_bytes());
+        data.extend_from_slice(&segment);
+        data.extend_from_slice(&jpeg[2..]);
+        data
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    #[test]
+    fn test_strips_metadata_and_turns_upright() {
+        let original = with_exif(&jpeg(40, 20), 6);
+        assert!(contains(&original, b"GPS 52.5200N"));
+
+        let transcoded = transcode(&original, &Options::default()).unwrap();
+        // Rotated by 90 degrees, as the orientation asks
+        assert_eq!((transcoded.image.width, transcoded.image.height), (20, 40));
+        for encoded in [&transcoded.image, &transcoded.thumbnail] {
+            assert!(!contains(&encoded.data, b"Exif"));
+            assert!(!contains(&encoded.data, b"GPS 52.5200N"));
+        }
+    }
+
+    #[test]
+    fn test_downscales() {
+        let options = Options {
+            max_dimension: 300,
+            thumbnail_dimension: 60,
+            quality: 80,
+        };
+
+        let transcoded = transcode(&jpeg(900, 300), &options).unwrap();
+        assert_eq!(
+            (transcoded.image.width, transcoded.image.height),
+            (300, 100)
+        );
+        assert_eq!(
+            (transcoded.thumbnail.width, transcoded.thumbnail.height),
+            (60, 20)
+        );
+        let decoded = decode(&transcoded.image.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (300, 100));
+        assert!(!transcoded.blurhash.is_empty());
+
+        // Small pictures aren't blown up
+        let transcoded = transcode(&jpeg(100, 50), &options).unwrap();
+        assert_eq!((transcoded.image.width, transcoded.image.height), (100, 50));
+    }
+
+    #[test]
+    fn test_flattens_transparency() {
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png)
+            .write_image(
+                RgbaImage::new(8, 8).as_raw(),
+                8,
+                8,
+                image::ExtendedColorType::Rgba8,
+            )
+            .unwrap();
+
+        let transcoded = transcode(&png, &Options::default()).unwrap();
+        let decoded = decode(&transcoded.image.data).unwrap().into_rgb8();
+        // Transparent is white, give or take the JPEG compression
+        assert!(decoded
+            .pixels()
+            .all(|pixel| pixel.0.iter().all(|&c| c > 245)));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert!(transcode(b"not a picture", &Options::default()).is_err());
+    }
+}
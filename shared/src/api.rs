@@ -0,0 +1,607 @@
+//! Types of the totem's HTTP API that are shared between the firmware and the app
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::http::{BodyError, RangeNotSatisfiable};
+use crate::mailbox::Refusal;
+use crate::media::MediaError;
+use crate::model::{Bundle, DeliveryReceipt, LastSeen, Message, Reaction};
+use crate::model::edit::PostVersion;
+use crate::model::validation::ValidationErrors;
+
+/// Machine-readable error codes returned by the totem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The request is missing something or has a malformed body
+    BadRequest,
+    /// The body could not be parsed as the expected JSON document
+    InvalidJson,
+    /// The body was parsed, but some of its fields are invalid
+    ValidationFailed,
+    /// The requested resource doesn't exist
+    NotFound,
+    /// The resource exists already, or conflicts with an existing one
+    Conflict,
+    /// The request didn't say how long its body is
+    LengthRequired,
+    /// The body is bigger than what the endpoint accepts
+    PayloadTooLarge,
+    /// The body has a content type the endpoint doesn't accept
+    UnsupportedMediaType,
+    /// The requested byte range lies outside of the resource
+    RangeNotSatisfiable,
+    /// The request uses a feature the totem doesn't implement
+    NotImplemented,
+    /// Something went wrong on the totem
+    Internal,
+    /// A code this version doesn't know about
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    /// HTTP status code that is sent along with this error code
+    pub fn status(self) -> u16 {
+        match self {
+            ErrorCode::BadRequest | ErrorCode::InvalidJson => 400,
+            ErrorCode::NotFound => 404,
+            ErrorCode::Conflict => 409,
+            ErrorCode::LengthRequired => 411,
+            ErrorCode::PayloadTooLarge => 413,
+            ErrorCode::UnsupportedMediaType => 415,
+            ErrorCode::RangeNotSatisfiable => 416,
+            ErrorCode::ValidationFailed => 422,
+            ErrorCode::NotImplemented => 501,
+            ErrorCode::Internal | ErrorCode::Unknown => 500,
+        }
+    }
+
+    /// Best guess for the error code of a response that didn't carry an error body
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            400 => ErrorCode::BadRequest,
+            404 => ErrorCode::NotFound,
+            409 => ErrorCode::Conflict,
+            411 => ErrorCode::LengthRequired,
+            413 => ErrorCode::PayloadTooLarge,
+            415 => ErrorCode::UnsupportedMediaType,
+            416 => ErrorCode::RangeNotSatisfiable,
+            422 => ErrorCode::ValidationFailed,
+            501 => ErrorCode::NotImplemented,
+            500..=599 => ErrorCode::Internal,
+            _ => ErrorCode::Unknown,
+        }
+    }
+}
+
+/// Additional information about an error, usually about a single field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDetail {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Body of every error response sent by the totem
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(default)]
+    pub details: Vec<ErrorDetail>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiError {
+            code,
+            message: message.into(),
+            details: Vec::new(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::BadRequest, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    /// Internal errors only carry a generic message, the cause is logged on the totem
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    /// Error for a body that isn't valid JSON or doesn't have the expected shape
+    /// `reason` is the description of the parser, which points at the offending position.
+    pub fn invalid_json(reason: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidJson, "Invalid JSON body").with_detail(None, reason)
+    }
+
+    /// Add a detail, optionally naming the field it is about
+    pub fn with_detail(mut self, field: Option<&str>, message: impl Into<String>) -> Self {
+        self.details.push(ErrorDetail {
+            field: field.map(str::to_string),
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.code.status()
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)?;
+        for detail in &self.details {
+            match &detail.field {
+                Some(field) => write!(f, "; {}: {}", field, detail.message)?,
+                None => write!(f, "; {}", detail.message)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<BodyError> for ApiError {
+    fn from(e: BodyError) -> Self {
+        let code = match e {
+            BodyError::LengthRequired => ErrorCode::LengthRequired,
+            BodyError::TooLarge => ErrorCode::PayloadTooLarge,
+            BodyError::UnsupportedEncoding => ErrorCode::NotImplemented,
+            BodyError::Malformed => ErrorCode::BadRequest,
+            // Don't leak the underlying I/O error to the client
+            BodyError::Io(_) => return ApiError::internal("Failed to read request body"),
+        };
+
+        ApiError::new(code, e.to_string())
+    }
+}
+
+impl From<MediaError> for ApiError {
+    fn from(e: MediaError) -> Self {
+        let code = match e {
+            MediaError::InvalidHash => ErrorCode::BadRequest,
+            MediaError::NotFound => ErrorCode::NotFound,
+            MediaError::AlreadyExists => ErrorCode::Conflict,
+            MediaError::OffsetMismatch { expected } => {
+                return ApiError::new(ErrorCode::Conflict, e.to_string())
+                    .with_detail(Some("offset"), expected.to_string());
+            }
+            MediaError::HashMismatch { .. } => ErrorCode::ValidationFailed,
+            MediaError::UnsupportedType => ErrorCode::UnsupportedMediaType,
+            // Don't leak paths on the totem to the client
+            MediaError::Io(_) => return ApiError::internal("Failed to access media"),
+        };
+
+        ApiError::new(code, e.to_string())
+    }
+}
+
+impl From<RangeNotSatisfiable> for ApiError {
+    fn from(_: RangeNotSatisfiable) -> Self {
+        ApiError::new(ErrorCode::RangeNotSatisfiable, "Range not satisfiable")
+    }
+}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(e: ValidationErrors) -> Self {
+        e.errors.into_iter().fold(
+            ApiError::new(ErrorCode::ValidationFailed, "Invalid record"),
+            |err, field| err.with_detail(Some(field.field), field.message),
+        )
+    }
+}
+
+/// Body of POST /users/compare
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct UsersCompareRequest {
+    pub user_uuids: Vec<String>,
+}
+
+/// Body of POST /users/last_seen
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct UsersLastSeenRequest {
+    /// The syncing user at this totem, signed if their ID is derived from a key
+    /// The signature covers the totem's ID, which a first request without it tells.
+    #[serde(default)]
+    pub sighting: Option<LastSeen>,
+    /// Users the client wants to know about, usually its friends
+    #[serde(default)]
+    pub user_uuids: Vec<String>,
+    /// Sightings known to the client
+    #[serde(default)]
+    pub entries: Vec<LastSeen>,
+}
+
+/// Response of POST /users/last_seen
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct UsersLastSeenResponse {
+    pub totem_id: String,
+    /// Sightings of the requested users, followed by the recent visitors of this totem
+    pub entries: Vec<LastSeen>,
+}
+
+/// Body of POST /posts/compare
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostsCompareRequest {
+    /// Start of the compared time range, ISO 8601
+    pub time_start: String,
+    /// End of the compared time range, ISO 8601
+    pub time_end: String,
+    /// Posts the client has in that range
+    pub post_uuids: Vec<String>,
+    /// Versions of the edited ones among them, the others are as first written
+    #[serde(default)]
+    pub versions: HashMap<String, PostVersion>,
+}
+
+/// Body of POST /replies/compare
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepliesCompareRequest {
+    /// Start of the compared time range, ISO 8601
+    pub time_start: String,
+    /// End of the compared time range, ISO 8601
+    pub time_end: String,
+    /// Replies the client has in that range
+    pub reply_uuids: Vec<String>,
+}
+
+/// Response of POST /replies/compare
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RepliesCompareResponse {
+    /// Replies the client has and the totem doesn't, to be uploaded
+    pub totem_missing: Vec<String>,
+    /// Replies the totem has and the client doesn't, to be downloaded
+    pub client_missing: Vec<String>,
+    /// Posts and replies answered by replies of the totem that the totem doesn't have,
+    /// to be uploaded if the client has them
+    #[serde(default)]
+    pub unresolved: Vec<String>,
+}
+
+/// Body of POST /reactions/compare, answered with a [`CompareResponse`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReactionsCompareRequest {
+    /// Start of the compared time range, ISO 8601
+    pub time_start: String,
+    /// End of the compared time range, ISO 8601
+    pub time_end: String,
+    /// Reactions the client has in that range, superseded ones are left out
+    pub reaction_uuids: Vec<String>,
+}
+
+/// Body of POST /reactions/fetch
+/// At most [`crate::reactions::MAX_REACTIONS_PER_REQUEST`] are handed out at once.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ReactionsFetchRequest {
+    pub reaction_uuids: Vec<String>,
+}
+
+/// Response of POST /reactions/fetch and body of POST /reactions/create
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ReactionsBatch {
+    pub reactions: Vec<Reaction>,
+}
+
+/// Response of POST /reactions/create
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ReactionsCreateResponse {
+    /// Reactions that were valid, whether they won over the stored ones or not
+    pub accepted: Vec<String>,
+    /// Reactions that failed validation or had a forged signature
+    pub rejected: Vec<String>,
+}
+
+/// Body of POST /reposts/compare
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepostsCompareRequest {
+    /// Start of the compared time range, ISO 8601
+    pub time_start: String,
+    /// End of the compared time range, ISO 8601
+    pub time_end: String,
+    /// Reposts the client has in that range
+    pub repost_uuids: Vec<String>,
+}
+
+/// Response of POST /reposts/compare
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RepostsCompareResponse {
+    /// Reposts the client has and the totem doesn't, to be uploaded
+    pub totem_missing: Vec<String>,
+    /// Reposts the totem has and the client doesn't, to be downloaded
+    pub client_missing: Vec<String>,
+    /// Posts reposted by reposts of the totem that the totem doesn't have, to be uploaded
+    /// if the client has them
+    #[serde(default)]
+    pub unresolved: Vec<String>,
+}
+
+/// Response of GET /trending, see [`crate::trending`]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TrendingResponse {
+    /// The totem the counts are from, so apps can tell which one they reached
+    #[serde(default)]
+    pub totem_id: String,
+    /// Start of the counted time range, ISO 8601
+    pub since: String,
+    /// Most used hashtags first
+    pub tags: Vec<TrendingTag>,
+    /// Threads with the most replies first
+    pub threads: Vec<TrendingThread>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendingTag {
+    /// In lowercase, without `#`
+    pub tag: String,
+    /// Posts received with it
+    pub posts: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendingThread {
+    /// The post the thread started with
+    pub post_id: String,
+    /// Replies received in it
+    pub replies: u32,
+}
+
+/// Body of POST /rotations/compare
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RotationsCompareRequest {
+    pub rotation_uuids: Vec<String>,
+}
+
+/// Body of POST /tombstones/compare
+/// Every tombstone is compared for as long as it is kept, see `model::tombstone`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TombstonesCompareRequest {
+    pub tombstone_uuids: Vec<String>,
+}
+
+/// Response of POST /users/compare, POST /posts/compare, POST /rotations/compare and
+/// POST /tombstones/compare
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct CompareResponse {
+    /// Records the client has and the totem doesn't, to be uploaded
+    pub totem_missing: Vec<String>,
+    /// Records the totem has and the client doesn't, to be downloaded
+    pub client_missing: Vec<String>,
+}
+
+/// Response of the endpoints creating a record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatedResponse {
+    pub uuid: String,
+}
+
+/// Body of POST /media/compare
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MediaCompareRequest {
+    /// Hashes of the blobs the client has
+    #[serde(default)]
+    pub hashes: Vec<String>,
+    /// Hashes of the blobs the client is missing
+    #[serde(default)]
+    pub wanted: Vec<String>,
+}
+
+/// Response of POST /media/compare
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MediaCompareResponse {
+    /// Blobs the client has and the totem doesn't, to be uploaded
+    pub totem_missing: Vec<String>,
+    /// Wanted blobs the totem has, to be downloaded
+    pub client_missing: Vec<String>,
+}
+
+/// Response of GET /uploads/<sha256>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadStatus {
+    pub name: String,
+    /// Bytes received so far, the next piece of the upload starts here
+    pub offset: u64,
+    /// Whether the blob has been verified and stored
+    pub complete: bool,
+}
+
+/// Response of POST /pic/<sha256>
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PicSaved {
+    pub name: String,
+    /// Bytes received so far, the next piece of an incomplete upload starts here
+    pub size: u64,
+    /// Whether the picture has been verified and stored
+    pub complete: bool,
+}
+
+/// Body of POST /mailbox/deposit, at most [`crate::mailbox::MAX_MAILBOX_BODY`] long
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MailboxDepositRequest {
+    /// Messages to keep until their recipients collect them
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    /// Receipts of delivered messages, the totem drops its copies of them
+    #[serde(default)]
+    pub receipts: Vec<DeliveryReceipt>,
+}
+
+/// A message the totem didn't take
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefusedMessage {
+    pub uuid: String,
+    pub reason: Refusal,
+}
+
+/// Response of POST /mailbox/deposit
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MailboxDepositResponse {
+    /// Messages the totem holds now, including ones it had already
+    pub stored: Vec<String>,
+    pub refused: Vec<RefusedMessage>,
+    /// Receipts the totem didn't know yet
+    pub receipts_accepted: u32,
+}
+
+/// Body of POST /mailbox/collect
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MailboxCollectRequest {
+    /// Mailbox of the collecting phone, see [`crate::model::message::mailbox_of`]
+    pub mailbox: String,
+    /// Messages of the mailbox the phone has already
+    #[serde(default)]
+    pub known: Vec<String>,
+    /// Messages the phone sent and is waiting for receipts of
+    #[serde(default)]
+    pub pending: Vec<String>,
+}
+
+/// Response of POST /mailbox/collect
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MailboxCollectResponse {
+    pub messages: Vec<Message>,
+    /// Receipts of the pending messages, followed by recent ones for the phone to pass on
+    pub receipts: Vec<DeliveryReceipt>,
+    /// Whether more messages are waiting, to be collected with another request
+    pub more: bool,
+}
+
+/// Body of POST /bundles/offer
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct BundlesOfferRequest {
+    /// User of the syncing phone, who gets the bundles addressed to them
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// Bundles the phone carries, the totem doesn't hand out copies of them
+    #[serde(default)]
+    pub carried: Vec<String>,
+    /// Whether the phone carries bundles for others, see [`crate::routing`]
+    #[serde(default)]
+    pub courier: bool,
+}
+
+/// Response of POST /bundles/offer
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct BundlesOfferResponse {
+    pub totem_id: String,
+    /// Carried bundles the totem doesn't have, to be handed over if the rules say so
+    pub wanted: Vec<String>,
+    /// Bundles addressed to the phone's user, and copies for couriers
+    pub bundles: Vec<Bundle>,
+}
+
+/// Body of POST /bundles/deliver, at most [`crate::routing::MAX_BUNDLE_BODY`] long
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct BundlesDeliverRequest {
+    pub bundles: Vec<Bundle>,
+}
+
+/// Response of POST /bundles/deliver
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct BundlesDeliverResponse {
+    /// Bundles delivered to the totem or taken into its custody
+    pub accepted: Vec<String>,
+    /// Bundles that failed validation or didn't fit, the phone keeps carrying them
+    pub refused: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_codes_round_trip() {
+        let codes = [
+            ErrorCode::BadRequest,
+            ErrorCode::NotFound,
+            ErrorCode::Conflict,
+            ErrorCode::LengthRequired,
+            ErrorCode::PayloadTooLarge,
+            ErrorCode::UnsupportedMediaType,
+            ErrorCode::RangeNotSatisfiable,
+            ErrorCode::ValidationFailed,
+            ErrorCode::NotImplemented,
+            ErrorCode::Internal,
+        ];
+
+        for code in codes {
+            assert_eq!(ErrorCode::from_status(code.status()), code);
+        }
+
+        assert_eq!(ErrorCode::InvalidJson.status(), 400);
+        assert_eq!(ErrorCode::from_status(418), ErrorCode::Unknown);
+    }
+
+    #[test]
+    fn test_from_body_error() {
+        let err = ApiError::from(BodyError::TooLarge);
+        assert_eq!(err.code, ErrorCode::PayloadTooLarge);
+        assert_eq!(err.status(), 413);
+
+        let io = std::io::Error::other("socket 3 reset by peer");
+        let err = ApiError::from(BodyError::Io(io));
+        assert_eq!(err.status(), 500);
+        assert!(!err.message.contains("socket"));
+    }
+
+    #[test]
+    fn test_wire_format() {
+        let err = ApiError::not_found("Post not found");
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"code":"not_found","message":"Post not found","details":[]}"#
+        );
+
+        // Codes from newer totems and missing details don't break older apps
+        let parsed: ApiError =
+            serde_json::from_str(r#"{"code":"rate_limited","message":"Slow down"}"#).unwrap();
+        assert_eq!(parsed.code, ErrorCode::Unknown);
+        assert!(parsed.details.is_empty());
+    }
+
+    #[test]
+    fn test_from_validation_errors() {
+        let user = crate::model::User {
+            uuid: "nope".to_string(),
+            username: "".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: chrono::Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        };
+
+        let err = ApiError::from(user.validate().unwrap_err());
+        assert_eq!(err.status(), 422);
+
+        let fields: Vec<_> = err
+            .details
+            .iter()
+            .filter_map(|d| d.field.as_deref())
+            .collect();
+        assert_eq!(fields, ["uuid", "username"]);
+    }
+
+    #[test]
+    fn test_display() {
+        let err = ApiError::new(ErrorCode::ValidationFailed, "Invalid post")
+            .with_detail(Some("title"), "too long")
+            .with_detail(None, "try again");
+
+        assert_eq!(
+            err.to_string(),
+            "ValidationFailed: Invalid post; title: too long; try again"
+        );
+    }
+}
@@ -1,12 +1,267 @@
-use crate::model::{Post, Totem, User};
+use crate::model::{
+    Attachment, AttachmentKind, Bundle, BundleHeader, BundlePayload, DeliveryReceipt, Destination,
+    ImagePreview, KeyRotation, LastSeen, Message, Post, Reaction, ReactionKind, Reply, Repost,
+    RotationKind, Tombstone, Totem, User,
+};
+use crate::model::tombstone::TOMBSTONE_TTL;
+use crate::identity::FINGERPRINT_LEN;
+use crate::model::edit::PostVersion;
+use crate::model::entities::{normalize_tag, Entities, Mention};
+use crate::reactions::ReactionCounts;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef};
+use std::collections::HashMap;
 use chrono::{DateTime, Utc};
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, Row, ToSql, params};
 use std::string::String;
 
 pub struct Database {
     connection: Connection,
 }
 
+/// Add a column to a table created by an older version of the app
+fn add_column(conn: &Connection, table: &str, column: &str) -> rusqlite::Result<()> {
+    let name = column.split_whitespace().next().unwrap_or(column);
+    let exists = conn
+        .prepare(&format!(
+            "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+            table
+        ))?
+        .exists(params![name])?;
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {}", table, column), ())?;
+    }
+    Ok(())
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1")?
+        .exists(params![table])
+}
+
+/// Read a preview stored in three columns, starting at `idx`
+fn preview_from_row(row: &Row, idx: usize) -> rusqlite::Result<Option<ImagePreview>> {
+    let blurhash: Option<String> = row.get(idx)?;
+    let width: Option<u32> = row.get(idx + 1)?;
+    let height: Option<u32> = row.get(idx + 2)?;
+
+    Ok(match (blurhash, width, height) {
+        (Some(blurhash), Some(width), Some(height)) => Some(ImagePreview {
+            blurhash,
+            width,
+            height,
+        }),
+        _ => None,
+    })
+}
+
+impl ToSql for AttachmentKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for AttachmentKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
+
+impl ToSql for RotationKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for RotationKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
+
+impl ToSql for ReactionKind {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for ReactionKind {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|e: String| FromSqlError::Other(e.into()))
+    }
+}
+
+fn key_rotation_from_row(row: &Row) -> rusqlite::Result<KeyRotation> {
+    Ok(KeyRotation {
+        uuid: row.get(0)?,
+        user_id: row.get(1)?,
+        identity_key: row.get(2)?,
+        sequence: row.get(3)?,
+        kind: row.get(4)?,
+        new_key: row.get(5)?,
+        recovery_key: row.get(6)?,
+        timestamp: row.get(7)?,
+        signature: row.get(8)?,
+    })
+}
+
+const POST_COLUMNS: &str = "uuid, user_id, title, body, timestamp, image, source_totem, thumbnail,
+    image_blurhash, image_width, image_height, signature, revision, edited_at";
+
+/// Read a post without its attachments, which are stored in their own table
+fn post_from_row(row: &Row) -> rusqlite::Result<Post> {
+    Ok(Post {
+        uuid: row.get(0)?,
+        user_id: row.get(1)?,
+        title: row.get(2)?,
+        body: row.get(3)?,
+        timestamp: row.get(4)?,
+        image: row.get(5)?,
+        source_totem: row.get(6)?,
+        thumbnail: row.get(7)?,
+        image_preview: preview_from_row(row, 8)?,
+        attachments: Vec::new(),
+        revision: row.get(12)?,
+        edited_at: row.get(13)?,
+        signature: row.get(11)?,
+    })
+}
+
+const MESSAGE_COLUMNS: &str =
+    "uuid, sender_id, recipient_id, sender_key, recipient_key, timestamp, nonce, ciphertext";
+
+fn message_from_row(row: &Row) -> rusqlite::Result<Message> {
+    Ok(Message {
+        uuid: row.get(0)?,
+        sender_id: row.get(1)?,
+        recipient_id: row.get(2)?,
+        sender_key: row.get(3)?,
+        recipient_key: row.get(4)?,
+        timestamp: row.get(5)?,
+        nonce: row.get(6)?,
+        ciphertext: row.get(7)?,
+    })
+}
+
+const REPLY_COLUMNS: &str = "uuid, user_id, parent_id, root_id, body, timestamp, signature";
+
+fn reply_from_row(row: &Row) -> rusqlite::Result<Reply> {
+    Ok(Reply {
+        uuid: row.get(0)?,
+        user_id: row.get(1)?,
+        parent_id: row.get(2)?,
+        root_id: row.get(3)?,
+        body: row.get(4)?,
+        timestamp: row.get(5)?,
+        signature: row.get(6)?,
+    })
+}
+
+const REACTION_COLUMNS: &str = "uuid, user_id, post_id, kind, active, timestamp, signature";
+
+fn reaction_from_row(row: &Row) -> rusqlite::Result<Reaction> {
+    Ok(Reaction {
+        uuid: row.get(0)?,
+        user_id: row.get(1)?,
+        post_id: row.get(2)?,
+        kind: row.get(3)?,
+        active: row.get(4)?,
+        timestamp: row.get(5)?,
+        signature: row.get(6)?,
+    })
+}
+
+const REPOST_COLUMNS: &str = "uuid, user_id, post_id, author_id, timestamp, signature";
+
+fn repost_from_row(row: &Row) -> rusqlite::Result<Repost> {
+    Ok(Repost {
+        uuid: row.get(0)?,
+        user_id: row.get(1)?,
+        post_id: row.get(2)?,
+        author_id: row.get(3)?,
+        timestamp: row.get(4)?,
+        signature: row.get(5)?,
+    })
+}
+
+const TOMBSTONE_COLUMNS: &str = "uuid, user_id, post_id, timestamp, signature";
+
+fn tombstone_from_row(row: &Row) -> rusqlite::Result<Tombstone> {
+    Ok(Tombstone {
+        uuid: row.get(0)?,
+        user_id: row.get(1)?,
+        post_id: row.get(2)?,
+        timestamp: row.get(3)?,
+        signature: row.get(4)?,
+    })
+}
+
+const BUNDLE_COLUMNS: &str =
+    "uuid, destination_kind, destination, created, ttl_secs, hop_limit, hops, copies, payload";
+
+fn bundle_from_row(row: &Row) -> rusqlite::Result<Bundle> {
+    let kind: String = row.get(1)?;
+    let id: String = row.get(2)?;
+    let destination = match kind.as_str() {
+        "totem" => Destination::Totem(id),
+        "user" => Destination::User(id),
+        other => {
+            return Err(rusqlite::Error::FromSqlConversionFailure(
+                1,
+                rusqlite::types::Type::Text,
+                format!("unknown destination kind: {}", other).into(),
+            ));
+        }
+    };
+    let payload: Vec<u8> = row.get(8)?;
+    let payload: BundlePayload = postcard::from_bytes(&payload).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(8, rusqlite::types::Type::Blob, e.into())
+    })?;
+
+    Ok(Bundle {
+        uuid: row.get(0)?,
+        header: BundleHeader {
+            destination,
+            created: row.get(3)?,
+            ttl_secs: row.get(4)?,
+            hop_limit: row.get(5)?,
+            hops: row.get(6)?,
+            copies: row.get(7)?,
+        },
+        payload,
+    })
+}
+
+fn delivery_receipt_from_row(row: &Row) -> rusqlite::Result<DeliveryReceipt> {
+    Ok(DeliveryReceipt {
+        message_uuid: row.get(0)?,
+        recipient_key: row.get(1)?,
+        timestamp: row.get(2)?,
+        signature: row.get(3)?,
+    })
+}
+
+fn attachment_from_row(row: &Row) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        kind: row.get(0)?,
+        mime: row.get(1)?,
+        hash: row.get(2)?,
+        size: row.get(3)?,
+        duration_ms: row.get(4)?,
+        width: row.get(5)?,
+        height: row.get(6)?,
+        alt_text: row.get(7)?,
+    })
+}
+
 impl Database {
     pub fn new(path: String) -> Database {
         let conn = Connection::open(path).unwrap();
@@ -21,11 +276,25 @@ impl Database {
             status TEXT NOT NULL,
             bio  TEXT NOT NULL,
             profile_picture,
-            last_contact TEXT NOT NULL
+            last_contact TEXT NOT NULL,
+            profile_picture_blurhash TEXT,
+            profile_picture_width INTEGER,
+            profile_picture_height INTEGER,
+            public_key TEXT,
+            signature TEXT
         )",
             (),
         )
         .expect("Failed to create users table.");
+        for column in [
+            "profile_picture_blurhash TEXT",
+            "profile_picture_width INTEGER",
+            "profile_picture_height INTEGER",
+            "public_key TEXT",
+            "signature TEXT",
+        ] {
+            add_column(&conn, "users", column).expect("Failed to migrate users table.");
+        }
 
         //totems
         conn.execute(
@@ -49,47 +318,414 @@ impl Database {
             timestamp TEXT NOT NULL,
             image TEXT,
             source_totem TEXT,
+            thumbnail TEXT,
+            image_blurhash TEXT,
+            image_width INTEGER,
+            image_height INTEGER,
+            signature TEXT,
+            revision INTEGER NOT NULL DEFAULT 0,
+            edited_at TEXT,
             FOREIGN KEY (user_id) REFERENCES users(uuid),
             FOREIGN KEY (source_totem) REFERENCES totems(uuid)
         )",
             (),
         )
         .expect("Failed to create posts table.");
+        for column in [
+            "thumbnail TEXT",
+            "image_blurhash TEXT",
+            "image_width INTEGER",
+            "image_height INTEGER",
+            "signature TEXT",
+            "revision INTEGER NOT NULL DEFAULT 0",
+            "edited_at TEXT",
+        ] {
+            add_column(&conn, "posts", column).expect("Failed to migrate posts table.");
+        }
+
+        // Versions of posts replaced by edits or that lost against them, see `model::edit`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS post_history (
+            post_id  TEXT NOT NULL,
+            revision  INTEGER NOT NULL,
+            record  BLOB NOT NULL,
+            PRIMARY KEY (post_id, record)
+        )",
+            (),
+        )
+        .expect("Failed to create post_history table.");
+
+        // last seen, newest sighting per user and totem
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS last_seen (
+            user_id  TEXT NOT NULL,
+            totem_id  TEXT NOT NULL,
+            seen_at TEXT NOT NULL,
+            signature TEXT,
+            PRIMARY KEY (user_id, totem_id)
+        )",
+            (),
+        )
+        .expect("Failed to create last_seen table.");
+
+        // media, where blobs referenced by content hash are stored on this device
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS media (
+            hash  TEXT PRIMARY KEY,
+            local_path  TEXT NOT NULL
+        )",
+            (),
+        )
+        .expect("Failed to create media table.");
+
+        // attachments of posts, in the order they are shown
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS attachments (
+            post_id  TEXT NOT NULL,
+            position  INTEGER NOT NULL,
+            kind  TEXT NOT NULL,
+            mime  TEXT NOT NULL,
+            hash  TEXT NOT NULL,
+            size  INTEGER NOT NULL,
+            duration_ms INTEGER,
+            width INTEGER,
+            height INTEGER,
+            alt_text TEXT,
+            PRIMARY KEY (post_id, position),
+            FOREIGN KEY (post_id) REFERENCES posts(uuid)
+        )",
+            (),
+        )
+        .expect("Failed to create attachments table.");
+
+        // key rotations of users, competing ones included, see `model::rotation`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS key_rotations (
+            uuid  TEXT PRIMARY KEY,
+            user_id  TEXT NOT NULL,
+            identity_key  TEXT NOT NULL,
+            sequence  INTEGER NOT NULL,
+            kind  TEXT NOT NULL,
+            new_key  TEXT NOT NULL,
+            recovery_key TEXT,
+            timestamp TEXT NOT NULL,
+            signature  TEXT NOT NULL
+        )",
+            (),
+        )
+        .expect("Failed to create key_rotations table.");
+
+        // Random user IDs from before identities, and the derived ID their user moved to
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_links (
+            legacy_id  TEXT PRIMARY KEY,
+            user_id  TEXT NOT NULL
+        )",
+            (),
+        )
+        .expect("Failed to create user_links table.");
+
+        // Direct messages, only ever stored encrypted
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS messages (
+            uuid  TEXT PRIMARY KEY,
+            sender_id  TEXT NOT NULL,
+            recipient_id  TEXT NOT NULL,
+            sender_key  TEXT NOT NULL,
+            recipient_key  TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            nonce  TEXT NOT NULL,
+            ciphertext  TEXT NOT NULL
+        )",
+            (),
+        )
+        .expect("Failed to create messages table.");
+
+        // Receipts of delivered messages, passed on to totems so they drop their copies
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS delivery_receipts (
+            message_uuid  TEXT NOT NULL,
+            recipient_key  TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            signature  TEXT NOT NULL,
+            PRIMARY KEY (message_uuid, recipient_key)
+        )",
+            (),
+        )
+        .expect("Failed to create delivery_receipts table.");
+
+        // Bundles carried for others in courier mode, see `crate::routing`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bundles (
+            uuid  TEXT PRIMARY KEY,
+            destination_kind  TEXT NOT NULL,
+            destination  TEXT NOT NULL,
+            created TEXT NOT NULL,
+            ttl_secs  INTEGER NOT NULL,
+            hop_limit  INTEGER NOT NULL,
+            hops  INTEGER NOT NULL,
+            copies  INTEGER NOT NULL,
+            payload  BLOB NOT NULL
+        )",
+            (),
+        )
+        .expect("Failed to create bundles table.");
+
+        // Replies to posts, kept even if the post or the reply they answer isn't known yet
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS replies (
+            uuid  TEXT PRIMARY KEY,
+            user_id  TEXT NOT NULL,
+            parent_id  TEXT NOT NULL,
+            root_id  TEXT NOT NULL,
+            body  TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            signature TEXT
+        )",
+            (),
+        )
+        .expect("Failed to create replies table.");
+
+        // Number of replies in the thread of each post, counted as they arrive
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS reply_counts (
+            post_id  TEXT PRIMARY KEY,
+            count  INTEGER NOT NULL
+        )",
+            (),
+        )
+        .expect("Failed to create reply_counts table.");
+
+        // The reaction that counts per user, post and kind, see `crate::reactions`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS reactions (
+            uuid  TEXT NOT NULL UNIQUE,
+            user_id  TEXT NOT NULL,
+            post_id  TEXT NOT NULL,
+            kind  TEXT NOT NULL,
+            active  INTEGER NOT NULL,
+            timestamp TEXT NOT NULL,
+            signature TEXT,
+            PRIMARY KEY (user_id, post_id, kind)
+        )",
+            (),
+        )
+        .expect("Failed to create reactions table.");
+
+        // Reposts, kept even if the reposted post isn't known yet
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS reposts (
+            uuid  TEXT PRIMARY KEY,
+            user_id  TEXT NOT NULL,
+            post_id  TEXT NOT NULL,
+            author_id  TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            signature TEXT
+        )",
+            (),
+        )
+        .expect("Failed to create reposts table.");
+
+        // Deleted posts, kept until they expire so the posts aren't taken again, see `model::tombstone`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tombstones (
+            uuid  TEXT PRIMARY KEY,
+            user_id  TEXT NOT NULL,
+            post_id  TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            signature TEXT
+        )",
+            (),
+        )
+        .expect("Failed to create tombstones table.");
+
+        // Tags and mentions in the bodies of posts, see `model::entities`
+        let index_posts =
+            !table_exists(&conn, "post_tags").expect("Failed to look up post_tags table.");
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS post_tags (
+            post_id  TEXT NOT NULL,
+            tag  TEXT NOT NULL,
+            PRIMARY KEY (tag, post_id)
+        )",
+            (),
+        )
+        .expect("Failed to create post_tags table.");
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS post_mentions (
+            post_id  TEXT NOT NULL,
+            kind  TEXT NOT NULL,
+            value  TEXT NOT NULL,
+            PRIMARY KEY (kind, value, post_id)
+        )",
+            (),
+        )
+        .expect("Failed to create post_mentions table.");
 
-        Database { connection: conn }
+        let db = Database { connection: conn };
+        // Posts stored by an older version of the app
+        if index_posts {
+            db.index_all_posts().expect("Failed to index posts.");
+        }
+        db
     }
 
     pub fn create_user(&self, user: &User) {
         self.connection
             .execute(
-                "INSERT OR IGNORE INTO users (uuid, username, status, bio, profile_picture, last_contact) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                "INSERT OR IGNORE INTO users (uuid, username, status, bio, profile_picture, last_contact,
+                    profile_picture_blurhash, profile_picture_width, profile_picture_height, public_key,
+                    signature)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
                 (
                     &user.uuid.to_string(),
                     &user.username.to_string(),
                     &user.status.to_string(),
                     &user.bio.to_string(),
                     &user.profile_picture.as_ref().map(|i| i.to_string()),
-                    &user.last_contact
+                    &user.last_contact,
+                    user.profile_picture_preview.as_ref().map(|p| &p.blurhash),
+                    user.profile_picture_preview.as_ref().map(|p| p.width),
+                    user.profile_picture_preview.as_ref().map(|p| p.height),
+                    &user.public_key,
+                    &user.signature,
                 ),
             )
             .expect("Failed to create user.");
     }
 
     pub fn create_post(&self, post: &Post) {
+        self.merge_post(post).expect("Failed to create post.");
+    }
+
+    /// Store a version of a post, see `model::edit`
+    /// Returns whether it's the version shown now. The version it replaced, or the version
+    /// itself if it lost against the stored one, is kept in the history of the post.
+    pub fn merge_post(&self, post: &Post) -> rusqlite::Result<bool> {
+        let tx = self.connection.unchecked_transaction()?;
+        let stored = match self.get_post_by_id(&post.uuid) {
+            Ok(stored) => Some(stored),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(e),
+        };
+
+        if self.is_post_deleted(&post.uuid, &post.user_id)? {
+            return Ok(false);
+        }
+
+        match stored {
+            // Versions changing the author or time are forged, not edits
+            Some(stored) if stored.same_version(post) || post.check_edit_of(&stored).is_err() => {
+                return Ok(false);
+            }
+            Some(stored) if !post.supersedes(&stored) => {
+                self.archive_post(post)?;
+                tx.commit()?;
+                return Ok(false);
+            }
+            Some(stored) => {
+                self.archive_post(&stored)?;
+                self.delete_post_rows(&post.uuid, &["attachments", "post_tags", "post_mentions"])?;
+            }
+            None => {}
+        }
+
+        self.connection.execute(
+            &format!("INSERT INTO posts ({POST_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)"),
+            params![
+                &post.uuid,
+                &post.user_id,
+                &post.title,
+                &post.body,
+                &post.timestamp,
+                &post.image,
+                &post.source_totem,
+                &post.thumbnail,
+                post.image_preview.as_ref().map(|p| &p.blurhash),
+                post.image_preview.as_ref().map(|p| p.width),
+                post.image_preview.as_ref().map(|p| p.height),
+                &post.signature,
+                post.revision,
+                &post.edited_at,
+            ],
+        )?;
+
+        self.index_entities(&post.uuid, &post.body)?;
+
+        let mut stmt = self.connection.prepare(
+            "INSERT INTO attachments (post_id, position, kind, mime, hash, size, duration_ms, width, height, alt_text)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        )?;
+        for (position, attachment) in post.attachments.iter().enumerate() {
+            stmt.execute(params![
+                &post.uuid,
+                position,
+                attachment.kind,
+                &attachment.mime,
+                &attachment.hash,
+                attachment.size,
+                attachment.duration_ms,
+                attachment.width,
+                attachment.height,
+                &attachment.alt_text,
+            ])?;
+        }
+        drop(stmt);
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Delete a post along with the rows of `tables` belonging to it
+    fn delete_post_rows(&self, uuid: &str, tables: &[&str]) -> rusqlite::Result<()> {
+        for table in tables {
+            self.connection.execute(
+                &format!("DELETE FROM {} WHERE post_id = ?1", table),
+                params![uuid],
+            )?;
+        }
         self.connection
-            .execute(
-                "INSERT OR IGNORE INTO posts (uuid, user_id, title, body, timestamp, image, source_totem) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                (
-                    &post.uuid.to_string(),
-                    &post.user_id.to_string(),
-                    &post.title.to_string(),
-                    &post.body.to_string(),
-                    &post.timestamp,
-                    post.image.as_ref().map(|i| i.to_string()),
-                    post.source_totem.as_ref().map(|i| i.to_string()),
-                ),
-            )
-            .expect("Failed to create post.");
+            .execute("DELETE FROM posts WHERE uuid = ?1", params![uuid])?;
+        Ok(())
+    }
+
+    fn archive_post(&self, post: &Post) -> rusqlite::Result<()> {
+        let record = postcard::to_allocvec(post)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        self.connection.execute(
+            "INSERT OR IGNORE INTO post_history (post_id, revision, record) VALUES (?1, ?2, ?3)",
+            params![&post.uuid, post.revision, record],
+        )?;
+        Ok(())
+    }
+
+    /// Every stored version of a post, the one shown now included, oldest first
+    pub fn get_post_history(&self, uuid: &str) -> rusqlite::Result<Vec<Post>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT record FROM post_history WHERE post_id = ?1")?;
+        let iter = stmt.query_map(params![uuid], |row| {
+            let record: Vec<u8> = row.get(0)?;
+            postcard::from_bytes::<Post>(&record).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Blob, e.into())
+            })
+        })?;
+        let mut versions = iter.collect::<rusqlite::Result<Vec<Post>>>()?;
+        match self.get_post_by_id(uuid) {
+            Ok(post) => versions.push(post),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {}
+            Err(e) => return Err(e),
+        }
+        versions.sort_by(|a, b| {
+            if a.supersedes(b) {
+                std::cmp::Ordering::Greater
+            } else if b.supersedes(a) {
+                std::cmp::Ordering::Less
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        });
+        Ok(versions)
     }
 
     pub fn create_totem(&self, totem: &Totem) {
@@ -106,11 +742,11 @@ impl Database {
             .expect("Failed to create totem.");
     }
 
-    /// Returns the IDs of all known posts in the given time range
+    /// Returns the IDs of all known posts written or edited in the given time range
     pub fn get_post_ids_in_range(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<String> {
         let mut stmt = self
             .connection
-            .prepare("SELECT uuid FROM posts WHERE timestamp >= ?1 AND timestamp <= ?2")
+            .prepare("SELECT uuid FROM posts WHERE COALESCE(edited_at, timestamp) >= ?1 AND COALESCE(edited_at, timestamp) <= ?2")
             .expect("Failed to prepare query");
 
         // 2. Map the rows
@@ -168,28 +804,123 @@ impl Database {
     }
 
     pub fn get_post_by_id(&self, uuid: &str) -> rusqlite::Result<Post> {
-        return self.connection.query_row(
-            "SELECT uuid, user_id, title, body, timestamp, image, source_totem
-                FROM posts
-                WHERE uuid = ?1",
+        let mut post = self.connection.query_row(
+            &format!("SELECT {POST_COLUMNS} FROM posts WHERE uuid = ?1"),
             params![uuid],
-            |row| {
-                Ok(Post {
-                    uuid: row.get(0).unwrap(),
-                    user_id: row.get(1).unwrap(),
-                    title: row.get(2).unwrap(),
-                    body: row.get(3).unwrap(),
-                    timestamp: row.get(4).unwrap(), // DateTime<Utc> works natively with feature
-                    image: row.get(5).unwrap(),
-                    source_totem: row.get(6).unwrap(),
-                })
-            },
-        );
+            post_from_row,
+        )?;
+
+        post.attachments = self.get_attachments(uuid)?;
+        Ok(post)
+    }
+
+    /// Versions of the posts edited in the given time range
+    pub fn get_post_versions_in_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> rusqlite::Result<HashMap<String, PostVersion>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT uuid, revision, edited_at, signature FROM posts
+             WHERE revision > 0 AND edited_at >= ?1 AND edited_at <= ?2",
+        )?;
+        let iter = stmt.query_map(params![start, end], |row| {
+            let version = PostVersion {
+                revision: row.get(1)?,
+                edited_at: row.get(2)?,
+                signature: row.get(3)?,
+            };
+            Ok((row.get(0)?, version))
+        })?;
+        iter.collect()
+    }
+
+    fn index_entities(&self, post_id: &str, body: &str) -> rusqlite::Result<()> {
+        let entities = Entities::parse(body);
+        for tag in &entities.tags {
+            self.connection.execute(
+                "INSERT OR IGNORE INTO post_tags (post_id, tag) VALUES (?1, ?2)",
+                params![post_id, tag],
+            )?;
+        }
+        for mention in &entities.mentions {
+            let (kind, value) = match mention {
+                Mention::Handle(handle) => ("handle", handle),
+                Mention::Key(fingerprint) => ("key", fingerprint),
+            };
+            self.connection.execute(
+                "INSERT OR IGNORE INTO post_mentions (post_id, kind, value) VALUES (?1, ?2, ?3)",
+                params![post_id, kind, value],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn index_all_posts(&self) -> rusqlite::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        let posts: Vec<(String, String)> = {
+            let mut stmt = self.connection.prepare("SELECT uuid, body FROM posts")?;
+            let iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            iter.collect::<rusqlite::Result<_>>()?
+        };
+        for (uuid, body) in posts {
+            self.index_entities(&uuid, &body)?;
+        }
+        tx.commit()
+    }
+
+    fn get_posts_where(&self, sql: &str, params: &[&dyn ToSql]) -> rusqlite::Result<Vec<Post>> {
+        let ids: Vec<String> = {
+            let mut stmt = self.connection.prepare(sql)?;
+            let iter = stmt.query_map(params, |row| row.get(0))?;
+            iter.collect::<rusqlite::Result<_>>()?
+        };
+        ids.iter().map(|uuid| self.get_post_by_id(uuid)).collect()
+    }
+
+    /// Posts tagged with `tag`, newest first
+    /// The tag may be given as typed, with `#` or in another case.
+    pub fn get_posts_with_tag(&self, tag: &str) -> rusqlite::Result<Vec<Post>> {
+        self.get_posts_where(
+            "SELECT posts.uuid FROM posts JOIN post_tags ON post_tags.post_id = posts.uuid
+             WHERE post_tags.tag = ?1 ORDER BY posts.timestamp DESC",
+            params![normalize_tag(tag)],
+        )
+    }
+
+    /// Posts mentioning `user` by username or key fingerprint, newest first
+    pub fn get_posts_mentioning(&self, user: &User) -> rusqlite::Result<Vec<Post>> {
+        let fingerprint = user
+            .public_key
+            .as_deref()
+            .map(|key| key.chars().take(FINGERPRINT_LEN).collect::<String>());
+        self.get_posts_where(
+            "SELECT posts.uuid FROM posts WHERE posts.uuid IN (
+                SELECT post_id FROM post_mentions
+                WHERE (kind = 'handle' AND value = ?1) OR (kind = 'key' AND value = ?2)
+             ) ORDER BY posts.timestamp DESC",
+            params![user.username.to_lowercase(), fingerprint],
+        )
+    }
+
+    /// The attachments of a post, in the order they are shown
+    pub fn get_attachments(&self, post_id: &str) -> rusqlite::Result<Vec<Attachment>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT kind, mime, hash, size, duration_ms, width, height, alt_text
+                FROM attachments
+                WHERE post_id = ?1
+                ORDER BY position",
+        )?;
+
+        let iter = stmt.query_map(params![post_id], attachment_from_row)?;
+        iter.collect()
     }
 
     pub fn get_user_by_id(&self, uuid: &str) -> rusqlite::Result<User> {
-        return self.connection.query_row(
-            "SELECT uuid, username, status, bio, profile_picture, last_contact FROM users WHERE uuid = ?1",
+        self.connection.query_row(
+            "SELECT uuid, username, status, bio, profile_picture, last_contact,
+                profile_picture_blurhash, profile_picture_width, profile_picture_height, public_key,
+                signature FROM users WHERE uuid = ?1",
             params![uuid],
             |row| {
                 Ok(User {
@@ -200,14 +931,19 @@ impl Database {
                     profile_picture: row.get::<_, Option<String>>(4)?
                         .map(|s| s.parse().expect("Failed to parse image string")),
                     last_contact: row.get(5)?,
+                    profile_picture_preview: preview_from_row(row, 6)?,
+                    public_key: row.get(9)?,
+                    signature: row.get(10)?,
                 })
             },
-        );
+        )
     }
 
     pub fn get_all_users(&self) -> rusqlite::Result<Vec<User>> {
         let mut stmt = self.connection.prepare(
-            "SELECT uuid, username, status, bio, profile_picture, last_contact FROM users"
+            "SELECT uuid, username, status, bio, profile_picture, last_contact,
+                profile_picture_blurhash, profile_picture_width, profile_picture_height, public_key,
+                signature FROM users",
         )?;
 
         let iter = stmt.query_map([], |row| {
@@ -219,6 +955,9 @@ impl Database {
                 profile_picture: row.get::<_, Option<String>>(4)?
                     .map(|s| s.parse().expect("Failed to parse image string")),
                 last_contact: row.get(5)?,
+                profile_picture_preview: preview_from_row(row, 6)?,
+                public_key: row.get(9)?,
+                signature: row.get(10)?,
             })
         })?;
 
@@ -243,23 +982,33 @@ impl Database {
     }
 
     pub fn get_all_posts(&self) -> rusqlite::Result<Vec<Post>> {
+        let mut stmt = self
+            .connection
+            .prepare(&format!("SELECT {POST_COLUMNS} FROM posts"))?;
+
+        let iter = stmt.query_map([], post_from_row)?;
+        let mut posts = iter.collect::<rusqlite::Result<Vec<Post>>>()?;
+
+        // Read all attachments at once instead of querying them post by post
         let mut stmt = self.connection.prepare(
-            "SELECT uuid, user_id, title, body, timestamp, image, source_totem FROM posts"
+            "SELECT kind, mime, hash, size, duration_ms, width, height, alt_text, post_id
+                FROM attachments
+                ORDER BY post_id, position",
         )?;
-
-        let iter = stmt.query_map([], |row| {
-            Ok(Post {
-                uuid: row.get(0).unwrap(),
-                user_id: row.get(1).unwrap(),
-                title: row.get(2).unwrap(),
-                body: row.get(3).unwrap(),
-                timestamp: row.get(4).unwrap(),
-                image: row.get(5).unwrap(),
-                source_totem: row.get(6).unwrap(),
-            })
+        let mut attachments: HashMap<String, Vec<Attachment>> = HashMap::new();
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(8)?, attachment_from_row(row)?))
         })?;
+        for row in rows {
+            let (post_id, attachment) = row?;
+            attachments.entry(post_id).or_default().push(attachment);
+        }
 
-        iter.collect()
+        for post in &mut posts {
+            post.attachments = attachments.remove(&post.uuid).unwrap_or_default();
+        }
+
+        Ok(posts)
     }
 
     pub fn update_totem_last_contact(&self, uuid: &str, last_contact: DateTime<Utc>) -> rusqlite::Result<()> {
@@ -278,38 +1027,725 @@ impl Database {
                  status = ?2,
                  bio = ?3,
                  profile_picture = ?4,
-                 last_contact = ?5
-             WHERE uuid = ?6",
+                 last_contact = ?5,
+                 profile_picture_blurhash = ?6,
+                 profile_picture_width = ?7,
+                 profile_picture_height = ?8,
+                 public_key = ?9,
+                 signature = ?10
+             WHERE uuid = ?11",
             params![
                 &user.username.as_str(),
                 &user.status.as_str(),
                 &user.bio.as_str(),
                 &user.profile_picture.as_ref().map(|i| i.to_string()),
                 &user.last_contact,
+                user.profile_picture_preview.as_ref().map(|p| &p.blurhash),
+                user.profile_picture_preview.as_ref().map(|p| p.width),
+                user.profile_picture_preview.as_ref().map(|p| p.height),
+                &user.public_key,
+                &user.signature,
                 &user.uuid.as_str()
             ],
         )?;
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::TimeDelta;
-    use std::ops::{Add, Sub};
+    /// Move a user with a random ID from before identities to their derived ID
+    /// The legacy profile and the records under it stay, they are known by that ID on
+    /// totems and other phones, but looking up their author leads to `user` from now on.
+    pub fn migrate_user(&self, legacy_id: &str, user: &User) -> rusqlite::Result<()> {
+        let tx = self.connection.unchecked_transaction()?;
+        self.create_user(user);
+        self.link_user(legacy_id, &user.uuid)?;
+        tx.commit()
+    }
 
-    #[test]
-    fn test_write_read() {
-        let db = Database::new(":memory:".to_string());
+    /// Record that the user of `legacy_id` moved to `user_id`, see [`Self::migrate_user`]
+    pub fn link_user(&self, legacy_id: &str, user_id: &str) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO user_links (legacy_id, user_id) VALUES (?1, ?2)",
+            params![legacy_id, user_id],
+        )?;
+        Ok(())
+    }
 
-        let user = User {
-            uuid: "550e8400-e29b-41d4-a716-446655440000".try_into().unwrap(),
-            username: "tag".try_into().unwrap(),
+    /// The derived ID a legacy ID moved to, if it did
+    pub fn get_linked_user_id(&self, legacy_id: &str) -> rusqlite::Result<Option<String>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT user_id FROM user_links WHERE legacy_id = ?1")?;
+        let mut rows = stmt.query_map(params![legacy_id], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    /// Every legacy ID that moved, with the derived ID it moved to
+    pub fn get_user_links(&self) -> rusqlite::Result<Vec<(String, String)>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT legacy_id, user_id FROM user_links")?;
+        let iter = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        iter.collect()
+    }
+
+    /// The profile behind the records of `user_id`, the one it moved to for a legacy ID
+    pub fn get_author(&self, user_id: &str) -> rusqlite::Result<User> {
+        let linked = self.get_linked_user_id(user_id)?;
+        self.get_user_by_id(linked.as_deref().unwrap_or(user_id))
+    }
+
+    /// The author records of `user_id` are checked against, with their key rotations
+    /// Follows the link of a legacy ID like [`Self::get_author`], see `model::signing`.
+    pub fn get_signer(&self, user_id: &str) -> rusqlite::Result<(Option<User>, Vec<KeyRotation>)> {
+        let author = match self.get_author(user_id) {
+            Ok(author) => Some(author),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(e),
+        };
+        let rotations =
+            self.get_key_rotations(author.as_ref().map_or(user_id, |author| &author.uuid))?;
+        Ok((author, rotations))
+    }
+
+    /// Write a consistent copy of the whole database to a new file at `path`
+    pub fn snapshot(&self, path: &str) -> rusqlite::Result<()> {
+        self.connection.execute("VACUUM INTO ?1", params![path])?;
+        Ok(())
+    }
+
+    /// Merge sightings, keeping the newest one per user and totem
+    /// Returns the number of sightings that were newer than the stored ones.
+    pub fn merge_last_seen(&self, entries: &[LastSeen]) -> rusqlite::Result<usize> {
+        let mut stmt = self.connection.prepare(
+            "INSERT INTO last_seen (user_id, totem_id, seen_at, signature) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (user_id, totem_id) DO UPDATE SET seen_at = excluded.seen_at, signature = excluded.signature
+             WHERE excluded.seen_at > last_seen.seen_at",
+        )?;
+
+        let mut changed = 0;
+        for entry in entries {
+            changed += stmt.execute(params![
+                &entry.user_id,
+                &entry.totem_id,
+                &entry.seen_at,
+                &entry.signature
+            ])?;
+        }
+
+        Ok(changed)
+    }
+
+    /// All known sightings, newest first
+    pub fn get_all_last_seen(&self) -> rusqlite::Result<Vec<LastSeen>> {
+        self.query_last_seen(
+            "SELECT user_id, totem_id, seen_at, signature FROM last_seen ORDER BY seen_at DESC",
+            params![],
+        )
+    }
+
+    /// The most recent sighting of a user, at any totem
+    /// Sightings under the legacy ID the user moved from count too.
+    pub fn get_latest_last_seen(&self, user_id: &str) -> rusqlite::Result<Option<LastSeen>> {
+        let entries = self.query_last_seen(
+            "SELECT user_id, totem_id, seen_at, signature FROM last_seen
+             WHERE user_id = ?1 OR user_id IN (SELECT legacy_id FROM user_links WHERE user_id = ?1)
+             ORDER BY seen_at DESC LIMIT 1",
+            params![user_id],
+        )?;
+
+        Ok(entries.into_iter().next())
+    }
+
+    /// Users that were seen at a totem since the given time, newest first
+    pub fn get_recent_visitors(
+        &self,
+        totem_id: &str,
+        since: DateTime<Utc>,
+    ) -> rusqlite::Result<Vec<LastSeen>> {
+        self.query_last_seen(
+            "SELECT user_id, totem_id, seen_at, signature FROM last_seen
+             WHERE totem_id = ?1 AND seen_at >= ?2 ORDER BY seen_at DESC",
+            params![totem_id, since],
+        )
+    }
+
+    /// Record where the blob with the given hash is stored on this device
+    pub fn set_media_path(&self, hash: &str, local_path: &str) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO media (hash, local_path) VALUES (?1, ?2)",
+            params![hash, local_path],
+        )?;
+        Ok(())
+    }
+
+    /// Forget where the blob with the given hash was stored, after it was removed
+    pub fn delete_media_path(&self, hash: &str) -> rusqlite::Result<()> {
+        self.connection
+            .execute("DELETE FROM media WHERE hash = ?1", params![hash])?;
+        Ok(())
+    }
+
+    /// Where the blob with the given hash is stored on this device, if it is
+    pub fn get_media_path(&self, hash: &str) -> rusqlite::Result<Option<String>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT local_path FROM media WHERE hash = ?1")?;
+        let mut rows = stmt.query_map(params![hash], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    /// Every blob reference of posts and users, whether the blob is stored or not
+    pub fn get_referenced_media(&self) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT image FROM posts WHERE image IS NOT NULL
+             UNION
+             SELECT thumbnail FROM posts WHERE thumbnail IS NOT NULL
+             UNION
+             SELECT hash FROM attachments
+             UNION
+             SELECT profile_picture FROM users WHERE profile_picture IS NOT NULL",
+        )?;
+
+        let iter = stmt.query_map([], |row| row.get(0))?;
+        iter.collect()
+    }
+
+    /// Store a key rotation, known ones are ignored
+    pub fn create_key_rotation(&self, rotation: &KeyRotation) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO key_rotations
+                (uuid, user_id, identity_key, sequence, kind, new_key, recovery_key, timestamp, signature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                &rotation.uuid,
+                &rotation.user_id,
+                &rotation.identity_key,
+                rotation.sequence,
+                rotation.kind,
+                &rotation.new_key,
+                &rotation.recovery_key,
+                &rotation.timestamp,
+                &rotation.signature,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_key_rotation_by_id(&self, uuid: &str) -> rusqlite::Result<KeyRotation> {
+        self.connection.query_row(
+            "SELECT uuid, user_id, identity_key, sequence, kind, new_key, recovery_key, timestamp, signature
+                FROM key_rotations WHERE uuid = ?1",
+            params![uuid],
+            key_rotation_from_row,
+        )
+    }
+
+    /// The key rotations of a user, to resolve their chain of keys
+    pub fn get_key_rotations(&self, user_id: &str) -> rusqlite::Result<Vec<KeyRotation>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT uuid, user_id, identity_key, sequence, kind, new_key, recovery_key, timestamp, signature
+                FROM key_rotations WHERE user_id = ?1 ORDER BY sequence",
+        )?;
+
+        let iter = stmt.query_map(params![user_id], key_rotation_from_row)?;
+        iter.collect()
+    }
+
+    /// Every key rotation, ordered so each follows the ones it builds on
+    pub fn get_all_key_rotations(&self) -> rusqlite::Result<Vec<KeyRotation>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT uuid, user_id, identity_key, sequence, kind, new_key, recovery_key, timestamp, signature
+                FROM key_rotations ORDER BY user_id, sequence",
+        )?;
+
+        let iter = stmt.query_map([], key_rotation_from_row)?;
+        iter.collect()
+    }
+
+    /// Store a message, known ones are ignored
+    pub fn create_message(&self, message: &Message) -> rusqlite::Result<()> {
+        self.connection.execute(
+            &format!("INSERT OR IGNORE INTO messages ({MESSAGE_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"),
+            params![
+                &message.uuid,
+                &message.sender_id,
+                &message.recipient_id,
+                &message.sender_key,
+                &message.recipient_key,
+                &message.timestamp,
+                &message.nonce,
+                &message.ciphertext,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_message_by_id(&self, uuid: &str) -> rusqlite::Result<Message> {
+        self.connection.query_row(
+            &format!("SELECT {MESSAGE_COLUMNS} FROM messages WHERE uuid = ?1"),
+            params![uuid],
+            message_from_row,
+        )
+    }
+
+    /// The messages sent or received by a user, oldest first
+    pub fn get_messages_of(&self, user_id: &str) -> rusqlite::Result<Vec<Message>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {MESSAGE_COLUMNS} FROM messages
+                WHERE sender_id = ?1 OR recipient_id = ?1 ORDER BY timestamp, uuid"
+        ))?;
+
+        let iter = stmt.query_map(params![user_id], message_from_row)?;
+        iter.collect()
+    }
+
+    /// The conversation between two users, oldest first
+    pub fn get_messages_between(
+        &self,
+        user_id: &str,
+        other_id: &str,
+    ) -> rusqlite::Result<Vec<Message>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {MESSAGE_COLUMNS} FROM messages
+                WHERE (sender_id = ?1 AND recipient_id = ?2) OR (sender_id = ?2 AND recipient_id = ?1)
+                ORDER BY timestamp, uuid"
+        ))?;
+
+        let iter = stmt.query_map(params![user_id, other_id], message_from_row)?;
+        iter.collect()
+    }
+
+    /// Store a delivery receipt, known ones are ignored
+    pub fn create_delivery_receipt(&self, receipt: &DeliveryReceipt) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO delivery_receipts (message_uuid, recipient_key, timestamp, signature)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                &receipt.message_uuid,
+                &receipt.recipient_key,
+                &receipt.timestamp,
+                &receipt.signature,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The receipt of a message, if it was delivered
+    /// Only receipts signed by the key the message was encrypted to count.
+    pub fn get_delivery_receipt(
+        &self,
+        message: &Message,
+    ) -> rusqlite::Result<Option<DeliveryReceipt>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT message_uuid, recipient_key, timestamp, signature
+                FROM delivery_receipts WHERE message_uuid = ?1 AND recipient_key = ?2",
+        )?;
+
+        let mut iter = stmt.query_map(
+            params![&message.uuid, &message.recipient_key],
+            delivery_receipt_from_row,
+        )?;
+        iter.next().transpose()
+    }
+
+    /// Receipts signed since the given time, newest first
+    pub fn get_delivery_receipts_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> rusqlite::Result<Vec<DeliveryReceipt>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT message_uuid, recipient_key, timestamp, signature
+                FROM delivery_receipts WHERE timestamp >= ?1 ORDER BY timestamp DESC",
+        )?;
+
+        let iter = stmt.query_map(params![since], delivery_receipt_from_row)?;
+        iter.collect()
+    }
+
+    /// Take a bundle into custody, merging its copies with the ones carried already
+    pub fn store_bundle(&self, bundle: &Bundle) -> rusqlite::Result<()> {
+        let mut bundle = bundle.clone();
+        if let Some(mut carried) = self.get_bundle(&bundle.uuid)? {
+            carried.merge(bundle);
+            bundle = carried;
+        }
+
+        let (kind, id) = match &bundle.header.destination {
+            Destination::Totem(id) => ("totem", id),
+            Destination::User(id) => ("user", id),
+        };
+        let payload = postcard::to_allocvec(&bundle.payload)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?;
+        self.connection.execute(
+            &format!("INSERT OR REPLACE INTO bundles ({BUNDLE_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"),
+            params![
+                &bundle.uuid,
+                kind,
+                id,
+                &bundle.header.created,
+                bundle.header.ttl_secs,
+                bundle.header.hop_limit,
+                bundle.header.hops,
+                bundle.header.copies,
+                payload,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_bundle(&self, uuid: &str) -> rusqlite::Result<Option<Bundle>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {BUNDLE_COLUMNS} FROM bundles WHERE uuid = ?1"
+        ))?;
+
+        let mut iter = stmt.query_map(params![uuid], bundle_from_row)?;
+        iter.next().transpose()
+    }
+
+    /// Every bundle in custody, oldest first
+    pub fn get_bundles(&self) -> rusqlite::Result<Vec<Bundle>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {BUNDLE_COLUMNS} FROM bundles ORDER BY created, uuid"
+        ))?;
+
+        let iter = stmt.query_map([], bundle_from_row)?;
+        iter.collect()
+    }
+
+    /// Keep the copies left after handing some out
+    pub fn set_bundle_copies(&self, uuid: &str, copies: u32) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "UPDATE bundles SET copies = ?2 WHERE uuid = ?1",
+            params![uuid, copies],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_bundle(&self, uuid: &str) -> rusqlite::Result<()> {
+        self.connection
+            .execute("DELETE FROM bundles WHERE uuid = ?1", params![uuid])?;
+        Ok(())
+    }
+
+    /// Store a reply and count it for its post
+    /// Returns false if it was known already.
+    pub fn create_reply(&self, reply: &Reply) -> rusqlite::Result<bool> {
+        let tx = self.connection.unchecked_transaction()?;
+        let created = tx.execute(
+            &format!("INSERT OR IGNORE INTO replies ({REPLY_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"),
+            params![
+                &reply.uuid,
+                &reply.user_id,
+                &reply.parent_id,
+                &reply.root_id,
+                &reply.body,
+                &reply.timestamp,
+                &reply.signature,
+            ],
+        )?;
+        if created > 0 {
+            tx.execute(
+                "INSERT INTO reply_counts (post_id, count) VALUES (?1, 1)
+                 ON CONFLICT (post_id) DO UPDATE SET count = count + 1",
+                params![&reply.root_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(created > 0)
+    }
+
+    pub fn get_reply_by_id(&self, uuid: &str) -> rusqlite::Result<Reply> {
+        self.connection.query_row(
+            &format!("SELECT {REPLY_COLUMNS} FROM replies WHERE uuid = ?1"),
+            params![uuid],
+            reply_from_row,
+        )
+    }
+
+    /// Every reply in the thread of a post, oldest first
+    /// See [`crate::model::reply::thread`] to put them in order.
+    pub fn get_replies_to(&self, post_id: &str) -> rusqlite::Result<Vec<Reply>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {REPLY_COLUMNS} FROM replies WHERE root_id = ?1 ORDER BY timestamp, uuid"
+        ))?;
+
+        let iter = stmt.query_map(params![post_id], reply_from_row)?;
+        iter.collect()
+    }
+
+    pub fn get_reply_ids_in_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT uuid FROM replies WHERE timestamp >= ?1 AND timestamp <= ?2")?;
+
+        let iter = stmt.query_map(params![start, end], |row| row.get(0))?;
+        iter.collect()
+    }
+
+    /// Number of replies in the thread of a post
+    pub fn get_reply_count(&self, post_id: &str) -> rusqlite::Result<u32> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT count FROM reply_counts WHERE post_id = ?1")?;
+
+        let mut iter = stmt.query_map(params![post_id], |row| row.get(0))?;
+        Ok(iter.next().transpose()?.unwrap_or(0))
+    }
+
+    /// Store a reaction if it wins over the stored one for its user, post and kind
+    /// Returns whether it did. Its signature has to be checked before.
+    pub fn merge_reaction(&self, reaction: &Reaction) -> rusqlite::Result<bool> {
+        let tx = self.connection.unchecked_transaction()?;
+        if let Some(current) =
+            self.get_reaction(&reaction.user_id, &reaction.post_id, reaction.kind)?
+            && !reaction.supersedes(&current)
+        {
+            return Ok(false);
+        }
+
+        tx.execute(
+            &format!("INSERT OR REPLACE INTO reactions ({REACTION_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"),
+            params![
+                &reaction.uuid,
+                &reaction.user_id,
+                &reaction.post_id,
+                reaction.kind,
+                reaction.active,
+                &reaction.timestamp,
+                &reaction.signature,
+            ],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// The reaction that counts for a user, post and kind
+    pub fn get_reaction(
+        &self,
+        user_id: &str,
+        post_id: &str,
+        kind: ReactionKind,
+    ) -> rusqlite::Result<Option<Reaction>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {REACTION_COLUMNS} FROM reactions WHERE user_id = ?1 AND post_id = ?2 AND kind = ?3"
+        ))?;
+
+        let mut iter = stmt.query_map(params![user_id, post_id, kind], reaction_from_row)?;
+        iter.next().transpose()
+    }
+
+    /// A stored reaction, `None` if it was superseded or never arrived
+    pub fn get_reaction_by_id(&self, uuid: &str) -> rusqlite::Result<Option<Reaction>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {REACTION_COLUMNS} FROM reactions WHERE uuid = ?1"
+        ))?;
+
+        let mut iter = stmt.query_map(params![uuid], reaction_from_row)?;
+        iter.next().transpose()
+    }
+
+    pub fn get_reaction_ids_in_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT uuid FROM reactions WHERE timestamp >= ?1 AND timestamp <= ?2")?;
+
+        let iter = stmt.query_map(params![start, end], |row| row.get(0))?;
+        iter.collect()
+    }
+
+    /// Number of active reactions to a post
+    pub fn get_reaction_counts(&self, post_id: &str) -> rusqlite::Result<ReactionCounts> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT kind FROM reactions WHERE post_id = ?1 AND active")?;
+
+        let mut counts = ReactionCounts::default();
+        for kind in stmt.query_map(params![post_id], |row| row.get(0))? {
+            counts.add(kind?);
+        }
+        Ok(counts)
+    }
+
+    /// Store a repost
+    /// Returns false if it was known already.
+    pub fn create_repost(&self, repost: &Repost) -> rusqlite::Result<bool> {
+        let created = self.connection.execute(
+            &format!(
+                "INSERT OR IGNORE INTO reposts ({REPOST_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+            ),
+            params![
+                &repost.uuid,
+                &repost.user_id,
+                &repost.post_id,
+                &repost.author_id,
+                &repost.timestamp,
+                &repost.signature,
+            ],
+        )?;
+        Ok(created > 0)
+    }
+
+    pub fn get_repost_by_id(&self, uuid: &str) -> rusqlite::Result<Repost> {
+        self.connection.query_row(
+            &format!("SELECT {REPOST_COLUMNS} FROM reposts WHERE uuid = ?1"),
+            params![uuid],
+            repost_from_row,
+        )
+    }
+
+    /// Every repost, newest first
+    pub fn get_all_reposts(&self) -> rusqlite::Result<Vec<Repost>> {
+        let mut stmt = self.connection.prepare(&format!(
+            "SELECT {REPOST_COLUMNS} FROM reposts ORDER BY timestamp DESC, uuid"
+        ))?;
+
+        let iter = stmt.query_map([], repost_from_row)?;
+        iter.collect()
+    }
+
+    pub fn get_repost_ids_in_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT uuid FROM reposts WHERE timestamp >= ?1 AND timestamp <= ?2")?;
+
+        let iter = stmt.query_map(params![start, end], |row| row.get(0))?;
+        iter.collect()
+    }
+
+    /// Posts that were reposted but didn't arrive yet
+    pub fn get_missing_reposted_ids(&self) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT DISTINCT post_id FROM reposts
+             WHERE post_id NOT IN (SELECT uuid FROM posts) AND post_id NOT IN (SELECT post_id FROM tombstones)
+             ORDER BY post_id",
+        )?;
+
+        let iter = stmt.query_map([], |row| row.get(0))?;
+        iter.collect()
+    }
+
+    /// Store a tombstone and delete the post it buries, known ones are ignored
+    /// Returns whether it was new. Its signature has to be checked before.
+    pub fn create_tombstone(&self, tombstone: &Tombstone) -> rusqlite::Result<bool> {
+        let tx = self.connection.unchecked_transaction()?;
+        let created = self.connection.execute(
+            &format!(
+                "INSERT OR IGNORE INTO tombstones ({TOMBSTONE_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5)"
+            ),
+            params![
+                &tombstone.uuid,
+                &tombstone.user_id,
+                &tombstone.post_id,
+                &tombstone.timestamp,
+                &tombstone.signature,
+            ],
+        )?;
+
+        match self.get_post_by_id(&tombstone.post_id) {
+            Ok(post) if tombstone.buries(&post) => {
+                let tables = [
+                    "attachments",
+                    "post_tags",
+                    "post_mentions",
+                    "post_history",
+                    "reply_counts",
+                    "reactions",
+                ];
+                self.delete_post_rows(&post.uuid, &tables)?;
+            }
+            Ok(_) | Err(rusqlite::Error::QueryReturnedNoRows) => {}
+            Err(e) => return Err(e),
+        }
+
+        tx.commit()?;
+        Ok(created > 0)
+    }
+
+    pub fn get_tombstone_by_id(&self, uuid: &str) -> rusqlite::Result<Tombstone> {
+        self.connection.query_row(
+            &format!("SELECT {TOMBSTONE_COLUMNS} FROM tombstones WHERE uuid = ?1"),
+            params![uuid],
+            tombstone_from_row,
+        )
+    }
+
+    /// IDs of all tombstones, they are compared with totems for as long as they are kept
+    pub fn get_tombstone_ids(&self) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self
+            .connection
+            .prepare("SELECT uuid FROM tombstones ORDER BY uuid")?;
+        let iter = stmt.query_map([], |row| row.get(0))?;
+        iter.collect()
+    }
+
+    /// Whether the author deleted the post, which may not have arrived yet
+    pub fn is_post_deleted(&self, post_id: &str, author_id: &str) -> rusqlite::Result<bool> {
+        self.connection
+            .prepare("SELECT 1 FROM tombstones WHERE post_id = ?1 AND user_id = ?2")?
+            .exists(params![post_id, author_id])
+    }
+
+    /// Drop the tombstones past `model::tombstone::TOMBSTONE_TTL`
+    /// Returns how many were dropped.
+    pub fn purge_tombstones(&self, now: DateTime<Utc>) -> rusqlite::Result<usize> {
+        self.connection.execute(
+            "DELETE FROM tombstones WHERE timestamp < ?1",
+            params![now - TOMBSTONE_TTL],
+        )
+    }
+
+    fn query_last_seen(
+        &self,
+        sql: &str,
+        params: &[&dyn rusqlite::ToSql],
+    ) -> rusqlite::Result<Vec<LastSeen>> {
+        let mut stmt = self.connection.prepare(sql)?;
+
+        let iter = stmt.query_map(params, |row| {
+            Ok(LastSeen {
+                user_id: row.get(0)?,
+                totem_id: row.get(1)?,
+                seen_at: row.get(2)?,
+                signature: row.get(3)?,
+            })
+        })?;
+
+        iter.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use std::ops::{Add, Sub};
+
+    #[test]
+    fn test_write_read() {
+        let db = Database::new(":memory:".to_string());
+
+        let user = User {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".try_into().unwrap(),
+            username: "tag".try_into().unwrap(),
             status: "Online".try_into().unwrap(),
             bio: "bio".try_into().unwrap(),
             profile_picture: Some("123e4567-e89b-12d3-a456-426697174000".try_into().unwrap()),
             last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
         };
 
         let totem = Totem {
@@ -335,6 +1771,12 @@ mod tests {
             // Assuming image/totem IDs are also UUIDs or short identifiers
             image: Some("000e8400-e29b-41d4-a716-446655440022".try_into().unwrap()),
             source_totem: Some("990e8400-e29b-41d4-a716-446655440011".try_into().unwrap()),
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         };
 
         db.create_user(&user);
@@ -375,6 +1817,9 @@ mod tests {
             bio: "bio".try_into().unwrap(),
             profile_picture: Some("123e4567-e89b-12d3-a456-426697174000".try_into().unwrap()),
             last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
         };
 
         let post = Post {
@@ -393,6 +1838,12 @@ mod tests {
             // Assuming image/totem IDs are also UUIDs or short identifiers
             image: None,
             source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         };
 
         db.create_user(&user);
@@ -416,4 +1867,913 @@ mod tests {
             user
         )
     }
+
+    #[test]
+    fn test_merge_last_seen() {
+        let db = Database::new(":memory:".to_string());
+        let now = Utc::now();
+        let seen = |user: &str, totem: &str, minutes_ago: i64| LastSeen {
+            user_id: user.to_string(),
+            totem_id: totem.to_string(),
+            seen_at: now.sub(TimeDelta::minutes(minutes_ago)),
+            signature: Some(format!("{user} at {totem}")),
+        };
+
+        assert_eq!(
+            db.merge_last_seen(&[seen("alice", "totem-a", 90), seen("bob", "totem-a", 30)])
+                .unwrap(),
+            2
+        );
+        // An older sighting doesn't replace a newer one
+        assert_eq!(
+            db.merge_last_seen(&[seen("bob", "totem-a", 60), seen("alice", "totem-b", 10)])
+                .unwrap(),
+            1
+        );
+
+        assert_eq!(
+            db.get_latest_last_seen("alice").unwrap(),
+            Some(seen("alice", "totem-b", 10))
+        );
+        assert_eq!(db.get_latest_last_seen("carol").unwrap(), None);
+
+        let visitors = db
+            .get_recent_visitors("totem-a", now.sub(TimeDelta::hours(1)))
+            .unwrap();
+        assert_eq!(visitors, [seen("bob", "totem-a", 30)]);
+
+        assert_eq!(db.get_all_last_seen().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_media() {
+        let db = Database::new(":memory:".to_string());
+
+        let user = User {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            username: "tag".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: Some("aa".repeat(32)),
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        };
+        let post = |uuid: &str, image: Option<String>| Post {
+            uuid: uuid.to_string(),
+            user_id: user.uuid.clone(),
+            title: "Title".to_string(),
+            body: "".to_string(),
+            timestamp: Utc::now(),
+            image,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+
+        db.create_user(&user);
+        db.create_post(&post(
+            "123e4567-e89b-12d3-a456-426614174000",
+            Some("bb".repeat(32)),
+        ));
+        db.create_post(&post(
+            "123e4567-e89b-12d3-a456-426614174001",
+            Some("aa".repeat(32)),
+        ));
+        db.create_post(&post("123e4567-e89b-12d3-a456-426614174002", None));
+        let mut with_thumbnail = post(
+            "123e4567-e89b-12d3-a456-426614174003",
+            Some("bb".repeat(32)),
+        );
+        with_thumbnail.thumbnail = Some("cc".repeat(32));
+        with_thumbnail.image_preview = Some(ImagePreview {
+            blurhash: "LEHV6nWB2yk8pyo0adR*.7kCMdnj".to_string(),
+            width: 1600,
+            height: 1067,
+        });
+        with_thumbnail.attachments = vec![
+            Attachment {
+                kind: AttachmentKind::Image,
+                mime: "image/jpeg".to_string(),
+                hash: "bb".repeat(32),
+                size: 200_000,
+                duration_ms: None,
+                width: Some(1600),
+                height: Some(1067),
+                alt_text: Some("A totem in the park".to_string()),
+            },
+            Attachment {
+                kind: AttachmentKind::Clip,
+                mime: "video/mp4".to_string(),
+                hash: "dd".repeat(32),
+                size: 3_000_000,
+                duration_ms: Some(12_000),
+                width: Some(720),
+                height: Some(1280),
+                alt_text: None,
+            },
+        ];
+        db.create_post(&with_thumbnail);
+        assert_eq!(
+            db.get_post_by_id(&with_thumbnail.uuid).unwrap(),
+            with_thumbnail
+        );
+
+        let mut updated = db.get_user_by_id(&user.uuid).unwrap();
+        updated.profile_picture_preview = with_thumbnail.image_preview.clone();
+        db.update_user(&updated).unwrap();
+        assert_eq!(db.get_all_users().unwrap(), [updated]);
+
+        let mut referenced = db.get_referenced_media().unwrap();
+        referenced.sort();
+        assert_eq!(
+            referenced,
+            [
+                "aa".repeat(32),
+                "bb".repeat(32),
+                "cc".repeat(32),
+                "dd".repeat(32)
+            ]
+        );
+        let posts = db.get_all_posts().unwrap();
+        assert_eq!(
+            posts.iter().find(|p| p.uuid == with_thumbnail.uuid),
+            Some(&with_thumbnail)
+        );
+        assert_eq!(posts.iter().map(|p| p.attachments.len()).sum::<usize>(), 2);
+
+        assert_eq!(db.get_media_path(&"aa".repeat(32)).unwrap(), None);
+        db.set_media_path(&"aa".repeat(32), "/data/media/aa")
+            .unwrap();
+        assert_eq!(
+            db.get_media_path(&"aa".repeat(32)).unwrap().as_deref(),
+            Some("/data/media/aa")
+        );
+    }
+
+    #[test]
+    fn test_migrate_user() {
+        let db = Database::new(":memory:".to_string());
+        let legacy_id = "550e8400-e29b-41d4-a716-446655440000";
+        let last_contact = Utc::now();
+        let profile = |uuid: &str, public_key: Option<String>| User {
+            uuid: uuid.to_string(),
+            username: "tag".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact,
+            profile_picture_preview: None,
+            public_key,
+            signature: None,
+        };
+        let legacy = profile(legacy_id, None);
+        db.create_user(&legacy);
+        let post = Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: legacy_id.to_string(),
+            title: "Title".to_string(),
+            body: "".to_string(),
+            timestamp: Utc::now(),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+        db.create_post(&post);
+        let seen = LastSeen {
+            user_id: legacy_id.to_string(),
+            totem_id: "totem-a".to_string(),
+            seen_at: Utc::now(),
+            signature: None,
+        };
+        db.merge_last_seen(std::slice::from_ref(&seen)).unwrap();
+
+        let identity = crate::identity::Identity::generate();
+        let mut user = profile(&identity.user_id(), Some(identity.public_key().to_string()));
+        user.sign(&identity);
+        db.migrate_user(legacy_id, &user).unwrap();
+
+        // The post stays under the legacy ID, but resolves to the new identity
+        let post = db.get_post_by_id(&post.uuid).unwrap();
+        assert_eq!(post.user_id, legacy_id);
+        assert_eq!(
+            db.get_linked_user_id(legacy_id).unwrap(),
+            Some(user.uuid.clone())
+        );
+        assert_eq!(db.get_author(&post.user_id).unwrap(), user);
+        assert_eq!(
+            db.get_user_links().unwrap(),
+            [(legacy_id.to_string(), user.uuid.clone())]
+        );
+
+        let (author, rotations) = db.get_signer(&post.user_id).unwrap();
+        assert_eq!(author.as_ref(), Some(&user));
+        let verification = post.verification(author.as_ref(), &rotations);
+        assert_eq!(verification, crate::model::signing::Verification::Unsigned);
+        assert_eq!(post.check_signature(author.as_ref(), &rotations), Ok(()));
+
+        // Sightings under the legacy ID count for the new one
+        assert_eq!(db.get_latest_last_seen(&user.uuid).unwrap(), Some(seen));
+
+        // The legacy profile stays as totems know it, other IDs aren't linked
+        assert_eq!(db.get_author(legacy_id).unwrap().uuid, user.uuid);
+        assert_eq!(db.get_user_by_id(legacy_id).unwrap(), legacy);
+        assert_eq!(db.get_linked_user_id(&user.uuid).unwrap(), None);
+        assert_eq!(
+            db.get_signer("650e8400-e29b-41d4-a716-446655440000")
+                .unwrap(),
+            (None, Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_key_rotations() {
+        let db = Database::new(":memory:".to_string());
+        let identity = crate::identity::Identity::generate();
+        let next = crate::identity::Identity::generate();
+        let mut rotation = KeyRotation {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: identity.user_id(),
+            identity_key: identity.public_key().to_string(),
+            sequence: 1,
+            kind: RotationKind::Endorsement,
+            new_key: next.public_key().to_string(),
+            recovery_key: Some(
+                crate::identity::Identity::generate()
+                    .public_key()
+                    .to_string(),
+            ),
+            timestamp: Utc::now(),
+            signature: String::new(),
+        };
+        rotation.sign(&identity);
+        db.create_key_rotation(&rotation).unwrap();
+        // Known rotations are ignored
+        db.create_key_rotation(&rotation).unwrap();
+
+        assert_eq!(db.get_key_rotation_by_id(&rotation.uuid).unwrap(), rotation);
+        assert_eq!(
+            db.get_key_rotations(&rotation.user_id).unwrap(),
+            [rotation.clone()]
+        );
+        assert_eq!(db.get_all_key_rotations().unwrap(), [rotation]);
+        assert!(db.get_key_rotations(&next.user_id()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_messages() {
+        let db = Database::new(":memory:".to_string());
+        let alice = crate::identity::Identity::generate();
+        let bob = crate::identity::Identity::generate();
+        let carol = crate::identity::Identity::generate();
+        let message = |uuid: &str,
+                       from: &crate::identity::Identity,
+                       to: &crate::identity::Identity,
+                       secs| Message {
+            uuid: uuid.to_string(),
+            sender_id: from.user_id(),
+            recipient_id: to.user_id(),
+            sender_key: from.public_key().to_string(),
+            recipient_key: to.public_key().to_string(),
+            timestamp: DateTime::from_timestamp(secs, 0).unwrap(),
+            nonce: "00".repeat(24),
+            ciphertext: "11".repeat(20),
+        };
+        let first = message(
+            "123e4567-e89b-12d3-a456-426614174000",
+            &alice,
+            &bob,
+            1_700_000_000,
+        );
+        let reply = message(
+            "123e4567-e89b-12d3-a456-426614174001",
+            &bob,
+            &alice,
+            1_700_000_100,
+        );
+        let other = message(
+            "123e4567-e89b-12d3-a456-426614174002",
+            &carol,
+            &alice,
+            1_700_000_050,
+        );
+        for m in [&reply, &first, &other] {
+            db.create_message(m).unwrap();
+        }
+        // Known messages are ignored
+        db.create_message(&first).unwrap();
+
+        assert_eq!(db.get_message_by_id(&first.uuid).unwrap(), first);
+        assert_eq!(
+            db.get_messages_between(&bob.user_id(), &alice.user_id())
+                .unwrap(),
+            [first.clone(), reply.clone()]
+        );
+        assert_eq!(
+            db.get_messages_of(&alice.user_id()).unwrap(),
+            [first, other, reply]
+        );
+        assert!(
+            db.get_messages_of(&crate::identity::Identity::generate().user_id())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_replies() {
+        let db = Database::new(":memory:".to_string());
+        let post_id = "123e4567-e89b-12d3-a456-426614174000";
+        let reply = |uuid: &str, parent_id: &str, secs| Reply {
+            uuid: uuid.to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            parent_id: parent_id.to_string(),
+            root_id: post_id.to_string(),
+            body: "Reply".to_string(),
+            timestamp: DateTime::from_timestamp(secs, 0).unwrap(),
+            signature: None,
+        };
+        let first = reply(
+            "223e4567-e89b-12d3-a456-426614174000",
+            post_id,
+            1_700_000_000,
+        );
+        let answer = reply(
+            "223e4567-e89b-12d3-a456-426614174001",
+            &first.uuid,
+            1_700_000_100,
+        );
+
+        // Replies are taken before the post and the reply they answer
+        assert!(db.create_reply(&answer).unwrap());
+        assert!(db.create_reply(&first).unwrap());
+        assert!(!db.create_reply(&first).unwrap());
+
+        assert_eq!(db.get_reply_by_id(&answer.uuid).unwrap(), answer);
+        assert_eq!(
+            db.get_replies_to(post_id).unwrap(),
+            [first.clone(), answer.clone()]
+        );
+        assert_eq!(db.get_reply_count(post_id).unwrap(), 2);
+        assert_eq!(db.get_reply_count(&first.uuid).unwrap(), 0);
+        assert_eq!(
+            db.get_reply_ids_in_range(first.timestamp, first.timestamp)
+                .unwrap(),
+            std::slice::from_ref(&first.uuid)
+        );
+    }
+
+    #[test]
+    fn test_reactions() {
+        let db = Database::new(":memory:".to_string());
+        let post_id = "123e4567-e89b-12d3-a456-426614174000";
+        let reaction = |uuid: &str, user: &str, kind, active, secs| Reaction {
+            uuid: uuid.to_string(),
+            user_id: user.to_string(),
+            post_id: post_id.to_string(),
+            kind,
+            active,
+            timestamp: DateTime::from_timestamp(secs, 0).unwrap(),
+            signature: None,
+        };
+        let like = reaction(
+            "223e4567-e89b-12d3-a456-426614174000",
+            "alice",
+            ReactionKind::Like,
+            true,
+            1_700_000_000,
+        );
+        let unlike = reaction(
+            "223e4567-e89b-12d3-a456-426614174001",
+            "alice",
+            ReactionKind::Like,
+            false,
+            1_700_000_100,
+        );
+        let share = reaction(
+            "223e4567-e89b-12d3-a456-426614174002",
+            "bob",
+            ReactionKind::Share,
+            true,
+            1_700_000_050,
+        );
+        let bob_like = reaction(
+            "223e4567-e89b-12d3-a456-426614174003",
+            "bob",
+            ReactionKind::Like,
+            true,
+            1_700_000_050,
+        );
+
+        // The order doesn't matter, the latest reaction wins
+        assert!(db.merge_reaction(&unlike).unwrap());
+        assert!(!db.merge_reaction(&like).unwrap());
+        assert!(db.merge_reaction(&share).unwrap());
+        assert!(db.merge_reaction(&bob_like).unwrap());
+
+        assert_eq!(
+            db.get_reaction_counts(post_id).unwrap(),
+            ReactionCounts {
+                likes: 1,
+                shares: 1
+            }
+        );
+        assert_eq!(
+            db.get_reaction("alice", post_id, ReactionKind::Like)
+                .unwrap(),
+            Some(unlike.clone())
+        );
+        assert_eq!(db.get_reaction_by_id(&like.uuid).unwrap(), None);
+        assert_eq!(db.get_reaction_by_id(&share.uuid).unwrap(), Some(share));
+        assert_eq!(
+            db.get_reaction_ids_in_range(unlike.timestamp, unlike.timestamp)
+                .unwrap(),
+            [unlike.uuid]
+        );
+    }
+
+    #[test]
+    fn test_tags_and_mentions() {
+        let path = std::env::temp_dir().join("loom_db_test_tags.sqlite");
+        let _ = std::fs::remove_file(&path);
+        let db = Database::new(path.to_string_lossy().into_owned());
+
+        let identity = crate::identity::Identity::generate();
+        let user = User {
+            uuid: identity.user_id(),
+            username: "Alice".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: Some(identity.public_key().to_string()),
+            signature: None,
+        };
+        db.create_user(&user);
+        let post = |uuid: &str, body: String, secs| Post {
+            uuid: uuid.to_string(),
+            user_id: user.uuid.clone(),
+            title: "Title".to_string(),
+            body,
+            timestamp: DateTime::from_timestamp(secs, 0).unwrap(),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+        let by_name = post(
+            "123e4567-e89b-12d3-a456-426614174000",
+            "#Market with @alice".to_string(),
+            1_700_000_000,
+        );
+        let by_key = post(
+            "123e4567-e89b-12d3-a456-426614174001",
+            format!(
+                "#market #fish, right @{}?",
+                identity.public_key().fingerprint()
+            ),
+            1_700_000_100,
+        );
+        let other = post(
+            "123e4567-e89b-12d3-a456-426614174002",
+            "#fish for @bob".to_string(),
+            1_700_000_200,
+        );
+        for post in [&by_name, &by_key, &other] {
+            db.create_post(post);
+        }
+
+        let uuids = |posts: Vec<Post>| posts.into_iter().map(|post| post.uuid).collect::<Vec<_>>();
+        assert_eq!(
+            uuids(db.get_posts_with_tag("#MARKET").unwrap()),
+            [by_key.uuid.clone(), by_name.uuid.clone()]
+        );
+        assert!(db.get_posts_with_tag("marke").unwrap().is_empty());
+        assert_eq!(
+            uuids(db.get_posts_mentioning(&user).unwrap()),
+            [by_key.uuid.clone(), by_name.uuid.clone()]
+        );
+
+        // Posts stored before the index existed are indexed when the database is opened
+        db.connection.execute("DROP TABLE post_tags", ()).unwrap();
+        db.connection
+            .execute("DROP TABLE post_mentions", ())
+            .unwrap();
+        drop(db);
+        let db = Database::new(path.to_string_lossy().into_owned());
+        assert_eq!(
+            uuids(db.get_posts_with_tag("fish").unwrap()),
+            [other.uuid, by_key.uuid]
+        );
+
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_post_edits() {
+        let db = Database::new(":memory:".to_string());
+        let author = User {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            username: "alice".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        };
+        db.create_user(&author);
+        let original = Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: author.uuid.clone(),
+            title: "Market".to_string(),
+            body: "#fish today".to_string(),
+            timestamp: DateTime::from_timestamp(1_767_225_600, 0).unwrap(),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+        let edited_at = original.timestamp + chrono::TimeDelta::hours(1);
+        let first = original.edited("Market".to_string(), "#bread today".to_string(), edited_at);
+        let second = first.edited(
+            "Market".to_string(),
+            "#bread tomorrow".to_string(),
+            edited_at,
+        );
+
+        assert!(db.merge_post(&original).unwrap());
+        assert!(!db.merge_post(&original).unwrap());
+        // Edits arriving out of order end up with the newest version
+        assert!(db.merge_post(&second).unwrap());
+        assert!(!db.merge_post(&first).unwrap());
+        assert_eq!(db.get_post_by_id(&original.uuid).unwrap(), second);
+        assert_eq!(db.get_all_posts().unwrap(), std::slice::from_ref(&second));
+        assert_eq!(
+            db.get_post_history(&original.uuid).unwrap(),
+            [original.clone(), first, second.clone()]
+        );
+
+        // The index follows the version shown
+        assert!(db.get_posts_with_tag("fish").unwrap().is_empty());
+        assert_eq!(
+            db.get_posts_with_tag("bread").unwrap(),
+            std::slice::from_ref(&second)
+        );
+
+        let window = |hours| {
+            let start = original.timestamp + chrono::TimeDelta::hours(hours);
+            (start, start + chrono::TimeDelta::hours(1))
+        };
+        let (start, end) = window(1);
+        assert_eq!(
+            db.get_post_ids_in_range(start, end),
+            std::slice::from_ref(&original.uuid)
+        );
+        let versions = db.get_post_versions_in_range(start, end).unwrap();
+        assert_eq!(
+            versions,
+            HashMap::from([(original.uuid.clone(), second.version())])
+        );
+        let (start, end) = window(-1);
+        assert!(db.get_post_ids_in_range(start, end).is_empty());
+
+        let forged = Post {
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            ..second.edited("Mine".to_string(), "".to_string(), edited_at)
+        };
+        assert!(!db.merge_post(&forged).unwrap());
+        assert_eq!(db.get_post_history(&original.uuid).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_reposts() {
+        let db = Database::new(":memory:".to_string());
+        let author = User {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            username: "author".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        };
+        db.create_user(&author);
+        let post = Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            title: "Title".to_string(),
+            body: "".to_string(),
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+        let repost = |uuid: &str, secs| Repost {
+            uuid: uuid.to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            post_id: post.uuid.clone(),
+            author_id: post.user_id.clone(),
+            timestamp: DateTime::from_timestamp(secs, 0).unwrap(),
+            signature: None,
+        };
+        let first = repost("223e4567-e89b-12d3-a456-426614174000", 1_700_000_100);
+        let second = repost("223e4567-e89b-12d3-a456-426614174001", 1_700_000_200);
+
+        // Reposts are taken before the post
+        assert!(db.create_repost(&first).unwrap());
+        assert!(db.create_repost(&second).unwrap());
+        assert!(!db.create_repost(&first).unwrap());
+        assert_eq!(
+            db.get_missing_reposted_ids().unwrap(),
+            std::slice::from_ref(&post.uuid)
+        );
+
+        db.create_post(&post);
+        assert!(db.get_missing_reposted_ids().unwrap().is_empty());
+
+        assert_eq!(db.get_repost_by_id(&first.uuid).unwrap(), first);
+        assert_eq!(
+            db.get_all_reposts().unwrap(),
+            [second.clone(), first.clone()]
+        );
+        assert_eq!(
+            db.get_repost_ids_in_range(first.timestamp, first.timestamp)
+                .unwrap(),
+            std::slice::from_ref(&first.uuid)
+        );
+    }
+
+    #[test]
+    fn test_tombstones() {
+        let db = Database::new(":memory:".to_string());
+        let author = User {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            username: "author".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        };
+        db.create_user(&author);
+        let post = Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: author.uuid.clone(),
+            title: "Title".to_string(),
+            body: "#market".to_string(),
+            timestamp: DateTime::from_timestamp(1_767_225_600, 0).unwrap(),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: vec![Attachment {
+                kind: AttachmentKind::Voice,
+                mime: "audio/ogg".to_string(),
+                hash: "ab".repeat(32),
+                size: 48_000,
+                duration_ms: Some(6_000),
+                width: None,
+                height: None,
+                alt_text: None,
+            }],
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+        db.create_post(&post);
+        let edit = post.edited(
+            "Title".to_string(),
+            "#market today".to_string(),
+            post.timestamp,
+        );
+        db.create_post(&edit);
+        let now = post.timestamp + chrono::TimeDelta::hours(1);
+
+        // Only the author can delete the post
+        let forged = Tombstone {
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            ..Tombstone::of(
+                &post,
+                "323e4567-e89b-12d3-a456-426614174000".to_string(),
+                now,
+            )
+        };
+        assert!(db.create_tombstone(&forged).unwrap());
+        assert_eq!(db.get_post_by_id(&post.uuid).unwrap(), edit);
+
+        db.create_reply(&Reply {
+            uuid: "423e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            parent_id: post.uuid.clone(),
+            root_id: post.uuid.clone(),
+            body: "Where?".to_string(),
+            timestamp: now,
+            signature: None,
+        })
+        .unwrap();
+        let like = Reaction {
+            uuid: "523e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            post_id: post.uuid.clone(),
+            kind: ReactionKind::Like,
+            active: true,
+            timestamp: now,
+            signature: None,
+        };
+        assert!(db.merge_reaction(&like).unwrap());
+        assert_eq!(db.get_reply_count(&post.uuid).unwrap(), 1);
+
+        let tombstone = Tombstone::of(
+            &post,
+            "323e4567-e89b-12d3-a456-426614174001".to_string(),
+            now,
+        );
+        assert!(db.create_tombstone(&tombstone).unwrap());
+        assert!(!db.create_tombstone(&tombstone).unwrap());
+        assert!(matches!(
+            db.get_post_by_id(&post.uuid),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
+        assert!(db.get_attachments(&post.uuid).unwrap().is_empty());
+        assert!(db.get_posts_with_tag("market").unwrap().is_empty());
+        assert!(db.get_post_history(&post.uuid).unwrap().is_empty());
+        assert!(
+            !db.get_referenced_media()
+                .unwrap()
+                .contains(&"ab".repeat(32))
+        );
+        assert_eq!(db.get_reply_count(&post.uuid).unwrap(), 0);
+        assert_eq!(
+            db.get_reaction_counts(&post.uuid).unwrap(),
+            ReactionCounts::default()
+        );
+        assert_eq!(db.get_reaction_by_id(&like.uuid).unwrap(), None);
+
+        // The post isn't brought back, nor asked for by reposts
+        assert!(!db.merge_post(&edit).unwrap());
+        assert!(db.get_all_posts().unwrap().is_empty());
+        let repost = Repost {
+            uuid: "223e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            post_id: post.uuid.clone(),
+            author_id: post.user_id.clone(),
+            timestamp: now,
+            signature: None,
+        };
+        db.create_repost(&repost).unwrap();
+        assert!(db.get_missing_reposted_ids().unwrap().is_empty());
+        assert!(db.is_post_deleted(&post.uuid, &post.user_id).unwrap());
+        assert!(!db.is_post_deleted(&repost.uuid, &post.user_id).unwrap());
+
+        assert_eq!(db.get_tombstone_by_id(&tombstone.uuid).unwrap(), tombstone);
+        assert_eq!(
+            db.get_tombstone_ids().unwrap(),
+            [forged.uuid, tombstone.uuid.clone()]
+        );
+        assert_eq!(db.purge_tombstones(now + TOMBSTONE_TTL).unwrap(), 0);
+        assert_eq!(
+            db.purge_tombstones(now + TOMBSTONE_TTL + chrono::TimeDelta::seconds(1))
+                .unwrap(),
+            2
+        );
+        assert!(db.get_tombstone_ids().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bundles() {
+        let db = Database::new(":memory:".to_string());
+        let identity = crate::identity::Identity::generate();
+        let now = Utc::now();
+        let message = Message {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            sender_id: identity.user_id(),
+            recipient_id: identity.user_id(),
+            sender_key: identity.public_key().to_string(),
+            recipient_key: identity.public_key().to_string(),
+            timestamp: now,
+            nonce: "00".repeat(24),
+            ciphertext: "11".repeat(20),
+        };
+        let bundle = Bundle {
+            uuid: "223e4567-e89b-12d3-a456-426614174000".to_string(),
+            header: BundleHeader::new(Destination::User(identity.user_id()), now),
+            payload: BundlePayload::Message(message),
+        };
+        assert_eq!(db.get_bundle(&bundle.uuid).unwrap(), None);
+
+        db.store_bundle(&bundle).unwrap();
+        assert_eq!(db.get_bundles().unwrap(), std::slice::from_ref(&bundle));
+
+        // Copies of a bundle carried already add up
+        db.store_bundle(&bundle).unwrap();
+        let carried = db.get_bundle(&bundle.uuid).unwrap().unwrap();
+        assert_eq!(carried.header.copies, 2 * bundle.header.copies);
+
+        db.set_bundle_copies(&bundle.uuid, 1).unwrap();
+        assert_eq!(
+            db.get_bundle(&bundle.uuid).unwrap().unwrap().header.copies,
+            1
+        );
+        db.delete_bundle(&bundle.uuid).unwrap();
+        assert!(db.get_bundles().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delivery_receipts() {
+        let db = Database::new(":memory:".to_string());
+        let sender = crate::identity::Identity::generate();
+        let recipient = crate::identity::Identity::generate();
+        let now = Utc::now();
+        let message = Message {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            sender_id: sender.user_id(),
+            recipient_id: recipient.user_id(),
+            sender_key: sender.public_key().to_string(),
+            recipient_key: recipient.public_key().to_string(),
+            timestamp: now,
+            nonce: "00".repeat(24),
+            ciphertext: "11".repeat(20),
+        };
+        assert_eq!(db.get_delivery_receipt(&message).unwrap(), None);
+
+        // Signed for another key, it doesn't confirm this message
+        let mut other = message.clone();
+        other.recipient_key = sender.public_key().to_string();
+        db.create_delivery_receipt(&DeliveryReceipt::new(&other, &sender, now))
+            .unwrap();
+        assert_eq!(db.get_delivery_receipt(&message).unwrap(), None);
+
+        let receipt =
+            DeliveryReceipt::new(&message, &recipient, now + chrono::TimeDelta::seconds(1));
+        db.create_delivery_receipt(&receipt).unwrap();
+        db.create_delivery_receipt(&receipt).unwrap();
+        assert_eq!(
+            db.get_delivery_receipt(&message).unwrap(),
+            Some(receipt.clone())
+        );
+        assert_eq!(db.get_delivery_receipts_since(now).unwrap().len(), 2);
+        assert_eq!(
+            db.get_delivery_receipts_since(receipt.timestamp).unwrap(),
+            [receipt]
+        );
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let db = Database::new(":memory:".to_string());
+        let user = User {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            username: "tag".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        };
+        db.create_user(&user);
+
+        let path = std::env::temp_dir().join(format!("loom-snapshot-{}.db", std::process::id()));
+        let path = path.to_str().unwrap();
+        db.snapshot(path).unwrap();
+        // Never overwrites an existing file
+        assert!(db.snapshot(path).is_err());
+
+        let copy = Database::new(path.to_string());
+        assert_eq!(copy.get_all_users().unwrap(), [user]);
+        drop(copy);
+        std::fs::remove_file(path).unwrap();
+    }
 }
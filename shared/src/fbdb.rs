@@ -1,8 +1,43 @@
-use crate::model::{Post, Totem, User};
+use crate::mailbox::{MailboxIndex, Refusal};
+use crate::model::edit;
+use crate::model::{
+    Bundle, DeliveryReceipt, KeyRotation, LastSeen, Message, Post, Reaction, Reply, Repost,
+    Tombstone, Totem, User,
+};
+use crate::presence::PresenceTable;
+use crate::reactions::ReactionSet;
+use crate::routing::Node;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 
+mod legacy;
+
+/// Layout of the records in the `*.bin` files
+/// postcard isn't self-describing, so `#[serde(default)]` doesn't let a struct read records
+/// written before a field was added. Every change to a stored struct bumps this, along with
+/// a step in [`FileBasedDB::upgrade`] that rewrites the older records.
+const FORMAT_VERSION: u32 = 1;
+
+/// Holds the [`FORMAT_VERSION`] of the files, missing before versions existed
+const VERSION_FILE: &str = "format_version";
+
+/// Superseded last-seen records tolerated before `last_seen.bin` is compacted
+const LAST_SEEN_SLACK: usize = 256;
+
+/// Superseded reactions tolerated before `reactions.bin` is compacted
+const REACTION_SLACK: usize = 256;
+
+/// Max number of bundles a totem keeps in custody, see [`crate::routing`]
+pub const MAX_CUSTODY_BUNDLES: usize = 256;
+
+/// Delivered or expired mailbox records tolerated before the mailbox files are compacted
+/// Messages are large, so they don't pile up for long.
+const MAILBOX_SLACK: usize = 16;
+
 /// File-based database that stores structs in append-only files
 pub struct FileBasedDB {
     base_path: PathBuf,
@@ -10,12 +45,95 @@ pub struct FileBasedDB {
 
 impl FileBasedDB {
     /// Initialize the file-based database with the given folder path
-    /// Creates all necessary directories
+    /// Creates all necessary directories, and upgrades files written by older firmware.
     pub fn init<P: AsRef<Path>>(folder_path: P) -> io::Result<Self> {
         let base_path = folder_path.as_ref().to_path_buf();
         fs::create_dir_all(&base_path)?;
 
-        Ok(FileBasedDB { base_path })
+        let db = FileBasedDB { base_path };
+        db.upgrade()?;
+        Ok(db)
+    }
+
+    /// Bring the files to the current [`FORMAT_VERSION`]
+    fn upgrade(&self) -> io::Result<()> {
+        let version = self.format_version()?;
+        if version > FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("format version {version} is newer than this firmware"),
+            ));
+        }
+
+        if version < 1 {
+            // Users and posts gained previews, attachments, keys, signatures and edits
+            self.upgrade_records::<legacy::UserV0, User>("users.bin")?;
+            self.upgrade_records::<legacy::PostV0, Post>("posts.bin")?;
+        }
+
+        if version < FORMAT_VERSION {
+            self.set_format_version(FORMAT_VERSION)?;
+        }
+        Ok(())
+    }
+
+    fn format_version(&self) -> io::Result<u32> {
+        match fs::read_to_string(self.get_file_path(VERSION_FILE)) {
+            Ok(version) => version
+                .trim()
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn set_format_version(&self, version: u32) -> io::Result<()> {
+        let path = self.get_file_path(VERSION_FILE);
+        let tmp_path = self.get_file_path(&format!("{VERSION_FILE}.tmp"));
+
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(version.to_string().as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        Self::replace_file(&tmp_path, &path)
+    }
+
+    /// Rewrite the records of the given file that are still in the layout `Old`
+    /// Records already in the current layout are kept as they are, so an upgrade that was
+    /// interrupted can run again. Which layout a record is in shows in how much of it the
+    /// struct reads: an older layout runs out of bytes, a newer one leaves some over.
+    fn upgrade_records<Old, New>(&self, filename: &str) -> io::Result<()>
+    where
+        Old: DeserializeOwned + Into<New>,
+        New: Serialize + DeserializeOwned,
+    {
+        let path = self.get_file_path(filename);
+        if !path.exists() {
+            return Ok(());
+        }
+        let tmp_path = self.get_file_path(&format!("{filename}.tmp"));
+
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        self.for_each_raw_record(filename, |bytes| {
+            let record: New = match read_exactly::<New>(bytes) {
+                Some(record) => record,
+                None => read_exactly::<Old>(bytes).map(Into::into).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown record layout in {filename}"),
+                    )
+                })?,
+            };
+            Self::write_records(&mut writer, [&record])?;
+            Ok(true)
+        })?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+
+        Self::replace_file(&tmp_path, &path)
     }
 
     /// Get the file path for a specific model type
@@ -33,29 +151,7 @@ impl FileBasedDB {
     where
         I: IntoIterator<Item = &'a User>,
     {
-        let path = self.get_file_path("users.bin");
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)?;
-
-        let mut writer = BufWriter::new(file);
-
-        for user in users {
-            let serialized = postcard::to_allocvec(user)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-            // Write length prefix (4 bytes) followed by data
-            let len = serialized.len() as u32;
-            writer.write_all(&len.to_le_bytes())?;
-            writer.write_all(&serialized)?;
-        }
-
-        // Sync at the end
-        writer.flush()?;
-        writer.get_ref().sync_all()?;
-
-        Ok(())
+        self.append_records("users.bin", users)
     }
 
     /// Write a single post to the database
@@ -68,29 +164,25 @@ impl FileBasedDB {
     where
         I: IntoIterator<Item = &'a Post>,
     {
-        let path = self.get_file_path("posts.bin");
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)?;
-
-        let mut writer = BufWriter::new(file);
-
-        for post in posts {
-            let serialized = postcard::to_allocvec(post)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-            // Write length prefix (4 bytes) followed by data
-            let len = serialized.len() as u32;
-            writer.write_all(&len.to_le_bytes())?;
-            writer.write_all(&serialized)?;
-        }
+        self.append_records("posts.bin", posts)
+    }
 
-        // Sync at the end
-        writer.flush()?;
-        writer.get_ref().sync_all()?;
+    /// Write replies to the database
+    /// Whether the post and the replies they answer are known doesn't matter.
+    pub fn write_replies<'a, I>(&self, replies: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a Reply>,
+    {
+        self.append_records("replies.bin", replies)
+    }
 
-        Ok(())
+    /// Write reposts to the database
+    /// Whether the reposted posts are known doesn't matter.
+    pub fn write_reposts<'a, I>(&self, reposts: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a Repost>,
+    {
+        self.append_records("reposts.bin", reposts)
     }
 
     /// Write a single totem to the database
@@ -103,29 +195,13 @@ impl FileBasedDB {
     where
         I: IntoIterator<Item = &'a Totem>,
     {
-        let path = self.get_file_path("totems.bin");
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(path)?;
-
-        let mut writer = BufWriter::new(file);
-
-        for totem in totems {
-            let serialized = postcard::to_allocvec(totem)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-            // Write length prefix (4 bytes) followed by data
-            let len = serialized.len() as u32;
-            writer.write_all(&len.to_le_bytes())?;
-            writer.write_all(&serialized)?;
-        }
-
-        // Sync at the end
-        writer.flush()?;
-        writer.get_ref().sync_all()?;
+        self.append_records("totems.bin", totems)
+    }
 
-        Ok(())
+    /// Write a key rotation to the database
+    /// Whether it fits the user's chain is up to the caller, see [`crate::model::rotation`].
+    pub fn write_key_rotation(&self, rotation: &KeyRotation) -> io::Result<()> {
+        self.append_records("key_rotations.bin", [rotation])
     }
 
     /// Read users from the database with a limit
@@ -148,40 +224,7 @@ impl FileBasedDB {
         F: Fn(&User) -> bool,
         M: Fn(User) -> R,
     {
-        let path = self.get_file_path("users.bin");
-
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
-        let mut results = Vec::new();
-        let mut len_buf = [0u8; 4];
-
-        while results.len() < limit {
-            // Read length prefix
-            match reader.read_exact(&mut len_buf) {
-                Ok(_) => {},
-                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
-                Err(e) => return Err(e),
-            }
-
-            let len = u32::from_le_bytes(len_buf) as usize;
-            let mut data_buf = vec![0u8; len];
-            reader.read_exact(&mut data_buf)?;
-
-            let user: User = postcard::from_bytes(&data_buf)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-            // First filter, then map to save RAM
-            if filter(&user) {
-                let mapped = map(user);
-                results.push(mapped);
-            }
-        }
-
-        Ok(results)
+        self.read_records_filter_map("users.bin", limit, filter, map)
     }
 
     /// Read posts from the database with a limit
@@ -204,40 +247,60 @@ impl FileBasedDB {
         F: Fn(&Post) -> bool,
         M: Fn(Post) -> R,
     {
-        let path = self.get_file_path("posts.bin");
-
-        if !path.exists() {
-            return Ok(Vec::new());
-        }
-
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
-        let mut results = Vec::new();
-        let mut len_buf = [0u8; 4];
+        self.read_records_filter_map("posts.bin", limit, filter, map)
+    }
 
-        while results.len() < limit {
-            // Read length prefix
-            match reader.read_exact(&mut len_buf) {
-                Ok(_) => {},
-                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
-                Err(e) => return Err(e),
-            }
+    /// Read the version of a post that wins among the stored ones, see `model::edit`
+    pub fn read_post(&self, uuid: &str) -> io::Result<Option<Post>> {
+        Ok(edit::newest(
+            self.read_posts_match(usize::MAX, |p| p.uuid == uuid)?,
+        ))
+    }
 
-            let len = u32::from_le_bytes(len_buf) as usize;
-            let mut data_buf = vec![0u8; len];
-            reader.read_exact(&mut data_buf)?;
+    /// Read replies from the database that match the given predicate
+    pub fn read_replies_match<F>(&self, limit: usize, matcher: F) -> io::Result<Vec<Reply>>
+    where
+        F: Fn(&Reply) -> bool,
+    {
+        self.read_replies_filter_map(limit, matcher, |reply| reply)
+    }
 
-            let post: Post = postcard::from_bytes(&data_buf)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    /// Read replies from the database with filter and map callbacks for memory efficiency
+    /// First filters each item, then maps it, then adds to result
+    pub fn read_replies_filter_map<F, M, R>(
+        &self,
+        limit: usize,
+        filter: F,
+        map: M,
+    ) -> io::Result<Vec<R>>
+    where
+        F: Fn(&Reply) -> bool,
+        M: Fn(Reply) -> R,
+    {
+        self.read_records_filter_map("replies.bin", limit, filter, map)
+    }
 
-            // First filter, then map to save RAM
-            if filter(&post) {
-                let mapped = map(post);
-                results.push(mapped);
-            }
-        }
+    /// Read reposts from the database that match the given predicate
+    pub fn read_reposts_match<F>(&self, limit: usize, matcher: F) -> io::Result<Vec<Repost>>
+    where
+        F: Fn(&Repost) -> bool,
+    {
+        self.read_reposts_filter_map(limit, matcher, |repost| repost)
+    }
 
-        Ok(results)
+    /// Read reposts from the database with filter and map callbacks for memory efficiency
+    /// First filters each item, then maps it, then adds to result
+    pub fn read_reposts_filter_map<F, M, R>(
+        &self,
+        limit: usize,
+        filter: F,
+        map: M,
+    ) -> io::Result<Vec<R>>
+    where
+        F: Fn(&Repost) -> bool,
+        M: Fn(Repost) -> R,
+    {
+        self.read_records_filter_map("reposts.bin", limit, filter, map)
     }
 
     /// Read totems from the database with a limit
@@ -260,18 +323,550 @@ impl FileBasedDB {
         F: Fn(&Totem) -> bool,
         M: Fn(Totem) -> R,
     {
-        let path = self.get_file_path("totems.bin");
+        self.read_records_filter_map("totems.bin", limit, filter, map)
+    }
+
+    /// Read key rotations from the database that match the given predicate
+    pub fn read_key_rotations_match<F>(
+        &self,
+        limit: usize,
+        matcher: F,
+    ) -> io::Result<Vec<KeyRotation>>
+    where
+        F: Fn(&KeyRotation) -> bool,
+    {
+        self.read_key_rotations_filter_map(limit, matcher, |rotation| rotation)
+    }
 
-        if !path.exists() {
+    /// Read key rotations from the database with filter and map callbacks for memory efficiency
+    /// First filters each item, then maps it, then adds to result
+    pub fn read_key_rotations_filter_map<F, M, R>(
+        &self,
+        limit: usize,
+        filter: F,
+        map: M,
+    ) -> io::Result<Vec<R>>
+    where
+        F: Fn(&KeyRotation) -> bool,
+        M: Fn(KeyRotation) -> R,
+    {
+        self.read_records_filter_map("key_rotations.bin", limit, filter, map)
+    }
+
+    /// Read the newest sighting of every user at every totem
+    pub fn read_presence(&self) -> io::Result<PresenceTable> {
+        self.read_presence_counted().map(|(table, _)| table)
+    }
+
+    /// Merge sightings into the database, keeping the newest one per user and totem
+    /// Only records that are newer than the stored ones get appended, which are returned.
+    /// Once too many superseded records have piled up, the file is compacted.
+    pub fn merge_last_seen<I>(&self, entries: I) -> io::Result<Vec<LastSeen>>
+    where
+        I: IntoIterator<Item = LastSeen>,
+    {
+        let (mut table, records) = self.read_presence_counted()?;
+        let changed = table.merge_all(entries);
+
+        if records + changed.len() > table.len() + LAST_SEEN_SLACK {
+            let compacted: Vec<_> = table.iter().collect();
+            self.rewrite_records("last_seen.bin", &compacted)?;
+        } else if !changed.is_empty() {
+            self.append_records("last_seen.bin", &changed)?;
+        }
+
+        Ok(changed)
+    }
+
+    /// Read the reactions that count, see [`crate::reactions`]
+    pub fn read_reactions(&self) -> io::Result<ReactionSet> {
+        self.read_reactions_counted().map(|(set, _)| set)
+    }
+
+    /// Merge reactions into the database, keeping the latest one per user, post and kind
+    /// Only reactions that win over the stored ones get appended, which are returned.
+    /// Their signatures have to be checked before.
+    pub fn merge_reactions<I>(&self, reactions: I) -> io::Result<Vec<Reaction>>
+    where
+        I: IntoIterator<Item = Reaction>,
+    {
+        let (mut set, records) = self.read_reactions_counted()?;
+        let changed = set.merge_all(reactions);
+
+        if records + changed.len() > set.len() + REACTION_SLACK {
+            self.rewrite_records("reactions.bin", set.iter())?;
+        } else if !changed.is_empty() {
+            self.append_records("reactions.bin", &changed)?;
+        }
+
+        Ok(changed)
+    }
+
+    /// Read the tombstones that didn't expire, see [`crate::model::tombstone`]
+    pub fn read_tombstones(&self, now: DateTime<Utc>) -> io::Result<Vec<Tombstone>> {
+        self.read_records_filter_map(
+            "tombstones.bin",
+            usize::MAX,
+            |t: &Tombstone| !t.is_expired(now),
+            |t| t,
+        )
+    }
+
+    /// Merge tombstones, dropping every version of the posts they bury
+    /// Returns the tombstones that weren't known and the dropped versions, whose media may
+    /// be unused now. Their signatures have to be checked before. Expired tombstones are
+    /// dropped along the way.
+    pub fn merge_tombstones<I>(
+        &self,
+        tombstones: I,
+        now: DateTime<Utc>,
+    ) -> io::Result<(Vec<Tombstone>, Vec<Post>)>
+    where
+        I: IntoIterator<Item = Tombstone>,
+    {
+        let mut known = Vec::new();
+        let mut expired = false;
+        self.for_each_record("tombstones.bin", |tombstone: Tombstone| {
+            if tombstone.is_expired(now) {
+                expired = true;
+            } else {
+                known.push(tombstone);
+            }
+            true
+        })?;
+
+        let mut merged: Vec<Tombstone> = Vec::new();
+        for tombstone in tombstones {
+            let is_known = known
+                .iter()
+                .chain(&merged)
+                .any(|t| t.uuid == tombstone.uuid);
+            if !is_known && !tombstone.is_expired(now) {
+                merged.push(tombstone);
+            }
+        }
+
+        if expired {
+            self.rewrite_records("tombstones.bin", known.iter().chain(&merged))?;
+        } else if !merged.is_empty() {
+            self.append_records("tombstones.bin", &merged)?;
+        }
+
+        // Most tombstones arrive long after the post was dropped here
+        let mut buries = false;
+        self.for_each_record("posts.bin", |post: Post| {
+            buries = merged.iter().any(|t| t.buries(&post));
+            !buries
+        })?;
+        let mut buried = Vec::new();
+        if buries {
+            self.map_records("posts.bin", |post: Post| {
+                if merged.iter().any(|t| t.buries(&post)) {
+                    buried.push(post);
+                    return None;
+                }
+                Some(post)
+            })?;
+        }
+
+        Ok((merged, buried))
+    }
+
+    /// Read which messages wait in mailboxes and which were delivered, see [`crate::mailbox`]
+    pub fn read_mailbox(&self, now: DateTime<Utc>) -> io::Result<MailboxIndex> {
+        self.read_mailbox_counted(now).map(|(index, _)| index)
+    }
+
+    /// Store messages in the mailboxes of their recipients
+    /// Returns for each message whether it is held now, known ones count as held.
+    pub fn deposit_messages(
+        &self,
+        messages: &[Message],
+        now: DateTime<Utc>,
+    ) -> io::Result<Vec<Result<(), Refusal>>> {
+        let (mut index, records) = self.read_mailbox_counted(now)?;
+        let mut stored = Vec::new();
+
+        let results = messages
+            .iter()
+            .map(|message| {
+                if index.holds(&message.uuid) {
+                    return Ok(());
+                }
+                index.check_deposit(message, now)?;
+                index.insert(message);
+                stored.push(message);
+                Ok(())
+            })
+            .collect();
+
+        if !stored.is_empty() {
+            self.append_records("messages.bin", stored.iter().copied())?;
+        }
+        self.compact_mailbox(&index, records + stored.len())?;
+
+        Ok(results)
+    }
+
+    /// Merge delivery receipts, dropping the messages they confirm
+    /// Returns the receipts that weren't known. Their signatures have to be checked before.
+    pub fn merge_receipts<I>(
+        &self,
+        receipts: I,
+        now: DateTime<Utc>,
+    ) -> io::Result<Vec<DeliveryReceipt>>
+    where
+        I: IntoIterator<Item = DeliveryReceipt>,
+    {
+        let (mut index, records) = self.read_mailbox_counted(now)?;
+        let merged: Vec<_> = receipts
+            .into_iter()
+            // Expired receipts would be dropped right away
+            .filter(|receipt| receipt.timestamp + crate::mailbox::MESSAGE_TTL >= now)
+            .filter(|receipt| index.accept(receipt.clone()))
+            .collect();
+
+        if !merged.is_empty() {
+            self.append_records("receipts.bin", &merged)?;
+        }
+        self.compact_mailbox(&index, records + merged.len())?;
+
+        Ok(merged)
+    }
+
+    /// Messages waiting in a mailbox, in the order they arrived, leaving out `known` ones
+    /// Stops once `max_bytes` of ciphertext are collected, but always returns one message if
+    /// there is one. Returns whether more are waiting.
+    pub fn read_mailbox_messages(
+        &self,
+        index: &MailboxIndex,
+        mailbox: &str,
+        known: &[String],
+        max_bytes: usize,
+    ) -> io::Result<(Vec<Message>, bool)> {
+        let mut messages = Vec::new();
+        let mut bytes = 0;
+        let mut more = false;
+
+        self.for_each_record("messages.bin", |message: Message| {
+            if !index.holds_in(&message.uuid, mailbox) || known.contains(&message.uuid) {
+                return true;
+            }
+            if !messages.is_empty() && bytes + message.ciphertext.len() > max_bytes {
+                more = true;
+                return false;
+            }
+            bytes += message.ciphertext.len();
+            messages.push(message);
+            true
+        })?;
+
+        Ok((messages, more))
+    }
+
+    /// Read the mailbox index, along with the number of records in the mailbox files
+    /// Messages and receipts that expired are left out.
+    fn read_mailbox_counted(&self, now: DateTime<Utc>) -> io::Result<(MailboxIndex, usize)> {
+        let mut index = MailboxIndex::new();
+        let mut records = 0;
+
+        self.for_each_record("messages.bin", |message: Message| {
+            index.insert(&message);
+            records += 1;
+            true
+        })?;
+        self.for_each_record("receipts.bin", |receipt: DeliveryReceipt| {
+            index.accept(receipt);
+            records += 1;
+            true
+        })?;
+        index.expire(now);
+
+        Ok((index, records))
+    }
+
+    /// Drop delivered and expired records from the mailbox files once enough piled up
+    fn compact_mailbox(&self, index: &MailboxIndex, records: usize) -> io::Result<()> {
+        if records <= index.len() + MAILBOX_SLACK {
+            return Ok(());
+        }
+
+        self.map_records("messages.bin", |message: Message| {
+            index.holds(&message.uuid).then_some(message)
+        })?;
+        self.rewrite_records("receipts.bin", index.receipts())
+    }
+
+    /// IDs of the bundles in custody that didn't expire, see [`crate::routing`]
+    pub fn read_bundle_uuids(&self, now: DateTime<Utc>) -> io::Result<Vec<String>> {
+        self.read_records_filter_map(
+            "bundles.bin",
+            usize::MAX,
+            |bundle: &Bundle| !bundle.is_spent(now),
+            |bundle| bundle.uuid,
+        )
+    }
+
+    /// Take bundles into custody, merging the copies of ones held already
+    /// Returns for each bundle whether it was taken, new ones are refused once
+    /// [`MAX_CUSTODY_BUNDLES`] are held. They have to be validated before.
+    pub fn take_custody(&self, bundles: Vec<Bundle>, now: DateTime<Utc>) -> io::Result<Vec<bool>> {
+        let held = self.read_bundle_uuids(now)?;
+        let mut merged: Vec<Bundle> = Vec::new();
+        let mut added: Vec<Bundle> = Vec::new();
+        let mut taken = Vec::new();
+
+        for bundle in bundles {
+            let is_held = held.contains(&bundle.uuid);
+            let has_room = held.len() + added.len() < MAX_CUSTODY_BUNDLES;
+            let list = if is_held { &mut merged } else { &mut added };
+
+            // Copies arriving twice in one go are merged right away
+            if let Some(copy) = list.iter_mut().find(|b| b.uuid == bundle.uuid) {
+                copy.merge(bundle);
+                taken.push(true);
+            } else if is_held || has_room {
+                list.push(bundle);
+                taken.push(true);
+            } else {
+                taken.push(false);
+            }
+        }
+
+        if !merged.is_empty() {
+            self.map_records("bundles.bin", |mut bundle: Bundle| {
+                if let Some(other) = merged.iter().find(|other| other.uuid == bundle.uuid) {
+                    bundle.merge(other.clone());
+                }
+                (!bundle.is_spent(now)).then_some(bundle)
+            })?;
+        }
+        if !added.is_empty() {
+            self.append_records("bundles.bin", &added)?;
+        }
+
+        Ok(taken)
+    }
+
+    /// Hand bundles in custody to `next` by the rules of [`crate::routing`]
+    /// Bundles `next` carries already are left alone. Stops once about `max_bytes` are
+    /// handed out, but hands out at least one bundle if there is one. Spent bundles are
+    /// dropped along the way.
+    pub fn hand_out_bundles(
+        &self,
+        next: Node,
+        carried: &[String],
+        max_bytes: usize,
+        now: DateTime<Utc>,
+    ) -> io::Result<Vec<Bundle>> {
+        // Most syncs change nothing, the file is only rewritten if they do
+        let mut changed = false;
+        self.for_each_record("bundles.bin", |bundle: Bundle| {
+            changed = bundle.is_spent(now)
+                || (!carried.contains(&bundle.uuid)
+                    && bundle.header.handover(next, now) != crate::routing::Handover::Wait);
+            !changed
+        })?;
+        if !changed {
             return Ok(Vec::new());
         }
 
+        let mut handed = Vec::new();
+        let mut bytes = 0;
+        self.map_records("bundles.bin", |mut bundle: Bundle| {
+            let full = !handed.is_empty() && bytes >= max_bytes;
+            if !full
+                && !carried.contains(&bundle.uuid)
+                && let Some(copy) = bundle.hand_to(next, now)
+            {
+                bytes += postcard::to_allocvec(&copy).map_or(0, |encoded| encoded.len());
+                handed.push(copy);
+            }
+            (!bundle.is_spent(now)).then_some(bundle)
+        })?;
+
+        Ok(handed)
+    }
+
+    /// Read the presence table, along with the number of records in the file
+    fn read_presence_counted(&self) -> io::Result<(PresenceTable, usize)> {
+        let mut table = PresenceTable::new();
+        let mut records = 0;
+
+        self.for_each_record("last_seen.bin", |entry: LastSeen| {
+            table.merge(entry);
+            records += 1;
+            true
+        })?;
+
+        Ok((table, records))
+    }
+
+    /// Read the reaction set, along with the number of records in `reactions.bin`
+    fn read_reactions_counted(&self) -> io::Result<(ReactionSet, usize)> {
+        let mut set = ReactionSet::new();
+        let mut records = 0;
+
+        self.for_each_record("reactions.bin", |reaction: Reaction| {
+            set.merge(reaction);
+            records += 1;
+            true
+        })?;
+
+        Ok((set, records))
+    }
+
+    /// Append records to the given file
+    /// Each record is written as a length prefix (4 bytes, little endian) followed by its postcard encoding.
+    fn append_records<'a, T, I>(&self, filename: &str, records: I) -> io::Result<()>
+    where
+        T: Serialize + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let path = self.get_file_path(filename);
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        let mut writer = BufWriter::new(file);
+        Self::write_records(&mut writer, records)?;
+
+        // Sync at the end
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+
+        Ok(())
+    }
+
+    /// Replace the contents of the given file with the given records
+    /// The new contents are written to a temporary file first, so a power loss can't leave a half-written file behind.
+    fn rewrite_records<'a, T, I>(&self, filename: &str, records: I) -> io::Result<()>
+    where
+        T: Serialize + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let path = self.get_file_path(filename);
+        let tmp_path = self.get_file_path(&format!("{filename}.tmp"));
+
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        Self::write_records(&mut writer, records)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+
+        Self::replace_file(&tmp_path, &path)
+    }
+
+    /// Replace every record of the given file with what `f` returns, dropping it for `None`
+    /// Streams the records into a temporary file, so they never all have to be in memory.
+    fn map_records<T, F>(&self, filename: &str, mut f: F) -> io::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnMut(T) -> Option<T>,
+    {
+        let path = self.get_file_path(filename);
+        let tmp_path = self.get_file_path(&format!("{filename}.tmp"));
+
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        let mut result = Ok(());
+        self.for_each_record(filename, |record: T| {
+            if let Some(record) = f(record) {
+                result = Self::write_records(&mut writer, [&record]);
+            }
+            result.is_ok()
+        })?;
+        result?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+
+        Self::replace_file(&tmp_path, &path)
+    }
+
+    fn replace_file(tmp_path: &Path, path: &Path) -> io::Result<()> {
+        // FAT doesn't support replacing a file by renaming over it
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        fs::rename(tmp_path, path)
+    }
+
+    fn write_records<'a, T, I, W>(writer: &mut W, records: I) -> io::Result<()>
+    where
+        T: Serialize + 'a,
+        I: IntoIterator<Item = &'a T>,
+        W: Write,
+    {
+        for record in records {
+            let serialized = postcard::to_allocvec(record)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            // Write length prefix (4 bytes) followed by data
+            let len = serialized.len() as u32;
+            writer.write_all(&len.to_le_bytes())?;
+            writer.write_all(&serialized)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read records from the given file with filter and map callbacks
+    /// First filters each item, then maps it, then adds to result
+    fn read_records_filter_map<T, F, M, R>(
+        &self,
+        filename: &str,
+        limit: usize,
+        filter: F,
+        map: M,
+    ) -> io::Result<Vec<R>>
+    where
+        T: DeserializeOwned,
+        F: Fn(&T) -> bool,
+        M: Fn(T) -> R,
+    {
+        let mut results = Vec::new();
+
+        self.for_each_record(filename, |record: T| {
+            // First filter, then map to save RAM
+            if filter(&record) {
+                results.push(map(record));
+            }
+            results.len() < limit
+        })?;
+
+        Ok(results)
+    }
+
+    /// Call `f` with every record in the given file, in the order they were written
+    /// Stops early once `f` returns false.
+    fn for_each_record<T, F>(&self, filename: &str, mut f: F) -> io::Result<()>
+    where
+        T: DeserializeOwned,
+        F: FnMut(T) -> bool,
+    {
+        self.for_each_raw_record(filename, |bytes| {
+            let record: T = postcard::from_bytes(bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            Ok(f(record))
+        })
+    }
+
+    /// Call `f` with the encoded bytes of every record in the given file
+    /// Stops early once `f` returns false or fails.
+    fn for_each_raw_record<F>(&self, filename: &str, mut f: F) -> io::Result<()>
+    where
+        F: FnMut(&[u8]) -> io::Result<bool>,
+    {
+        let path = self.get_file_path(filename);
+
+        if !path.exists() {
+            return Ok(());
+        }
+
         let file = File::open(path)?;
         let mut reader = BufReader::new(file);
-        let mut results = Vec::new();
         let mut len_buf = [0u8; 4];
+        let mut data_buf = Vec::new();
 
-        while results.len() < limit {
+        loop {
             // Read length prefix
             match reader.read_exact(&mut len_buf) {
                 Ok(_) => {},
@@ -280,26 +875,30 @@ impl FileBasedDB {
             }
 
             let len = u32::from_le_bytes(len_buf) as usize;
-            let mut data_buf = vec![0u8; len];
+            data_buf.resize(len, 0);
             reader.read_exact(&mut data_buf)?;
 
-            let totem: Totem = postcard::from_bytes(&data_buf)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-            // First filter, then map to save RAM
-            if filter(&totem) {
-                let mapped = map(totem);
-                results.push(mapped);
+            if !f(&data_buf)? {
+                break;
             }
         }
 
-        Ok(results)
+        Ok(())
+    }
+}
+
+/// Decode a record that uses up all of `bytes`, see [`FileBasedDB::upgrade_records`]
+fn read_exactly<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    match postcard::take_from_bytes(bytes) {
+        Ok((record, [])) => Some(record),
+        _ => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{Attachment, AttachmentKind, ImagePreview, RotationKind};
     use chrono::Utc;
 
     #[test]
@@ -316,6 +915,9 @@ mod tests {
             bio: "Test user 1".to_string(),
             profile_picture: Some("pic1.jpg".to_string()),
             last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
         };
 
         let user2 = User {
@@ -325,6 +927,9 @@ mod tests {
             bio: "Test user 2".to_string(),
             profile_picture: None,
             last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
         };
 
         db.write_user(&user1).unwrap();
@@ -363,6 +968,12 @@ mod tests {
             timestamp: Utc::now(),
             image: None,
             source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         };
 
         let post2 = Post {
@@ -373,6 +984,25 @@ mod tests {
             timestamp: Utc::now(),
             image: Some("image.jpg".to_string()),
             source_totem: None,
+            thumbnail: None,
+            image_preview: Some(ImagePreview {
+                blurhash: "LEHV6nWB2yk8pyo0adR*.7kCMdnj".to_string(),
+                width: 1600,
+                height: 1067,
+            }),
+            attachments: vec![Attachment {
+                kind: AttachmentKind::Voice,
+                mime: "audio/ogg".to_string(),
+                hash: "ab".repeat(32),
+                size: 48_000,
+                duration_ms: Some(6_000),
+                width: None,
+                height: None,
+                alt_text: None,
+            }],
+            revision: 0,
+            edited_at: None,
+            signature: None,
         };
 
         db.write_posts([&post1, &post2]).unwrap();
@@ -380,6 +1010,7 @@ mod tests {
         let posts = db.read_posts(10).unwrap();
         assert_eq!(posts.len(), 2);
         assert_eq!(posts[0].title, "First Post");
+        assert_eq!(posts[1], post2);
         assert_eq!(posts[1].title, "Second Post");
 
         // Test with matcher
@@ -387,6 +1018,53 @@ mod tests {
         assert_eq!(posts_with_image.len(), 1);
         assert_eq!(posts_with_image[0].title, "Second Post");
 
+        // Edits are appended, the newest version is read back
+        let edit = post1.edited(
+            "First Post".to_string(),
+            "This is the edited post".to_string(),
+            Utc::now(),
+        );
+        db.write_post(&edit).unwrap();
+        db.write_post(&post1).unwrap();
+        assert_eq!(db.read_post(&post1.uuid).unwrap(), Some(edit));
+        assert_eq!(
+            db.read_post("123e4567-e89b-12d3-a456-426614174002")
+                .unwrap(),
+            None
+        );
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_write_read_replies() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_replies");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+
+        let post_id = "123e4567-e89b-12d3-a456-426614174000";
+        let reply = |uuid: &str, parent_id: &str| Reply {
+            uuid: uuid.to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            parent_id: parent_id.to_string(),
+            root_id: post_id.to_string(),
+            body: "Reply".to_string(),
+            timestamp: Utc::now(),
+            signature: None,
+        };
+        let first = reply("223e4567-e89b-12d3-a456-426614174000", post_id);
+        let answer = reply("223e4567-e89b-12d3-a456-426614174001", &first.uuid);
+
+        db.write_replies([&answer, &first]).unwrap();
+
+        let thread = db.read_replies_match(10, |r| r.root_id == post_id).unwrap();
+        assert_eq!(thread, [answer.clone(), first]);
+        let answers = db
+            .read_replies_filter_map(10, |r| !r.is_direct(), |r| r.uuid)
+            .unwrap();
+        assert_eq!(answers, [answer.uuid]);
+
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
@@ -422,6 +1100,45 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_write_read_key_rotations() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_key_rotations");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+
+        let identity = crate::identity::Identity::generate();
+        let mut rotation = KeyRotation {
+            uuid: "990e8400-e29b-41d4-a716-446655440021".to_string(),
+            user_id: identity.user_id(),
+            identity_key: identity.public_key().to_string(),
+            sequence: 1,
+            kind: RotationKind::Endorsement,
+            new_key: identity.public_key().to_string(),
+            recovery_key: Some(
+                crate::identity::Identity::generate()
+                    .public_key()
+                    .to_string(),
+            ),
+            timestamp: Utc::now(),
+            signature: String::new(),
+        };
+        rotation.sign(&identity);
+        db.write_key_rotation(&rotation).unwrap();
+
+        let rotations = db
+            .read_key_rotations_match(10, |r| r.user_id == identity.user_id())
+            .unwrap();
+        assert_eq!(rotations, [rotation]);
+        assert!(
+            db.read_key_rotations_match(10, |r| r.sequence == 2)
+                .unwrap()
+                .is_empty()
+        );
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_read_users_filter_map() {
         let temp_dir = std::env::temp_dir().join("fbdb_test_users_filter_map");
@@ -436,6 +1153,9 @@ mod tests {
             bio: "Test user 1".to_string(),
             profile_picture: Some("pic1.jpg".to_string()),
             last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
         };
 
         let user2 = User {
@@ -445,6 +1165,9 @@ mod tests {
             bio: "Test user 2".to_string(),
             profile_picture: None,
             last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
         };
 
         let user3 = User {
@@ -454,6 +1177,9 @@ mod tests {
             bio: "Test user 3".to_string(),
             profile_picture: Some("pic3.jpg".to_string()),
             last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
         };
 
         db.write_user(&user1).unwrap();
@@ -512,6 +1238,12 @@ mod tests {
             timestamp: Utc::now(),
             image: None,
             source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         };
 
         let post2 = Post {
@@ -522,6 +1254,12 @@ mod tests {
             timestamp: Utc::now(),
             image: Some("image.jpg".to_string()),
             source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         };
 
         let post3 = Post {
@@ -532,6 +1270,12 @@ mod tests {
             timestamp: Utc::now(),
             image: Some("image2.jpg".to_string()),
             source_totem: Some("totem1".to_string()),
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
         };
 
         db.write_posts([&post1, &post2, &post3]).unwrap();
@@ -640,4 +1384,447 @@ mod tests {
 
         let _ = fs::remove_dir_all(&temp_dir);
     }
+
+    #[test]
+    fn test_upgrade_baseline_records() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_upgrade");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        // The structs as the first release stored them
+        #[derive(Serialize)]
+        struct BaselineUser {
+            uuid: String,
+            username: String,
+            status: String,
+            bio: String,
+            profile_picture: Option<String>,
+            last_contact: DateTime<Utc>,
+        }
+        #[derive(Serialize)]
+        struct BaselinePost {
+            uuid: String,
+            user_id: String,
+            title: String,
+            body: String,
+            timestamp: DateTime<Utc>,
+            image: Option<String>,
+            source_totem: Option<String>,
+        }
+
+        let timestamp = "2025-12-14T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let baseline_user = BaselineUser {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            username: "alice".to_string(),
+            status: "".to_string(),
+            bio: "Hi".to_string(),
+            profile_picture: Some("alice.jpg".to_string()),
+            last_contact: timestamp,
+        };
+        let baseline_post = BaselinePost {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: baseline_user.uuid.clone(),
+            title: "Title".to_string(),
+            body: "Body".to_string(),
+            timestamp,
+            image: None,
+            source_totem: Some("Totem-7F3K9Q2M".to_string()),
+        };
+        let old = FileBasedDB {
+            base_path: temp_dir.clone(),
+        };
+        old.append_records("users.bin", [&baseline_user]).unwrap();
+        old.append_records("posts.bin", [&baseline_post]).unwrap();
+        // Without the upgrade, the current structs run out of bytes
+        assert!(old.read_posts(10).is_err());
+
+        // A record in the current layout, as left behind by an interrupted upgrade
+        let current = Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174001".to_string(),
+            user_id: baseline_user.uuid.clone(),
+            title: "Edited".to_string(),
+            body: "Body".to_string(),
+            timestamp,
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: vec![Attachment {
+                hash: "ab".repeat(32),
+                kind: AttachmentKind::Image,
+                mime: "image/jpeg".to_string(),
+                size: 1000,
+                duration_ms: None,
+                width: Some(10),
+                height: Some(10),
+                alt_text: None,
+            }],
+            revision: 1,
+            edited_at: Some(timestamp),
+            signature: Some("signature".to_string()),
+        };
+        old.append_records("posts.bin", [&current]).unwrap();
+
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+        let users = db.read_users(10).unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].username, "alice");
+        assert_eq!(users[0].profile_picture.as_deref(), Some("alice.jpg"));
+        assert_eq!(users[0].last_contact, timestamp);
+        assert_eq!(users[0].public_key, None);
+
+        let posts = db.read_posts(10).unwrap();
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].uuid, baseline_post.uuid);
+        assert_eq!(posts[0].source_totem, baseline_post.source_totem);
+        assert_eq!(posts[0].revision, 0);
+        assert!(posts[0].attachments.is_empty());
+        assert_eq!(posts[1], current);
+
+        // Upgraded once, the files are left alone afterwards
+        assert_eq!(db.format_version().unwrap(), FORMAT_VERSION);
+        drop(db);
+        assert_eq!(
+            FileBasedDB::init(&temp_dir)
+                .unwrap()
+                .read_posts(10)
+                .unwrap(),
+            posts
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_last_seen() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_last_seen");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+        let now = Utc::now();
+        let seen = |user: &str, minutes_ago: i64| LastSeen {
+            user_id: user.to_string(),
+            totem_id: "totem-a".to_string(),
+            seen_at: now - chrono::TimeDelta::minutes(minutes_ago),
+            signature: None,
+        };
+
+        let changed = db
+            .merge_last_seen([seen("alice", 10), seen("bob", 5)])
+            .unwrap();
+        assert_eq!(changed.len(), 2);
+
+        // Older records don't replace newer ones
+        let changed = db
+            .merge_last_seen([seen("alice", 20), seen("bob", 1)])
+            .unwrap();
+        assert_eq!(changed, [seen("bob", 1)]);
+
+        let table = db.read_presence().unwrap();
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.latest_for_user("alice"), Some(seen("alice", 10)));
+        assert_eq!(table.latest_for_user("bob"), Some(seen("bob", 1)));
+
+        // Enough updates to trigger a compaction
+        for minutes_ago in (0..LAST_SEEN_SLACK as i64 + 10).rev() {
+            db.merge_last_seen([seen("alice", minutes_ago - 100)])
+                .unwrap();
+        }
+
+        let (table, records) = db.read_presence_counted().unwrap();
+        assert!(records <= table.len() + LAST_SEEN_SLACK);
+        assert_eq!(table.latest_for_user("alice"), Some(seen("alice", -100)));
+        assert_eq!(table.latest_for_user("bob"), Some(seen("bob", 1)));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_reactions() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_reactions");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+        let now = Utc::now();
+        let post_id = "123e4567-e89b-12d3-a456-426614174000";
+        let reaction = |i: i64, active| Reaction {
+            uuid: format!("223e4567-e89b-12d3-a456-{:012}", i),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            post_id: post_id.to_string(),
+            kind: crate::model::ReactionKind::Like,
+            active,
+            timestamp: now + chrono::TimeDelta::seconds(i),
+            signature: None,
+        };
+
+        assert_eq!(db.merge_reactions([reaction(1, true)]).unwrap().len(), 1);
+        // An older reaction doesn't replace a newer one
+        assert!(db.merge_reactions([reaction(0, false)]).unwrap().is_empty());
+        assert_eq!(db.read_reactions().unwrap().counts(post_id).likes, 1);
+
+        // Enough updates to trigger a compaction
+        for i in 2..REACTION_SLACK as i64 + 10 {
+            db.merge_reactions([reaction(i, i % 2 == 0)]).unwrap();
+        }
+
+        let (set, records) = db.read_reactions_counted().unwrap();
+        assert!(records <= set.len() + REACTION_SLACK);
+        assert_eq!(set.len(), 1);
+        assert!(set.holds(&reaction(REACTION_SLACK as i64 + 9, false).uuid));
+        assert_eq!(set.counts(post_id).likes, 0);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_tombstones() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_tombstones");
+        let _ = fs::remove_dir_all(&temp_dir);
+
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+        let now = Utc::now();
+        let post = |uuid: &str| Post {
+            uuid: uuid.to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            title: "Title".to_string(),
+            body: "".to_string(),
+            timestamp: now,
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+        let deleted = post("123e4567-e89b-12d3-a456-426614174000");
+        let edit = deleted.edited("Title".to_string(), "Edited".to_string(), now);
+        let kept = post("123e4567-e89b-12d3-a456-426614174001");
+        db.write_posts([&deleted, &kept, &edit]).unwrap();
+
+        let tombstone = Tombstone::of(
+            &deleted,
+            "323e4567-e89b-12d3-a456-426614174000".to_string(),
+            now,
+        );
+        let old = Tombstone::of(
+            &kept,
+            "323e4567-e89b-12d3-a456-426614174001".to_string(),
+            now - crate::model::tombstone::TOMBSTONE_TTL - chrono::TimeDelta::hours(1),
+        );
+        let (merged, buried) = db
+            .merge_tombstones([tombstone.clone(), old, tombstone.clone()], now)
+            .unwrap();
+        assert_eq!(merged, std::slice::from_ref(&tombstone));
+        assert_eq!(buried, [deleted, edit]);
+        assert_eq!(db.read_posts(10).unwrap(), std::slice::from_ref(&kept));
+
+        let (merged, buried) = db.merge_tombstones([tombstone.clone()], now).unwrap();
+        assert!(merged.is_empty() && buried.is_empty());
+        assert_eq!(
+            db.read_tombstones(now).unwrap(),
+            std::slice::from_ref(&tombstone)
+        );
+        assert!(
+            db.read_tombstones(now + crate::model::tombstone::TOMBSTONE_TTL * 2)
+                .unwrap()
+                .is_empty()
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_mailbox() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_mailbox");
+        let _ = fs::remove_dir_all(&temp_dir);
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+        let now = Utc::now();
+        let recipient = crate::identity::Identity::generate();
+        let message = |i: usize| {
+            let sender = crate::identity::Identity::generate();
+            Message {
+                uuid: format!("123e4567-e89b-12d3-a456-{:012}", i),
+                sender_id: sender.user_id(),
+                recipient_id: recipient.user_id(),
+                sender_key: sender.public_key().to_string(),
+                recipient_key: recipient.public_key().to_string(),
+                timestamp: now,
+                nonce: "00".repeat(24),
+                ciphertext: "11".repeat(100),
+            }
+        };
+        let messages: Vec<_> = (0..3).map(message).collect();
+        let mailbox = messages[0].mailbox();
+
+        let results = db.deposit_messages(&messages, now).unwrap();
+        assert!(results.iter().all(Result::is_ok));
+        // Known messages are held already
+        assert_eq!(db.deposit_messages(&messages[..1], now).unwrap(), [Ok(())]);
+
+        let index = db.read_mailbox(now).unwrap();
+        let (collected, more) = db
+            .read_mailbox_messages(&index, &mailbox, &[messages[0].uuid.clone()], 200)
+            .unwrap();
+        assert_eq!(collected, [messages[1].clone()]);
+        assert!(more);
+        assert!(
+            db.read_mailbox_messages(&index, "elsewhere", &[], usize::MAX)
+                .unwrap()
+                .0
+                .is_empty()
+        );
+
+        let receipt = DeliveryReceipt::new(&messages[0], &recipient, now);
+        assert_eq!(
+            db.merge_receipts([receipt.clone()], now).unwrap(),
+            std::slice::from_ref(&receipt)
+        );
+        assert!(
+            db.merge_receipts([receipt.clone()], now)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            db.deposit_messages(&messages[..1], now).unwrap(),
+            [Err(Refusal::Delivered)]
+        );
+
+        // Enough deliveries to trigger a compaction
+        let more_messages: Vec<_> = (3..MAILBOX_SLACK + 10).map(message).collect();
+        db.deposit_messages(&more_messages, now).unwrap();
+        db.merge_receipts(
+            more_messages
+                .iter()
+                .map(|m| DeliveryReceipt::new(m, &recipient, now)),
+            now,
+        )
+        .unwrap();
+
+        let (index, records) = db.read_mailbox_counted(now).unwrap();
+        assert!(records <= index.len() + MAILBOX_SLACK);
+        let (collected, _) = db
+            .read_mailbox_messages(&index, &mailbox, &[], usize::MAX)
+            .unwrap();
+        assert_eq!(collected, messages[1..]);
+        assert!(index.knows(&receipt));
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_custody() {
+        let temp_dir = std::env::temp_dir().join("fbdb_test_custody");
+        let _ = fs::remove_dir_all(&temp_dir);
+        let db = FileBasedDB::init(&temp_dir).unwrap();
+        let now = Utc::now();
+        let bundle = |i: usize, destination: crate::model::Destination| Bundle {
+            uuid: format!("123e4567-e89b-12d3-a456-{:012}", i),
+            header: crate::model::BundleHeader::new(destination, now),
+            payload: crate::model::BundlePayload::Post(Post {
+                uuid: format!("223e4567-e89b-12d3-a456-{:012}", i),
+                user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+                title: "Lost cat".to_string(),
+                body: "Seen near the fountain?".to_string(),
+                timestamp: now,
+                image: None,
+                source_totem: None,
+                thumbnail: None,
+                image_preview: None,
+                attachments: Vec::new(),
+                revision: 0,
+                edited_at: None,
+                signature: None,
+            }),
+        };
+        let far = bundle(0, crate::model::Destination::Totem("far".to_string()));
+        let to_bob = bundle(1, crate::model::Destination::User("bob".to_string()));
+
+        assert_eq!(
+            db.take_custody(vec![far.clone(), to_bob.clone()], now)
+                .unwrap(),
+            [true, true]
+        );
+        // Copies arriving later add up
+        assert_eq!(db.take_custody(vec![far.clone()], now).unwrap(), [true]);
+        assert_eq!(
+            db.read_bundle_uuids(now).unwrap(),
+            [far.uuid.clone(), to_bob.uuid.clone()]
+        );
+
+        // A phone carrying the bundle already gets nothing
+        let carried = [far.uuid.clone()];
+        let handed = db
+            .hand_out_bundles(
+                Node::Phone {
+                    user: Some("alice"),
+                    courier: true,
+                },
+                &carried,
+                usize::MAX,
+                now,
+            )
+            .unwrap();
+        assert_eq!(handed.len(), 1);
+        assert_eq!(handed[0].header.copies, to_bob.header.copies / 2);
+
+        // Bob gets half of the merged copies of the other bundle and every copy left of his
+        let handed = db
+            .hand_out_bundles(
+                Node::Phone {
+                    user: Some("bob"),
+                    courier: true,
+                },
+                &[],
+                usize::MAX,
+                now,
+            )
+            .unwrap();
+        let copies: Vec<_> = handed
+            .iter()
+            .map(|b| (b.uuid.clone(), b.header.copies))
+            .collect();
+        assert_eq!(
+            copies,
+            [
+                (far.uuid.clone(), far.header.copies),
+                (to_bob.uuid.clone(), to_bob.header.copies / 2)
+            ]
+        );
+        assert_eq!(
+            db.read_bundle_uuids(now).unwrap(),
+            std::slice::from_ref(&far.uuid)
+        );
+
+        // Full custody refuses new bundles, but still merges known ones
+        let many: Vec<_> = (2..MAX_CUSTODY_BUNDLES + 1)
+            .map(|i| bundle(i, crate::model::Destination::Totem("far".to_string())))
+            .collect();
+        assert!(
+            db.take_custody(many, now)
+                .unwrap()
+                .iter()
+                .all(|taken| *taken)
+        );
+        let extra = bundle(
+            MAX_CUSTODY_BUNDLES + 1,
+            crate::model::Destination::Totem("far".to_string()),
+        );
+        assert_eq!(
+            db.take_custody(vec![extra, far.clone()], now).unwrap(),
+            [false, true]
+        );
+
+        // Expired bundles are dropped at the next hand out
+        let later = now + crate::routing::MAX_TTL;
+        assert!(
+            db.hand_out_bundles(Node::Totem("elsewhere"), &[], usize::MAX, later)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(db.read_bundle_uuids(now).unwrap().is_empty());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
 }
@@ -0,0 +1,69 @@
+//! Records as they were stored before format versions existed, version 0
+//!
+//! The first release stored users and posts with just these fields. Previews, attachments,
+//! keys, signatures and edits were added later, they start out empty for upgraded records.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::model::validation::deserialize_timestamp;
+use crate::model::{Post, User};
+
+#[derive(Deserialize)]
+pub(super) struct UserV0 {
+    uuid: String,
+    username: String,
+    status: String,
+    bio: String,
+    profile_picture: Option<String>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    last_contact: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PostV0 {
+    uuid: String,
+    user_id: String,
+    title: String,
+    body: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    timestamp: DateTime<Utc>,
+    image: Option<String>,
+    source_totem: Option<String>,
+}
+
+impl From<UserV0> for User {
+    fn from(user: UserV0) -> Self {
+        User {
+            uuid: user.uuid,
+            username: user.username,
+            status: user.status,
+            bio: user.bio,
+            profile_picture: user.profile_picture,
+            last_contact: user.last_contact,
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        }
+    }
+}
+
+impl From<PostV0> for Post {
+    fn from(post: PostV0) -> Self {
+        Post {
+            uuid: post.uuid,
+            user_id: post.user_id,
+            title: post.title,
+            body: post.body,
+            timestamp: post.timestamp,
+            image: post.image,
+            source_totem: post.source_totem,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Helpers for reading HTTP request bodies and handling byte ranges
+//!
+//! Bodies are delimited by `Content-Length`. ESP-IDF's httpd only reads bodies up to the
+//! length a request announces, so `Transfer-Encoding: chunked` isn't supported and such
+//! requests are answered with 411, asking the client to send the length instead.
+//! Everything in here works on a plain [`Read`], so it can be used on top of the ESP-IDF
+//! connection and in host tests.
+//!
+//! Byte ranges are used to resume interrupted transfers: downloads honour `Range`,
+//! uploads can be sent in pieces with `Content-Range`.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Errors that can occur while reading a request body
+#[derive(Debug)]
+pub enum BodyError {
+    /// The body has no `Content-Length`
+    LengthRequired,
+    /// The body is bigger than the limit of the endpoint
+    TooLarge,
+    /// A transfer encoding other than `chunked` was requested
+    UnsupportedEncoding,
+    /// The body ended before the announced length
+    Malformed,
+    /// Reading from the connection failed
+    Io(io::Error),
+}
+
+impl BodyError {
+    /// HTTP status code that should be sent back for this error
+    pub fn status(&self) -> u16 {
+        match self {
+            BodyError::LengthRequired => 411,
+            BodyError::TooLarge => 413,
+            BodyError::UnsupportedEncoding => 501,
+            BodyError::Malformed => 400,
+            BodyError::Io(_) => 500,
+        }
+    }
+}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::LengthRequired => write!(f, "Length required"),
+            BodyError::TooLarge => write!(f, "Request too big"),
+            BodyError::UnsupportedEncoding => write!(f, "Unsupported transfer encoding"),
+            BodyError::Malformed => write!(f, "Malformed request body"),
+            BodyError::Io(e) => write!(f, "Failed to read request body: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BodyError {}
+
+impl From<io::Error> for BodyError {
+    fn from(e: io::Error) -> Self {
+        BodyError::Io(e)
+    }
+}
+
+/// Determine the length of a request body from its headers
+/// Chunked bodies are answered with 411, other transfer encodings with 501.
+/// Bodies announced to be bigger than `limit` are rejected right away.
+pub fn body_length(
+    content_length: Option<u64>,
+    transfer_encoding: Option<&str>,
+    limit: usize,
+) -> Result<usize, BodyError> {
+    if let Some(encoding) = transfer_encoding {
+        // chunked has to be the last coding applied, and we don't support any other
+        let mut codings = encoding.split(',').map(str::trim).filter(|c| !c.is_empty());
+        return match (codings.next(), codings.next()) {
+            (Some(coding), None) if coding.eq_ignore_ascii_case("chunked") => {
+                Err(BodyError::LengthRequired)
+            }
+            _ => Err(BodyError::UnsupportedEncoding),
+        };
+    }
+
+    match content_length {
+        Some(len) if len > limit as u64 => Err(BodyError::TooLarge),
+        Some(len) => Ok(len as usize),
+        None => Err(BodyError::LengthRequired),
+    }
+}
+
+/// Reads a request body of a known length
+pub struct BodyReader<R> {
+    inner: R,
+    /// Body bytes still to be read
+    remaining: usize,
+    /// Body bytes handed out so far
+    total: usize,
+}
+
+impl<R: Read> BodyReader<R> {
+    pub fn new(inner: R, len: usize) -> Self {
+        BodyReader {
+            inner,
+            remaining: len,
+            total: 0,
+        }
+    }
+
+    /// Number of body bytes read so far
+    pub fn bytes_read(&self) -> usize {
+        self.total
+    }
+
+    /// Read the next part of the body into `buf`
+    /// Returns 0 once the end of the body has been reached.
+    pub fn read_some(&mut self, buf: &mut [u8]) -> Result<usize, BodyError> {
+        if self.remaining == 0 || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let to_read = self.remaining.min(buf.len());
+        let n = self.inner.read(&mut buf[..to_read])?;
+        if n == 0 {
+            // Connection closed before the announced amount of data arrived
+            return Err(BodyError::Malformed);
+        }
+
+        self.total += n;
+        self.remaining -= n;
+        Ok(n)
+    }
+
+    /// Read the whole body into memory
+    pub fn read_to_vec(mut self) -> Result<Vec<u8>, BodyError> {
+        let mut body = Vec::with_capacity(self.remaining);
+        let mut buf = [0u8; 512];
+
+        loop {
+            let n = self.read_some(&mut buf)?;
+            if n == 0 {
+                return Ok(body);
+            }
+            body.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    /// Stream the whole body into `writer` using `buf` as scratch space
+    /// Returns the number of bytes written.
+    pub fn copy_to<W: Write>(
+        &mut self,
+        writer: &mut W,
+        buf: &mut [u8],
+    ) -> Result<usize, BodyError> {
+        let mut written = 0;
+
+        loop {
+            let n = self.read_some(buf)?;
+            if n == 0 {
+                return Ok(written);
+            }
+            writer.write_all(&buf[..n])?;
+            written += n;
+        }
+    }
+}
+
+/// An inclusive range of bytes within a resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The `Range` of a request can't be served, which is answered with 416
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeNotSatisfiable;
+
+impl RangeNotSatisfiable {
+    /// Value of the `Content-Range` header of the 416 response, naming the size (RFC 9110, 15.5.17)
+    pub fn content_range(&self, size: u64) -> String {
+        format!("bytes */{size}")
+    }
+}
+
+// A range always contains at least one byte, so there's no `is_empty`
+#[allow(clippy::len_without_is_empty)]
+impl ByteRange {
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Resolve a `Range` request header against a resource of `size` bytes
+    ///
+    /// Only single ranges are supported (`bytes=0-499`, `bytes=500-`, `bytes=-500`).
+    /// Headers we don't understand, including multiple ranges, are ignored, so
+    /// `Ok(None)` means the whole resource should be sent.
+    pub fn from_range_header(value: &str, size: u64) -> Result<Option<Self>, RangeNotSatisfiable> {
+        let Some(spec) = value.trim().strip_prefix("bytes=") else {
+            return Ok(None);
+        };
+        if spec.contains(',') {
+            return Ok(None);
+        }
+        let Some((first, last)) = spec.trim().split_once('-') else {
+            return Ok(None);
+        };
+
+        let range = match (first.trim(), last.trim()) {
+            // Suffix range: the last `n` bytes
+            ("", n) => match n.parse::<u64>() {
+                Ok(0) => return Err(RangeNotSatisfiable),
+                Ok(n) => ByteRange {
+                    start: size.saturating_sub(n),
+                    end: size.wrapping_sub(1),
+                },
+                Err(_) => return Ok(None),
+            },
+            (first, last) => {
+                let Ok(start) = first.parse::<u64>() else {
+                    return Ok(None);
+                };
+                let end = match last {
+                    "" => size.wrapping_sub(1),
+                    last => match last.parse::<u64>() {
+                        Ok(end) if end < start => return Ok(None),
+                        Ok(end) => end.min(size.wrapping_sub(1)),
+                        Err(_) => return Ok(None),
+                    },
+                };
+                ByteRange { start, end }
+            }
+        };
+
+        if size == 0 || range.start >= size {
+            return Err(RangeNotSatisfiable);
+        }
+
+        Ok(Some(range))
+    }
+
+    /// Value of the `Content-Range` header of a response carrying this range
+    pub fn content_range(&self, size: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, size)
+    }
+}
+
+/// The `Content-Range` header of an upload that is sent in pieces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRange {
+    /// The bytes contained in this request
+    pub range: ByteRange,
+    /// Size of the complete upload
+    pub total: u64,
+}
+
+impl ContentRange {
+    /// Parse `bytes <first>-<last>/<total>`
+    /// The total has to be known, since the upload is only checked once it is complete.
+    pub fn parse(value: &str) -> Option<Self> {
+        let spec = value.trim().strip_prefix("bytes ")?;
+        let (range, total) = spec.split_once('/')?;
+        let (start, end) = range.split_once('-')?;
+
+        let start = start.trim().parse::<u64>().ok()?;
+        let end = end.trim().parse::<u64>().ok()?;
+        let total = total.trim().parse::<u64>().ok()?;
+
+        if end < start || end >= total {
+            return None;
+        }
+
+        Some(ContentRange {
+            range: ByteRange { start, end },
+            total,
+        })
+    }
+
+    /// Whether this piece completes the upload
+    pub fn is_last(&self) -> bool {
+        self.range.end + 1 == self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_length() {
+        assert_eq!(body_length(Some(10), None, 100).unwrap(), 10);
+
+        assert_eq!(body_length(None, None, 100).unwrap_err().status(), 411);
+        assert_eq!(body_length(Some(101), None, 100).unwrap_err().status(), 413);
+        // Chunked bodies can't be read, even if there is a Content-Length too
+        assert_eq!(
+            body_length(None, Some("chunked"), 100)
+                .unwrap_err()
+                .status(),
+            411
+        );
+        assert_eq!(
+            body_length(Some(10), Some("Chunked"), 100)
+                .unwrap_err()
+                .status(),
+            411
+        );
+        assert_eq!(
+            body_length(None, Some("gzip, chunked"), 100)
+                .unwrap_err()
+                .status(),
+            501
+        );
+    }
+
+    #[test]
+    fn test_read_fixed_length() {
+        let body = BodyReader::new(&b"{\"a\":1}trailing"[..], 7)
+            .read_to_vec()
+            .unwrap();
+        assert_eq!(body, b"{\"a\":1}");
+
+        let empty = BodyReader::new(&b""[..], 0).read_to_vec().unwrap();
+        assert!(empty.is_empty());
+
+        // Connection closed early
+        let err = BodyReader::new(&b"abc"[..], 5).read_to_vec().unwrap_err();
+        assert!(matches!(err, BodyError::Malformed));
+    }
+
+    #[test]
+    fn test_copy_to_writer() {
+        let mut out = Vec::new();
+        let mut buf = [0u8; 2];
+
+        let mut body = BodyReader::new(&b"abcdefgh"[..], 6);
+        let written = body.copy_to(&mut out, &mut buf).unwrap();
+
+        assert_eq!(written, 6);
+        assert_eq!(body.bytes_read(), 6);
+        assert_eq!(out, b"abcdef");
+    }
+
+    #[test]
+    fn test_range_header() {
+        let parse = |value| ByteRange::from_range_header(value, 1000);
+        let range = |start, end| Ok(Some(ByteRange { start, end }));
+
+        assert_eq!(parse("bytes=0-499"), range(0, 499));
+        assert_eq!(parse("bytes=500-"), range(500, 999));
+        assert_eq!(parse("bytes=-100"), range(900, 999));
+        assert_eq!(parse("bytes=-5000"), range(0, 999));
+        // The end is capped to the size
+        assert_eq!(parse("bytes=900-5000"), range(900, 999));
+
+        assert_eq!(parse("bytes=1000-"), Err(RangeNotSatisfiable));
+        assert_eq!(parse("bytes=-0"), Err(RangeNotSatisfiable));
+        assert_eq!(
+            ByteRange::from_range_header("bytes=0-", 0),
+            Err(RangeNotSatisfiable)
+        );
+
+        // Ignored, the whole resource is sent instead
+        assert_eq!(parse("bytes=0-1,5-6"), Ok(None));
+        assert_eq!(parse("items=0-1"), Ok(None));
+        assert_eq!(parse("bytes=5-1"), Ok(None));
+        assert_eq!(parse("bytes=a-b"), Ok(None));
+
+        assert_eq!(
+            ByteRange {
+                start: 900,
+                end: 999
+            }
+            .content_range(1000),
+            "bytes 900-999/1000"
+        );
+        assert_eq!(RangeNotSatisfiable.content_range(1000), "bytes */1000");
+    }
+
+    #[test]
+    fn test_content_range() {
+        let range = ContentRange::parse("bytes 0-999/2000").unwrap();
+        assert_eq!(range.range, ByteRange { start: 0, end: 999 });
+        assert_eq!(range.range.len(), 1000);
+        assert!(!range.is_last());
+
+        assert!(
+            ContentRange::parse("bytes 1000-1999/2000")
+                .unwrap()
+                .is_last()
+        );
+
+        assert_eq!(ContentRange::parse("bytes 0-999/*"), None);
+        assert_eq!(ContentRange::parse("bytes 0-2000/2000"), None);
+        assert_eq!(ContentRange::parse("bytes 10-5/2000"), None);
+        assert_eq!(ContentRange::parse("0-5/10"), None);
+    }
+}
@@ -0,0 +1,309 @@
+//! Cryptographic identities of users
+//!
+//! Every user owns an Ed25519 keypair. The user ID is derived from the public key, so
+//! a user record can only be published under an ID by whoever holds the matching
+//! secret key, and anyone can check that a public key belongs to an ID without asking
+//! a server. IDs keep the UUID format, so they fit everywhere UUIDs were used before.
+//!
+//! Keys and signatures travel as lowercase hex, like content hashes.
+
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+use uuid::{Builder, Uuid};
+
+/// Length of public and secret keys in bytes
+pub const KEY_LEN: usize = 32;
+/// Length of signatures in bytes
+pub const SIGNATURE_LEN: usize = 64;
+/// Length of key fingerprints in hex characters
+pub const FINGERPRINT_LEN: usize = 16;
+
+/// Prefix of the hashed public key, so the ID can't be confused with other hashes of a key
+const USER_ID_DOMAIN: &[u8] = b"loom user id v1\0";
+
+/// Errors of keys received from somewhere else
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentityError {
+    /// Not the right number of hex digits
+    InvalidEncoding,
+    /// 32 bytes, but not a point on the curve
+    InvalidKey,
+}
+
+impl fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentityError::InvalidEncoding => {
+                write!(f, "Must be lowercase hex of the right length")
+            }
+            IdentityError::InvalidKey => write!(f, "Not an Ed25519 public key"),
+        }
+    }
+}
+
+impl std::error::Error for IdentityError {}
+
+/// The public half of a user's keypair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicKey(VerifyingKey);
+
+impl PublicKey {
+    pub fn from_bytes(bytes: &[u8; KEY_LEN]) -> Result<Self, IdentityError> {
+        VerifyingKey::from_bytes(bytes)
+            .map(PublicKey)
+            .map_err(|_| IdentityError::InvalidKey)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; KEY_LEN] {
+        self.0.as_bytes()
+    }
+
+    /// The user ID belonging to this key
+    /// The first 16 bytes of a SHA-256 hash of the key, as a version 8 UUID.
+    pub fn user_id(&self) -> String {
+        let hash = Sha256::new()
+            .chain_update(USER_ID_DOMAIN)
+            .chain_update(self.as_bytes())
+            .finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+
+        Builder::from_custom_bytes(bytes)
+            .into_uuid()
+            .hyphenated()
+            .to_string()
+    }
+
+    /// Short form of the key that people can read out and type, the start of its hex form
+    pub fn fingerprint(&self) -> String {
+        let mut hex = self.to_string();
+        hex.truncate(FINGERPRINT_LEN);
+        hex
+    }
+
+    /// Whether the ID was derived from this key
+    pub fn owns(&self, user_id: &str) -> bool {
+        self.user_id() == user_id
+    }
+
+    /// Whether `signature` was made over `message` by the secret half of this key
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        self.0.verify_strict(message, &signature.0).is_ok()
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, self.as_bytes())
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = IdentityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PublicKey::from_bytes(&parse_hex(s)?)
+    }
+}
+
+/// A detached Ed25519 signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature(ed25519_dalek::Signature);
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8; SIGNATURE_LEN]) -> Self {
+        Signature(ed25519_dalek::Signature::from_bytes(bytes))
+    }
+
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_LEN] {
+        self.0.to_bytes()
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hex(f, &self.to_bytes())
+    }
+}
+
+impl FromStr for Signature {
+    type Err = IdentityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Signature::from_bytes(&parse_hex(s)?))
+    }
+}
+
+/// A user's keypair, only ever stored on their own devices
+pub struct Identity {
+    signing_key: SigningKey,
+}
+
+impl Identity {
+    /// Create a new identity from the operating system's random number generator
+    pub fn generate() -> Self {
+        Identity {
+            signing_key: SigningKey::generate(&mut OsRng),
+        }
+    }
+
+    pub fn from_secret_bytes(secret: &[u8; KEY_LEN]) -> Self {
+        Identity {
+            signing_key: SigningKey::from_bytes(secret),
+        }
+    }
+
+    /// The secret key, to be stored away safely
+    pub fn secret_bytes(&self) -> [u8; KEY_LEN] {
+        self.signing_key.to_bytes()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.signing_key.verifying_key())
+    }
+
+    pub fn user_id(&self) -> String {
+        self.public_key().user_id()
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        Signature(self.signing_key.sign(message))
+    }
+
+    /// X25519 key agreement with another user's key, to encrypt messages between the two
+    /// Both keys are used in their Montgomery form, so both sides arrive at the same secret.
+    /// `None` for keys of low order, which would give away the secret.
+    pub fn agree(&self, other: &PublicKey) -> Option<[u8; KEY_LEN]> {
+        let shared = other
+            .0
+            .to_montgomery()
+            .mul_clamped(self.signing_key.to_scalar_bytes())
+            .to_bytes();
+        (shared != [0u8; KEY_LEN]).then_some(shared)
+    }
+}
+
+impl fmt::Debug for Identity {
+    // Never print the secret key
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Identity")
+            .field("public_key", &self.public_key().to_string())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Check that a user ID is a well-formed derived ID, without knowing the key
+pub fn is_derived_user_id(user_id: &str) -> bool {
+    Uuid::try_parse(user_id).is_ok_and(|uuid| uuid.get_version_num() == 8)
+}
+
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+}
+
+fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N], IdentityError> {
+    // Only one spelling, records are compared and signed as they are
+    if s.len() != N * 2 || !s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return Err(IdentityError::InvalidEncoding);
+    }
+
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+            .map_err(|_| IdentityError::InvalidEncoding)?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::validation::check_uuid;
+
+    #[test]
+    fn test_user_id_is_derived_from_key() {
+        let identity = Identity::from_secret_bytes(&[7u8; KEY_LEN]);
+        let user_id = identity.user_id();
+
+        assert!(check_uuid(&user_id).is_ok());
+        assert!(is_derived_user_id(&user_id));
+        assert!(identity.public_key().owns(&user_id));
+        // Stable across versions, IDs are stored everywhere
+        assert_eq!(
+            user_id,
+            Identity::from_secret_bytes(&[7u8; KEY_LEN]).user_id()
+        );
+
+        let other = Identity::generate();
+        assert_ne!(other.user_id(), user_id);
+        assert!(!other.public_key().owns(&user_id));
+        assert!(!is_derived_user_id("550e8400-e29b-41d4-a716-446655440000"));
+    }
+
+    #[test]
+    fn test_public_key_round_trip() {
+        let identity = Identity::generate();
+        let key = identity.public_key();
+        let hex = key.to_string();
+
+        assert_eq!(hex.len(), 64);
+        assert_eq!(hex.parse::<PublicKey>(), Ok(key));
+        assert_eq!(
+            "abc".parse::<PublicKey>(),
+            Err(IdentityError::InvalidEncoding)
+        );
+        assert_eq!(
+            "+a".repeat(32).parse::<PublicKey>(),
+            Err(IdentityError::InvalidEncoding)
+        );
+
+        let restored = Identity::from_secret_bytes(&identity.secret_bytes());
+        assert_eq!(restored.public_key(), key);
+        assert_eq!(
+            format!("{:?}", identity),
+            format!("Identity {{ public_key: {:?}, .. }}", hex)
+        );
+    }
+
+    #[test]
+    fn test_agree() {
+        let alice = Identity::generate();
+        let bob = Identity::generate();
+
+        let secret = alice.agree(&bob.public_key()).unwrap();
+        assert_eq!(bob.agree(&alice.public_key()), Some(secret));
+        assert_ne!(Identity::generate().agree(&bob.public_key()), Some(secret));
+
+        // The identity point has low order
+        let mut identity_point = [0u8; KEY_LEN];
+        identity_point[0] = 1;
+        let weak = PublicKey::from_bytes(&identity_point).unwrap();
+        assert_eq!(alice.agree(&weak), None);
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let identity = Identity::generate();
+        let signature = identity.sign(b"message");
+        let key = identity.public_key();
+
+        assert!(key.verify(b"message", &signature));
+        assert!(!key.verify(b"massage", &signature));
+        assert!(
+            !Identity::generate()
+                .public_key()
+                .verify(b"message", &signature)
+        );
+
+        let hex = signature.to_string();
+        assert_eq!(hex.len(), SIGNATURE_LEN * 2);
+        assert_eq!(hex.parse::<Signature>(), Ok(signature));
+        assert_eq!(
+            hex.to_uppercase().parse::<Signature>(),
+            Err(IdentityError::InvalidEncoding)
+        );
+    }
+}
@@ -1,7 +1,16 @@
+pub mod api;
 pub mod model;
 #[cfg(feature = "sqlite")]
 pub mod db;
 pub mod fbdb;
+pub mod http;
+pub mod mailbox;
+pub mod identity;
+pub mod media;
+pub mod presence;
+pub mod reactions;
+pub mod routing;
+pub mod trending;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
@@ -0,0 +1,332 @@
+//! Store-and-forward of direct messages on totems
+//!
+//! Sender and recipient are rarely near the same totem at the same time, so totems keep
+//! messages in mailboxes until the recipient collects them. A mailbox is named after the
+//! hash of the recipient's key, see [`crate::model::message::mailbox_of`].
+//!
+//! Once the recipient has a message, their phone signs a [`DeliveryReceipt`]. Phones carry
+//! receipts from totem to totem like other records, every totem that learns of one drops
+//! its copy of the message and refuses it from then on. A receipt is kept for
+//! [`MESSAGE_TTL`], by then every copy of the message has expired anyway.
+//!
+//! Mailboxes have a quota, the SD card is shared by everyone passing by.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::model::{DeliveryReceipt, Message};
+
+/// Messages nobody collected within this time are dropped
+pub const MESSAGE_TTL: TimeDelta = TimeDelta::days(30);
+
+/// Max number of messages waiting for a single recipient
+pub const MAILBOX_MAX_MESSAGES: usize = 100;
+
+/// Max bytes of ciphertext waiting for a single recipient, as hex
+pub const MAILBOX_MAX_BYTES: usize = 512 * 1024;
+
+/// Max size of the bodies of POST /mailbox/deposit and the messages of a collect response
+/// Holds at least one message of the longest kind.
+pub const MAX_MAILBOX_BODY: usize = 64 * 1024;
+
+/// Why a totem didn't take a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Refusal {
+    /// The recipient collected it already
+    Delivered,
+    /// It is older than [`MESSAGE_TTL`]
+    Expired,
+    /// The recipient's mailbox is over its quota, try another totem
+    MailboxFull,
+    /// It failed validation
+    Invalid,
+    /// A reason this version doesn't know about
+    #[serde(other)]
+    Unknown,
+}
+
+impl Refusal {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Refusal::Delivered => "delivered",
+            Refusal::Expired => "expired",
+            Refusal::MailboxFull => "mailbox_full",
+            Refusal::Invalid => "invalid",
+            Refusal::Unknown => "unknown",
+        }
+    }
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Delivered => write!(f, "The recipient collected this message already"),
+            Refusal::Expired => write!(
+                f,
+                "The message is older than {} days",
+                MESSAGE_TTL.num_days()
+            ),
+            Refusal::MailboxFull => write!(f, "The recipient's mailbox is full, try another totem"),
+            Refusal::Invalid => write!(f, "The message is invalid"),
+            Refusal::Unknown => write!(f, "The message was refused"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    mailbox: String,
+    timestamp: DateTime<Utc>,
+    size: usize,
+}
+
+/// Which messages a totem holds, without their ciphertext, and the receipts it knows
+#[derive(Debug, Default)]
+pub struct MailboxIndex {
+    messages: HashMap<String, Entry>,
+    /// By message and the key it was encrypted to, receipts signed by another key don't
+    /// shadow the real one
+    receipts: HashMap<(String, String), DeliveryReceipt>,
+}
+
+impl MailboxIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of messages and receipts in the index
+    pub fn len(&self) -> usize {
+        self.messages.len() + self.receipts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty() && self.receipts.is_empty()
+    }
+
+    /// Whether the message is held and waiting to be collected
+    pub fn holds(&self, uuid: &str) -> bool {
+        self.messages.contains_key(uuid)
+    }
+
+    /// Whether the receipt is known
+    pub fn knows(&self, receipt: &DeliveryReceipt) -> bool {
+        self.receipts
+            .contains_key(&(receipt.message_uuid.clone(), receipt.recipient_key.clone()))
+    }
+
+    /// Number of messages and bytes of ciphertext waiting in a mailbox
+    pub fn usage(&self, mailbox: &str) -> (usize, usize) {
+        self.messages
+            .values()
+            .filter(|entry| entry.mailbox == mailbox)
+            .fold((0, 0), |(count, bytes), entry| {
+                (count + 1, bytes + entry.size)
+            })
+    }
+
+    /// Check whether a message that isn't held yet may be
+    pub fn check_deposit(&self, message: &Message, now: DateTime<Utc>) -> Result<(), Refusal> {
+        if self
+            .receipts
+            .contains_key(&(message.uuid.clone(), message.recipient_key.clone()))
+        {
+            return Err(Refusal::Delivered);
+        }
+        if message.timestamp + MESSAGE_TTL < now {
+            return Err(Refusal::Expired);
+        }
+
+        let (count, bytes) = self.usage(&message.mailbox());
+        if count >= MAILBOX_MAX_MESSAGES || bytes + message.ciphertext.len() > MAILBOX_MAX_BYTES {
+            return Err(Refusal::MailboxFull);
+        }
+        Ok(())
+    }
+
+    /// Add a message, quotas are checked by [`MailboxIndex::check_deposit`]
+    pub fn insert(&mut self, message: &Message) {
+        self.messages.insert(
+            message.uuid.clone(),
+            Entry {
+                mailbox: message.mailbox(),
+                timestamp: message.timestamp,
+                size: message.ciphertext.len(),
+            },
+        );
+    }
+
+    /// Add a receipt, dropping the message it confirms
+    /// Returns true if it wasn't known. Its signature has to be checked before.
+    pub fn accept(&mut self, receipt: DeliveryReceipt) -> bool {
+        let key = (receipt.message_uuid.clone(), receipt.recipient_key.clone());
+        if self.receipts.contains_key(&key) {
+            return false;
+        }
+
+        let mailbox = crate::model::message::mailbox_of(&receipt.recipient_key);
+        if self
+            .messages
+            .get(&receipt.message_uuid)
+            .is_some_and(|entry| entry.mailbox == mailbox)
+        {
+            self.messages.remove(&receipt.message_uuid);
+        }
+        self.receipts.insert(key, receipt);
+        true
+    }
+
+    /// Drop messages and receipts older than [`MESSAGE_TTL`]
+    /// Returns the number of entries dropped.
+    pub fn expire(&mut self, now: DateTime<Utc>) -> usize {
+        let before = self.len();
+        self.messages
+            .retain(|_, entry| entry.timestamp + MESSAGE_TTL >= now);
+        self.receipts
+            .retain(|_, receipt| receipt.timestamp + MESSAGE_TTL >= now);
+        before - self.len()
+    }
+
+    /// Whether a message waiting in `mailbox` is held
+    pub fn holds_in(&self, uuid: &str, mailbox: &str) -> bool {
+        self.messages
+            .get(uuid)
+            .is_some_and(|entry| entry.mailbox == mailbox)
+    }
+
+    /// The receipt for a message, if it was delivered
+    pub fn receipt_for(&self, message_uuid: &str) -> Option<&DeliveryReceipt> {
+        self.receipts
+            .values()
+            .find(|receipt| receipt.message_uuid == message_uuid)
+    }
+
+    /// All receipts, newest first
+    pub fn receipts(&self) -> Vec<&DeliveryReceipt> {
+        let mut receipts: Vec<_> = self.receipts.values().collect();
+        receipts.sort_by_key(|receipt| std::cmp::Reverse(receipt.timestamp));
+        receipts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::Identity;
+
+    fn message(uuid: &str, recipient: &Identity, timestamp: DateTime<Utc>) -> Message {
+        let sender = Identity::generate();
+        Message {
+            uuid: uuid.to_string(),
+            sender_id: sender.user_id(),
+            recipient_id: recipient.user_id(),
+            sender_key: sender.public_key().to_string(),
+            recipient_key: recipient.public_key().to_string(),
+            timestamp,
+            nonce: "00".repeat(24),
+            ciphertext: "11".repeat(100),
+        }
+    }
+
+    #[test]
+    fn test_receipt_drops_message() {
+        let now = Utc::now();
+        let recipient = Identity::generate();
+        let message = message("123e4567-e89b-12d3-a456-426614174000", &recipient, now);
+        let mut index = MailboxIndex::new();
+        assert_eq!(index.check_deposit(&message, now), Ok(()));
+        index.insert(&message);
+        assert!(index.holds_in(&message.uuid, &message.mailbox()));
+
+        // Signed by someone else, it doesn't count for this message
+        let forged = DeliveryReceipt::new(&message, &Identity::generate(), now);
+        let forged = DeliveryReceipt {
+            recipient_key: Identity::generate().public_key().to_string(),
+            ..forged
+        };
+        assert!(index.accept(forged));
+        assert!(index.holds(&message.uuid));
+
+        let receipt = DeliveryReceipt::new(&message, &recipient, now);
+        assert!(index.accept(receipt.clone()));
+        assert!(!index.accept(receipt.clone()));
+        assert!(!index.holds(&message.uuid));
+        assert!(index.knows(&receipt));
+        assert_eq!(index.check_deposit(&message, now), Err(Refusal::Delivered));
+    }
+
+    #[test]
+    fn test_quota() {
+        let now = Utc::now();
+        let recipient = Identity::generate();
+        let mut index = MailboxIndex::new();
+        for i in 0..MAILBOX_MAX_MESSAGES {
+            let message = message(
+                &format!("123e4567-e89b-12d3-a456-{:012}", i),
+                &recipient,
+                now,
+            );
+            assert_eq!(index.check_deposit(&message, now), Ok(()));
+            index.insert(&message);
+        }
+
+        let full = message("223e4567-e89b-12d3-a456-426614174000", &recipient, now);
+        assert_eq!(index.check_deposit(&full, now), Err(Refusal::MailboxFull));
+        assert_eq!(
+            index.usage(&full.mailbox()),
+            (MAILBOX_MAX_MESSAGES, MAILBOX_MAX_MESSAGES * 200)
+        );
+
+        // Other recipients have their own quota
+        let other = message(
+            "323e4567-e89b-12d3-a456-426614174000",
+            &Identity::generate(),
+            now,
+        );
+        assert_eq!(index.check_deposit(&other, now), Ok(()));
+    }
+
+    #[test]
+    fn test_expire() {
+        let now = Utc::now();
+        let recipient = Identity::generate();
+        let old = message(
+            "123e4567-e89b-12d3-a456-426614174000",
+            &recipient,
+            now - MESSAGE_TTL - TimeDelta::hours(1),
+        );
+        let recent = message(
+            "123e4567-e89b-12d3-a456-426614174001",
+            &recipient,
+            now - TimeDelta::hours(1),
+        );
+        let mut index = MailboxIndex::new();
+        assert_eq!(index.check_deposit(&old, now), Err(Refusal::Expired));
+
+        index.insert(&old);
+        index.insert(&recent);
+        index.accept(DeliveryReceipt::new(&old, &recipient, old.timestamp));
+        assert_eq!(index.expire(now), 1);
+        assert!(index.holds(&recent.uuid));
+        assert!(index.receipt_for(&old.uuid).is_none());
+    }
+
+    #[test]
+    fn test_refusal_names() {
+        for reason in [
+            Refusal::Delivered,
+            Refusal::Expired,
+            Refusal::MailboxFull,
+            Refusal::Invalid,
+            Refusal::Unknown,
+        ] {
+            assert_eq!(serde_json::to_value(reason).unwrap(), reason.as_str());
+        }
+        assert_eq!(
+            Refusal::Expired.to_string(),
+            "The message is older than 30 days"
+        );
+    }
+}
@@ -0,0 +1,669 @@
+//! Content-addressed store for pictures and other blobs
+//!
+//! Blobs are stored under the hex encoded SHA-256 hash of their content, so the name
+//! of a blob can't be chosen by the client and the same picture is only stored once.
+//! Uploads are written to a `.part` file next to the final one, and only moved into
+//! place once the hash has been verified and the content type has been recognised.
+//! An interrupted upload keeps its `.part` file, so it can be resumed at the offset
+//! returned by [`MediaStore::upload_state`].
+//!
+//! The store only uses `std::fs`, so it works on the SD card of a totem as well as in
+//! a directory on the host.
+
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Number of bytes needed to recognise every supported content type
+const SNIFF_LEN: usize = 12;
+
+/// SHA-256 hash of a blob
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentHash([u8; 32]);
+
+impl ContentHash {
+    /// Value of the `ETag` header for the blob with this hash
+    pub fn etag(&self) -> String {
+        format!("\"{self}\"")
+    }
+
+    /// Whether an `If-None-Match` or `If-Range` header refers to this blob
+    pub fn matches_etag(&self, header: &str) -> bool {
+        let etag = self.etag();
+        header
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == etag)
+    }
+
+    /// Hash the given data
+    pub fn of(data: &[u8]) -> Self {
+        ContentHash(Sha256::digest(data).into())
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ContentHash {
+    type Err = MediaError;
+
+    /// Parse a hash from 64 hex digits
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 64 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(MediaError::InvalidHash);
+        }
+
+        let mut hash = [0u8; 32];
+        for (i, byte) in hash.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)
+                .map_err(|_| MediaError::InvalidHash)?;
+        }
+
+        Ok(ContentHash(hash))
+    }
+}
+
+/// Content types the store accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaType {
+    Jpeg,
+    Png,
+    Webp,
+    /// MPEG-4 video, usually recorded by phones
+    Mp4,
+    /// MPEG-4 audio, as recorded by voice note apps
+    M4a,
+    Webm,
+    /// Ogg, usually Opus voice notes
+    Ogg,
+    Mp3,
+    Pdf,
+    Zip,
+}
+
+impl MediaType {
+    /// Recognise the content type from the first bytes of a blob
+    pub fn sniff(head: &[u8]) -> Option<Self> {
+        if head.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(MediaType::Jpeg)
+        } else if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(MediaType::Png)
+        } else if head.len() >= SNIFF_LEN && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+            Some(MediaType::Webp)
+        } else if head.len() >= SNIFF_LEN && &head[4..8] == b"ftyp" {
+            // The major brand tells audio from video
+            match &head[8..12] {
+                b"M4A " | b"M4B " => Some(MediaType::M4a),
+                _ => Some(MediaType::Mp4),
+            }
+        } else if head.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+            Some(MediaType::Webm)
+        } else if head.starts_with(b"OggS") {
+            Some(MediaType::Ogg)
+        } else if head.starts_with(b"ID3")
+            || (head.len() >= 2 && head[0] == 0xFF && head[1] & 0xE0 == 0xE0)
+        {
+            Some(MediaType::Mp3)
+        } else if head.starts_with(b"%PDF-") {
+            Some(MediaType::Pdf)
+        } else if head.starts_with(b"PK\x03\x04") {
+            Some(MediaType::Zip)
+        } else {
+            None
+        }
+    }
+
+    /// Value of the `Content-Type` header
+    pub fn mime(self) -> &'static str {
+        match self {
+            MediaType::Jpeg => "image/jpeg",
+            MediaType::Png => "image/png",
+            MediaType::Webp => "image/webp",
+            MediaType::Mp4 => "video/mp4",
+            MediaType::M4a => "audio/mp4",
+            MediaType::Webm => "video/webm",
+            MediaType::Ogg => "audio/ogg",
+            MediaType::Mp3 => "audio/mpeg",
+            MediaType::Pdf => "application/pdf",
+            MediaType::Zip => "application/zip",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            MediaType::Jpeg => "jpg",
+            MediaType::Png => "png",
+            MediaType::Webp => "webp",
+            MediaType::Mp4 => "mp4",
+            MediaType::M4a => "m4a",
+            MediaType::Webm => "webm",
+            MediaType::Ogg => "ogg",
+            MediaType::Mp3 => "mp3",
+            MediaType::Pdf => "pdf",
+            MediaType::Zip => "zip",
+        }
+    }
+}
+
+/// What the store knows about a blob
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub hash: ContentHash,
+    pub media_type: MediaType,
+    pub size: u64,
+}
+
+/// Errors of the media store
+#[derive(Debug)]
+pub enum MediaError {
+    /// The name of the blob isn't a SHA-256 hash
+    InvalidHash,
+    /// There is no blob with that hash
+    NotFound,
+    /// A blob with that hash is stored already
+    AlreadyExists,
+    /// The uploaded content doesn't match the hash it was uploaded under
+    HashMismatch { actual: ContentHash },
+    /// The content isn't of a known [`MediaType`]
+    UnsupportedType,
+    /// A piece of an upload doesn't continue where the stored part ends
+    OffsetMismatch { expected: u64 },
+    /// Reading or writing the storage failed
+    Io(io::Error),
+}
+
+impl fmt::Display for MediaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaError::InvalidHash => write!(f, "Not a SHA-256 hash"),
+            MediaError::NotFound => write!(f, "Media not found"),
+            MediaError::AlreadyExists => write!(f, "Media exists already"),
+            MediaError::HashMismatch { actual } => write!(f, "Content hash is {actual}"),
+            MediaError::UnsupportedType => write!(f, "Unsupported content type"),
+            MediaError::OffsetMismatch { expected } => {
+                write!(f, "Upload continues at offset {expected}")
+            }
+            MediaError::Io(e) => write!(f, "Storage error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MediaError {}
+
+impl From<io::Error> for MediaError {
+    fn from(e: io::Error) -> Self {
+        MediaError::Io(e)
+    }
+}
+
+/// How far the upload of a blob has come
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadState {
+    /// Number of bytes received so far, the next piece has to start here
+    pub offset: u64,
+    /// The blob is stored completely
+    pub complete: bool,
+}
+
+/// Blob store in a single directory
+#[derive(Debug, Clone)]
+pub struct MediaStore {
+    dir: PathBuf,
+}
+
+impl MediaStore {
+    /// Open the store in the given directory, creating it if needed
+    pub fn init<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        Ok(MediaStore { dir })
+    }
+
+    /// Where the blob with the given hash is stored
+    pub fn blob_path(&self, hash: &ContentHash) -> PathBuf {
+        self.path(hash)
+    }
+
+    fn path(&self, hash: &ContentHash) -> PathBuf {
+        self.dir.join(hash.to_string())
+    }
+
+    fn part_path(&self, hash: &ContentHash) -> PathBuf {
+        self.dir.join(format!("{hash}.part"))
+    }
+
+    pub fn contains(&self, hash: &ContentHash) -> bool {
+        self.path(hash).exists()
+    }
+
+    /// Start an upload of the blob with the given hash
+    /// Fails with [`MediaError::AlreadyExists`] if the blob is stored already.
+    /// A previously interrupted upload of the blob is thrown away.
+    pub fn begin_upload(&self, hash: ContentHash) -> Result<Upload, MediaError> {
+        self.resume_upload(hash, 0)
+    }
+
+    /// How much of the blob with the given hash has been received
+    pub fn upload_state(&self, hash: &ContentHash) -> Result<UploadState, MediaError> {
+        if let Ok(metadata) = fs::metadata(self.path(hash)) {
+            return Ok(UploadState {
+                offset: metadata.len(),
+                complete: true,
+            });
+        }
+
+        let offset = match fs::metadata(self.part_path(hash)) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(UploadState {
+            offset,
+            complete: false,
+        })
+    }
+
+    /// Continue the upload of the blob with the given hash at `offset`
+    ///
+    /// `offset` has to be where the interrupted upload ended, see [`Self::upload_state`],
+    /// otherwise this fails with [`MediaError::OffsetMismatch`]. An offset of 0 starts over.
+    pub fn resume_upload(&self, hash: ContentHash, offset: u64) -> Result<Upload, MediaError> {
+        if self.contains(&hash) {
+            return Err(MediaError::AlreadyExists);
+        }
+
+        let part = self.part_path(&hash);
+        let mut hasher = Sha256::new();
+        let mut head = Vec::with_capacity(SNIFF_LEN);
+
+        let file = if offset == 0 {
+            File::create(&part)?
+        } else {
+            let expected = self.upload_state(&hash)?.offset;
+            if offset != expected {
+                return Err(MediaError::OffsetMismatch { expected });
+            }
+
+            // The hash covers the whole content, so feed what we have so far back into it
+            let mut file = OpenOptions::new().read(true).append(true).open(&part)?;
+            let mut buf = [0u8; 1024];
+            loop {
+                match file.read(&mut buf)? {
+                    0 => break,
+                    n => record(&mut hasher, &mut head, &buf[..n]),
+                }
+            }
+            file
+        };
+
+        Ok(Upload {
+            writer: BufWriter::new(file),
+            hasher,
+            head,
+            size: offset,
+            hash,
+            part,
+            path: self.path(&hash),
+        })
+    }
+
+    /// Store a blob that is already in memory
+    pub fn put(&self, data: &[u8]) -> Result<MediaInfo, MediaError> {
+        let mut upload = self.begin_upload(ContentHash::of(data))?;
+        if let Err(e) = upload.write_all(data) {
+            upload.abort();
+            return Err(e.into());
+        }
+        upload.finish()
+    }
+
+    /// Describe a stored blob
+    pub fn info(&self, hash: &ContentHash) -> Result<MediaInfo, MediaError> {
+        self.open(hash).map(|(_, info)| info)
+    }
+
+    /// Open a stored blob for reading
+    pub fn open(&self, hash: &ContentHash) -> Result<(File, MediaInfo), MediaError> {
+        let mut file = match File::open(self.path(hash)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(MediaError::NotFound),
+            Err(e) => return Err(e.into()),
+        };
+
+        let size = file.metadata()?.len();
+        let mut head = [0u8; SNIFF_LEN];
+        let len = read_up_to(&mut file, &mut head)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        let media_type = MediaType::sniff(&head[..len]).ok_or(MediaError::UnsupportedType)?;
+
+        Ok((
+            file,
+            MediaInfo {
+                hash: *hash,
+                media_type,
+                size,
+            },
+        ))
+    }
+
+    /// Delete a stored blob
+    pub fn remove(&self, hash: &ContentHash) -> Result<(), MediaError> {
+        match fs::remove_file(self.path(hash)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(MediaError::NotFound),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Add written content to the hash and the bytes used to recognise the type
+fn record(hasher: &mut Sha256, head: &mut Vec<u8>, data: &[u8]) {
+    hasher.update(data);
+    if head.len() < SNIFF_LEN {
+        let missing = (SNIFF_LEN - head.len()).min(data.len());
+        head.extend_from_slice(&data[..missing]);
+    }
+}
+
+/// Read until `buf` is full or the end of the file
+fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    Ok(len)
+}
+
+/// A blob being written to the store
+/// Nothing is visible in the store until [`Upload::finish`] succeeds.
+pub struct Upload {
+    writer: BufWriter<File>,
+    hasher: Sha256,
+    /// First bytes of the content, to recognise its type
+    head: Vec<u8>,
+    size: u64,
+    hash: ContentHash,
+    part: PathBuf,
+    path: PathBuf,
+}
+
+impl Upload {
+    /// Number of bytes received so far, including those of earlier attempts
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Verify the upload and move it into the store
+    pub fn finish(mut self) -> Result<MediaInfo, MediaError> {
+        if let Err(e) = self
+            .writer
+            .flush()
+            .and_then(|_| self.writer.get_ref().sync_all())
+        {
+            self.abort();
+            return Err(e.into());
+        }
+
+        let actual = ContentHash(self.hasher.finalize_reset().into());
+        let result = if actual != self.hash {
+            Err(MediaError::HashMismatch { actual })
+        } else if self.path.exists() {
+            Err(MediaError::AlreadyExists)
+        } else {
+            MediaType::sniff(&self.head).ok_or(MediaError::UnsupportedType)
+        };
+
+        let media_type = match result {
+            Ok(media_type) => media_type,
+            Err(e) => {
+                self.abort();
+                return Err(e);
+            }
+        };
+
+        let Upload {
+            writer,
+            hash,
+            size,
+            part,
+            path,
+            ..
+        } = self;
+        drop(writer);
+        fs::rename(&part, &path)?;
+
+        Ok(MediaInfo {
+            hash,
+            media_type,
+            size,
+        })
+    }
+
+    /// Keep what has been uploaded so far, so the upload can be resumed later
+    /// Returns the offset at which it has to continue.
+    pub fn suspend(self) -> Result<u64, MediaError> {
+        let Upload {
+            mut writer, size, ..
+        } = self;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(size)
+    }
+
+    /// Throw away what has been uploaded
+    pub fn abort(self) {
+        let Upload { writer, part, .. } = self;
+        drop(writer);
+        fs::remove_file(part).ok();
+    }
+}
+
+impl Write for Upload {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.writer.write(buf)?;
+
+        record(&mut self.hasher, &mut self.head, &buf[..n]);
+        self.size += n as u64;
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR fake png data";
+    const JPEG: &[u8] = b"\xFF\xD8\xFF\xE0\0\x10JFIF fake jpeg data";
+
+    fn store(name: &str) -> MediaStore {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        MediaStore::init(dir).unwrap()
+    }
+
+    #[test]
+    fn test_hash_hex_round_trip() {
+        let hash = ContentHash::of(b"abc");
+        assert_eq!(
+            hash.to_string(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(hash.to_string().parse::<ContentHash>().unwrap(), hash);
+        assert_eq!(
+            hash.to_string()
+                .to_uppercase()
+                .parse::<ContentHash>()
+                .unwrap(),
+            hash
+        );
+
+        assert!("abc".parse::<ContentHash>().is_err());
+        assert!("+a".repeat(32).parse::<ContentHash>().is_err());
+        assert!("../".repeat(22)[..64].parse::<ContentHash>().is_err());
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(MediaType::sniff(PNG), Some(MediaType::Png));
+        assert_eq!(MediaType::sniff(JPEG), Some(MediaType::Jpeg));
+        assert_eq!(
+            MediaType::sniff(b"RIFF\x24\0\0\0WEBPVP8 "),
+            Some(MediaType::Webp)
+        );
+        assert_eq!(MediaType::sniff(b"RIFF\x24\0\0\0WAVEfmt "), None);
+        assert_eq!(MediaType::sniff(b"GIF89a"), None);
+        assert_eq!(MediaType::sniff(b""), None);
+
+        assert_eq!(
+            MediaType::sniff(b"\0\0\0\x20ftypisom\0\0"),
+            Some(MediaType::Mp4)
+        );
+        assert_eq!(
+            MediaType::sniff(b"\0\0\0\x20ftypM4A \0\0"),
+            Some(MediaType::M4a)
+        );
+        assert_eq!(MediaType::sniff(b"OggS\0\x02"), Some(MediaType::Ogg));
+        assert_eq!(MediaType::sniff(b"ID3\x04\0"), Some(MediaType::Mp3));
+        assert_eq!(MediaType::sniff(b"%PDF-1.7"), Some(MediaType::Pdf));
+    }
+
+    #[test]
+    fn test_upload_and_open() {
+        let store = store("media_test_upload");
+        let hash = ContentHash::of(PNG);
+
+        let mut upload = store.begin_upload(hash).unwrap();
+        // Write in small pieces, so the type has to be sniffed across writes
+        for piece in PNG.chunks(5) {
+            upload.write_all(piece).unwrap();
+        }
+        let info = upload.finish().unwrap();
+
+        assert_eq!(info.media_type, MediaType::Png);
+        assert_eq!(info.size, PNG.len() as u64);
+        assert!(store.contains(&hash));
+
+        let (mut file, opened) = store.open(&hash).unwrap();
+        assert_eq!(opened, info);
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).unwrap();
+        assert_eq!(content, PNG);
+
+        // Duplicates are rejected
+        assert!(matches!(
+            store.begin_upload(hash),
+            Err(MediaError::AlreadyExists)
+        ));
+        assert!(matches!(store.put(PNG), Err(MediaError::AlreadyExists)));
+    }
+
+    #[test]
+    fn test_rejected_uploads_leave_nothing_behind() {
+        let store = store("media_test_rejected");
+
+        // Content doesn't match the hash it was uploaded under
+        let claimed = ContentHash::of(JPEG);
+        let mut upload = store.begin_upload(claimed).unwrap();
+        upload.write_all(PNG).unwrap();
+        match upload.finish() {
+            Err(MediaError::HashMismatch { actual }) => assert_eq!(actual, ContentHash::of(PNG)),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert!(!store.contains(&claimed));
+
+        // Not an image
+        assert!(matches!(
+            store.put(b"#!/bin/sh\nrm -rf /"),
+            Err(MediaError::UnsupportedType)
+        ));
+
+        let leftovers = fs::read_dir(&store.dir).unwrap().count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_open_missing() {
+        let store = store("media_test_missing");
+        let hash = ContentHash::of(b"nothing");
+
+        assert!(matches!(store.open(&hash), Err(MediaError::NotFound)));
+        assert!(matches!(store.remove(&hash), Err(MediaError::NotFound)));
+    }
+
+    #[test]
+    fn test_resume_upload() {
+        let store = store("media_test_resume");
+        let hash = ContentHash::of(JPEG);
+
+        assert_eq!(
+            store.upload_state(&hash).unwrap(),
+            UploadState {
+                offset: 0,
+                complete: false
+            }
+        );
+
+        let mut upload = store.begin_upload(hash).unwrap();
+        upload.write_all(&JPEG[..10]).unwrap();
+        assert_eq!(upload.suspend().unwrap(), 10);
+        assert_eq!(store.upload_state(&hash).unwrap().offset, 10);
+
+        // The next piece has to continue where the last one ended
+        match store.resume_upload(hash, 5) {
+            Err(MediaError::OffsetMismatch { expected }) => assert_eq!(expected, 10),
+            other => panic!("unexpected result {:?}", other.map(|u| u.size())),
+        }
+        assert_eq!(store.upload_state(&hash).unwrap().offset, 10);
+
+        let mut upload = store.resume_upload(hash, 10).unwrap();
+        assert_eq!(upload.size(), 10);
+        upload.write_all(&JPEG[10..]).unwrap();
+        let info = upload.finish().unwrap();
+
+        assert_eq!(info.media_type, MediaType::Jpeg);
+        assert_eq!(info.size, JPEG.len() as u64);
+        assert_eq!(
+            store.upload_state(&hash).unwrap(),
+            UploadState {
+                offset: JPEG.len() as u64,
+                complete: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_etag() {
+        let hash = ContentHash::of(PNG);
+        let etag = hash.etag();
+
+        assert!(hash.matches_etag(&etag));
+        assert!(hash.matches_etag(&format!("\"other\", W/{etag}")));
+        assert!(hash.matches_etag("*"));
+        assert!(!hash.matches_etag(&ContentHash::of(JPEG).etag()));
+    }
+}
@@ -1,6 +1,17 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+pub mod edit;
+pub mod entities;
+pub mod message;
+pub mod reply;
+pub mod rotation;
+pub mod signing;
+pub mod tombstone;
+pub mod validation;
+
+use validation::{deserialize_optional_timestamp, deserialize_timestamp};
+
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct User{
@@ -9,18 +20,210 @@ pub struct User{
     pub status: String,
     pub bio: String,
     pub profile_picture: Option<String>,
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub last_contact: DateTime<Utc>,
+    /// Shown until the profile picture has been downloaded
+    #[serde(default)]
+    pub profile_picture_preview: Option<ImagePreview>,
+    /// Ed25519 key the `uuid` is derived from, see [`crate::identity`]
+    /// Missing for users created before identities existed.
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// Made with the secret half of `public_key`, see [`signing`]
+    #[serde(default)]
+    pub signature: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Post{
     pub uuid: String,
     pub user_id: String,
     pub title: String,
     pub body: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub timestamp: DateTime<Utc>,
     pub image: Option<String>,
-    pub source_totem: Option<String>
+    pub source_totem: Option<String>,
+    /// Content hash of a downscaled variant of `image` for feeds
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    /// Shown until `image` has been downloaded
+    #[serde(default)]
+    pub image_preview: Option<ImagePreview>,
+    /// Pictures, clips, voice notes and files, in the order they are shown
+    /// `image` repeats the first picture for apps and totems that don't know attachments.
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// 0 as first written, counted up by each edit of the author, see [`edit`]
+    #[serde(default)]
+    pub revision: u32,
+    /// When the edit making this revision was made, `None` for revision 0
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Made with the author's key, see [`signing`]
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// A reply to a post or to another reply, see [`reply`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reply {
+    pub uuid: String,
+    pub user_id: String,
+    /// The post or reply this one answers
+    pub parent_id: String,
+    /// The post the thread started with, the same as `parent_id` for direct replies
+    pub root_id: String,
+    pub body: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    /// Made with the author's key, see [`signing`]
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// What a reaction to a post expresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionKind {
+    Like,
+    Share,
+}
+
+impl ReactionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReactionKind::Like => "like",
+            ReactionKind::Share => "share",
+        }
+    }
+}
+
+impl std::str::FromStr for ReactionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "like" => Ok(ReactionKind::Like),
+            "share" => Ok(ReactionKind::Share),
+            _ => Err(format!("'{}' is not a reaction kind", s)),
+        }
+    }
+}
+
+/// A user adding or taking back a reaction to a post
+/// Only the latest one per user, post and kind counts, see [`crate::reactions`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reaction {
+    pub uuid: String,
+    pub user_id: String,
+    pub post_id: String,
+    pub kind: ReactionKind,
+    /// False if the user takes the reaction back
+    pub active: bool,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    /// Made with the author's key, see [`signing`]
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// A user passing someone else's post on to their followers
+/// The post may arrive after the repost, stores keep reposts without it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Repost {
+    pub uuid: String,
+    /// The user who reposted
+    pub user_id: String,
+    pub post_id: String,
+    /// Author of the reposted post, so it can be attributed before the post arrives
+    pub author_id: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    /// Made with the reposting user's key, see [`signing`]
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// The author deleting one of their posts, see [`tombstone`]
+/// Stores drop the post and keep the tombstone, so the post isn't brought back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub uuid: String,
+    /// Author of the deleted post
+    pub user_id: String,
+    pub post_id: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    /// Made with the author's key, see [`signing`]
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// A blurred placeholder of a picture and its size
+/// Records arrive long before their pictures, this lets the app lay out the feed right away.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImagePreview {
+    /// See <https://blurha.sh>
+    pub blurhash: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// What an attachment of a post is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    Image,
+    Clip,
+    Voice,
+    File,
+}
+
+impl AttachmentKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttachmentKind::Image => "image",
+            AttachmentKind::Clip => "clip",
+            AttachmentKind::Voice => "voice",
+            AttachmentKind::File => "file",
+        }
+    }
+}
+
+impl std::str::FromStr for AttachmentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "image" => Ok(AttachmentKind::Image),
+            "clip" => Ok(AttachmentKind::Clip),
+            "voice" => Ok(AttachmentKind::Voice),
+            "file" => Ok(AttachmentKind::File),
+            _ => Err(format!("'{}' is not an attachment kind", s)),
+        }
+    }
+}
+
+/// A blob attached to a post, transferred separately by its content hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub kind: AttachmentKind,
+    pub mime: String,
+    /// See [`crate::media::ContentHash`]
+    pub hash: String,
+    /// Size of the blob in bytes
+    pub size: u64,
+    /// Length of clips and voice notes
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    /// Description for screen readers
+    #[serde(default)]
+    pub alt_text: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -28,5 +231,142 @@ pub struct Totem{
     pub uuid: String,
     pub name: String,
     pub location: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
     pub last_contact: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+/// The last time a user synced with a totem
+/// Merged with max-timestamp semantics, see [`crate::presence::PresenceTable`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LastSeen {
+    pub user_id: String,
+    pub totem_id: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub seen_at: DateTime<Utc>,
+    /// Signature of the user, required if their ID is derived from a key
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// How a key rotation was authorized, see [`rotation`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationKind {
+    /// Signed by the key in use, which hands over to the new key
+    Endorsement,
+    /// Signed by the recovery key, the key in use is compromised
+    Revocation,
+}
+
+impl RotationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RotationKind::Endorsement => "endorsement",
+            RotationKind::Revocation => "revocation",
+        }
+    }
+}
+
+impl std::str::FromStr for RotationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "endorsement" => Ok(RotationKind::Endorsement),
+            "revocation" => Ok(RotationKind::Revocation),
+            _ => Err(format!("'{}' is not a rotation kind", s)),
+        }
+    }
+}
+
+/// A step in the chain of keys a user signs with, see [`rotation`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRotation {
+    pub uuid: String,
+    pub user_id: String,
+    /// The key `user_id` is derived from, so the chain can be checked without the user
+    pub identity_key: String,
+    /// Position in the user's chain, the first rotation is 1
+    pub sequence: u32,
+    pub kind: RotationKind,
+    /// Signs the user's records from `timestamp` on
+    pub new_key: String,
+    /// Key that may revoke `new_key`, kept offline by the user
+    #[serde(default)]
+    pub recovery_key: Option<String>,
+    /// Records signed by the previous key after this are not accepted
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    pub signature: String,
+}
+
+/// A direct message as it is stored and relayed, encrypted end to end, see [`message`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub uuid: String,
+    pub sender_id: String,
+    pub recipient_id: String,
+    /// Key of the sender at `timestamp`, see [`rotation`]
+    pub sender_key: String,
+    /// Key of the recipient the message was encrypted to
+    pub recipient_key: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    /// XChaCha20-Poly1305 nonce, as hex
+    pub nonce: String,
+    /// The encrypted text with its tag, as hex
+    pub ciphertext: String,
+}
+
+/// Signed by the recipient of a message once they collected it, see [`crate::mailbox`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryReceipt {
+    pub message_uuid: String,
+    /// Key the message was encrypted to, which signs the receipt
+    pub recipient_key: String,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub timestamp: DateTime<Utc>,
+    pub signature: String,
+}
+
+/// Where a bundle is headed, see [`crate::routing`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+    /// Delivered when a phone carrying it syncs with the totem
+    Totem(String),
+    /// Delivered when the user's own phone picks it up
+    User(String),
+}
+
+/// Routing information carried along with the content of a bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleHeader {
+    pub destination: Destination,
+    #[serde(deserialize_with = "deserialize_timestamp")]
+    pub created: DateTime<Utc>,
+    /// Seconds after `created` the bundle is dropped if it wasn't delivered
+    pub ttl_secs: u32,
+    /// Max number of custodians the bundle passes through
+    pub hop_limit: u8,
+    /// Custodians the bundle passed through so far
+    pub hops: u8,
+    /// Copies the custodian may still hand out, see [`crate::routing`]
+    pub copies: u32,
+}
+
+/// The content of a bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BundlePayload {
+    Post(Post),
+    Message(Message),
+}
+
+/// Content addressed to a totem or user, carried there by phones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub uuid: String,
+    pub header: BundleHeader,
+    pub payload: BundlePayload,
+}
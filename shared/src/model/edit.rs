@@ -0,0 +1,262 @@
+//! Edits of posts
+//!
+//! An edit is a new version of a post under the same UUID, with the next `revision`
+//! and the time of the edit in `edited_at`, signed by the author like the first one.
+//! What the post is about stays fixed: its author and the time it was written. Only
+//! signed edits of known authors are accepted, so users without a key can't edit.
+//!
+//! Versions spread like posts and arrive in any order. Of two versions the one with the
+//! higher revision wins, later edits and then signatures break ties between versions
+//! made on two devices at once. Every store ends up showing the same version, and keeps
+//! the ones it replaced as the post's history. Syncs compare the [`PostVersion`]s of edited
+//! posts, so two versions of the same revision don't pass as the same.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::validation::{deserialize_optional_timestamp, ValidationErrors};
+use super::Post;
+
+/// What tells the versions of a post apart, ordered like [`Post::supersedes`] ranks them
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct PostVersion {
+    pub revision: u32,
+    #[serde(default, deserialize_with = "deserialize_optional_timestamp")]
+    pub edited_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+impl Post {
+    /// When the post was written or last edited
+    pub fn changed_at(&self) -> DateTime<Utc> {
+        self.edited_at.unwrap_or(self.timestamp)
+    }
+
+    pub fn version(&self) -> PostVersion {
+        PostVersion {
+            revision: self.revision,
+            edited_at: self.edited_at,
+            signature: self.signature.clone(),
+        }
+    }
+
+    /// Whether this version wins over another version of the same post
+    pub fn supersedes(&self, other: &Post) -> bool {
+        (self.revision, self.edited_at, &self.signature)
+            > (other.revision, other.edited_at, &other.signature)
+    }
+
+    /// Whether both are the same version, stored twice
+    pub fn same_version(&self, other: &Post) -> bool {
+        self.revision == other.revision
+            && self.edited_at == other.edited_at
+            && self.signature == other.signature
+    }
+
+    /// Reject a version that changes what edits can't change
+    pub fn check_edit_of(&self, other: &Post) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.uuid != other.uuid {
+            errors.add("uuid", "isn't the edited post");
+        }
+        if self.user_id != other.user_id {
+            errors.add("user_id", "must stay the author of the post");
+        }
+        if self.timestamp != other.timestamp {
+            errors.add("timestamp", "must stay when the post was written");
+        }
+        errors.into_result()
+    }
+
+    /// The next version with a new title and body, to be signed by the author
+    /// Its edit time is after the one of this version, even if the clock went back.
+    pub fn edited(&self, title: String, body: String, now: DateTime<Utc>) -> Post {
+        let edited_at = match self.changed_at() {
+            previous if previous >= now => previous + TimeDelta::milliseconds(1),
+            _ => now,
+        };
+        Post {
+            title,
+            body,
+            revision: self.revision + 1,
+            edited_at: Some(edited_at),
+            signature: None,
+            ..self.clone()
+        }
+    }
+}
+
+/// The version that wins among several versions of the same post
+pub fn newest<I: IntoIterator<Item = Post>>(versions: I) -> Option<Post> {
+    versions.into_iter().reduce(|newest, version| {
+        if version.supersedes(&newest) {
+            version
+        } else {
+            newest
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::Identity;
+
+    fn post() -> Post {
+        Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            title: "Title".to_string(),
+            body: "Teh body".to_string(),
+            timestamp: DateTime::from_timestamp(1_767_225_600, 0).unwrap(),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_edit() {
+        let original = post();
+        let now = original.timestamp + TimeDelta::hours(1);
+        let edit = original.edited("Title".to_string(), "The body".to_string(), now);
+        assert_eq!((edit.revision, edit.edited_at), (1, Some(now)));
+        assert_eq!(edit.validate_at(now), Ok(()));
+        assert_eq!(edit.check_edit_of(&original), Ok(()));
+        assert!(edit.supersedes(&original));
+        assert!(!original.supersedes(&edit));
+
+        // A clock behind the last edit still makes a later one
+        let again = edit.edited(
+            "Title".to_string(),
+            "The body!".to_string(),
+            original.timestamp,
+        );
+        assert!(again.edited_at > edit.edited_at);
+
+        let moved = Post {
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            ..edit.clone()
+        };
+        assert!(moved.check_edit_of(&original).unwrap_err().has("user_id"));
+
+        let undated = Post {
+            edited_at: None,
+            ..edit
+        };
+        assert!(undated.validate_at(now).unwrap_err().has("edited_at"));
+    }
+
+    #[test]
+    fn test_newest() {
+        let original = post();
+        let now = original.timestamp + TimeDelta::hours(1);
+        let first = original.edited("Title".to_string(), "One".to_string(), now);
+        let second = first.edited("Title".to_string(), "Two".to_string(), now);
+        // Edited on another device before it got the first edit
+        let other = original.edited(
+            "Title".to_string(),
+            "Other".to_string(),
+            now + TimeDelta::minutes(1),
+        );
+
+        for versions in [
+            vec![
+                original.clone(),
+                first.clone(),
+                second.clone(),
+                other.clone(),
+            ],
+            vec![
+                second.clone(),
+                other.clone(),
+                original.clone(),
+                first.clone(),
+            ],
+        ] {
+            assert_eq!(newest(versions).unwrap().body, "Two");
+        }
+        assert_eq!(
+            newest([first.clone(), other.clone()]).unwrap().body,
+            "Other"
+        );
+        assert!(first.same_version(&first.clone()));
+        assert!(!first.same_version(&other));
+        assert!(other.version() > first.version());
+        assert!(second.version() > other.version());
+        assert!(original.version() < first.version());
+        assert_eq!(newest(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_edit_signature() {
+        let identity = Identity::generate();
+        let author = crate::model::User {
+            uuid: identity.user_id(),
+            username: "alice".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: Some(identity.public_key().to_string()),
+            signature: None,
+        };
+        let mut original = Post {
+            user_id: author.uuid.clone(),
+            ..post()
+        };
+        original.sign(&identity);
+
+        let mut edit = original.edited("Title".to_string(), "The body".to_string(), Utc::now());
+        edit.sign(&identity);
+        assert!(edit.check_signature(Some(&author), &[]).is_ok());
+
+        // Replaying the first signature on a later revision
+        let forged = Post {
+            revision: 2,
+            ..edit.clone()
+        };
+        assert!(forged.check_signature(Some(&author), &[]).is_err());
+        let forged = Post {
+            signature: original.signature.clone(),
+            ..edit.clone()
+        };
+        assert!(forged.check_signature(Some(&author), &[]).is_err());
+
+        // Signed edits of unknown authors wait until the author is known
+        assert!(
+            edit.check_signature(None, &[])
+                .unwrap_err()
+                .has("signature")
+        );
+
+        // Nobody can edit the posts of a user without a key, unsigned posts stay fine
+        let legacy = crate::model::User {
+            uuid: post().user_id,
+            public_key: None,
+            ..author
+        };
+        let unsigned = post();
+        assert!(unsigned.check_signature(Some(&legacy), &[]).is_ok());
+        let forged = unsigned.edited("Title".to_string(), "Forged".to_string(), Utc::now());
+        assert!(
+            forged
+                .check_signature(Some(&legacy), &[])
+                .unwrap_err()
+                .has("signature")
+        );
+        assert!(
+            forged
+                .check_signature(None, &[])
+                .unwrap_err()
+                .has("signature")
+        );
+    }
+}
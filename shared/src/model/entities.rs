@@ -0,0 +1,189 @@
+//! Hashtags and mentions in the text of posts
+//!
+//! They aren't part of the signed record, they are read from the body wherever they are
+//! needed. `#tag` tags a post, tags are compared in lowercase. `@name` mentions a user by
+//! username, `@` followed by a key fingerprint mentions the owner of the key, see
+//! [`crate::identity::PublicKey::fingerprint`]. Neither counts right after a letter or
+//! digit, so e-mail addresses and `C#` stay plain text.
+
+use super::{Post, User};
+use crate::identity::FINGERPRINT_LEN;
+
+/// Longer tags are plain text
+pub const MAX_TAG_LEN: usize = 64;
+
+/// Someone mentioned in a post
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Mention {
+    /// A username, in lowercase
+    Handle(String),
+    /// A key fingerprint, for users whose name is taken or changes
+    Key(String),
+}
+
+impl Mention {
+    fn parse(word: &str) -> Mention {
+        let word = word.to_lowercase();
+        if word.len() == FINGERPRINT_LEN && word.bytes().all(|b| b.is_ascii_hexdigit()) {
+            Mention::Key(word)
+        } else {
+            Mention::Handle(word)
+        }
+    }
+
+    /// Whether the mention refers to `user`
+    pub fn matches(&self, user: &User) -> bool {
+        match self {
+            Mention::Handle(handle) => user.username.to_lowercase() == *handle,
+            Mention::Key(fingerprint) => user
+                .public_key
+                .as_deref()
+                .is_some_and(|key| key.starts_with(fingerprint.as_str())),
+        }
+    }
+}
+
+/// The tags and mentions of a text, each once, in the order they first appear
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Entities {
+    pub tags: Vec<String>,
+    pub mentions: Vec<Mention>,
+}
+
+impl Entities {
+    pub fn parse(text: &str) -> Entities {
+        let mut entities = Entities::default();
+        let mut previous: Option<char> = None;
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            let starts_word = !previous.is_some_and(|p| p.is_alphanumeric() || p == '_');
+            previous = Some(c);
+            if !starts_word || (c != '#' && c != '@') {
+                continue;
+            }
+
+            let rest = &text[i + 1..];
+            let word = if c == '#' {
+                take_while(rest, is_tag_char)
+            } else {
+                take_while(rest, |c| is_tag_char(c) || c == '.' || c == '-')
+                    .trim_end_matches(['.', '-'])
+            };
+            if word.is_empty() {
+                continue;
+            }
+            for _ in word.chars() {
+                previous = chars.next().map(|(_, c)| c);
+            }
+
+            if c == '#' {
+                let tag = word.to_lowercase();
+                let is_number = tag.chars().all(|c| c.is_ascii_digit());
+                if !is_number && tag.chars().count() <= MAX_TAG_LEN && !entities.tags.contains(&tag)
+                {
+                    entities.tags.push(tag);
+                }
+            } else {
+                let mention = Mention::parse(word);
+                if !entities.mentions.contains(&mention) {
+                    entities.mentions.push(mention);
+                }
+            }
+        }
+
+        entities
+    }
+}
+
+impl Post {
+    /// The tags and mentions in the body
+    pub fn entities(&self) -> Entities {
+        Entities::parse(&self.body)
+    }
+}
+
+/// Bring a tag typed by someone into the form it is stored in
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn take_while(text: &str, f: impl Fn(char) -> bool) -> &str {
+    let end = text.find(|c: char| !f(c)).unwrap_or(text.len());
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::Identity;
+    use chrono::Utc;
+
+    fn handle(name: &str) -> Mention {
+        Mention::Handle(name.to_string())
+    }
+
+    #[test]
+    fn test_parse() {
+        let entities = Entities::parse(
+            "Meet @Alice and @bob.smith at the #Market. #market again, #1 stall, ask @carol-\n\
+             mail me@example.com, learn C# or #Straße_2 (@dave)",
+        );
+        assert_eq!(entities.tags, ["market", "straße_2"]);
+        assert_eq!(
+            entities.mentions,
+            [
+                handle("alice"),
+                handle("bob.smith"),
+                handle("carol"),
+                handle("dave")
+            ]
+        );
+
+        assert_eq!(Entities::parse("# @ #-tag"), Entities::default());
+        let long = format!("#{}", "a".repeat(MAX_TAG_LEN + 1));
+        assert!(Entities::parse(&long).tags.is_empty());
+    }
+
+    #[test]
+    fn test_mention_by_key() {
+        let identity = Identity::generate();
+        let fingerprint = identity.public_key().fingerprint();
+        let mut user = User {
+            uuid: identity.user_id(),
+            username: "Alice".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc::now(),
+            profile_picture_preview: None,
+            public_key: Some(identity.public_key().to_string()),
+            signature: None,
+        };
+
+        let entities = Entities::parse(&format!("Hi @{}, and @ALICE", fingerprint.to_uppercase()));
+        assert_eq!(
+            entities.mentions,
+            [Mention::Key(fingerprint), handle("alice")]
+        );
+        assert!(
+            entities
+                .mentions
+                .iter()
+                .all(|mention| mention.matches(&user))
+        );
+
+        user.username = "alice2".to_string();
+        user.public_key = None;
+        assert!(
+            !entities
+                .mentions
+                .iter()
+                .any(|mention| mention.matches(&user))
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Direct messages, encrypted end to end
+//!
+//! Sender and recipient agree on a secret with X25519, using their signing keys in
+//! Montgomery form, see [`crate::identity::Identity::agree`]. The text is encrypted with
+//! XChaCha20-Poly1305 under a key derived from that secret, by the app. Totems and phones
+//! relaying a message only see who it is from and to, never the text.
+//!
+//! The header fields are the associated data of the encryption, so a relay can't change
+//! them without the message failing to decrypt. Since both sides share the secret, a
+//! message that decrypts was written by one of them. It is shown as the sender's if
+//! `sender_key` was the sender's key at `timestamp`.
+//!
+//! Totems keep messages in mailboxes until the recipient collects them, see
+//! [`crate::mailbox`]. The recipient then signs a [`DeliveryReceipt`] with the key the
+//! message was encrypted to.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::signing::{Verification, canonical, verify_with};
+use super::validation::ValidationErrors;
+use super::{DeliveryReceipt, KeyRotation, Message, User};
+use crate::identity::{Identity, PublicKey};
+
+const MESSAGE_DOMAIN: &[u8] = b"loom message v1\0";
+const RECEIPT_DOMAIN: &[u8] = b"loom delivery receipt v1\0";
+const MAILBOX_DOMAIN: &[u8] = b"loom mailbox v1\0";
+
+/// Name of the mailbox of messages to `recipient_key`, as hex
+/// A hash, so listing mailboxes doesn't hand out keys to look up.
+pub fn mailbox_of(recipient_key: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(MAILBOX_DOMAIN)
+        .chain_update(recipient_key.as_bytes())
+        .finalize();
+    digest.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[derive(Serialize)]
+struct MessageHeader<'a> {
+    uuid: &'a str,
+    sender_id: &'a str,
+    recipient_id: &'a str,
+    sender_key: &'a str,
+    recipient_key: &'a str,
+    timestamp: (i64, u32),
+}
+
+impl Message {
+    /// The header fields, authenticated along with the text
+    pub fn associated_data(&self) -> Vec<u8> {
+        canonical(
+            MESSAGE_DOMAIN,
+            &MessageHeader {
+                uuid: &self.uuid,
+                sender_id: &self.sender_id,
+                recipient_id: &self.recipient_id,
+                sender_key: &self.sender_key,
+                recipient_key: &self.recipient_key,
+                timestamp: (
+                    self.timestamp.timestamp(),
+                    self.timestamp.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Name of the mailbox the message waits in until it is collected
+    pub fn mailbox(&self) -> String {
+        mailbox_of(&self.recipient_key)
+    }
+
+    /// Whether `sender_key` belonged to the sender when the message was written
+    /// Only meaningful once the message decrypted, which proves who holds the key.
+    pub fn sender_verification(
+        &self,
+        sender: Option<&User>,
+        rotations: &[KeyRotation],
+    ) -> Verification {
+        let Some(sender) = sender else {
+            return Verification::UnknownAuthor;
+        };
+        if sender.uuid != self.sender_id {
+            return Verification::Invalid;
+        }
+        if sender.public_key.is_none() {
+            return Verification::Unsigned;
+        }
+
+        match sender.key_chain(rotations) {
+            Some(chain) if chain.key_at(self.timestamp).to_string() == self.sender_key => {
+                Verification::Verified
+            }
+            _ => Verification::Invalid,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SignedReceipt<'a> {
+    message_uuid: &'a str,
+    recipient_key: &'a str,
+    timestamp: (i64, u32),
+}
+
+impl DeliveryReceipt {
+    /// A signed receipt for a message to this identity
+    pub fn new(
+        message: &Message,
+        identity: &Identity,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        let mut receipt = DeliveryReceipt {
+            message_uuid: message.uuid.clone(),
+            recipient_key: message.recipient_key.clone(),
+            timestamp,
+            signature: String::new(),
+        };
+        receipt.signature = identity.sign(&receipt.canonical_bytes()).to_string();
+        receipt
+    }
+
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            RECEIPT_DOMAIN,
+            &SignedReceipt {
+                message_uuid: &self.message_uuid,
+                recipient_key: &self.recipient_key,
+                timestamp: (
+                    self.timestamp.timestamp(),
+                    self.timestamp.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Whether the receipt is for `message`, so it may have it deleted
+    pub fn is_for(&self, message: &Message) -> bool {
+        self.message_uuid == message.uuid && self.recipient_key == message.recipient_key
+    }
+
+    /// Reject receipts not signed by the key the message was encrypted to
+    /// Anyone else could have messages deleted before they reach their recipient.
+    pub fn check_signature(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        let verified = self
+            .recipient_key
+            .parse::<PublicKey>()
+            .map(|key| verify_with(&key, &Some(self.signature.clone()), &self.canonical_bytes()));
+        if verified != Ok(Verification::Verified) {
+            errors.add("signature", "doesn't match the recipient's key");
+        }
+        errors.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::Identity;
+    use chrono::{TimeZone, Utc};
+
+    fn message(sender: &Identity, recipient: &Identity) -> Message {
+        Message {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            sender_id: sender.user_id(),
+            recipient_id: recipient.user_id(),
+            sender_key: sender.public_key().to_string(),
+            recipient_key: recipient.public_key().to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap(),
+            nonce: "00".repeat(24),
+            ciphertext: "00".repeat(20),
+        }
+    }
+
+    #[test]
+    fn test_associated_data() {
+        let sender = Identity::generate();
+        let message = message(&sender, &Identity::generate());
+        let data = message.associated_data();
+
+        let mut altered = message.clone();
+        altered.recipient_id = sender.user_id();
+        assert_ne!(altered.associated_data(), data);
+        // The encrypted parts are authenticated by the cipher itself
+        let mut altered = message.clone();
+        altered.ciphertext = "11".repeat(20);
+        assert_eq!(altered.associated_data(), data);
+    }
+
+    #[test]
+    fn test_delivery_receipt() {
+        let recipient = Identity::generate();
+        let message = message(&Identity::generate(), &recipient);
+        let receipt = DeliveryReceipt::new(&message, &recipient, message.timestamp);
+        assert!(receipt.is_for(&message));
+        assert_eq!(receipt.check_signature(), Ok(()));
+
+        // Only the recipient can confirm the delivery
+        let forged = DeliveryReceipt::new(&message, &Identity::generate(), message.timestamp);
+        assert!(forged.check_signature().is_err());
+
+        let mut other = message.clone();
+        other.recipient_key = Identity::generate().public_key().to_string();
+        assert!(!receipt.is_for(&other));
+        assert_ne!(other.mailbox(), message.mailbox());
+        assert_eq!(message.mailbox().len(), 64);
+    }
+
+    #[test]
+    fn test_sender_verification() {
+        let sender = Identity::generate();
+        let message = message(&sender, &Identity::generate());
+        let user = User {
+            uuid: sender.user_id(),
+            username: "alice".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: message.timestamp,
+            profile_picture_preview: None,
+            public_key: Some(sender.public_key().to_string()),
+            signature: None,
+        };
+
+        assert_eq!(
+            message.sender_verification(Some(&user), &[]),
+            Verification::Verified
+        );
+        assert_eq!(
+            message.sender_verification(None, &[]),
+            Verification::UnknownAuthor
+        );
+
+        // Written with a key the sender doesn't have
+        let mut forged = message.clone();
+        forged.sender_key = Identity::generate().public_key().to_string();
+        assert_eq!(
+            forged.sender_verification(Some(&user), &[]),
+            Verification::Invalid
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Threads of replies under a post
+//!
+//! Replies are records of their own, synced like posts. They arrive in any order, so a
+//! store may get a reply before the reply it answers, or before the post. Stores keep it
+//! anyway, and a thread is put together from the replies that are there. One whose parent
+//! is still missing is shown as a direct reply to the post until the parent arrives.
+
+use std::collections::{HashMap, HashSet};
+
+use super::Reply;
+
+/// A reply in its place in a thread
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadEntry {
+    pub reply: Reply,
+    /// 1 for replies to the post, 2 for replies to those and so on
+    pub depth: u32,
+    /// The reply it answers didn't arrive yet
+    pub parent_missing: bool,
+}
+
+impl Reply {
+    /// Whether it answers the post itself
+    pub fn is_direct(&self) -> bool {
+        self.parent_id == self.root_id
+    }
+}
+
+/// Order the replies to the post `root_id` as a thread
+/// Depth first, so each reply is followed by its answers, siblings oldest first. Replies
+/// to other posts are left out.
+pub fn thread(root_id: &str, replies: Vec<Reply>) -> Vec<ThreadEntry> {
+    let replies: Vec<Reply> = replies
+        .into_iter()
+        .filter(|reply| reply.root_id == root_id)
+        .collect();
+    let uuids: HashSet<String> = replies.iter().map(|reply| reply.uuid.clone()).collect();
+
+    let mut children: HashMap<String, Vec<(Reply, bool)>> = HashMap::new();
+    for reply in replies {
+        let parent_missing = !reply.is_direct() && !uuids.contains(&reply.parent_id);
+        let parent = if parent_missing {
+            root_id.to_string()
+        } else {
+            reply.parent_id.clone()
+        };
+        children
+            .entry(parent)
+            .or_default()
+            .push((reply, parent_missing));
+    }
+    for siblings in children.values_mut() {
+        sort(siblings);
+    }
+
+    let mut thread = Vec::new();
+    let mut stack: Vec<(Reply, bool, u32)> = children
+        .remove(root_id)
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .map(|(reply, parent_missing)| (reply, parent_missing, 1))
+        .collect();
+    while let Some((reply, parent_missing, depth)) = stack.pop() {
+        if let Some(answers) = children.remove(&reply.uuid) {
+            stack.extend(
+                answers
+                    .into_iter()
+                    .rev()
+                    .map(|(answer, missing)| (answer, missing, depth + 1)),
+            );
+        }
+        thread.push(ThreadEntry {
+            reply,
+            depth,
+            parent_missing,
+        });
+    }
+
+    // Replies answering each other in a circle never hang off the post, they go last
+    let mut rest: Vec<_> = children.into_values().flatten().collect();
+    sort(&mut rest);
+    thread.extend(rest.into_iter().map(|(reply, _)| ThreadEntry {
+        reply,
+        depth: 1,
+        parent_missing: true,
+    }));
+
+    thread
+}
+
+fn sort(replies: &mut [(Reply, bool)]) {
+    replies.sort_by(|(a, _), (b, _)| (a.timestamp, &a.uuid).cmp(&(b.timestamp, &b.uuid)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    const ROOT: &str = "123e4567-e89b-12d3-a456-426614174000";
+
+    fn reply(uuid: &str, parent_id: &str, secs: i64) -> Reply {
+        Reply {
+            uuid: uuid.to_string(),
+            user_id: "223e4567-e89b-12d3-a456-426614174000".to_string(),
+            parent_id: parent_id.to_string(),
+            root_id: ROOT.to_string(),
+            body: uuid.to_string(),
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            signature: None,
+        }
+    }
+
+    fn order(thread: &[ThreadEntry]) -> Vec<(&str, u32, bool)> {
+        thread
+            .iter()
+            .map(|entry| (entry.reply.uuid.as_str(), entry.depth, entry.parent_missing))
+            .collect()
+    }
+
+    #[test]
+    fn test_thread_order() {
+        let replies = vec![
+            reply("b", ROOT, 20),
+            reply("a1", "a", 30),
+            reply("a", ROOT, 10),
+            reply("a2", "a", 25),
+            reply("a1x", "a1", 40),
+            Reply {
+                root_id: "other".to_string(),
+                ..reply("elsewhere", "other", 0)
+            },
+        ];
+
+        assert_eq!(
+            order(&thread(ROOT, replies)),
+            [
+                ("a", 1, false),
+                ("a2", 2, false),
+                ("a1", 2, false),
+                ("a1x", 3, false),
+                ("b", 1, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_parent() {
+        let mut replies = vec![reply("a", ROOT, 10), reply("c1", "c", 30)];
+        assert_eq!(
+            order(&thread(ROOT, replies.clone())),
+            [("a", 1, false), ("c1", 1, true)]
+        );
+
+        // Once the parent arrives, the reply moves under it
+        replies.push(reply("c", ROOT, 20));
+        assert_eq!(
+            order(&thread(ROOT, replies)),
+            [("a", 1, false), ("c", 1, false), ("c1", 2, false)]
+        );
+    }
+
+    #[test]
+    fn test_circle() {
+        let replies = vec![
+            reply("a", ROOT, 10),
+            reply("x", "y", 20),
+            reply("y", "x", 30),
+        ];
+        assert_eq!(
+            order(&thread(ROOT, replies)),
+            [("a", 1, false), ("x", 1, true), ("y", 1, true)]
+        );
+    }
+}
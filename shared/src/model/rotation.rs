@@ -0,0 +1,526 @@
+//! Rotation and revocation of the keys users sign with
+//!
+//! A user ID is derived from the user's first key, the identity key, and stays the same
+//! for good. Which key signs the user's records can change through a chain of
+//! [`KeyRotation`] records, numbered from 1:
+//!
+//! - An endorsement is signed by the key in use and hands over to a new key. The first
+//!   one usually keeps the key and registers a recovery key, which is generated on the
+//!   phone, written down and deleted.
+//! - A revocation is signed by the recovery key and replaces a key that was stolen. It
+//!   wins over an endorsement with the same number, so a thief can't lock the user out
+//!   by rotating first. Endorsements can't change a registered recovery key.
+//!
+//! Each key signs the records timestamped from its rotation on, until the next rotation.
+//! A revocation can be dated back to when the phone went missing, down to the previous
+//! rotation, which invalidates what the thief signed in between.
+//!
+//! Rotations travel between phones and totems like other records. Everyone resolves the
+//! same chain from the same set of rotations, whatever order they arrived in.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::cmp::Ordering;
+
+use super::signing::{Verification, canonical, verify_with};
+use super::validation::ValidationErrors;
+use super::{KeyRotation, RotationKind, User};
+use crate::identity::{Identity, PublicKey, Signature};
+
+const ROTATION_DOMAIN: &[u8] = b"loom key rotation v1\0";
+
+#[derive(Serialize)]
+struct SignedRotation<'a> {
+    uuid: &'a str,
+    user_id: &'a str,
+    identity_key: &'a str,
+    sequence: u32,
+    kind: &'a str,
+    new_key: &'a str,
+    recovery_key: Option<&'a str>,
+    timestamp: (i64, u32),
+}
+
+impl KeyRotation {
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            ROTATION_DOMAIN,
+            &SignedRotation {
+                uuid: &self.uuid,
+                user_id: &self.user_id,
+                identity_key: &self.identity_key,
+                sequence: self.sequence,
+                kind: self.kind.as_str(),
+                new_key: &self.new_key,
+                recovery_key: self.recovery_key.as_deref(),
+                timestamp: (
+                    self.timestamp.timestamp(),
+                    self.timestamp.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Sign the rotation with the key in use or, for revocations, the recovery key
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = identity.sign(&self.canonical_bytes()).to_string();
+    }
+
+    /// Reject a rotation that doesn't extend the chain of the user's known `rotations`
+    /// Needs no user record, the chain starts at the identity key the rotation carries.
+    pub fn check_chain(&self, rotations: &[KeyRotation]) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        match self.identity_key.parse() {
+            Ok(key) => errors.check(
+                "signature",
+                KeyChain::resolve(&self.user_id, key, rotations).check(self),
+            ),
+            Err(_) => errors.add("identity_key", "is not a public key"),
+        }
+        errors.into_result()
+    }
+
+    /// Which of two rotations with the same number applies
+    /// Revocations first, then the earlier one, then the lower UUID so everyone agrees.
+    fn precedence(&self, other: &KeyRotation) -> Ordering {
+        let rank = |r: &KeyRotation| match r.kind {
+            RotationKind::Revocation => 0,
+            RotationKind::Endorsement => 1,
+        };
+        rank(self)
+            .cmp(&rank(other))
+            .then(self.timestamp.cmp(&other.timestamp))
+            .then_with(|| self.uuid.cmp(&other.uuid))
+    }
+}
+
+/// A key of the chain and the recovery key registered along with it
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    key: PublicKey,
+    recovery_key: Option<PublicKey>,
+    /// When the key took over, `None` for the identity key
+    since: Option<DateTime<Utc>>,
+}
+
+/// The keys a user signed with over time
+#[derive(Debug, Clone)]
+pub struct KeyChain {
+    user_id: String,
+    /// The identity key, followed by the key of every applied rotation
+    links: Vec<Link>,
+}
+
+impl KeyChain {
+    /// A chain without rotations, the identity key signs everything
+    pub fn new(user_id: &str, identity_key: PublicKey) -> Self {
+        KeyChain {
+            user_id: user_id.to_string(),
+            links: vec![Link {
+                key: identity_key,
+                recovery_key: None,
+                since: None,
+            }],
+        }
+    }
+
+    /// Apply the rotations of a user, in any order
+    /// Rotations that don't fit the chain, or lost against another one, are skipped.
+    pub fn resolve(user_id: &str, identity_key: PublicKey, rotations: &[KeyRotation]) -> Self {
+        let mut chain = KeyChain::new(user_id, identity_key);
+        loop {
+            let next = chain.sequence() + 1;
+            let winner = rotations
+                .iter()
+                .filter(|r| r.sequence == next && chain.check(r).is_ok())
+                .min_by(|a, b| a.precedence(b));
+            let Some(rotation) = winner else {
+                return chain;
+            };
+            chain.push(rotation);
+        }
+    }
+
+    /// Number of applied rotations, the next rotation has the one after
+    pub fn sequence(&self) -> u32 {
+        (self.links.len() - 1) as u32
+    }
+
+    /// The key the user signs with now
+    pub fn current_key(&self) -> PublicKey {
+        self.last().key
+    }
+
+    pub fn recovery_key(&self) -> Option<PublicKey> {
+        self.last().recovery_key
+    }
+
+    /// The key that signs records timestamped at `time`
+    pub fn key_at(&self, time: DateTime<Utc>) -> PublicKey {
+        self.links
+            .iter()
+            .rev()
+            .find(|link| link.since.is_none_or(|since| since <= time))
+            .unwrap_or(&self.links[0])
+            .key
+    }
+
+    /// Check that a rotation is a valid step from the key before it
+    /// Rotations competing with an applied one pass too, they may still win elsewhere.
+    pub fn check(&self, rotation: &KeyRotation) -> Result<(), String> {
+        if rotation.user_id != self.user_id
+            || rotation.identity_key != self.links[0].key.to_string()
+        {
+            return Err("belongs to another user".to_string());
+        }
+        let previous = rotation
+            .sequence
+            .checked_sub(1)
+            .and_then(|i| self.links.get(i as usize))
+            .ok_or_else(|| "doesn't follow a known rotation".to_string())?;
+
+        if previous
+            .since
+            .is_some_and(|since| rotation.timestamp < since)
+        {
+            return Err("predates the previous rotation".to_string());
+        }
+        rotation
+            .new_key
+            .parse::<PublicKey>()
+            .map_err(|e| format!("new key: {}", e))?;
+        let recovery_key = rotation
+            .recovery_key
+            .as_deref()
+            .map(str::parse::<PublicKey>)
+            .transpose()
+            .map_err(|e| format!("recovery key: {}", e))?;
+
+        let signer = match rotation.kind {
+            RotationKind::Endorsement => {
+                if previous.recovery_key.is_some()
+                    && recovery_key.is_some_and(|k| Some(k) != previous.recovery_key)
+                {
+                    return Err("only a revocation can replace the recovery key".to_string());
+                }
+                previous.key
+            }
+            RotationKind::Revocation => previous
+                .recovery_key
+                .ok_or_else(|| "no recovery key was registered".to_string())?,
+        };
+
+        let signature = rotation
+            .signature
+            .parse::<Signature>()
+            .map_err(|e| format!("{}", e))?;
+        if !signer.verify(&rotation.canonical_bytes(), &signature) {
+            return Err("isn't signed by the key it replaces or the recovery key".to_string());
+        }
+        Ok(())
+    }
+
+    /// Check a signature made at `time` against the key in use then
+    pub(super) fn verify(
+        &self,
+        time: DateTime<Utc>,
+        signature: &Option<String>,
+        message: &[u8],
+    ) -> Verification {
+        verify_with(&self.key_at(time), signature, message)
+    }
+
+    fn last(&self) -> &Link {
+        self.links
+            .last()
+            .expect("a chain starts with the identity key")
+    }
+
+    /// Apply a rotation that passed [`KeyChain::check`]
+    fn push(&mut self, rotation: &KeyRotation) {
+        let previous = *self.last();
+        let recovery_key = rotation
+            .recovery_key
+            .as_deref()
+            .and_then(|k| k.parse().ok());
+        self.links.push(Link {
+            key: rotation.new_key.parse().expect("checked before"),
+            // Revocations may replace the recovery key, endorsements only register one
+            recovery_key: match rotation.kind {
+                RotationKind::Endorsement => previous.recovery_key.or(recovery_key),
+                RotationKind::Revocation => recovery_key.or(previous.recovery_key),
+            },
+            since: Some(rotation.timestamp),
+        });
+    }
+}
+
+impl User {
+    /// The keys of this user, `None` if they have none or it is malformed
+    pub fn key_chain(&self, rotations: &[KeyRotation]) -> Option<KeyChain> {
+        let key = self.public_key.as_deref()?.parse().ok()?;
+        Some(KeyChain::resolve(&self.uuid, key, rotations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeDelta, TimeZone};
+
+    fn at(hours: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + TimeDelta::hours(hours)
+    }
+
+    fn rotation(
+        signer: &Identity,
+        identity: &Identity,
+        sequence: u32,
+        kind: RotationKind,
+        new_key: &Identity,
+        recovery_key: Option<&Identity>,
+        hours: i64,
+    ) -> KeyRotation {
+        let mut rotation = KeyRotation {
+            uuid: format!(
+                "00000000-0000-4000-8000-{:012}",
+                sequence * 100 + hours as u32
+            ),
+            user_id: identity.user_id(),
+            identity_key: identity.public_key().to_string(),
+            sequence,
+            kind,
+            new_key: new_key.public_key().to_string(),
+            recovery_key: recovery_key.map(|k| k.public_key().to_string()),
+            timestamp: at(hours),
+            signature: String::new(),
+        };
+        rotation.sign(signer);
+        rotation
+    }
+
+    #[test]
+    fn test_endorsement() {
+        let identity = Identity::generate();
+        let user_id = identity.user_id();
+        let next = Identity::generate();
+
+        let chain = KeyChain::new(&user_id, identity.public_key());
+        assert_eq!(chain.key_at(at(0)), identity.public_key());
+
+        let endorsed = rotation(
+            &identity,
+            &identity,
+            1,
+            RotationKind::Endorsement,
+            &next,
+            None,
+            10,
+        );
+        let chain = KeyChain::resolve(
+            &user_id,
+            identity.public_key(),
+            std::slice::from_ref(&endorsed),
+        );
+        assert_eq!(chain.sequence(), 1);
+        assert_eq!(chain.current_key(), next.public_key());
+        assert_eq!(chain.key_at(at(9)), identity.public_key());
+        assert_eq!(chain.key_at(at(10)), next.public_key());
+
+        // Only the key in use can hand over
+        let forged = rotation(
+            &next,
+            &identity,
+            1,
+            RotationKind::Endorsement,
+            &next,
+            None,
+            10,
+        );
+        assert!(chain.check(&forged).is_err());
+        let mut altered = endorsed.clone();
+        altered.new_key = Identity::generate().public_key().to_string();
+        assert!(chain.check(&altered).is_err());
+
+        // Gaps and other users' rotations don't apply
+        let third = rotation(
+            &next,
+            &identity,
+            3,
+            RotationKind::Endorsement,
+            &identity,
+            None,
+            20,
+        );
+        assert!(chain.check(&third).is_err());
+        let stranger = Identity::generate();
+        let other = rotation(
+            &stranger,
+            &stranger,
+            1,
+            RotationKind::Endorsement,
+            &next,
+            None,
+            10,
+        );
+        assert!(chain.check(&other).is_err());
+
+        // Going back in time isn't allowed
+        let early = rotation(
+            &next,
+            &identity,
+            2,
+            RotationKind::Endorsement,
+            &identity,
+            None,
+            5,
+        );
+        assert!(chain.check(&early).is_err());
+
+        let second = rotation(
+            &next,
+            &identity,
+            2,
+            RotationKind::Endorsement,
+            &identity,
+            None,
+            20,
+        );
+        assert_eq!(second.check_chain(std::slice::from_ref(&endorsed)), Ok(()));
+        assert!(second.check_chain(&[]).unwrap_err().has("signature"));
+    }
+
+    #[test]
+    fn test_revocation() {
+        let identity = Identity::generate();
+        let user_id = identity.user_id();
+        let recovery = Identity::generate();
+        let stolen = Identity::generate();
+        let replacement = Identity::generate();
+
+        // The recovery key is registered without changing the key
+        let registered = rotation(
+            &identity,
+            &identity,
+            1,
+            RotationKind::Endorsement,
+            &identity,
+            Some(&recovery),
+            1,
+        );
+        // The thief hands over to a key of their own and tries to drop the recovery key
+        let thief = rotation(
+            &identity,
+            &identity,
+            2,
+            RotationKind::Endorsement,
+            &stolen,
+            Some(&stolen),
+            10,
+        );
+        let chain = KeyChain::resolve(
+            &user_id,
+            identity.public_key(),
+            std::slice::from_ref(&registered),
+        );
+        assert_eq!(chain.recovery_key(), Some(recovery.public_key()));
+        assert!(chain.check(&thief).is_err());
+
+        let thief = rotation(
+            &identity,
+            &identity,
+            2,
+            RotationKind::Endorsement,
+            &stolen,
+            None,
+            10,
+        );
+        let revoked = rotation(
+            &recovery,
+            &identity,
+            2,
+            RotationKind::Revocation,
+            &replacement,
+            None,
+            5,
+        );
+        // Same result in either order
+        for rotations in [
+            vec![thief.clone(), revoked.clone(), registered.clone()],
+            vec![revoked.clone(), registered.clone(), thief.clone()],
+        ] {
+            let chain = KeyChain::resolve(&user_id, identity.public_key(), &rotations);
+            assert_eq!(chain.sequence(), 2);
+            assert_eq!(chain.current_key(), replacement.public_key());
+            assert_eq!(chain.recovery_key(), Some(recovery.public_key()));
+            // Posts before the phone went missing stay valid, the thief's don't
+            assert_eq!(chain.key_at(at(4)), identity.public_key());
+            assert_eq!(chain.key_at(at(12)), replacement.public_key());
+        }
+
+        // Revoking the thief's key later works as well
+        let revoked_later = rotation(
+            &recovery,
+            &identity,
+            3,
+            RotationKind::Revocation,
+            &replacement,
+            None,
+            11,
+        );
+        let chain = KeyChain::resolve(
+            &user_id,
+            identity.public_key(),
+            &[registered.clone(), thief, revoked_later],
+        );
+        assert_eq!(chain.sequence(), 3);
+        assert_eq!(chain.key_at(at(10)), stolen.public_key());
+        assert_eq!(chain.current_key(), replacement.public_key());
+
+        // Without a recovery key nothing can revoke
+        let chain = KeyChain::new(&user_id, identity.public_key());
+        let revoked = rotation(
+            &recovery,
+            &identity,
+            1,
+            RotationKind::Revocation,
+            &replacement,
+            None,
+            5,
+        );
+        assert!(chain.check(&revoked).is_err());
+    }
+
+    #[test]
+    fn test_verify_with_chain() {
+        let identity = Identity::generate();
+        let user_id = identity.user_id();
+        let next = Identity::generate();
+        let endorsed = rotation(
+            &identity,
+            &identity,
+            1,
+            RotationKind::Endorsement,
+            &next,
+            None,
+            10,
+        );
+        let chain = KeyChain::resolve(&user_id, identity.public_key(), &[endorsed]);
+
+        let old = Some(identity.sign(b"message").to_string());
+        let new = Some(next.sign(b"message").to_string());
+        assert_eq!(
+            chain.verify(at(9), &old, b"message"),
+            Verification::Verified
+        );
+        assert_eq!(
+            chain.verify(at(11), &old, b"message"),
+            Verification::Invalid
+        );
+        assert_eq!(
+            chain.verify(at(11), &new, b"message"),
+            Verification::Verified
+        );
+    }
+}
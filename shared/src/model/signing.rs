@@ -0,0 +1,897 @@
+//! Signatures binding posts and profiles to their authors
+//!
+//! Records pass through several totems and phones before they reach a reader, and any of
+//! them could change a record on the way. The author signs a canonical serialization of
+//! the record, which travels along in its `signature` field. Everyone who stores the
+//! record checks the signature against the author's public key first.
+//!
+//! The canonical form is a domain tag followed by the postcard encoding of the signed
+//! fields in a fixed order. Fields that hops fill in, like `source_totem`, are not signed.
+//! Changing what is signed needs a new domain tag, or old signatures stop verifying.
+//!
+//! Users created before identities existed have no key, so neither they nor their posts
+//! can be verified. They are accepted as [`Verification::Unsigned`] for the UI to flag.
+//! Once such a user moves to an identity, their phone links the old ID to the new one and
+//! checks the old records against the new profile, see `Database::migrate_user`.
+//!
+//! Records are checked against the key the author used at the time, which follows from
+//! the author's [`KeyRotation`]s, see [`super::rotation`].
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::validation::ValidationErrors;
+use super::{
+    Attachment, ImagePreview, KeyRotation, LastSeen, Post, Reaction, Reply, Repost, Tombstone, User,
+};
+use crate::identity::{is_derived_user_id, Identity, PublicKey, Signature};
+
+const USER_DOMAIN: &[u8] = b"loom user v1\0";
+const POST_DOMAIN: &[u8] = b"loom post v1\0";
+const POST_EDIT_DOMAIN: &[u8] = b"loom post edit v1\0";
+const REPLY_DOMAIN: &[u8] = b"loom reply v1\0";
+const REACTION_DOMAIN: &[u8] = b"loom reaction v1\0";
+const REPOST_DOMAIN: &[u8] = b"loom repost v1\0";
+const TOMBSTONE_DOMAIN: &[u8] = b"loom tombstone v1\0";
+const SIGHTING_DOMAIN: &[u8] = b"loom sighting v1\0";
+
+/// Whether a record was made by its author
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    /// Signed by the key of the author
+    Verified,
+    /// The author has no key, from before identities existed
+    Unsigned,
+    /// The author of the post isn't known, so their key isn't either
+    UnknownAuthor,
+    /// The signature is missing or doesn't match, the record was forged or altered
+    Invalid,
+}
+
+/// Timestamps as seconds and nanoseconds, independent of how they are formatted
+type Timestamp = (i64, u32);
+
+#[derive(Serialize)]
+struct SignedUser<'a> {
+    uuid: &'a str,
+    username: &'a str,
+    status: &'a str,
+    bio: &'a str,
+    profile_picture: Option<&'a str>,
+    last_contact: Timestamp,
+    profile_picture_preview: Option<&'a ImagePreview>,
+    public_key: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct SignedPost<'a> {
+    uuid: &'a str,
+    user_id: &'a str,
+    title: &'a str,
+    body: &'a str,
+    timestamp: Timestamp,
+    image: Option<&'a str>,
+    thumbnail: Option<&'a str>,
+    image_preview: Option<&'a ImagePreview>,
+    attachments: &'a [Attachment],
+}
+
+/// Edits sign the revision along with the post, see [`super::edit`]
+#[derive(Serialize)]
+struct SignedPostEdit<'a> {
+    post: SignedPost<'a>,
+    revision: u32,
+    edited_at: Option<Timestamp>,
+}
+
+#[derive(Serialize)]
+struct SignedReply<'a> {
+    uuid: &'a str,
+    user_id: &'a str,
+    parent_id: &'a str,
+    root_id: &'a str,
+    body: &'a str,
+    timestamp: Timestamp,
+}
+
+#[derive(Serialize)]
+struct SignedReaction<'a> {
+    uuid: &'a str,
+    user_id: &'a str,
+    post_id: &'a str,
+    kind: &'a str,
+    active: bool,
+    timestamp: Timestamp,
+}
+
+#[derive(Serialize)]
+struct SignedRepost<'a> {
+    uuid: &'a str,
+    user_id: &'a str,
+    post_id: &'a str,
+    author_id: &'a str,
+    timestamp: Timestamp,
+}
+
+#[derive(Serialize)]
+struct SignedTombstone<'a> {
+    uuid: &'a str,
+    user_id: &'a str,
+    post_id: &'a str,
+    timestamp: Timestamp,
+}
+
+#[derive(Serialize)]
+struct SignedSighting<'a> {
+    user_id: &'a str,
+    totem_id: &'a str,
+    seen_at: Timestamp,
+}
+
+pub(super) fn canonical<T: Serialize>(domain: &[u8], fields: &T) -> Vec<u8> {
+    postcard::to_extend(fields, domain.to_vec()).expect("serializing to a Vec can't fail")
+}
+
+pub(super) fn verify_with(
+    key: &PublicKey,
+    signature: &Option<String>,
+    message: &[u8],
+) -> Verification {
+    let Some(Ok(signature)) = signature.as_deref().map(str::parse::<Signature>) else {
+        return Verification::Invalid;
+    };
+
+    if key.verify(message, &signature) {
+        Verification::Verified
+    } else {
+        Verification::Invalid
+    }
+}
+
+/// Check the signature of a record written by `user_id` at `timestamp`
+fn verify_authored(
+    user_id: &str,
+    author: Option<&User>,
+    rotations: &[KeyRotation],
+    timestamp: DateTime<Utc>,
+    signature: &Option<String>,
+    message: &[u8],
+) -> Verification {
+    let Some(author) = author else {
+        return Verification::UnknownAuthor;
+    };
+    if author.uuid != user_id {
+        // Only a random ID from before identities can lead to another author, the one its
+        // user moved to. Their records from before the move were never signed.
+        if is_derived_user_id(user_id) || !is_derived_user_id(&author.uuid) {
+            return Verification::Invalid;
+        }
+        if signature.is_none() {
+            return Verification::Unsigned;
+        }
+    }
+    if author.public_key.is_none() {
+        return Verification::Unsigned;
+    }
+
+    match author.key_chain(rotations) {
+        Some(chain) => chain.verify(timestamp, signature, message),
+        None => Verification::Invalid,
+    }
+}
+
+impl User {
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            USER_DOMAIN,
+            &SignedUser {
+                uuid: &self.uuid,
+                username: &self.username,
+                status: &self.status,
+                bio: &self.bio,
+                profile_picture: self.profile_picture.as_deref(),
+                last_contact: (
+                    self.last_contact.timestamp(),
+                    self.last_contact.timestamp_subsec_nanos(),
+                ),
+                profile_picture_preview: self.profile_picture_preview.as_ref(),
+                public_key: self.public_key.as_deref(),
+            },
+        )
+    }
+
+    /// Sign the profile, after all other fields have been set
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = Some(identity.sign(&self.canonical_bytes()).to_string());
+    }
+
+    /// Check the signature against the user's key at `last_contact`
+    /// `rotations` are the user's key rotations known to the local store.
+    pub fn verification(&self, rotations: &[KeyRotation]) -> Verification {
+        if self.public_key.is_none() {
+            return Verification::Unsigned;
+        }
+        match self.key_chain(rotations) {
+            Some(chain) => {
+                chain.verify(self.last_contact, &self.signature, &self.canonical_bytes())
+            }
+            None => Verification::Invalid,
+        }
+    }
+
+    /// Reject a profile that wasn't made by the user
+    pub fn check_signature(&self, rotations: &[KeyRotation]) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.verification(rotations) == Verification::Invalid {
+            errors.add("signature", "doesn't match the user's key");
+        }
+        errors.into_result()
+    }
+}
+
+impl Post {
+    /// The bytes the signature is made over
+    /// Posts as first written keep the form they had before edits existed.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let post = SignedPost {
+            uuid: &self.uuid,
+            user_id: &self.user_id,
+            title: &self.title,
+            body: &self.body,
+            timestamp: (
+                self.timestamp.timestamp(),
+                self.timestamp.timestamp_subsec_nanos(),
+            ),
+            image: self.image.as_deref(),
+            thumbnail: self.thumbnail.as_deref(),
+            image_preview: self.image_preview.as_ref(),
+            attachments: &self.attachments,
+        };
+        if self.revision == 0 {
+            return canonical(POST_DOMAIN, &post);
+        }
+
+        canonical(
+            POST_EDIT_DOMAIN,
+            &SignedPostEdit {
+                post,
+                revision: self.revision,
+                edited_at: self
+                    .edited_at
+                    .map(|edited_at| (edited_at.timestamp(), edited_at.timestamp_subsec_nanos())),
+            },
+        )
+    }
+
+    /// Sign the post, after all other fields have been set
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = Some(identity.sign(&self.canonical_bytes()).to_string());
+    }
+
+    /// Check the signature against the key `author` used when the post was written or edited
+    /// `author` and their `rotations` are the ones found in the local store.
+    pub fn verification(&self, author: Option<&User>, rotations: &[KeyRotation]) -> Verification {
+        verify_authored(
+            &self.user_id,
+            author,
+            rotations,
+            self.changed_at(),
+            &self.signature,
+            &self.canonical_bytes(),
+        )
+    }
+
+    /// Reject a post that wasn't made by `author`
+    /// Posts of unknown authors pass, whether they may be stored is up to the caller. Edits
+    /// don't, anyone could rewrite the posts of users without a key otherwise.
+    pub fn check_signature(
+        &self,
+        author: Option<&User>,
+        rotations: &[KeyRotation],
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        match self.verification(author, rotations) {
+            Verification::Invalid => errors.add("signature", "doesn't match the author's key"),
+            Verification::Verified => {}
+            _ if self.revision > 0 => {
+                errors.add("signature", "edits must be signed by a known author")
+            }
+            _ => {}
+        }
+        errors.into_result()
+    }
+}
+
+impl Reply {
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            REPLY_DOMAIN,
+            &SignedReply {
+                uuid: &self.uuid,
+                user_id: &self.user_id,
+                parent_id: &self.parent_id,
+                root_id: &self.root_id,
+                body: &self.body,
+                timestamp: (
+                    self.timestamp.timestamp(),
+                    self.timestamp.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Sign the reply, after all other fields have been set
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = Some(identity.sign(&self.canonical_bytes()).to_string());
+    }
+
+    /// Check the signature against the key `author` used at `timestamp`
+    pub fn verification(&self, author: Option<&User>, rotations: &[KeyRotation]) -> Verification {
+        verify_authored(
+            &self.user_id,
+            author,
+            rotations,
+            self.timestamp,
+            &self.signature,
+            &self.canonical_bytes(),
+        )
+    }
+
+    /// Reject a reply that wasn't made by `author`
+    /// Replies of unknown authors pass, whether they may be stored is up to the caller.
+    pub fn check_signature(
+        &self,
+        author: Option<&User>,
+        rotations: &[KeyRotation],
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.verification(author, rotations) == Verification::Invalid {
+            errors.add("signature", "doesn't match the author's key");
+        }
+        errors.into_result()
+    }
+}
+
+impl Reaction {
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            REACTION_DOMAIN,
+            &SignedReaction {
+                uuid: &self.uuid,
+                user_id: &self.user_id,
+                post_id: &self.post_id,
+                kind: self.kind.as_str(),
+                active: self.active,
+                timestamp: (
+                    self.timestamp.timestamp(),
+                    self.timestamp.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Sign the reaction, after all other fields have been set
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = Some(identity.sign(&self.canonical_bytes()).to_string());
+    }
+
+    /// Check the signature against the key `author` used at `timestamp`
+    pub fn verification(&self, author: Option<&User>, rotations: &[KeyRotation]) -> Verification {
+        verify_authored(
+            &self.user_id,
+            author,
+            rotations,
+            self.timestamp,
+            &self.signature,
+            &self.canonical_bytes(),
+        )
+    }
+
+    /// Reject a reaction that wasn't made by `author`
+    /// Reactions of unknown authors pass, whether they may be stored is up to the caller.
+    pub fn check_signature(
+        &self,
+        author: Option<&User>,
+        rotations: &[KeyRotation],
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.verification(author, rotations) == Verification::Invalid {
+            errors.add("signature", "doesn't match the author's key");
+        }
+        errors.into_result()
+    }
+}
+
+impl Repost {
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            REPOST_DOMAIN,
+            &SignedRepost {
+                uuid: &self.uuid,
+                user_id: &self.user_id,
+                post_id: &self.post_id,
+                author_id: &self.author_id,
+                timestamp: (
+                    self.timestamp.timestamp(),
+                    self.timestamp.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Sign the repost, after all other fields have been set
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = Some(identity.sign(&self.canonical_bytes()).to_string());
+    }
+
+    /// Check the signature against the key the reposting user used at `timestamp`
+    pub fn verification(&self, user: Option<&User>, rotations: &[KeyRotation]) -> Verification {
+        verify_authored(
+            &self.user_id,
+            user,
+            rotations,
+            self.timestamp,
+            &self.signature,
+            &self.canonical_bytes(),
+        )
+    }
+
+    /// Reject a repost that wasn't made by `user`
+    /// Reposts of unknown users pass, whether they may be stored is up to the caller.
+    pub fn check_signature(
+        &self,
+        user: Option<&User>,
+        rotations: &[KeyRotation],
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.verification(user, rotations) == Verification::Invalid {
+            errors.add("signature", "doesn't match the user's key");
+        }
+        errors.into_result()
+    }
+}
+
+impl Tombstone {
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            TOMBSTONE_DOMAIN,
+            &SignedTombstone {
+                uuid: &self.uuid,
+                user_id: &self.user_id,
+                post_id: &self.post_id,
+                timestamp: (
+                    self.timestamp.timestamp(),
+                    self.timestamp.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Sign the tombstone, after all other fields have been set
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = Some(identity.sign(&self.canonical_bytes()).to_string());
+    }
+
+    /// Check the signature against the key the author used at `timestamp`
+    pub fn verification(&self, author: Option<&User>, rotations: &[KeyRotation]) -> Verification {
+        verify_authored(
+            &self.user_id,
+            author,
+            rotations,
+            self.timestamp,
+            &self.signature,
+            &self.canonical_bytes(),
+        )
+    }
+
+    /// Reject a tombstone that wasn't made by `author`
+    /// Unlike other records, unsigned tombstones are rejected even for authors without a key.
+    pub fn check_signature(
+        &self,
+        author: Option<&User>,
+        rotations: &[KeyRotation],
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.verification(author, rotations) != Verification::Verified {
+            errors.add("signature", "doesn't match the author's key");
+        }
+        errors.into_result()
+    }
+}
+
+impl LastSeen {
+    /// The bytes the signature is made over
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical(
+            SIGHTING_DOMAIN,
+            &SignedSighting {
+                user_id: &self.user_id,
+                totem_id: &self.totem_id,
+                seen_at: (
+                    self.seen_at.timestamp(),
+                    self.seen_at.timestamp_subsec_nanos(),
+                ),
+            },
+        )
+    }
+
+    /// Sign the sighting, after all other fields have been set
+    pub fn sign(&mut self, identity: &Identity) {
+        self.signature = Some(identity.sign(&self.canonical_bytes()).to_string());
+    }
+
+    /// Check the signature against the key `user` used at `seen_at`
+    pub fn verification(&self, user: Option<&User>, rotations: &[KeyRotation]) -> Verification {
+        verify_authored(
+            &self.user_id,
+            user,
+            rotations,
+            self.seen_at,
+            &self.signature,
+            &self.canonical_bytes(),
+        )
+    }
+
+    /// Reject a sighting its user didn't sign
+    /// Users without a key pass unsigned. Users with a key-derived ID must be known, or the
+    /// signature can't be checked, otherwise anyone could place them at a totem.
+    pub fn check_signature(
+        &self,
+        user: Option<&User>,
+        rotations: &[KeyRotation],
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        match self.verification(user, rotations) {
+            Verification::Verified | Verification::Unsigned => {}
+            Verification::UnknownAuthor if !is_derived_user_id(&self.user_id) => {}
+            _ => errors.add("signature", "doesn't match the user's key"),
+        }
+        errors.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AttachmentKind, ImagePreview};
+    use chrono::{TimeZone, Utc};
+
+    fn user(identity: &Identity) -> User {
+        User {
+            uuid: identity.user_id(),
+            username: "alice".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap(),
+            profile_picture_preview: None,
+            public_key: Some(identity.public_key().to_string()),
+            signature: None,
+        }
+    }
+
+    fn post(author: &User) -> Post {
+        Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: author.uuid.clone(),
+            title: "Hello".to_string(),
+            body: "World".to_string(),
+            timestamp: Utc.timestamp_opt(1_735_732_800, 123_456_000).unwrap(),
+            image: Some("ab".repeat(32)),
+            source_totem: None,
+            thumbnail: None,
+            image_preview: Some(ImagePreview {
+                blurhash: "LEHV6nWB2yk8pyo0adR*.7kCMdnj".to_string(),
+                width: 640,
+                height: 480,
+            }),
+            attachments: vec![Attachment {
+                kind: AttachmentKind::Image,
+                mime: "image/jpeg".to_string(),
+                hash: "ab".repeat(32),
+                size: 1024,
+                duration_ms: None,
+                width: Some(640),
+                height: Some(480),
+                alt_text: None,
+            }],
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_user_signature() {
+        let identity = Identity::generate();
+        let mut user = user(&identity);
+        assert_eq!(user.verification(&[]), Verification::Invalid);
+
+        user.sign(&identity);
+        assert_eq!(user.verification(&[]), Verification::Verified);
+
+        user.bio = "altered".to_string();
+        assert_eq!(user.verification(&[]), Verification::Invalid);
+
+        // Signed by someone else
+        user.sign(&Identity::generate());
+        assert_eq!(user.verification(&[]), Verification::Invalid);
+
+        user.public_key = None;
+        assert_eq!(user.verification(&[]), Verification::Unsigned);
+    }
+
+    #[test]
+    fn test_post_signature() {
+        let identity = Identity::generate();
+        let mut author = user(&identity);
+        author.sign(&identity);
+        let mut post = post(&author);
+        assert_eq!(post.verification(Some(&author), &[]), Verification::Invalid);
+
+        post.sign(&identity);
+        assert_eq!(
+            post.verification(Some(&author), &[]),
+            Verification::Verified
+        );
+        assert_eq!(post.check_signature(Some(&author), &[]), Ok(()));
+        assert_eq!(post.verification(None, &[]), Verification::UnknownAuthor);
+
+        // Relays may fill in where they got the post from
+        post.source_totem = Some("totem-1".to_string());
+        assert_eq!(
+            post.verification(Some(&author), &[]),
+            Verification::Verified
+        );
+
+        // Survives the trip through JSON
+        let json = serde_json::to_string(&post).unwrap();
+        let relayed: Post = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            relayed.verification(Some(&author), &[]),
+            Verification::Verified
+        );
+
+        post.attachments[0].alt_text = Some("altered".to_string());
+        assert_eq!(post.verification(Some(&author), &[]), Verification::Invalid);
+        assert!(
+            post.check_signature(Some(&author), &[])
+                .unwrap_err()
+                .has("signature")
+        );
+
+        // Claiming someone else's post
+        let mut other = user(&Identity::generate());
+        other.public_key = None;
+        assert_eq!(post.verification(Some(&other), &[]), Verification::Invalid);
+
+        author.public_key = None;
+        assert_eq!(
+            post.verification(Some(&author), &[]),
+            Verification::Unsigned
+        );
+    }
+
+    #[test]
+    fn test_legacy_post_signature() {
+        let identity = Identity::generate();
+        let author = user(&identity);
+        let mut post = post(&author);
+        post.user_id = "550e8400-e29b-41d4-a716-446655440000".to_string();
+
+        // Checked against the profile the legacy ID moved to
+        assert_eq!(
+            post.verification(Some(&author), &[]),
+            Verification::Unsigned
+        );
+        assert_eq!(post.check_signature(Some(&author), &[]), Ok(()));
+        post.sign(&Identity::generate());
+        assert_eq!(post.verification(Some(&author), &[]), Verification::Invalid);
+        post.sign(&identity);
+        assert_eq!(
+            post.verification(Some(&author), &[]),
+            Verification::Verified
+        );
+
+        // A legacy ID can't lead to another legacy ID
+        let mut legacy = user(&identity);
+        legacy.uuid = "650e8400-e29b-41d4-a716-446655440000".to_string();
+        assert_eq!(post.verification(Some(&legacy), &[]), Verification::Invalid);
+    }
+
+    #[test]
+    fn test_reply_signature() {
+        let identity = Identity::generate();
+        let author = user(&identity);
+        let post = post(&author);
+        let mut reply = Reply {
+            uuid: "123e4567-e89b-12d3-a456-426614174002".to_string(),
+            user_id: author.uuid.clone(),
+            parent_id: post.uuid.clone(),
+            root_id: post.uuid.clone(),
+            body: "Agreed".to_string(),
+            timestamp: post.timestamp,
+            signature: None,
+        };
+        reply.sign(&identity);
+        assert_eq!(
+            reply.verification(Some(&author), &[]),
+            Verification::Verified
+        );
+        assert_eq!(reply.verification(None, &[]), Verification::UnknownAuthor);
+
+        // Moved to another thread
+        reply.root_id = "123e4567-e89b-12d3-a456-426614174003".to_string();
+        assert_eq!(
+            reply.verification(Some(&author), &[]),
+            Verification::Invalid
+        );
+        assert!(
+            reply
+                .check_signature(Some(&author), &[])
+                .unwrap_err()
+                .has("signature")
+        );
+    }
+
+    #[test]
+    fn test_repost_signature() {
+        let identity = Identity::generate();
+        let booster = user(&identity);
+        let original = post(&user(&Identity::generate()));
+        let mut repost = Repost {
+            uuid: "123e4567-e89b-12d3-a456-426614174002".to_string(),
+            user_id: booster.uuid.clone(),
+            post_id: original.uuid.clone(),
+            author_id: original.user_id.clone(),
+            timestamp: original.timestamp,
+            signature: None,
+        };
+        repost.sign(&identity);
+        assert_eq!(
+            repost.verification(Some(&booster), &[]),
+            Verification::Verified
+        );
+
+        // Attributed to someone else
+        repost.author_id = booster.uuid.clone();
+        assert_eq!(
+            repost.verification(Some(&booster), &[]),
+            Verification::Invalid
+        );
+        assert!(
+            repost
+                .check_signature(Some(&booster), &[])
+                .unwrap_err()
+                .has("signature")
+        );
+    }
+
+    #[test]
+    fn test_tombstone_signature() {
+        let identity = Identity::generate();
+        let author = user(&identity);
+        let original = post(&author);
+        let mut tombstone = Tombstone::of(
+            &original,
+            "123e4567-e89b-12d3-a456-426614174003".to_string(),
+            original.timestamp,
+        );
+        assert!(
+            tombstone
+                .check_signature(Some(&author), &[])
+                .unwrap_err()
+                .has("signature")
+        );
+
+        tombstone.sign(&identity);
+        assert_eq!(
+            tombstone.verification(Some(&author), &[]),
+            Verification::Verified
+        );
+        assert_eq!(tombstone.check_signature(Some(&author), &[]), Ok(()));
+        // Tombstones of unknown authors can't be checked, so they aren't taken
+        assert!(tombstone.check_signature(None, &[]).is_err());
+
+        // Moved to another post
+        tombstone.post_id = "123e4567-e89b-12d3-a456-426614174004".to_string();
+        assert_eq!(
+            tombstone.verification(Some(&author), &[]),
+            Verification::Invalid
+        );
+    }
+
+    #[test]
+    fn test_sighting_signature() {
+        let identity = Identity::generate();
+        let user = user(&identity);
+        let mut sighting = LastSeen {
+            user_id: user.uuid.clone(),
+            totem_id: "totem-a".to_string(),
+            seen_at: user.last_contact,
+            signature: None,
+        };
+        assert!(
+            sighting
+                .check_signature(Some(&user), &[])
+                .unwrap_err()
+                .has("signature")
+        );
+
+        sighting.sign(&identity);
+        assert_eq!(
+            sighting.verification(Some(&user), &[]),
+            Verification::Verified
+        );
+        assert_eq!(sighting.check_signature(Some(&user), &[]), Ok(()));
+        // The key of an unknown user isn't known either
+        assert!(sighting.check_signature(None, &[]).is_err());
+
+        // Replayed at another totem
+        let moved = LastSeen {
+            totem_id: "totem-b".to_string(),
+            ..sighting.clone()
+        };
+        assert_eq!(moved.verification(Some(&user), &[]), Verification::Invalid);
+
+        // Users without a key can't sign
+        let legacy = LastSeen {
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            signature: None,
+            ..sighting
+        };
+        assert_eq!(legacy.check_signature(None, &[]), Ok(()));
+    }
+
+    #[test]
+    fn test_signature_after_rotation() {
+        let identity = Identity::generate();
+        let next = Identity::generate();
+        let mut author = user(&identity);
+        author.sign(&identity);
+
+        let mut rotation = KeyRotation {
+            uuid: "123e4567-e89b-12d3-a456-426614174001".to_string(),
+            user_id: author.uuid.clone(),
+            identity_key: identity.public_key().to_string(),
+            sequence: 1,
+            kind: crate::model::RotationKind::Endorsement,
+            new_key: next.public_key().to_string(),
+            recovery_key: None,
+            timestamp: author.last_contact,
+            signature: String::new(),
+        };
+        rotation.sign(&identity);
+        let rotations = [rotation];
+
+        // Posts written after the rotation are signed with the new key
+        let mut post = post(&author);
+        post.sign(&next);
+        assert_eq!(
+            post.verification(Some(&author), &rotations),
+            Verification::Verified
+        );
+        assert_eq!(post.verification(Some(&author), &[]), Verification::Invalid);
+
+        // The old key doesn't sign for the user anymore
+        post.sign(&identity);
+        assert_eq!(
+            post.verification(Some(&author), &rotations),
+            Verification::Invalid
+        );
+        assert!(
+            post.check_signature(Some(&author), &rotations)
+                .unwrap_err()
+                .has("signature")
+        );
+
+        author.sign(&next);
+        assert_eq!(author.check_signature(&rotations), Ok(()));
+        assert!(author.check_signature(&[]).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Deletion of posts
+//!
+//! Records are copied from store to store, deleting a post in one place would just get it
+//! back from the next. So the author signs a [`Tombstone`] instead, which syncs like any
+//! other record. Every store that learns of it drops the post with its attachments and
+//! refuses it from then on.
+//!
+//! Tombstones are kept for [`TOMBSTONE_TTL`], then dropped as well. A store that has been
+//! out of reach for longer than that may bring the post back.
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::validation::ValidationErrors;
+use super::{Post, Tombstone};
+
+/// Tombstones are dropped this long after the deletion
+pub const TOMBSTONE_TTL: TimeDelta = TimeDelta::days(90);
+
+impl Tombstone {
+    /// A tombstone for a post, to be signed by its author
+    pub fn of(post: &Post, uuid: String, now: DateTime<Utc>) -> Tombstone {
+        Tombstone {
+            uuid,
+            user_id: post.user_id.clone(),
+            post_id: post.uuid.clone(),
+            timestamp: now,
+            signature: None,
+        }
+    }
+
+    /// Whether the tombstone is past [`TOMBSTONE_TTL`]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.timestamp + TOMBSTONE_TTL < now
+    }
+
+    /// Whether this tombstone deletes `post`, which only its author can do
+    pub fn buries(&self, post: &Post) -> bool {
+        self.post_id == post.uuid && self.user_id == post.user_id
+    }
+
+    /// Reject a tombstone for `post` that wasn't made by its author
+    pub fn check_post(&self, post: &Post) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.post_id != post.uuid {
+            errors.add("post_id", "isn't the deleted post");
+        }
+        if self.user_id != post.user_id {
+            errors.add("user_id", "isn't the author of the post");
+        }
+        errors.into_result()
+    }
+}
+
+impl Post {
+    /// Hashes of the blobs the post refers to, its pictures and attachments
+    pub fn media_hashes(&self) -> Vec<&str> {
+        let attachments = self.attachments.iter().map(|attachment| &attachment.hash);
+        let mut hashes = Vec::new();
+        for hash in self.image.iter().chain(&self.thumbnail).chain(attachments) {
+            if !hashes.contains(&hash.as_str()) {
+                hashes.push(hash.as_str());
+            }
+        }
+        hashes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Attachment, AttachmentKind};
+
+    fn post() -> Post {
+        Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            title: "Title".to_string(),
+            body: "Body".to_string(),
+            timestamp: DateTime::from_timestamp(1_767_225_600, 0).unwrap(),
+            image: Some("ab".repeat(32)),
+            source_totem: None,
+            thumbnail: Some("cd".repeat(32)),
+            image_preview: None,
+            attachments: vec![Attachment {
+                kind: AttachmentKind::Image,
+                mime: "image/jpeg".to_string(),
+                hash: "ab".repeat(32),
+                size: 1_000,
+                duration_ms: None,
+                width: Some(640),
+                height: Some(480),
+                alt_text: None,
+            }],
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_tombstone() {
+        let post = post();
+        let now = post.timestamp + TimeDelta::hours(1);
+        let tombstone = Tombstone::of(
+            &post,
+            "123e4567-e89b-12d3-a456-426614174001".to_string(),
+            now,
+        );
+        assert!(tombstone.buries(&post));
+        assert_eq!(tombstone.check_post(&post), Ok(()));
+        assert!(!tombstone.is_expired(now + TOMBSTONE_TTL));
+        assert!(tombstone.is_expired(now + TOMBSTONE_TTL + TimeDelta::seconds(1)));
+
+        // Someone else's post
+        let other = Tombstone {
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            ..tombstone
+        };
+        assert!(!other.buries(&post));
+        assert!(other.check_post(&post).unwrap_err().has("user_id"));
+    }
+
+    #[test]
+    fn test_media_hashes() {
+        assert_eq!(post().media_hashes(), ["ab".repeat(32), "cd".repeat(32)]);
+    }
+}
@@ -0,0 +1,1294 @@
+//! Validation rules for records exchanged between phones and totems
+//!
+//! Totems check every record on ingest, and the app checks them before they are
+//! stored or uploaded, so both sides agree on what a valid record looks like.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use uuid::Uuid;
+
+use super::{
+    Attachment, AttachmentKind, Bundle, BundlePayload, DeliveryReceipt, Destination, ImagePreview,
+    KeyRotation, LastSeen, Message, Post, Reaction, Reply, Repost, Tombstone, Totem, User,
+};
+use super::tombstone::TOMBSTONE_TTL;
+use crate::presence::MAX_SIGHTING_SKEW;
+use crate::routing::{MAX_COPIES, MAX_HOP_LIMIT, MAX_TTL};
+use crate::identity::{PublicKey, Signature, is_derived_user_id};
+use crate::media::ContentHash;
+
+pub const MAX_USERNAME_LEN: usize = 32;
+pub const MAX_STATUS_LEN: usize = 64;
+pub const MAX_BIO_LEN: usize = 512;
+pub const MAX_TITLE_LEN: usize = 128;
+pub const MAX_BODY_LEN: usize = 4096;
+pub const MAX_REPLY_LEN: usize = 1024;
+pub const MAX_NAME_LEN: usize = 64;
+pub const MAX_LOCATION_LEN: usize = 128;
+/// Image references are local paths on the phone or media identifiers
+pub const MAX_IMAGE_REF_LEN: usize = 256;
+pub const MAX_TOTEM_ID_LEN: usize = 64;
+/// Pictures are downscaled before they are posted, anything bigger is bogus
+pub const MAX_IMAGE_DIMENSION: u32 = 16384;
+pub const MAX_ATTACHMENTS: usize = 8;
+pub const MAX_MIME_LEN: usize = 127;
+pub const MAX_ALT_TEXT_LEN: usize = 1000;
+pub const MAX_MESSAGE_LEN: usize = 4096;
+/// Bytes of a nonce of XChaCha20-Poly1305
+pub const MESSAGE_NONCE_LEN: usize = 24;
+/// Bytes of the tag of XChaCha20-Poly1305
+pub const MESSAGE_TAG_LEN: usize = 16;
+/// Clips and voice notes are short, a totem has no room for anything longer
+pub const MAX_DURATION_MS: u32 = 10 * 60 * 1000;
+
+/// Characters of the base 83 encoding used by blurhash
+const BLURHASH_ALPHABET: &str =
+    "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+/// How far a timestamp may lie in the future, to allow for phones with a wrong clock
+pub const MAX_CLOCK_SKEW: TimeDelta = TimeDelta::hours(24);
+
+/// Nothing was written on Loom before this date
+pub fn earliest_timestamp() -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(2024, 1, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("valid date")
+        .and_utc()
+}
+
+/// A single field that failed validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+/// All fields of a record that failed validation
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    pub(crate) fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.errors.push(FieldError {
+            field,
+            message: message.into(),
+        });
+    }
+
+    pub(crate) fn check(&mut self, field: &'static str, result: Result<(), String>) {
+        if let Err(message) = result {
+            self.add(field, message);
+        }
+    }
+
+    /// Whether a specific field failed validation
+    pub fn has(&self, field: &str) -> bool {
+        self.errors.iter().any(|e| e.field == field)
+    }
+
+    pub(crate) fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.errors.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}: {}", e.field, e.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+/// Check that `id` is a UUID in its canonical, lowercase hyphenated form
+/// IDs are compared as strings during sync, so other spellings would create duplicates.
+pub fn check_uuid(id: &str) -> Result<(), String> {
+    match Uuid::try_parse(id) {
+        Ok(uuid) if uuid.hyphenated().to_string() == id => Ok(()),
+        Ok(_) => Err("UUID must be lowercase and hyphenated".to_string()),
+        Err(_) => Err("not a valid UUID".to_string()),
+    }
+}
+
+/// Check the length of a text field, counted in characters
+pub fn check_text(value: &str, min: usize, max: usize) -> Result<(), String> {
+    let len = value.chars().count();
+
+    if len < min {
+        return Err(if min == 1 {
+            "must not be empty".to_string()
+        } else {
+            format!("must be at least {} characters long", min)
+        });
+    }
+    if len > max {
+        return Err(format!("must be at most {} characters long", max));
+    }
+    if value
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return Err("must not contain control characters".to_string());
+    }
+
+    Ok(())
+}
+
+/// Check a single-line text field, like a name
+pub fn check_line(value: &str, min: usize, max: usize) -> Result<(), String> {
+    check_text(value, min, max)?;
+
+    if value.contains(['\n', '\t']) {
+        return Err("must be a single line".to_string());
+    }
+    if value.trim() != value {
+        return Err("must not start or end with whitespace".to_string());
+    }
+
+    Ok(())
+}
+
+/// Check an optional reference to an image
+pub fn check_image_ref(value: &Option<String>) -> Result<(), String> {
+    match value {
+        Some(value) => check_line(value, 1, MAX_IMAGE_REF_LEN),
+        None => Ok(()),
+    }
+}
+
+/// Check that a user ID belongs to the public key it is published with
+/// Derived IDs always need their key, older random IDs can't have one.
+pub fn check_public_key(user_id: &str, public_key: &Option<String>) -> Result<(), String> {
+    match public_key {
+        Some(key) => {
+            let key: PublicKey = key.parse().map_err(|e| format!("{}", e))?;
+            if !key.owns(user_id) {
+                return Err("doesn't match the user ID".to_string());
+            }
+            Ok(())
+        }
+        None if is_derived_user_id(user_id) => Err("required for this user ID".to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Check that a key is a well-formed public key
+pub fn check_key(key: &str) -> Result<(), String> {
+    key.parse::<PublicKey>()
+        .map(|_| ())
+        .map_err(|e| format!("{}", e))
+}
+
+/// Check that a value is lowercase hex of between `min` and `max` bytes
+pub fn check_hex(value: &str, min: usize, max: usize) -> Result<(), String> {
+    if !value
+        .bytes()
+        .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        || !value.len().is_multiple_of(2)
+    {
+        return Err("must be lowercase hex".to_string());
+    }
+    let len = value.len() / 2;
+    if len < min || len > max {
+        return Err(format!("must be between {} and {} bytes", min, max));
+    }
+    Ok(())
+}
+
+/// Check that a signature is well-formed, whether it matches is checked separately
+pub fn check_signature(signature: &Option<String>) -> Result<(), String> {
+    match signature {
+        Some(signature) => signature
+            .parse::<Signature>()
+            .map(|_| ())
+            .map_err(|e| format!("{}", e)),
+        None => Ok(()),
+    }
+}
+
+/// Check the placeholder of a picture, which only makes sense if there is a picture
+pub fn check_image_preview(
+    preview: &Option<ImagePreview>,
+    image: &Option<String>,
+) -> Result<(), String> {
+    let Some(preview) = preview else {
+        return Ok(());
+    };
+    if image.is_none() {
+        return Err("requires an image".to_string());
+    }
+    if !(1..=MAX_IMAGE_DIMENSION).contains(&preview.width)
+        || !(1..=MAX_IMAGE_DIMENSION).contains(&preview.height)
+    {
+        return Err(format!(
+            "dimensions must be between 1 and {} pixels",
+            MAX_IMAGE_DIMENSION
+        ));
+    }
+
+    check_blurhash(&preview.blurhash)
+}
+
+/// Check that a blurhash is well-formed, its length follows from its first character
+pub fn check_blurhash(value: &str) -> Result<(), String> {
+    if !value.chars().all(|c| BLURHASH_ALPHABET.contains(c)) {
+        return Err("is not a blurhash".to_string());
+    }
+    let Some(size_flag) = value.chars().next().and_then(|c| BLURHASH_ALPHABET.find(c)) else {
+        return Err("is not a blurhash".to_string());
+    };
+    let components = (size_flag % 9 + 1) * (size_flag / 9 + 1);
+    if value.len() != 4 + 2 * components {
+        return Err("is not a blurhash".to_string());
+    }
+
+    Ok(())
+}
+
+/// Check the attachments of a post, the error names the offending attachment
+pub fn check_attachments(attachments: &[Attachment]) -> Result<(), String> {
+    if attachments.len() > MAX_ATTACHMENTS {
+        return Err(format!("must not be more than {}", MAX_ATTACHMENTS));
+    }
+
+    attachments
+        .iter()
+        .enumerate()
+        .try_for_each(|(i, attachment)| {
+            check_attachment(attachment).map_err(|e| format!("attachment {}: {}", i, e))
+        })
+}
+
+fn check_attachment(attachment: &Attachment) -> Result<(), String> {
+    attachment
+        .hash
+        .parse::<ContentHash>()
+        .map_err(|_| "hash is not a SHA-256 hash".to_string())?;
+
+    check_line(&attachment.mime, 3, MAX_MIME_LEN).map_err(|e| format!("MIME type {}", e))?;
+    let (top_level, _) = attachment
+        .mime
+        .split_once('/')
+        .ok_or_else(|| "MIME type must contain a '/'".to_string())?;
+    let expected = match attachment.kind {
+        AttachmentKind::Image => Some("image"),
+        AttachmentKind::Clip => Some("video"),
+        AttachmentKind::Voice => Some("audio"),
+        AttachmentKind::File => None,
+    };
+    if expected.is_some_and(|expected| expected != top_level) {
+        return Err(format!(
+            "MIME type {} doesn't match kind {}",
+            attachment.mime,
+            attachment.kind.as_str()
+        ));
+    }
+
+    if attachment.size == 0 {
+        return Err("must not be empty".to_string());
+    }
+    if let Some(duration) = attachment.duration_ms {
+        if !matches!(
+            attachment.kind,
+            AttachmentKind::Clip | AttachmentKind::Voice
+        ) {
+            return Err("only clips and voice notes have a duration".to_string());
+        }
+        if duration > MAX_DURATION_MS {
+            return Err(format!("must not be longer than {} ms", MAX_DURATION_MS));
+        }
+    }
+    match (attachment.width, attachment.height) {
+        (None, None) => {}
+        (Some(width), Some(height))
+            if (1..=MAX_IMAGE_DIMENSION).contains(&width)
+                && (1..=MAX_IMAGE_DIMENSION).contains(&height) => {}
+        _ => {
+            return Err(format!(
+                "dimensions must both be between 1 and {} pixels",
+                MAX_IMAGE_DIMENSION
+            ));
+        }
+    }
+    if let Some(alt_text) = &attachment.alt_text {
+        check_text(alt_text, 0, MAX_ALT_TEXT_LEN).map_err(|e| format!("alt text {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Largest blob a totem accepts per kind of attachment, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttachmentLimits {
+    pub image: u64,
+    pub clip: u64,
+    pub voice: u64,
+    pub file: u64,
+}
+
+impl AttachmentLimits {
+    pub fn max_size(&self, kind: AttachmentKind) -> u64 {
+        match kind {
+            AttachmentKind::Image => self.image,
+            AttachmentKind::Clip => self.clip,
+            AttachmentKind::Voice => self.voice,
+            AttachmentKind::File => self.file,
+        }
+    }
+
+    /// The largest blob accepted for any kind, for uploads that aren't tied to a post yet
+    pub fn largest(&self) -> u64 {
+        self.image.max(self.clip).max(self.voice).max(self.file)
+    }
+}
+
+impl Default for AttachmentLimits {
+    fn default() -> Self {
+        AttachmentLimits {
+            image: 4 * 1024 * 1024,
+            clip: 16 * 1024 * 1024,
+            voice: 2 * 1024 * 1024,
+            file: 4 * 1024 * 1024,
+        }
+    }
+}
+
+/// Check the ID of a totem, as advertised over BLE
+pub fn check_totem_id(id: &str) -> Result<(), String> {
+    check_line(id, 1, MAX_TOTEM_ID_LEN)?;
+
+    if !id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    {
+        return Err("may only contain letters, digits and - _ . :".to_string());
+    }
+
+    Ok(())
+}
+
+/// Check that a timestamp lies between the start of Loom and shortly after `now`
+pub fn check_timestamp(timestamp: &DateTime<Utc>, now: DateTime<Utc>) -> Result<(), String> {
+    if *timestamp < earliest_timestamp() {
+        return Err("lies too far in the past".to_string());
+    }
+    if *timestamp > now + MAX_CLOCK_SKEW {
+        return Err("lies in the future".to_string());
+    }
+
+    Ok(())
+}
+
+/// Parse a timestamp sent by a client
+///
+/// Accepted are RFC 3339 timestamps (`2025-12-14T13:11:31.123+01:00`), with either `T`
+/// or a space between date and time. Timestamps without a timezone are taken to be in
+/// UTC, so clients have to convert local times before sending them; the app sends
+/// `toUtc().toIso8601String()`, which ends in `Z`. Anything else is rejected.
+pub fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let with_t = value.replacen(' ', "T", 1);
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(&with_t) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    NaiveDateTime::parse_from_str(&with_t, "%Y-%m-%dT%H:%M:%S%.f")
+        .map(|naive| naive.and_utc())
+        .map_err(|_| format!("'{}' is not an ISO 8601 timestamp", value))
+}
+
+/// Deserialize a timestamp using [`parse_timestamp`]
+/// Used by the models, so a missing timezone doesn't make a whole record unreadable.
+pub fn deserialize_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DateTime<Utc>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_timestamp(&value).map_err(serde::de::Error::custom)
+}
+
+pub fn deserialize_optional_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) => parse_timestamp(&value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+impl User {
+    /// Validate the user against the current time
+    /// Whether the signature matches depends on the user's key rotations, see
+    /// [`User::check_signature`].
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("username", check_line(&self.username, 1, MAX_USERNAME_LEN));
+        errors.check("status", check_line(&self.status, 0, MAX_STATUS_LEN));
+        errors.check("bio", check_text(&self.bio, 0, MAX_BIO_LEN));
+        errors.check("profile_picture", check_image_ref(&self.profile_picture));
+        errors.check(
+            "profile_picture_preview",
+            check_image_preview(&self.profile_picture_preview, &self.profile_picture),
+        );
+        errors.check("last_contact", check_timestamp(&self.last_contact, now));
+        errors.check("public_key", check_public_key(&self.uuid, &self.public_key));
+        errors.check("signature", check_signature(&self.signature));
+
+        errors.into_result()
+    }
+}
+
+impl Post {
+    /// Validate the post against the current time
+    /// Whether the author exists has to be checked against the store by the caller.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("user_id", check_uuid(&self.user_id));
+        errors.check("title", check_line(&self.title, 1, MAX_TITLE_LEN));
+        errors.check("body", check_text(&self.body, 0, MAX_BODY_LEN));
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        errors.check("image", check_image_ref(&self.image));
+        errors.check("thumbnail", check_image_ref(&self.thumbnail));
+        if self.thumbnail.is_some() && self.image.is_none() {
+            errors.check("thumbnail", Err("requires an image".to_string()));
+        }
+        errors.check(
+            "image_preview",
+            check_image_preview(&self.image_preview, &self.image),
+        );
+        errors.check("attachments", check_attachments(&self.attachments));
+        if let Some(totem) = &self.source_totem {
+            errors.check("source_totem", check_totem_id(totem));
+        }
+        match (self.revision, &self.edited_at) {
+            (0, None) => {}
+            (0, Some(_)) => errors.add("edited_at", "must be empty before the first edit"),
+            (_, None) => errors.add("edited_at", "is required for edits"),
+            (_, Some(edited_at)) => {
+                errors.check("edited_at", check_timestamp(edited_at, now));
+                if *edited_at < self.timestamp {
+                    errors.add("edited_at", "must not be before the post was written");
+                }
+            }
+        }
+        errors.check("signature", check_signature(&self.signature));
+
+        errors.into_result()
+    }
+}
+
+impl Reply {
+    /// Validate the reply against the current time
+    /// The post and reply it answers may arrive later, stores take it without them.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("user_id", check_uuid(&self.user_id));
+        errors.check("parent_id", check_uuid(&self.parent_id));
+        if self.parent_id == self.uuid {
+            errors.add("parent_id", "must not be the reply itself");
+        }
+        errors.check("root_id", check_uuid(&self.root_id));
+        errors.check("body", check_text(&self.body, 1, MAX_REPLY_LEN));
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        errors.check("signature", check_signature(&self.signature));
+
+        errors.into_result()
+    }
+}
+
+impl Reaction {
+    /// Validate the reaction against the current time
+    /// The post may arrive later, stores take reactions without it.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("user_id", check_uuid(&self.user_id));
+        errors.check("post_id", check_uuid(&self.post_id));
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        errors.check("signature", check_signature(&self.signature));
+
+        errors.into_result()
+    }
+}
+
+impl Tombstone {
+    /// Validate the tombstone against the current time
+    /// The post may arrive later, stores take tombstones without it.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("user_id", check_uuid(&self.user_id));
+        errors.check("post_id", check_uuid(&self.post_id));
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        // Stores would drop it right away
+        if self.timestamp + TOMBSTONE_TTL < now {
+            errors.add("timestamp", "lies beyond the time tombstones are kept");
+        }
+        errors.check("signature", check_signature(&self.signature));
+
+        errors.into_result()
+    }
+}
+
+impl Repost {
+    /// Validate the repost against the current time
+    /// The post may arrive later, stores take reposts without it.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("user_id", check_uuid(&self.user_id));
+        errors.check("post_id", check_uuid(&self.post_id));
+        errors.check("author_id", check_uuid(&self.author_id));
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        errors.check("signature", check_signature(&self.signature));
+
+        errors.into_result()
+    }
+
+    /// Reject a repost that doesn't attribute `post` to its author
+    pub fn check_original(&self, post: &Post) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.post_id != post.uuid {
+            errors.add("post_id", "isn't the reposted post");
+        }
+        if self.author_id != post.user_id {
+            errors.add("author_id", "isn't the author of the post");
+        }
+        errors.into_result()
+    }
+}
+
+impl Post {
+    /// Check the attachments against the size limits of a totem
+    pub fn check_attachment_limits(
+        &self,
+        limits: &AttachmentLimits,
+    ) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        for (i, attachment) in self.attachments.iter().enumerate() {
+            let max_size = limits.max_size(attachment.kind);
+            if attachment.size > max_size {
+                errors.add(
+                    "attachments",
+                    format!(
+                        "attachment {}: {} must not be larger than {} bytes",
+                        i,
+                        attachment.kind.as_str(),
+                        max_size
+                    ),
+                );
+            }
+        }
+
+        errors.into_result()
+    }
+}
+
+impl Totem {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_totem_id(&self.uuid));
+        errors.check("name", check_line(&self.name, 1, MAX_NAME_LEN));
+        errors.check("location", check_text(&self.location, 0, MAX_LOCATION_LEN));
+        errors.check("last_contact", check_timestamp(&self.last_contact, now));
+
+        errors.into_result()
+    }
+}
+
+impl LastSeen {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("user_id", check_uuid(&self.user_id));
+        errors.check("totem_id", check_totem_id(&self.totem_id));
+        errors.check("seen_at", check_timestamp(&self.seen_at, now));
+        // Nobody is seen ahead of time, and a future sighting would beat every real one
+        if !errors.has("seen_at") && self.seen_at > now + MAX_SIGHTING_SKEW {
+            errors.add("seen_at", "lies in the future");
+        }
+
+        errors.into_result()
+    }
+}
+
+impl KeyRotation {
+    /// Validate the rotation against the current time
+    /// Whether it fits the user's chain is checked by [`super::rotation::KeyChain::check`].
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("user_id", check_uuid(&self.user_id));
+        if !errors.has("user_id") && !is_derived_user_id(&self.user_id) {
+            errors.add("user_id", "users without a key can't rotate it");
+        }
+        errors.check(
+            "identity_key",
+            check_public_key(&self.user_id, &Some(self.identity_key.clone())),
+        );
+        if self.sequence == 0 {
+            errors.add("sequence", "must start at 1");
+        }
+        errors.check("new_key", check_key(&self.new_key));
+        if let Some(key) = &self.recovery_key {
+            errors.check("recovery_key", check_key(key));
+            if *key == self.new_key {
+                errors.add("recovery_key", "must differ from the new key");
+            }
+        }
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        errors.check(
+            "signature",
+            self.signature
+                .parse::<Signature>()
+                .map(|_| ())
+                .map_err(|e| format!("{}", e)),
+        );
+
+        errors.into_result()
+    }
+}
+
+impl Message {
+    /// Validate the envelope against the current time
+    /// Only the recipient can tell whether the text is valid, by decrypting it.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check("sender_id", check_uuid(&self.sender_id));
+        errors.check("recipient_id", check_uuid(&self.recipient_id));
+        errors.check("sender_key", check_key(&self.sender_key));
+        errors.check("recipient_key", check_key(&self.recipient_key));
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        errors.check(
+            "nonce",
+            check_hex(&self.nonce, MESSAGE_NONCE_LEN, MESSAGE_NONCE_LEN),
+        );
+        // Every character of the text takes up to 4 bytes in UTF-8
+        errors.check(
+            "ciphertext",
+            check_hex(
+                &self.ciphertext,
+                MESSAGE_TAG_LEN + 1,
+                MESSAGE_TAG_LEN + MAX_MESSAGE_LEN * 4,
+            ),
+        );
+
+        errors.into_result()
+    }
+}
+
+impl DeliveryReceipt {
+    /// Validate the receipt against the current time
+    /// Whether the recipient signed it is checked by [`DeliveryReceipt::check_signature`].
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        errors.check("message_uuid", check_uuid(&self.message_uuid));
+        errors.check("recipient_key", check_key(&self.recipient_key));
+        errors.check("timestamp", check_timestamp(&self.timestamp, now));
+        errors.check(
+            "signature",
+            self.signature
+                .parse::<Signature>()
+                .map(|_| ())
+                .map_err(|e| format!("{}", e)),
+        );
+
+        errors.into_result()
+    }
+}
+
+impl Bundle {
+    /// Validate the bundle and its content against the current time
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        self.validate_at(Utc::now())
+    }
+
+    pub fn validate_at(&self, now: DateTime<Utc>) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        let header = &self.header;
+
+        errors.check("uuid", check_uuid(&self.uuid));
+        errors.check(
+            "destination",
+            match &header.destination {
+                Destination::Totem(id) => check_totem_id(id),
+                Destination::User(id) => check_uuid(id),
+            },
+        );
+        errors.check("created", check_timestamp(&header.created, now));
+        if header.ttl_secs == 0 || i64::from(header.ttl_secs) > MAX_TTL.num_seconds() {
+            errors.add(
+                "ttl_secs",
+                format!("must be between 1 and {}", MAX_TTL.num_seconds()),
+            );
+        }
+        if header.hop_limit > MAX_HOP_LIMIT {
+            errors.add("hop_limit", format!("must be at most {}", MAX_HOP_LIMIT));
+        }
+        if header.hops > header.hop_limit {
+            errors.add("hops", "exceeds the hop limit");
+        }
+        if header.copies == 0 || header.copies > MAX_COPIES {
+            errors.add("copies", format!("must be between 1 and {}", MAX_COPIES));
+        }
+
+        let payload = match &self.payload {
+            BundlePayload::Post(post) => post.validate_at(now),
+            BundlePayload::Message(message) => message.validate_at(now),
+        };
+        if let Err(payload) = payload {
+            for e in payload.errors {
+                errors.add("payload", format!("{}: {}", e.field, e.message));
+            }
+        }
+
+        errors.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::Identity;
+    use crate::model::BundleHeader;
+
+    fn now() -> DateTime<Utc> {
+        parse_timestamp("2025-12-14T12:00:00Z").unwrap()
+    }
+
+    fn post() -> Post {
+        Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            title: "First Post".to_string(),
+            body: "This is the first post\nwith two lines".to_string(),
+            timestamp: now(),
+            image: None,
+            source_totem: Some("Totem-7F3K9Q2M".to_string()),
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        }
+    }
+
+    fn user() -> User {
+        User {
+            uuid: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            username: "alice".to_string(),
+            status: "".to_string(),
+            bio: "".to_string(),
+            profile_picture: None,
+            last_contact: now(),
+            profile_picture_preview: None,
+            public_key: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_records() {
+        assert_eq!(post().validate_at(now()), Ok(()));
+        assert_eq!(user().validate_at(now()), Ok(()));
+    }
+
+    #[test]
+    fn test_reply() {
+        let reply = Reply {
+            uuid: "123e4567-e89b-12d3-a456-426614174001".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            parent_id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            root_id: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            body: "Nice one".to_string(),
+            timestamp: now(),
+            signature: None,
+        };
+        assert_eq!(reply.validate_at(now()), Ok(()));
+
+        let invalid = Reply {
+            parent_id: reply.uuid.clone(),
+            root_id: "not a uuid".to_string(),
+            body: "".to_string(),
+            ..reply.clone()
+        };
+        let errors = invalid.validate_at(now()).unwrap_err();
+        assert!(errors.has("parent_id"));
+        assert!(errors.has("root_id"));
+        assert!(errors.has("body"));
+
+        let long = Reply {
+            body: "x".repeat(MAX_REPLY_LEN + 1),
+            ..reply
+        };
+        assert!(long.validate_at(now()).unwrap_err().has("body"));
+    }
+
+    #[test]
+    fn test_tombstone() {
+        let tombstone = Tombstone::of(
+            &post(),
+            "123e4567-e89b-12d3-a456-426614174001".to_string(),
+            now(),
+        );
+        assert_eq!(tombstone.validate_at(now()), Ok(()));
+
+        let expired = Tombstone {
+            timestamp: now() - TOMBSTONE_TTL - TimeDelta::days(1),
+            ..tombstone.clone()
+        };
+        assert!(expired.validate_at(now()).unwrap_err().has("timestamp"));
+
+        let invalid = Tombstone {
+            post_id: "not a uuid".to_string(),
+            ..tombstone
+        };
+        assert!(invalid.validate_at(now()).unwrap_err().has("post_id"));
+    }
+
+    #[test]
+    fn test_last_seen() {
+        let seen = LastSeen {
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            totem_id: "Totem-7F3K9Q2M".to_string(),
+            seen_at: now() + MAX_SIGHTING_SKEW,
+            signature: None,
+        };
+        assert_eq!(seen.validate_at(now()), Ok(()));
+
+        let ahead = LastSeen {
+            seen_at: now() + MAX_SIGHTING_SKEW + TimeDelta::minutes(1),
+            ..seen
+        };
+        assert!(ahead.validate_at(now()).unwrap_err().has("seen_at"));
+    }
+
+    #[test]
+    fn test_repost() {
+        let original = post();
+        let repost = Repost {
+            uuid: "123e4567-e89b-12d3-a456-426614174001".to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440001".to_string(),
+            post_id: original.uuid.clone(),
+            author_id: original.user_id.clone(),
+            timestamp: now(),
+            signature: None,
+        };
+        assert_eq!(repost.validate_at(now()), Ok(()));
+        assert_eq!(repost.check_original(&original), Ok(()));
+
+        let misattributed = Repost {
+            author_id: repost.user_id.clone(),
+            ..repost.clone()
+        };
+        assert_eq!(misattributed.validate_at(now()), Ok(()));
+        assert!(
+            misattributed
+                .check_original(&original)
+                .unwrap_err()
+                .has("author_id")
+        );
+
+        let invalid = Repost {
+            post_id: "not a uuid".to_string(),
+            ..repost
+        };
+        assert!(invalid.validate_at(now()).unwrap_err().has("post_id"));
+    }
+
+    #[test]
+    fn test_invalid_post_reports_every_field() {
+        let post = Post {
+            uuid: "123E4567-E89B-12D3-A456-426614174000".to_string(),
+            user_id: "not-a-uuid".to_string(),
+            title: "".to_string(),
+            body: "x".repeat(MAX_BODY_LEN + 1),
+            timestamp: now() + TimeDelta::days(365 * 3),
+            image: Some("".to_string()),
+            source_totem: Some("../../etc".to_string()),
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+
+        let errors = post.validate_at(now()).unwrap_err();
+        for field in [
+            "uuid",
+            "user_id",
+            "title",
+            "body",
+            "timestamp",
+            "image",
+            "source_totem",
+        ] {
+            assert!(errors.has(field), "expected error for {}", field);
+        }
+        assert_eq!(errors.errors.len(), 7);
+    }
+
+    #[test]
+    fn test_thumbnail_requires_image() {
+        let mut post = post();
+        post.thumbnail = Some("thumbnail".to_string());
+        assert!(post.validate_at(now()).unwrap_err().has("thumbnail"));
+
+        post.image = Some("image".to_string());
+        assert_eq!(post.validate_at(now()), Ok(()));
+    }
+
+    #[test]
+    fn test_image_preview() {
+        let preview = ImagePreview {
+            blurhash: "LEHV6nWB2yk8pyo0adR*.7kCMdnj".to_string(),
+            width: 1600,
+            height: 1067,
+        };
+        let mut post = post();
+        post.image_preview = Some(preview.clone());
+        assert!(post.validate_at(now()).unwrap_err().has("image_preview"));
+
+        post.image = Some("image".to_string());
+        assert_eq!(post.validate_at(now()), Ok(()));
+
+        for blurhash in [
+            "",
+            "LEHV6nWB2yk8pyo0adR*.7kCMdn",
+            "LEHV6nWB2yk8pyo0adR*.7kCMdn\"",
+        ] {
+            assert!(check_blurhash(blurhash).is_err(), "{:?}", blurhash);
+        }
+        post.image_preview = Some(ImagePreview {
+            width: 0,
+            ..preview
+        });
+        assert!(post.validate_at(now()).unwrap_err().has("image_preview"));
+    }
+
+    #[test]
+    fn test_public_key() {
+        let identity = Identity::generate();
+        let mut user = user();
+        user.uuid = identity.user_id();
+        assert!(user.validate_at(now()).unwrap_err().has("public_key"));
+
+        user.public_key = Some(identity.public_key().to_string());
+        assert_eq!(user.validate_at(now()), Ok(()));
+        assert!(user.check_signature(&[]).unwrap_err().has("signature"));
+        user.sign(&identity);
+        assert_eq!(user.validate_at(now()), Ok(()));
+        assert_eq!(user.check_signature(&[]), Ok(()));
+
+        user.bio = "altered".to_string();
+        assert!(user.check_signature(&[]).unwrap_err().has("signature"));
+        user.signature = Some("not a signature".to_string());
+        assert!(user.validate_at(now()).unwrap_err().has("signature"));
+
+        // Someone else's key can't claim the ID
+        user.public_key = Some(Identity::generate().public_key().to_string());
+        assert!(user.validate_at(now()).unwrap_err().has("public_key"));
+
+        user.public_key = Some("not a key".to_string());
+        assert!(user.validate_at(now()).unwrap_err().has("public_key"));
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let identity = Identity::generate();
+        let next = Identity::generate();
+        let mut rotation = KeyRotation {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: identity.user_id(),
+            identity_key: identity.public_key().to_string(),
+            sequence: 1,
+            kind: crate::model::RotationKind::Endorsement,
+            new_key: next.public_key().to_string(),
+            recovery_key: None,
+            timestamp: now(),
+            signature: String::new(),
+        };
+        assert!(rotation.validate_at(now()).unwrap_err().has("signature"));
+        rotation.sign(&identity);
+        assert_eq!(rotation.validate_at(now()), Ok(()));
+
+        rotation.recovery_key = Some(rotation.new_key.clone());
+        rotation.sequence = 0;
+        let errors = rotation.validate_at(now()).unwrap_err();
+        assert!(errors.has("recovery_key"));
+        assert!(errors.has("sequence"));
+
+        // Legacy users have no key to rotate
+        rotation.user_id = user().uuid;
+        rotation.new_key = "not a key".to_string();
+        let errors = rotation.validate_at(now()).unwrap_err();
+        assert!(errors.has("user_id"));
+        assert!(errors.has("identity_key"));
+        assert!(errors.has("new_key"));
+    }
+
+    #[test]
+    fn test_message() {
+        let sender = Identity::generate();
+        let recipient = Identity::generate();
+        let mut message = Message {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            sender_id: sender.user_id(),
+            recipient_id: recipient.user_id(),
+            sender_key: sender.public_key().to_string(),
+            recipient_key: recipient.public_key().to_string(),
+            timestamp: now(),
+            nonce: "ab".repeat(MESSAGE_NONCE_LEN),
+            ciphertext: "cd".repeat(MESSAGE_TAG_LEN + 5),
+        };
+        assert_eq!(message.validate_at(now()), Ok(()));
+
+        message.nonce = "ab".repeat(12);
+        message.ciphertext = "cd".repeat(MESSAGE_TAG_LEN);
+        message.recipient_key = "not a key".to_string();
+        let errors = message.validate_at(now()).unwrap_err();
+        assert!(errors.has("nonce"));
+        assert!(errors.has("ciphertext"));
+        assert!(errors.has("recipient_key"));
+
+        assert!(check_hex("ABCD", 0, 10).is_err());
+        assert!(check_hex("abc", 0, 10).is_err());
+    }
+
+    #[test]
+    fn test_bundle() {
+        let identity = Identity::generate();
+        let post = Post {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            user_id: identity.user_id(),
+            title: "Lost cat".to_string(),
+            body: "Seen near the fountain?".to_string(),
+            timestamp: now(),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        };
+        let mut bundle = Bundle {
+            uuid: "223e4567-e89b-12d3-a456-426614174000".to_string(),
+            header: BundleHeader::new(Destination::User(identity.user_id()), now()),
+            payload: BundlePayload::Post(post),
+        };
+        assert_eq!(bundle.validate_at(now()), Ok(()));
+
+        bundle.header.copies = 0;
+        bundle.header.hops = bundle.header.hop_limit + 1;
+        bundle.header.destination = Destination::User("nope".to_string());
+        if let BundlePayload::Post(post) = &mut bundle.payload {
+            post.title = String::new();
+        }
+        let errors = bundle.validate_at(now()).unwrap_err();
+        assert!(errors.has("copies"));
+        assert!(errors.has("hops"));
+        assert!(errors.has("destination"));
+        assert!(errors.has("payload"));
+    }
+
+    fn clip() -> Attachment {
+        Attachment {
+            kind: AttachmentKind::Clip,
+            mime: "video/mp4".to_string(),
+            hash: "ab".repeat(32),
+            size: 3 * 1024 * 1024,
+            duration_ms: Some(12_000),
+            width: Some(720),
+            height: Some(1280),
+            alt_text: Some("Sunset at the lake".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_attachments() {
+        let mut post = post();
+        post.attachments = vec![clip()];
+        assert_eq!(post.validate_at(now()), Ok(()));
+
+        let invalid = [
+            Attachment {
+                hash: "not-a-hash".to_string(),
+                ..clip()
+            },
+            Attachment {
+                mime: "audio/mp4".to_string(),
+                ..clip()
+            },
+            Attachment {
+                mime: "video".to_string(),
+                ..clip()
+            },
+            Attachment { size: 0, ..clip() },
+            Attachment {
+                kind: AttachmentKind::File,
+                ..clip()
+            },
+            Attachment {
+                duration_ms: Some(MAX_DURATION_MS + 1),
+                ..clip()
+            },
+            Attachment {
+                width: None,
+                ..clip()
+            },
+            Attachment {
+                alt_text: Some("x".repeat(MAX_ALT_TEXT_LEN + 1)),
+                ..clip()
+            },
+        ];
+        for attachment in invalid {
+            post.attachments = vec![clip(), attachment.clone()];
+            let errors = post.validate_at(now()).unwrap_err();
+            assert!(
+                errors.errors[0].message.starts_with("attachment 1:"),
+                "{:?}",
+                attachment
+            );
+        }
+
+        post.attachments = vec![clip(); MAX_ATTACHMENTS + 1];
+        assert!(post.validate_at(now()).unwrap_err().has("attachments"));
+    }
+
+    #[test]
+    fn test_attachment_limits() {
+        let mut post = post();
+        post.attachments = vec![clip()];
+        let limits = AttachmentLimits::default();
+        assert_eq!(post.check_attachment_limits(&limits), Ok(()));
+
+        let strict = AttachmentLimits {
+            clip: 1024 * 1024,
+            ..limits
+        };
+        assert!(
+            post.check_attachment_limits(&strict)
+                .unwrap_err()
+                .has("attachments")
+        );
+        assert_eq!(strict.largest(), limits.file);
+    }
+
+    #[test]
+    fn test_text_limits_count_characters() {
+        assert!(check_line(&"ü".repeat(MAX_USERNAME_LEN), 1, MAX_USERNAME_LEN).is_ok());
+        assert!(check_line(&"ü".repeat(MAX_USERNAME_LEN + 1), 1, MAX_USERNAME_LEN).is_err());
+        assert!(check_line(" alice", 1, MAX_USERNAME_LEN).is_err());
+        assert!(check_line("ali\nce", 1, MAX_USERNAME_LEN).is_err());
+        assert!(check_text("a\u{0}b", 0, 10).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_bounds() {
+        assert!(check_timestamp(&(now() + TimeDelta::hours(23)), now()).is_ok());
+        assert!(check_timestamp(&(now() + TimeDelta::hours(25)), now()).is_err());
+        assert!(check_timestamp(&DateTime::<Utc>::default(), now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = parse_timestamp("2025-12-14T13:11:31.250Z").unwrap();
+
+        assert_eq!(
+            parse_timestamp("2025-12-14T14:11:31.250+01:00"),
+            Ok(expected)
+        );
+        assert_eq!(parse_timestamp("2025-12-14 13:11:31.250Z"), Ok(expected));
+        // Without a timezone the timestamp is read as UTC
+        assert_eq!(parse_timestamp("2025-12-14T13:11:31.250"), Ok(expected));
+        assert_eq!(
+            parse_timestamp("2025-12-14T13:11:31"),
+            Ok(expected - TimeDelta::milliseconds(250))
+        );
+
+        assert!(parse_timestamp("2025-12-14").is_err());
+        assert!(parse_timestamp("yesterday").is_err());
+        assert!(parse_timestamp("").is_err());
+    }
+}
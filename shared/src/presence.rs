@@ -0,0 +1,225 @@
+//! Presence information: when users were last seen at which totem
+//!
+//! Every sighting is a [`LastSeen`] record for a pair of user and totem. Phones and
+//! totems exchange these records and only ever keep the newest one per pair, so the
+//! stores converge no matter in which order the records arrive.
+//!
+//! A totem records a sighting when a user syncs with it. Users with a key sign their
+//! sightings, so nobody else can place them at a totem, see [`LastSeen::check_signature`].
+
+use chrono::{DateTime, TimeDelta, Utc};
+use std::collections::HashMap;
+
+use crate::model::LastSeen;
+
+/// How far the time of a sighting may be off from the clock of the totem recording it
+/// Sightings relayed later may lie at most this far in the future.
+pub const MAX_SIGHTING_SKEW: TimeDelta = TimeDelta::minutes(15);
+
+/// Newest sighting per user and totem
+#[derive(Debug, Default)]
+pub struct PresenceTable {
+    entries: HashMap<(String, String), LastSeen>,
+}
+
+impl PresenceTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of user/totem pairs in the table
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Merge a sighting into the table
+    /// Returns true if it was newer than what the table knew about the pair.
+    pub fn merge(&mut self, entry: LastSeen) -> bool {
+        match self
+            .entries
+            .get_mut(&(entry.user_id.clone(), entry.totem_id.clone()))
+        {
+            Some(known) if known.seen_at >= entry.seen_at => false,
+            Some(known) => {
+                *known = entry;
+                true
+            }
+            None => {
+                self.entries
+                    .insert((entry.user_id.clone(), entry.totem_id.clone()), entry);
+                true
+            }
+        }
+    }
+
+    /// Merge several sightings, returning the ones that changed the table
+    pub fn merge_all<I: IntoIterator<Item = LastSeen>>(&mut self, entries: I) -> Vec<LastSeen> {
+        entries
+            .into_iter()
+            .filter(|entry| self.merge(entry.clone()))
+            .collect()
+    }
+
+    /// The most recent sighting of a user, at any totem
+    pub fn latest_for_user(&self, user_id: &str) -> Option<LastSeen> {
+        self.entries
+            .values()
+            .filter(|entry| entry.user_id == user_id)
+            .max_by_key(|entry| entry.seen_at)
+            .cloned()
+    }
+
+    /// All sightings of the given users, newest first
+    pub fn for_users(&self, user_ids: &[String]) -> Vec<LastSeen> {
+        self.collect(|entry| user_ids.contains(&entry.user_id))
+    }
+
+    /// Users that were seen at a totem since the given time, newest first
+    pub fn recent_visitors(&self, totem_id: &str, since: DateTime<Utc>) -> Vec<LastSeen> {
+        self.collect(|entry| entry.totem_id == totem_id && entry.seen_at >= since)
+    }
+
+    /// All sightings, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = LastSeen> + '_ {
+        self.entries.values().cloned()
+    }
+
+    fn collect<F>(&self, filter: F) -> Vec<LastSeen>
+    where
+        F: Fn(&LastSeen) -> bool,
+    {
+        let mut entries: Vec<_> = self
+            .entries
+            .values()
+            .filter(|entry| filter(entry))
+            .cloned()
+            .collect();
+
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.seen_at));
+        entries
+    }
+}
+
+impl FromIterator<LastSeen> for PresenceTable {
+    fn from_iter<I: IntoIterator<Item = LastSeen>>(iter: I) -> Self {
+        let mut table = PresenceTable::new();
+        for entry in iter {
+            table.merge(entry);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn seen(user: &str, totem: &str, minutes_ago: i64) -> LastSeen {
+        let base = DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        LastSeen {
+            user_id: user.to_string(),
+            totem_id: totem.to_string(),
+            seen_at: base - TimeDelta::minutes(minutes_ago),
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_newest() {
+        let mut table = PresenceTable::new();
+
+        assert!(table.merge(seen("alice", "totem-a", 30)));
+        assert!(table.merge(seen("alice", "totem-a", 10)));
+        assert!(!table.merge(seen("alice", "totem-a", 20)));
+        assert!(!table.merge(seen("alice", "totem-a", 10)));
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(
+            table.latest_for_user("alice"),
+            Some(seen("alice", "totem-a", 10))
+        );
+
+        // The whole record is kept, so its signature can be passed on
+        let signed = LastSeen {
+            signature: Some("signature".to_string()),
+            ..seen("alice", "totem-a", 5)
+        };
+        assert!(table.merge(signed.clone()));
+        assert_eq!(table.latest_for_user("alice"), Some(signed));
+    }
+
+    #[test]
+    fn test_merge_order_independent() {
+        let entries = [
+            seen("alice", "totem-a", 30),
+            seen("bob", "totem-a", 5),
+            seen("alice", "totem-b", 15),
+            seen("alice", "totem-a", 1),
+            seen("bob", "totem-a", 50),
+        ];
+
+        let forward: PresenceTable = entries.iter().cloned().collect();
+        let backward: PresenceTable = entries.iter().rev().cloned().collect();
+
+        let mut forward: Vec<_> = forward.iter().collect();
+        let mut backward: Vec<_> = backward.iter().collect();
+        forward.sort_by(|a, b| (&a.user_id, &a.totem_id).cmp(&(&b.user_id, &b.totem_id)));
+        backward.sort_by(|a, b| (&a.user_id, &a.totem_id).cmp(&(&b.user_id, &b.totem_id)));
+
+        assert_eq!(forward, backward);
+        assert_eq!(forward.len(), 3);
+    }
+
+    #[test]
+    fn test_merge_all_returns_changes() {
+        let mut table: PresenceTable = [seen("alice", "totem-a", 10)].into_iter().collect();
+
+        let changed = table.merge_all([
+            seen("alice", "totem-a", 20),
+            seen("alice", "totem-a", 5),
+            seen("bob", "totem-a", 20),
+        ]);
+
+        assert_eq!(
+            changed,
+            [seen("alice", "totem-a", 5), seen("bob", "totem-a", 20)]
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let table: PresenceTable = [
+            seen("alice", "totem-a", 120),
+            seen("alice", "totem-b", 10),
+            seen("bob", "totem-a", 30),
+            seen("carol", "totem-a", 5),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(table.latest_for_user("alice").unwrap().totem_id, "totem-b");
+        assert_eq!(table.latest_for_user("dave"), None);
+
+        let since = seen("", "", 60).seen_at;
+        let visitors: Vec<_> = table
+            .recent_visitors("totem-a", since)
+            .into_iter()
+            .map(|e| e.user_id)
+            .collect();
+        assert_eq!(visitors, ["carol", "bob"]);
+
+        let friends = table.for_users(&["alice".to_string()]);
+        assert_eq!(
+            friends,
+            [seen("alice", "totem-b", 10), seen("alice", "totem-a", 120)]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Reactions to posts, counted without coordination
+//!
+//! Each user's reaction of one kind to one post is a last-writer-wins register. Of all
+//! the [`Reaction`] records for the same user, post and kind, the one with the latest
+//! timestamp counts, ties are broken by UUID. Stores only keep that record. Which one
+//! wins doesn't depend on the order records arrive in, so every store that has seen the
+//! same records ends up with the same counts.
+//!
+//! Taking a reaction back is a record like adding it, with `active` unset. Superseded
+//! records are dropped, so a store never hands out a reaction that doesn't count anymore.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::model::{Reaction, ReactionKind};
+
+/// Max number of reactions in a single request or response between a phone and a totem
+pub const MAX_REACTIONS_PER_REQUEST: usize = 100;
+
+impl Reaction {
+    /// Whether this record wins over another one for the same user, post and kind
+    pub fn supersedes(&self, other: &Reaction) -> bool {
+        (self.timestamp, &self.uuid) > (other.timestamp, &other.uuid)
+    }
+}
+
+/// Timestamp for a new reaction that supersedes `previous`, even if the clock went back
+pub fn next_timestamp(previous: Option<&Reaction>, now: DateTime<Utc>) -> DateTime<Utc> {
+    match previous {
+        Some(previous) if previous.timestamp >= now => {
+            previous.timestamp + TimeDelta::milliseconds(1)
+        }
+        _ => now,
+    }
+}
+
+/// Number of active reactions to a post
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReactionCounts {
+    pub likes: u32,
+    pub shares: u32,
+}
+
+impl ReactionCounts {
+    pub fn add(&mut self, kind: ReactionKind) {
+        match kind {
+            ReactionKind::Like => self.likes += 1,
+            ReactionKind::Share => self.shares += 1,
+        }
+    }
+}
+
+/// The winning reaction per user, post and kind
+#[derive(Debug, Default)]
+pub struct ReactionSet {
+    entries: HashMap<(String, String, ReactionKind), Reaction>,
+}
+
+impl ReactionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of user/post/kind registers in the set
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Merge a reaction into the set
+    /// Returns true if it won over what the set knew, its signature has to be checked before.
+    pub fn merge(&mut self, reaction: Reaction) -> bool {
+        let key = (
+            reaction.user_id.clone(),
+            reaction.post_id.clone(),
+            reaction.kind,
+        );
+        match self.entries.get_mut(&key) {
+            Some(current) if !reaction.supersedes(current) => false,
+            Some(current) => {
+                *current = reaction;
+                true
+            }
+            None => {
+                self.entries.insert(key, reaction);
+                true
+            }
+        }
+    }
+
+    /// Merge several reactions, returning the ones that changed the set
+    pub fn merge_all<I: IntoIterator<Item = Reaction>>(&mut self, reactions: I) -> Vec<Reaction> {
+        reactions
+            .into_iter()
+            .filter(|reaction| self.merge(reaction.clone()))
+            .collect()
+    }
+
+    /// Whether the reaction is the one that counts for its user, post and kind
+    pub fn holds(&self, uuid: &str) -> bool {
+        self.entries.values().any(|reaction| reaction.uuid == uuid)
+    }
+
+    /// The reaction that counts for a user, post and kind
+    pub fn get(&self, user_id: &str, post_id: &str, kind: ReactionKind) -> Option<&Reaction> {
+        self.entries
+            .get(&(user_id.to_string(), post_id.to_string(), kind))
+    }
+
+    /// Whether the user currently reacts to the post this way
+    pub fn has_reacted(&self, user_id: &str, post_id: &str, kind: ReactionKind) -> bool {
+        self.get(user_id, post_id, kind)
+            .is_some_and(|reaction| reaction.active)
+    }
+
+    pub fn counts(&self, post_id: &str) -> ReactionCounts {
+        let mut counts = ReactionCounts::default();
+        for reaction in self.entries.values() {
+            if reaction.post_id == post_id && reaction.active {
+                counts.add(reaction.kind);
+            }
+        }
+        counts
+    }
+
+    /// All winning reactions, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Reaction> + '_ {
+        self.entries.values()
+    }
+}
+
+impl FromIterator<Reaction> for ReactionSet {
+    fn from_iter<I: IntoIterator<Item = Reaction>>(iter: I) -> Self {
+        let mut set = ReactionSet::new();
+        for reaction in iter {
+            set.merge(reaction);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POST: &str = "123e4567-e89b-12d3-a456-426614174000";
+
+    fn reaction(uuid: &str, user: &str, kind: ReactionKind, active: bool, secs: i64) -> Reaction {
+        Reaction {
+            uuid: uuid.to_string(),
+            user_id: user.to_string(),
+            post_id: POST.to_string(),
+            kind,
+            active,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            signature: None,
+        }
+    }
+
+    fn history() -> Vec<Reaction> {
+        vec![
+            reaction("a1", "alice", ReactionKind::Like, true, 0),
+            reaction("a2", "alice", ReactionKind::Like, false, 10),
+            reaction("a3", "alice", ReactionKind::Like, true, 20),
+            reaction("b1", "bob", ReactionKind::Like, true, 5),
+            reaction("b2", "bob", ReactionKind::Like, false, 15),
+            reaction("b3", "bob", ReactionKind::Share, true, 15),
+            // Same time, the higher UUID wins
+            reaction("c2", "carol", ReactionKind::Share, false, 30),
+            reaction("c1", "carol", ReactionKind::Share, true, 30),
+        ]
+    }
+
+    #[test]
+    fn test_converges_in_any_order() {
+        let expected = ReactionCounts {
+            likes: 1,
+            shares: 1,
+        };
+
+        let mut reactions = history();
+        for rotation in 0..reactions.len() {
+            reactions.rotate_left(rotation);
+            let forward: ReactionSet = reactions.iter().cloned().collect();
+            let backward: ReactionSet = reactions.iter().rev().cloned().collect();
+            assert_eq!(forward.counts(POST), expected);
+            assert_eq!(backward.counts(POST), expected);
+        }
+
+        let set: ReactionSet = history().into_iter().collect();
+        assert!(set.has_reacted("alice", POST, ReactionKind::Like));
+        assert!(!set.has_reacted("bob", POST, ReactionKind::Like));
+        assert!(!set.has_reacted("carol", POST, ReactionKind::Share));
+        assert!(set.holds("c2"));
+        assert!(!set.holds("a1"));
+        assert_eq!(set.len(), 4);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut set = ReactionSet::new();
+        let like = reaction("a1", "alice", ReactionKind::Like, true, 0);
+        let unlike = reaction("a2", "alice", ReactionKind::Like, false, 10);
+        assert!(set.merge(unlike.clone()));
+        assert!(!set.merge(like.clone()));
+        assert!(!set.merge(unlike.clone()));
+        assert_eq!(set.counts(POST), ReactionCounts::default());
+
+        let changed = set.merge_all([like, reaction("a3", "alice", ReactionKind::Like, true, 20)]);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(set.counts(POST).likes, 1);
+        assert_eq!(
+            set.counts("223e4567-e89b-12d3-a456-426614174000"),
+            ReactionCounts::default()
+        );
+    }
+
+    #[test]
+    fn test_next_timestamp() {
+        let previous = reaction("a1", "alice", ReactionKind::Like, true, 100);
+        let earlier = previous.timestamp - TimeDelta::seconds(5);
+        let later = previous.timestamp + TimeDelta::seconds(5);
+
+        assert_eq!(next_timestamp(None, earlier), earlier);
+        assert_eq!(next_timestamp(Some(&previous), later), later);
+        // A clock that went back still makes a reaction that wins
+        let next = next_timestamp(Some(&previous), earlier);
+        assert!(next > previous.timestamp);
+    }
+}
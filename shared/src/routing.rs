@@ -0,0 +1,368 @@
+//! Delay-tolerant routing of bundles through phones
+//!
+//! A [`Bundle`] is content addressed to a totem or a user. Phones in courier mode carry
+//! bundles from totem to totem, totems keep them in custody until the next phone comes
+//! by, until one reaches the destination.
+//!
+//! Forwarding follows binary spray-and-wait. A bundle starts out with a budget of copies.
+//! A custodian that meets a node that doesn't carry the bundle yet hands it half of its
+//! copies and keeps the rest. A custodian left with a single copy waits until it meets the
+//! destination. Copies of one bundle that meet again are merged. The number of copies in
+//! the network never grows, and every copy is dropped once the bundle expires or its hop
+//! limit is reached.
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::model::{Bundle, BundleHeader, Destination};
+
+/// Copies a new bundle starts out with
+/// Enough for a path through four custodians, each one taking half
+pub const DEFAULT_COPIES: u32 = 16;
+pub const MAX_COPIES: u32 = 64;
+
+pub const DEFAULT_HOP_LIMIT: u8 = 8;
+pub const MAX_HOP_LIMIT: u8 = 16;
+
+pub const DEFAULT_TTL: TimeDelta = TimeDelta::days(7);
+pub const MAX_TTL: TimeDelta = TimeDelta::days(30);
+
+/// Max size of the bundles of a single request or response between a phone and a totem
+/// Holds at least one bundle of the largest kind.
+pub const MAX_BUNDLE_BODY: usize = 64 * 1024;
+
+/// A node a custodian meets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node<'a> {
+    Totem(&'a str),
+    Phone {
+        /// `None` for a phone without an identity
+        user: Option<&'a str>,
+        /// Whether the phone carries bundles for others, otherwise it only takes its own
+        courier: bool,
+    },
+}
+
+/// What a custodian does with a bundle when it meets another node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handover {
+    /// The node is the destination, it gets every copy
+    Deliver,
+    /// The node gets this many copies
+    Spray { copies: u32 },
+    /// The custodian keeps the bundle
+    Wait,
+}
+
+impl BundleHeader {
+    /// A header for new content, with the default budget
+    pub fn new(destination: Destination, now: DateTime<Utc>) -> Self {
+        BundleHeader {
+            destination,
+            created: now,
+            ttl_secs: DEFAULT_TTL.num_seconds() as u32,
+            hop_limit: DEFAULT_HOP_LIMIT,
+            hops: 0,
+            copies: DEFAULT_COPIES,
+        }
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.created + TimeDelta::seconds(self.ttl_secs.into())
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at() < now
+    }
+
+    /// Whether `node` is the destination
+    pub fn is_for(&self, node: Node) -> bool {
+        match (&self.destination, node) {
+            (Destination::Totem(id), Node::Totem(totem)) => id == totem,
+            (
+                Destination::User(id),
+                Node::Phone {
+                    user: Some(user), ..
+                },
+            ) => id == user,
+            _ => false,
+        }
+    }
+
+    /// What to do when meeting `next`, which doesn't carry the bundle yet
+    pub fn handover(&self, next: Node, now: DateTime<Utc>) -> Handover {
+        if self.is_expired(now) {
+            return Handover::Wait;
+        }
+        if self.is_for(next) {
+            return Handover::Deliver;
+        }
+        if matches!(next, Node::Phone { courier: false, .. }) {
+            return Handover::Wait;
+        }
+        if self.copies < 2 || self.hops >= self.hop_limit {
+            return Handover::Wait;
+        }
+        Handover::Spray {
+            copies: self.copies / 2,
+        }
+    }
+}
+
+impl Bundle {
+    /// Hand the bundle to `next` if the rules say so
+    /// Returns what `next` gets, the copies handed out are taken from this bundle. A bundle
+    /// left without copies was delivered and is dropped by the custodian.
+    pub fn hand_to(&mut self, next: Node, now: DateTime<Utc>) -> Option<Bundle> {
+        let copies = match self.header.handover(next, now) {
+            Handover::Deliver => self.header.copies,
+            Handover::Spray { copies } => copies,
+            Handover::Wait => return None,
+        };
+
+        self.header.copies -= copies;
+        let mut handed = self.clone();
+        handed.header.copies = copies;
+        handed.header.hops += 1;
+        Some(handed)
+    }
+
+    /// Merge another copy of the bundle that reached the same custodian
+    pub fn merge(&mut self, other: Bundle) {
+        self.header.copies = (self.header.copies + other.header.copies).min(MAX_COPIES);
+        self.header.hops = self.header.hops.min(other.header.hops);
+    }
+
+    /// Whether the custodian should drop the bundle
+    pub fn is_spent(&self, now: DateTime<Utc>) -> bool {
+        self.header.copies == 0
+            || self.header.is_expired(now)
+            || self.header.hops > self.header.hop_limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{BundlePayload, Post};
+    use std::collections::HashMap;
+
+    fn courier(user: Option<&str>) -> Node<'_> {
+        Node::Phone {
+            user,
+            courier: true,
+        }
+    }
+
+    fn bundle(destination: Destination, now: DateTime<Utc>) -> Bundle {
+        Bundle {
+            uuid: "123e4567-e89b-12d3-a456-426614174000".to_string(),
+            header: BundleHeader::new(destination, now),
+            payload: BundlePayload::Post(Post {
+                uuid: "223e4567-e89b-12d3-a456-426614174000".to_string(),
+                user_id: "323e4567-e89b-12d3-a456-426614174000".to_string(),
+                title: "Lost cat".to_string(),
+                body: "Seen near the fountain?".to_string(),
+                timestamp: now,
+                image: None,
+                source_totem: None,
+                thumbnail: None,
+                image_preview: None,
+                attachments: Vec::new(),
+                revision: 0,
+                edited_at: None,
+                signature: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_spray_and_wait() {
+        let now = Utc::now();
+        let mut carried = bundle(Destination::Totem("far".to_string()), now);
+
+        // Half of the copies go to every node met on the way
+        let handed = carried.hand_to(Node::Totem("near"), now).unwrap();
+        assert_eq!((carried.header.copies, handed.header.copies), (8, 8));
+        assert_eq!(handed.header.hops, 1);
+
+        // Phones that aren't couriers only take their own bundles
+        let bystander = Node::Phone {
+            user: Some("someone"),
+            courier: false,
+        };
+        assert_eq!(carried.hand_to(bystander, now), None);
+        carried.hand_to(courier(None), now).unwrap();
+        carried.hand_to(Node::Totem("nearer"), now).unwrap();
+        carried.hand_to(courier(Some("someone")), now).unwrap();
+        assert_eq!(carried.header.copies, 1);
+
+        // The last copy waits for the destination
+        assert_eq!(carried.hand_to(Node::Totem("near"), now), None);
+        let delivered = carried.hand_to(Node::Totem("far"), now).unwrap();
+        assert_eq!(delivered.header.copies, 1);
+        assert!(carried.is_spent(now));
+    }
+
+    #[test]
+    fn test_limits() {
+        let now = Utc::now();
+        let mut carried = bundle(Destination::User("alice".to_string()), now);
+        carried.header.hops = carried.header.hop_limit;
+        assert_eq!(
+            carried.header.handover(Node::Totem("t"), now),
+            Handover::Wait
+        );
+        // The destination is reached no matter the hops
+        assert_eq!(
+            carried.header.handover(courier(Some("alice")), now),
+            Handover::Deliver
+        );
+
+        let later = now + DEFAULT_TTL + TimeDelta::seconds(1);
+        assert_eq!(
+            carried.header.handover(courier(Some("alice")), later),
+            Handover::Wait
+        );
+        assert!(carried.is_spent(later));
+
+        let mut other = carried.clone();
+        other.header.hops = 2;
+        carried.merge(other);
+        assert_eq!(
+            (carried.header.copies, carried.header.hops),
+            (2 * DEFAULT_COPIES, 2)
+        );
+    }
+
+    /// Custodians in the simulation, keyed by bundle
+    type Custody = HashMap<String, Bundle>;
+
+    /// A phone syncing with a totem, the way the app and the firmware go about it
+    fn sync(
+        phone: &mut Custody,
+        user: &str,
+        totem: &mut Custody,
+        totem_id: &str,
+        now: DateTime<Utc>,
+    ) -> Vec<Bundle> {
+        let mut delivered = Vec::new();
+
+        // The totem hands out bundles the phone doesn't carry
+        for bundle in totem.values_mut() {
+            if phone.contains_key(&bundle.uuid) {
+                continue;
+            }
+            if let Some(handed) = bundle.hand_to(courier(Some(user)), now) {
+                if handed.header.is_for(courier(Some(user))) {
+                    delivered.push(handed);
+                } else {
+                    phone.insert(handed.uuid.clone(), handed);
+                }
+            }
+        }
+        totem.retain(|_, bundle| !bundle.is_spent(now));
+
+        // Then the phone hands out bundles the totem doesn't have
+        for bundle in phone.values_mut() {
+            if totem.contains_key(&bundle.uuid) {
+                continue;
+            }
+            if let Some(handed) = bundle.hand_to(Node::Totem(totem_id), now) {
+                if handed.header.is_for(Node::Totem(totem_id)) {
+                    delivered.push(handed);
+                } else {
+                    totem.insert(handed.uuid.clone(), handed);
+                }
+            }
+        }
+        phone.retain(|_, bundle| !bundle.is_spent(now));
+
+        delivered
+    }
+
+    fn copies(custodians: &[Custody], uuid: &str) -> u32 {
+        custodians
+            .iter()
+            .filter_map(|custody| custody.get(uuid))
+            .map(|bundle| bundle.header.copies)
+            .sum()
+    }
+
+    #[test]
+    fn test_simulated_topology() {
+        // Totems along a street, phones walking up and down between neighbouring ones
+        let totem_ids = ["t0", "t1", "t2", "t3", "t4"];
+        let users = ["p0", "p1", "p2", "p3"];
+        let mut totems: Vec<Custody> = vec![Custody::new(); totem_ids.len()];
+        let mut phones: Vec<Custody> = vec![Custody::new(); users.len()];
+        let mut positions = [0usize, 1, 3, 4];
+        let mut now = Utc::now();
+
+        // p0 wants a post shown at the far end and nobody walks the whole street
+        let to_totem = bundle(Destination::Totem("t4".to_string()), now);
+        let mut to_user = bundle(Destination::User("p3".to_string()), now);
+        to_user.uuid = "123e4567-e89b-12d3-a456-426614174001".to_string();
+        phones[0].insert(to_totem.uuid.clone(), to_totem.clone());
+        phones[0].insert(to_user.uuid.clone(), to_user.clone());
+
+        let mut delivered: Vec<Bundle> = Vec::new();
+        let mut seed = 7u64;
+        for _ in 0..200 {
+            for (phone, position) in positions.iter_mut().enumerate() {
+                // Deterministic walk, p0 stays near the start and p3 near the end
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let step = (seed >> 33) % 3;
+                let (low, high) = (phone.min(2), (phone + 2).min(totem_ids.len() - 1));
+                *position = match step {
+                    0 if *position > low => *position - 1,
+                    1 if *position < high => *position + 1,
+                    _ => *position,
+                };
+
+                delivered.extend(sync(
+                    &mut phones[phone],
+                    users[phone],
+                    &mut totems[*position],
+                    totem_ids[*position],
+                    now,
+                ));
+            }
+
+            // Copies are only ever split, never made up
+            let custodians: Vec<Custody> = totems.iter().chain(phones.iter()).cloned().collect();
+            for uuid in [&to_totem.uuid, &to_user.uuid] {
+                let in_flight = copies(&custodians, uuid);
+                let arrived: u32 = delivered
+                    .iter()
+                    .filter(|b| &b.uuid == uuid)
+                    .map(|b| b.header.copies)
+                    .sum();
+                assert!(in_flight + arrived <= DEFAULT_COPIES);
+            }
+            now += TimeDelta::minutes(10);
+        }
+
+        assert!(delivered.iter().any(|b| b.uuid == to_totem.uuid));
+        assert!(delivered.iter().any(|b| b.uuid == to_user.uuid));
+        assert!(
+            delivered
+                .iter()
+                .all(|b| b.header.hops <= b.header.hop_limit)
+        );
+
+        // Once expired, every copy left is dropped at the next sync
+        now += DEFAULT_TTL;
+        for (phone, position) in positions.iter().enumerate() {
+            sync(
+                &mut phones[phone],
+                users[phone],
+                &mut totems[*position],
+                totem_ids[*position],
+                now,
+            );
+        }
+        assert!(phones.iter().all(Custody::is_empty));
+    }
+}
@@ -0,0 +1,232 @@
+//! What is talked about at a totem lately
+//!
+//! A totem counts the hashtags of the posts and the replies per thread it receives, in
+//! buckets of an hour. Records are counted as they arrive and whole buckets are dropped
+//! once they are older than [`TRENDING_WINDOW`], so the counts never need a rescan of the
+//! stored records. Each record counts once, however often it is uploaded.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::api::{TrendingResponse, TrendingTag, TrendingThread};
+use crate::model::{Post, Reply};
+
+/// How far back records are counted
+pub const TRENDING_WINDOW: TimeDelta = TimeDelta::hours(24);
+/// Max number of tags and of threads in a [`TrendingResponse`]
+pub const MAX_TRENDING: usize = 10;
+
+const BUCKET_SECS: i64 = 60 * 60;
+/// Max number of distinct tags, and of threads, counted per bucket
+/// Keeps the memory of a totem bounded when someone floods it with tags, later ones are
+/// left out until the next bucket.
+const MAX_KEYS_PER_BUCKET: usize = 256;
+
+#[derive(Debug, Default)]
+struct Bucket {
+    /// Records counted in this bucket
+    records: HashSet<String>,
+    tags: HashMap<String, u32>,
+    threads: HashMap<String, u32>,
+}
+
+/// Rolling counts of tags and thread activity
+#[derive(Debug, Default)]
+pub struct Trending {
+    /// By start of the hour, in seconds since the epoch
+    buckets: BTreeMap<i64, Bucket>,
+    /// Start of the newest bucket that left the window
+    expired: Option<i64>,
+}
+
+impl Trending {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count the tags of a post received at `at`
+    /// Returns false if it was counted already or is too old.
+    pub fn add_post(&mut self, post: &Post, at: DateTime<Utc>) -> bool {
+        let tags = post.entities().tags;
+        self.add(&post.uuid, at, |bucket| {
+            for tag in tags {
+                bump(&mut bucket.tags, tag);
+            }
+        })
+    }
+
+    /// Count a reply received at `at` for its thread
+    /// Returns false if it was counted already or is too old.
+    pub fn add_reply(&mut self, reply: &Reply, at: DateTime<Utc>) -> bool {
+        self.add(&reply.uuid, at, |bucket| {
+            bump(&mut bucket.threads, reply.root_id.clone())
+        })
+    }
+
+    fn add(&mut self, uuid: &str, at: DateTime<Utc>, count: impl FnOnce(&mut Bucket)) -> bool {
+        self.prune(at);
+        let key = bucket_of(at);
+        if self.expired.is_some_and(|expired| key <= expired) {
+            return false;
+        }
+        if self
+            .buckets
+            .values()
+            .any(|bucket| bucket.records.contains(uuid))
+        {
+            return false;
+        }
+
+        let bucket = self.buckets.entry(key).or_default();
+        bucket.records.insert(uuid.to_string());
+        count(bucket);
+        true
+    }
+
+    /// Drop the buckets that left the window
+    /// The window only moves forward, records from before the latest `now` don't move it back.
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let expired = bucket_of(now - TRENDING_WINDOW).max(self.expired.unwrap_or(i64::MIN));
+        self.expired = Some(expired);
+        self.buckets = self.buckets.split_off(&(expired + 1));
+    }
+
+    /// The most used tags and the most active threads of the window, most first
+    /// The totem ID is left for the caller to fill in.
+    pub fn top(&mut self, now: DateTime<Utc>) -> TrendingResponse {
+        self.prune(now);
+
+        let mut tags: HashMap<&str, u32> = HashMap::new();
+        let mut threads: HashMap<&str, u32> = HashMap::new();
+        for bucket in self.buckets.values() {
+            for (tag, count) in &bucket.tags {
+                *tags.entry(tag).or_default() += count;
+            }
+            for (post_id, count) in &bucket.threads {
+                *threads.entry(post_id).or_default() += count;
+            }
+        }
+
+        TrendingResponse {
+            totem_id: String::new(),
+            since: (now - TRENDING_WINDOW).to_rfc3339(),
+            tags: most(tags)
+                .map(|(tag, posts)| TrendingTag { tag, posts })
+                .collect(),
+            threads: most(threads)
+                .map(|(post_id, replies)| TrendingThread { post_id, replies })
+                .collect(),
+        }
+    }
+}
+
+fn bucket_of(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(BUCKET_SECS) * BUCKET_SECS
+}
+
+fn bump(counts: &mut HashMap<String, u32>, key: String) {
+    let full = counts.len() >= MAX_KEYS_PER_BUCKET;
+    match counts.get_mut(&key) {
+        Some(count) => *count += 1,
+        None if !full => {
+            counts.insert(key, 1);
+        }
+        None => {}
+    }
+}
+
+/// The [`MAX_TRENDING`] entries with the highest counts, ties by name
+fn most(counts: HashMap<&str, u32>) -> impl Iterator<Item = (String, u32)> {
+    let mut counts: Vec<(&str, u32)> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    counts
+        .into_iter()
+        .take(MAX_TRENDING)
+        .map(|(key, count)| (key.to_string(), count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT: &str = "123e4567-e89b-12d3-a456-426614174000";
+
+    fn at(hours: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap() + TimeDelta::hours(hours)
+    }
+
+    fn post(uuid: &str, body: &str) -> Post {
+        Post {
+            uuid: uuid.to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            title: "Title".to_string(),
+            body: body.to_string(),
+            timestamp: at(0),
+            image: None,
+            source_totem: None,
+            thumbnail: None,
+            image_preview: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+            signature: None,
+        }
+    }
+
+    fn reply(uuid: &str, root_id: &str) -> Reply {
+        Reply {
+            uuid: uuid.to_string(),
+            user_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            parent_id: root_id.to_string(),
+            root_id: root_id.to_string(),
+            body: "Reply".to_string(),
+            timestamp: at(0),
+            signature: None,
+        }
+    }
+
+    fn tags(response: &TrendingResponse) -> Vec<(&str, u32)> {
+        response
+            .tags
+            .iter()
+            .map(|tag| (tag.tag.as_str(), tag.posts))
+            .collect()
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut trending = Trending::new();
+        assert!(trending.add_post(&post("p1", "#market #fish"), at(0)));
+        assert!(trending.add_post(&post("p2", "#Fish"), at(1)));
+        assert!(trending.add_post(&post("p3", "#bakery"), at(2)));
+        // Uploaded again, by another phone
+        assert!(!trending.add_post(&post("p2", "#Fish"), at(3)));
+        assert!(trending.add_reply(&reply("r1", ROOT), at(2)));
+        assert!(trending.add_reply(&reply("r2", ROOT), at(3)));
+
+        let top = trending.top(at(3));
+        assert_eq!(tags(&top), [("fish", 2), ("bakery", 1), ("market", 1)]);
+        assert_eq!(
+            top.threads,
+            [TrendingThread {
+                post_id: ROOT.to_string(),
+                replies: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_window() {
+        let mut trending = Trending::new();
+        assert!(trending.add_post(&post("p1", "#market"), at(0)));
+        assert!(trending.add_post(&post("p2", "#fish"), at(20)));
+        assert_eq!(tags(&trending.top(at(20))), [("fish", 1), ("market", 1)]);
+
+        // The first post leaves the window, and older ones aren't taken anymore
+        assert_eq!(tags(&trending.top(at(25))), [("fish", 1)]);
+        assert!(!trending.add_post(&post("p3", "#market"), at(0)));
+        assert!(trending.add_post(&post("p1", "#market"), at(26)));
+        assert_eq!(tags(&trending.top(at(45))), [("market", 1)]);
+    }
+}